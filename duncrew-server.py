@@ -30,12 +30,14 @@ import sys
 import re
 import json
 import argparse
+import hmac
 import threading
 import time
 import uuid
 import subprocess
 import shlex
 import shutil
+import ssl
 from pathlib import Path
 from http.server import ThreadingHTTPServer, BaseHTTPRequestHandler
 from urllib.parse import unquote, urlparse, parse_qs
@@ -499,8 +501,10 @@ def count_experience_entries(exp_dir: Path) -> int:
 class ToolRegistry:
     """动态工具发现与注册 - 支持内置工具 + 插件工具 + 指令型工具 + MCP工具"""
 
-    def __init__(self, clawd_path: Path, project_path: Path = None):
+    def __init__(self, clawd_path: Path, project_path: Path = None, safe_mode: bool = False):
         self.clawd_path = clawd_path
+        # 安全模式：不加载插件与 MCP 服务器，只提供内置工具（桌面端在崩溃循环后以 --safe-mode 启动）
+        self.safe_mode = safe_mode
         # 项目目录 (脚本/exe 所在目录)，用于加载内置技能
         self.project_path = project_path or APP_DIR
         self.builtin_tools: dict = {}      # name -> callable
@@ -528,6 +532,9 @@ class ToolRegistry:
 
     def scan_plugins(self):
         """递归扫描 skills/ 目录，统一从 SKILL.md frontmatter 注册可执行插件 + 指令型技能"""
+        if self.safe_mode:
+            print("[ToolRegistry] Safe mode: skipping plugins")
+            return
         skills_dirs = self._get_skills_dirs()
         if not skills_dirs:
             return
@@ -693,6 +700,9 @@ class ToolRegistry:
 
     def scan_mcp_servers(self):
         """扫描并连接 MCP 服务器"""
+        if self.safe_mode:
+            print("[ToolRegistry] Safe mode: skipping MCP servers")
+            return
         if not HAS_MCP:
             print("[ToolRegistry] MCP support not available (missing mcp_manager)")
             return
@@ -1396,11 +1406,22 @@ class BrowserManager:
 _browser_manager = BrowserManager()
 
 
+# 桌面端启动时由 Tauri 传入的会话 token；未设置时（浏览器模式）不做校验
+AUTH_TOKEN = os.environ.get('DUNCREW_AUTH_TOKEN') or None
+AUTH_TOKEN_HEADER = 'X-DunCrew-Token'
+# 只读会话（--read-only）中仍允许的写方法端点：不修改数据目录
+READ_ONLY_ALLOWED_PATHS = {'/shutdown', '/api/llm/proxy'}
+
+
 class ClawdDataHandler(BaseHTTPRequestHandler):
     clawd_path = None
     project_path = None  # 项目目录，用于加载内置技能
     registry = None  # type: ToolRegistry
     subagent_manager = None  # type: SubagentManager
+    instance_id = None  # 桌面端传入的数据目录指纹，由 /instance 原样返回
+    cache_path = None  # 缓存目录，来自 --cache-dir
+    read_only = False  # 临时使用默认数据目录时为 True，拒绝修改数据的请求
+    features = {}  # 功能开关：名称 -> 是否启用，来自 --enable-<名称> / --disable-<名称>
     tasks = {}
     tasks_lock = threading.Lock()
     _gene_file_lock = threading.Lock()  # 基因文件读写锁，防止并发写入损坏
@@ -1412,7 +1433,7 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
     def send_cors_headers(self):
         self.send_header('Access-Control-Allow-Origin', '*')
         self.send_header('Access-Control-Allow-Methods', 'GET, POST, PUT, DELETE, OPTIONS')
-        self.send_header('Access-Control-Allow-Headers', 'Content-Type')
+        self.send_header('Access-Control-Allow-Headers', 'Content-Type, X-DunCrew-Token')
     
     def send_json(self, data, status=200):
         self.send_response(status)
@@ -1434,6 +1455,22 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
     def send_error_json(self, message, status=404):
         self.send_json({'error': message, 'status': 'error'}, status)
     
+    def check_auth(self, path):
+        """校验会话 token，失败时直接返回 401（/health 与 /instance 不需要 token）"""
+        if not AUTH_TOKEN or path in ('/health', '/instance'):
+            return True
+        if hmac.compare_digest(self.headers.get(AUTH_TOKEN_HEADER, ''), AUTH_TOKEN):
+            return True
+        self.send_error_json('Unauthorized', 401)
+        return False
+    
+    def check_writable(self, path):
+        """只读会话中拒绝 POST/PUT/DELETE（少数不写数据目录的端点除外），直接返回 403"""
+        if not self.read_only or path in READ_ONLY_ALLOWED_PATHS:
+            return True
+        self.send_error_json('Data directory is read-only in this session', 403)
+        return False
+    
     def do_OPTIONS(self):
         self.send_response(200)
         self.send_cors_headers()
@@ -1442,10 +1479,14 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
     def do_GET(self):
         parsed = urlparse(self.path)
         path = unquote(parsed.path)
+        if not self.check_auth(path):
+            return
         query = parse_qs(parsed.query)
         
         routes = {
             '/status': self.handle_status,
+            '/health': self.handle_health,
+            '/instance': self.handle_instance,
             '/files': self.handle_files,
             '/skills': self.handle_skills,
             '/nexuses': self.handle_nexuses,
@@ -1534,6 +1575,8 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
     def do_POST(self):
         parsed = urlparse(self.path)
         path = unquote(parsed.path)
+        if not self.check_auth(path) or not self.check_writable(path):
+            return
         content_type = self.headers.get('Content-Type', '')
         
         # 文件上传：multipart/form-data 单独处理（避免大文件 JSON 编码 OOM）
@@ -1550,6 +1593,10 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
             self.send_error_json('Invalid JSON', 400)
             return
         
+        if path == '/shutdown':
+            self.handle_shutdown()
+            return
+        
         # 🌟 新增：工具执行接口
         if path == '/api/tools/execute':
             self.handle_tool_execution(data)
@@ -1642,6 +1689,8 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
     def do_PUT(self):
         parsed = urlparse(self.path)
         path = unquote(parsed.path)
+        if not self.check_auth(path) or not self.check_writable(path):
+            return
         content_length = int(self.headers.get('Content-Length', 0))
         body = self.rfile.read(content_length).decode('utf-8') if content_length > 0 else '{}'
         try:
@@ -1659,6 +1708,8 @@ class ClawdDataHandler(BaseHTTPRequestHandler):
     def do_DELETE(self):
         parsed = urlparse(self.path)
         path = unquote(parsed.path)
+        if not self.check_auth(path) or not self.check_writable(path):
+            return
         
         if path.startswith('/api/sessions/') and path.endswith('/checkpoint'):
             session_id = path[14:-11]
@@ -3700,6 +3751,18 @@ curl -X POST http://localhost:3001/api/tools/execute \\
         self.end_headers()
         self.wfile.write(html.encode('utf-8'))
     
+    def handle_health(self):
+        self.send_json({'status': 'ok', 'version': VERSION})
+    
+    def handle_instance(self):
+        """桌面端据此判断端口上的后端是否属于自己的数据目录；独立运行时为 null"""
+        self.send_json({'instance_id': self.instance_id})
+    
+    def handle_shutdown(self):
+        """桌面端优雅退出：先响应，再在后台线程停止 serve_forever"""
+        self.send_json({'status': 'shutting_down'})
+        threading.Thread(target=self.server.shutdown, daemon=True).start()
+    
     def handle_status(self):
         files = list_files(self.clawd_path)
         skills_dir = self.clawd_path / 'skills'
@@ -3709,6 +3772,9 @@ curl -X POST http://localhost:3001/api/tools/execute \\
             'status': 'ok',
             'version': VERSION,
             'mode': 'native',
+            'safeMode': self.registry.safe_mode,
+            'readOnly': self.read_only,
+            'features': self.features,
             'clawdPath': str(self.clawd_path),
             'fileCount': len(files),
             'skillCount': skill_count,
@@ -5599,7 +5665,7 @@ curl -X POST http://localhost:3001/api/tools/execute \\
                 self.send_header('Cache-Control', 'no-cache')
                 self.send_header('Access-Control-Allow-Origin', '*')
                 self.send_header('Access-Control-Allow-Methods', 'GET, POST, PUT, DELETE, OPTIONS')
-                self.send_header('Access-Control-Allow-Headers', 'Content-Type, Authorization')
+                self.send_header('Access-Control-Allow-Headers', 'Content-Type, Authorization, X-DunCrew-Token')
                 self.send_header('Transfer-Encoding', 'chunked')
                 self.end_headers()
                 
@@ -5827,6 +5893,9 @@ def cleanup_old_logs(clawd_path, max_age_hours=24):
     now = time.time()
     count = 0
     for f in logs_dir.glob('*.log'):
+        # backend*.log / app*.log 由桌面壳按大小滚动管理，这里不清理
+        if f.name.startswith(('backend.', 'app.')):
+            continue
         try:
             age = now - f.stat().st_mtime
             if age > max_age_hours * 3600:
@@ -5878,14 +5947,82 @@ def cleanup_temp_uploads(clawd_path, max_age_hours=1):
         print(f"[Cleanup] Removed {count} old temp upload files")
 
 
-def main():
+def watch_parent_process(parent_pid):
+    """桌面壳进程被强杀时随之退出，避免成为占用端口的孤儿进程（仅 Unix，Windows 由 Job Object 负责）"""
+    if os.name == 'nt':
+        return
+    while True:
+        time.sleep(1)
+        try:
+            os.kill(parent_pid, 0)
+        except ProcessLookupError:
+            print(f"[Server] Parent process {parent_pid} is gone, exiting")
+            os._exit(0)
+        except PermissionError:
+            pass
+
+
+def apply_locale(args):
+    """打包后的进程继承不到有用的区域设置：按桌面端检测到的系统区域与时区设置，报告中的日期据此格式化"""
+    if args.locale:
+        os.environ['DUNCREW_LOCALE'] = args.locale
+        os.environ['LANG'] = args.locale.replace('-', '_') + '.UTF-8'
+        try:
+            import locale as _locale
+            _locale.setlocale(_locale.LC_TIME, '')
+        except Exception:
+            pass  # 系统未安装该区域时保持默认
+    if args.languages:
+        os.environ['DUNCREW_LANGUAGES'] = args.languages
+    # Windows 的 C 运行库不认识 IANA 时区名，只在有 tzset 的平台上设置 TZ
+    if args.timezone and hasattr(time, 'tzset'):
+        os.environ['TZ'] = args.timezone
+        time.tzset()
+
+
+def build_arg_parser():
     parser = argparse.ArgumentParser(description='DunCrew Native Server')
     parser.add_argument('--port', type=int, default=3001, help='Server port (default: 3001)')
     # 支持环境变量覆盖默认路径
     default_path = os.getenv('DUNCREW_DATA_PATH', os.getenv('DDOS_DATA_PATH', '~/.duncrew'))
     parser.add_argument('--path', type=str, default=default_path, help='Data directory path (default: ~/.duncrew)')
     parser.add_argument('--host', type=str, default='0.0.0.0', help='Server host (default: 0.0.0.0)')
-    args = parser.parse_args()
+    parser.add_argument('--cache-dir', type=str, default=None, help='Cache directory for thumbnails and embeddings (default: <path>/cache)')
+    parser.add_argument('--parent-pid', type=int, default=None, help='Exit when this process dies (desktop shell)')
+    parser.add_argument('--log-level', type=str, default='info', choices=['error', 'warn', 'info', 'debug'], help='Log level (default: info)')
+    parser.add_argument('--instance-id', type=str, default=None, help='Data directory fingerprint echoed by GET /instance (desktop shell)')
+    parser.add_argument('--locale', type=str, default=None, help='OS locale, BCP 47 (e.g. zh-CN)')
+    parser.add_argument('--languages', type=str, default=None, help='Preferred languages, comma separated')
+    parser.add_argument('--timezone', type=str, default=None, help='IANA timezone (e.g. Asia/Shanghai)')
+    parser.add_argument('--tls-cert', type=str, default=None, help='PEM certificate; serve HTTPS together with --tls-key')
+    parser.add_argument('--tls-key', type=str, default=None, help='PEM private key for --tls-cert')
+    parser.add_argument('--safe-mode', action='store_true', help='Skip plugins and MCP servers (recovery after crash loops)')
+    parser.add_argument('--read-only', action='store_true', help='Reject requests that modify the data directory (temporary session)')
+    return parser
+
+
+def parse_args(argv=None):
+    """解析启动参数；桌面端的功能开关 --enable-<名称> / --disable-<名称> 名称不固定，收集到 args.features"""
+    parser = build_arg_parser()
+    args, unknown = parser.parse_known_args(argv)
+    args.features = {}
+    for arg in unknown:
+        match = re.fullmatch(r'--(enable|disable)-([A-Za-z0-9_-]+)', arg)
+        if not match:
+            parser.error(f'unrecognized arguments: {arg}')
+        args.features[match.group(2)] = match.group(1) == 'enable'
+    if bool(args.tls_cert) != bool(args.tls_key):
+        parser.error('--tls-cert and --tls-key must be given together')
+    return args
+
+
+def main():
+    args = parse_args()
+    os.environ['DUNCREW_LOG_LEVEL'] = args.log_level
+    apply_locale(args)
+    
+    if args.parent_pid:
+        threading.Thread(target=watch_parent_process, args=(args.parent_pid,), daemon=True).start()
     
     clawd_path = Path(args.path).expanduser().resolve()
     
@@ -5927,10 +6064,18 @@ You are DunCrew, a local AI operating system running directly on the user's comp
     skills_dir = clawd_path / 'skills'
     skills_dir.mkdir(exist_ok=True)
     
-    cleanup_old_logs(clawd_path)
+    # 只读会话不清理旧日志、追踪与上传，数据目录保持原样
+    if not args.read_only:
+        cleanup_old_logs(clawd_path)
+    
+    # 缩略图、向量等缓存统一写到桌面端指定的目录，桌面端可按分类清除；插件经 DUNCREW_CACHE_DIR 取得
+    cache_path = Path(args.cache_dir).expanduser().resolve() if args.cache_dir else clawd_path / 'cache'
+    if not args.read_only:
+        cache_path.mkdir(parents=True, exist_ok=True)
+    os.environ['DUNCREW_CACHE_DIR'] = str(cache_path)
     
     # 🔌 初始化工具注册表
-    registry = ToolRegistry(clawd_path)
+    registry = ToolRegistry(clawd_path, safe_mode=args.safe_mode)
     # 注册内置工具
     builtin_names = [
         'readFile', 'writeFile', 'appendFile', 'listDir', 'runCmd',
@@ -5946,8 +6091,9 @@ You are DunCrew, a local AI operating system running directly on the user's comp
     registry.scan_mcp_servers()
 
     # 清理过期执行追踪 (P2: 保留最近6个月)
-    cleanup_old_traces(clawd_path)
-    cleanup_temp_uploads(clawd_path)
+    if not args.read_only:
+        cleanup_old_traces(clawd_path)
+        cleanup_temp_uploads(clawd_path)
 
     # V2: 初始化 SQLite 数据库
     global _db_conn
@@ -5961,8 +6107,20 @@ You are DunCrew, a local AI operating system running directly on the user's comp
     ClawdDataHandler.project_path = project_path
     ClawdDataHandler.registry = registry
     ClawdDataHandler.subagent_manager = SubagentManager(registry)
+    ClawdDataHandler.instance_id = args.instance_id
+    ClawdDataHandler.cache_path = cache_path
+    ClawdDataHandler.read_only = args.read_only
+    ClawdDataHandler.features = args.features
     
     server = ThreadingHTTPServer((args.host, args.port), ClawdDataHandler)
+    scheme = 'http'
+    if args.tls_cert:
+        # 证书由桌面端生成并固定信任，这里只负责以 HTTPS 提供服务；
+        # 握手推迟到请求线程中的首次读取，慢速客户端不会阻塞 accept
+        tls_context = ssl.SSLContext(ssl.PROTOCOL_TLS_SERVER)
+        tls_context.load_cert_chain(args.tls_cert, args.tls_key)
+        server.socket = tls_context.wrap_socket(server.socket, server_side=True, do_handshake_on_connect=False)
+        scheme = 'https'
     
     tool_names = [t['name'] for t in registry.list_all()]
     plugin_count = len(registry.plugin_tools)
@@ -5971,8 +6129,8 @@ You are DunCrew, a local AI operating system running directly on the user's comp
 +==================================================================+
 |              DunCrew Native Server v{VERSION}                         |
 +==================================================================+
-|  Mode:    NATIVE (standalone, no OpenClaw needed)                |
-|  Server:  http://{args.host}:{args.port}                                    |
+|  Mode:    {'SAFE MODE (plugins disabled)' if args.safe_mode else 'NATIVE (standalone, no OpenClaw needed)':<55}|
+|  Server:  {scheme}://{args.host}:{args.port}                                    |
 |  Data:    {str(clawd_path)[:50]:<50} |
 +------------------------------------------------------------------+
 |  Tools:   {len(tool_names)} registered ({len(builtin_names)} builtin + {plugin_count} plugins + {mcp_count} mcp)    |
@@ -5985,9 +6143,14 @@ You are DunCrew, a local AI operating system running directly on the user's comp
     try:
         server.serve_forever()
     except KeyboardInterrupt:
-        print("\nShutting down...")
-        _browser_manager.shutdown()
-        server.shutdown()
+        pass
+    
+    # Ctrl+C 或 /shutdown 都会走到这里，确保数据库在退出前落盘
+    print("\nShutting down...")
+    _browser_manager.shutdown()
+    server.server_close()
+    if _db_conn is not None:
+        _db_conn.close()
 
 
 if __name__ == '__main__':
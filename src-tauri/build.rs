@@ -1,3 +1,26 @@
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+// 计算打包用 Sidecar 的 SHA-256，写入 $OUT_DIR/sidecar_hash.rs，运行时启动前比对。
+// 二进制不存在（例如只构建前端壳）时生成 None，运行时跳过校验
+fn write_sidecar_hash() {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let suffix = if target.contains("windows") { ".exe" } else { "" };
+    let sidecar = PathBuf::from("binaries").join(format!("duncrew-server-{}{}", target, suffix));
+    println!("cargo:rerun-if-changed={}", sidecar.display());
+
+    let hash = std::fs::read(&sidecar).ok().map(|bytes| {
+        Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect::<String>()
+    });
+    let source = match hash {
+        Some(hash) => format!("pub const EXPECTED_SIDECAR_SHA256: Option<&str> = Some(\"{}\");\n", hash),
+        None => "pub const EXPECTED_SIDECAR_SHA256: Option<&str> = None;\n".to_string(),
+    };
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("sidecar_hash.rs");
+    std::fs::write(out, source).expect("failed to write sidecar_hash.rs");
+}
+
 fn main() {
+    write_sidecar_hash();
     tauri_build::build()
 }
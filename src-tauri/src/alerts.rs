@@ -0,0 +1,356 @@
+// 原生对话框与系统通知的协调器：本应用的消息对话框与通知都经这里发出，避免一连串故障时弹窗堆叠。
+// 同一分类、同一内容的提示在 DEDUPE_WINDOW 内只出现一次；同一时刻最多显示一个模态对话框，其余按优先级排队，
+// 前一个关闭后依次显示。已有对话框在显示时，不需要作答的非高优先级提示改为系统通知。
+// 分类可通过 mute_alerts 静音到指定时间（写入 config.json 的 alert_mutes），静音期间不需要作答的提示只写日志；
+// 需要作答的提示不受静音影响，由调用方决定静音时的做法（例如剩余空间检查在静音期间直接放行）。
+// 排队中的提示可由 get_pending_alerts 读取并在界面内显示，队列变化时发送 `alerts://pending`。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+use tauri_plugin_notification::NotificationExt;
+
+const DEDUPE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 提示分类，也是 alert_mutes 的键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCategory {
+    BackendFailure,
+    Crash,
+    LowDisk,
+    Update,
+    Security,
+    VersionMismatch,
+    /// 端口上的其他 DunCrew 实例
+    Instance,
+    Startup,
+    /// 数据目录、旧数据迁移
+    Data,
+    General,
+}
+
+impl AlertCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertCategory::BackendFailure => "backend_failure",
+            AlertCategory::Crash => "crash",
+            AlertCategory::LowDisk => "low_disk",
+            AlertCategory::Update => "update",
+            AlertCategory::Security => "security",
+            AlertCategory::VersionMismatch => "version_mismatch",
+            AlertCategory::Instance => "instance",
+            AlertCategory::Startup => "startup",
+            AlertCategory::Data => "data",
+            AlertCategory::General => "general",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertPriority {
+    Low,
+    Normal,
+    /// 总是以对话框显示，不会改为通知
+    High,
+}
+
+/// 一条对话框提示；默认为 Normal 优先级、Info 类型、只有“确定”按钮
+pub struct Alert {
+    category: AlertCategory,
+    priority: AlertPriority,
+    kind: MessageDialogKind,
+    message: String,
+    buttons: MessageDialogButtons,
+}
+
+impl Alert {
+    pub fn new(category: AlertCategory, message: impl Into<String>) -> Self {
+        Self {
+            category,
+            priority: AlertPriority::Normal,
+            kind: MessageDialogKind::Info,
+            message: message.into(),
+            buttons: MessageDialogButtons::Ok,
+        }
+    }
+
+    pub fn kind(mut self, kind: MessageDialogKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn priority(mut self, priority: AlertPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn buttons(mut self, buttons: MessageDialogButtons) -> Self {
+        self.buttons = buttons;
+        self
+    }
+}
+
+// 对话框关闭后的回调；None 表示不需要作答
+enum Answer {
+    None,
+    Bool(Box<dyn FnOnce(bool) + Send>),
+    Result(Box<dyn FnOnce(MessageDialogResult) + Send>),
+}
+
+impl Answer {
+    fn needed(&self) -> bool {
+        !matches!(self, Answer::None)
+    }
+}
+
+/// 排队中的提示，get_pending_alerts 与 `alerts://pending` 的负载
+#[derive(Clone, serde::Serialize)]
+pub struct PendingAlert {
+    pub id: u64,
+    pub category: AlertCategory,
+    pub priority: AlertPriority,
+    /// info / warning / error
+    pub kind: &'static str,
+    pub message: String,
+    /// 需要作答的提示只能在原生对话框中处理，dismiss_alert 不能移除
+    pub needs_answer: bool,
+    pub queued_at: SystemTime,
+}
+
+struct Queued {
+    info: PendingAlert,
+    alert: Alert,
+    answer: Answer,
+}
+
+type AlertKey = (AlertCategory, String);
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    // 最近显示过的提示，用于去重
+    recent: HashMap<AlertKey, Instant>,
+    // 正在显示的对话框
+    showing: Option<AlertKey>,
+    queue: VecDeque<Queued>,
+}
+
+impl Inner {
+    // 与正在显示或排队中的提示相同，或不需要作答且在去重窗口内出现过
+    fn is_duplicate(&mut self, key: &AlertKey, needs_answer: bool) -> bool {
+        self.recent.retain(|_, at| at.elapsed() < DEDUPE_WINDOW);
+        self.showing.as_ref() == Some(key)
+            || self.queue.iter().any(|queued| queued.info.category == key.0 && queued.info.message == key.1)
+            || (!needs_answer && self.recent.contains_key(key))
+    }
+
+    fn pending(&self) -> Vec<PendingAlert> {
+        self.queue.iter().map(|queued| queued.info.clone()).collect()
+    }
+}
+
+/// 在 setup 中注册
+#[derive(Default)]
+pub struct AlertCoordinator {
+    inner: Mutex<Inner>,
+}
+
+fn kind_name(kind: MessageDialogKind) -> &'static str {
+    match kind {
+        MessageDialogKind::Warning => "warning",
+        MessageDialogKind::Error => "error",
+        _ => "info",
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// 该分类是否处于静音期
+pub fn is_muted(app: &AppHandle, category: AlertCategory) -> bool {
+    crate::app_config(app).alert_mutes.get(category.as_str()).is_some_and(|until| *until > now_secs())
+}
+
+fn notify_native(app: &AppHandle, message: &str) {
+    if let Err(e) = app.notification().builder().title("DunCrew").body(message).show() {
+        app_error!("Failed to show notification: {}", e);
+    }
+}
+
+fn emit_pending(app: &AppHandle, pending: Vec<PendingAlert>) {
+    crate::app_events::emit_to_frontend(app, "alerts://pending", pending);
+}
+
+fn submit(app: &AppHandle, alert: Alert, answer: Answer) {
+    let needs_answer = answer.needed();
+    if !needs_answer {
+        if crate::headless::enabled(app) {
+            app_log!("{}", alert.message);
+            return;
+        }
+        if is_muted(app, alert.category) {
+            app_log!("Muted {} alert: {}", alert.category.as_str(), alert.message);
+            return;
+        }
+    }
+    let coordinator = app.state::<AlertCoordinator>();
+    let mut inner = coordinator.inner.lock().unwrap();
+    let key = (alert.category, alert.message.clone());
+    if inner.is_duplicate(&key, needs_answer) {
+        app_log!("Suppressed duplicate {} alert: {}", alert.category.as_str(), alert.message);
+        return;
+    }
+    inner.recent.insert(key.clone(), Instant::now());
+    if inner.showing.is_none() {
+        inner.showing = Some(key);
+        drop(inner);
+        display(app, alert, answer);
+        return;
+    }
+    if !needs_answer && alert.priority < AlertPriority::High {
+        drop(inner);
+        notify_native(app, &alert.message);
+        return;
+    }
+    inner.next_id += 1;
+    let info = PendingAlert {
+        id: inner.next_id,
+        category: alert.category,
+        priority: alert.priority,
+        kind: kind_name(alert.kind),
+        message: alert.message.clone(),
+        needs_answer,
+        queued_at: SystemTime::now(),
+    };
+    // 同优先级按先后，高优先级排在前面
+    let position = inner.queue.iter().position(|queued| queued.info.priority < alert.priority).unwrap_or(inner.queue.len());
+    inner.queue.insert(position, Queued { info, alert, answer });
+    let pending = inner.pending();
+    drop(inner);
+    emit_pending(app, pending);
+}
+
+fn display(app: &AppHandle, alert: Alert, answer: Answer) {
+    let dialog = app.dialog().message(alert.message).title("DunCrew").kind(alert.kind).buttons(alert.buttons);
+    let app = app.clone();
+    match answer {
+        Answer::None => dialog.show(move |_| closed(&app)),
+        Answer::Bool(on_answer) => dialog.show(move |ok| {
+            on_answer(ok);
+            closed(&app);
+        }),
+        Answer::Result(on_answer) => dialog.show_with_result(move |result| {
+            on_answer(result);
+            closed(&app);
+        }),
+    }
+}
+
+// 当前对话框已关闭：显示队列中的下一个
+fn closed(app: &AppHandle) {
+    let coordinator = app.state::<AlertCoordinator>();
+    let mut inner = coordinator.inner.lock().unwrap();
+    let Some(next) = inner.queue.pop_front() else {
+        inner.showing = None;
+        return;
+    };
+    inner.showing = Some((next.info.category, next.info.message.clone()));
+    let pending = inner.pending();
+    drop(inner);
+    emit_pending(app, pending);
+    display(app, next.alert, next.answer);
+}
+
+/// 只需告知用户的对话框
+pub fn show(app: &AppHandle, alert: Alert) {
+    submit(app, alert, Answer::None);
+}
+
+/// 需要用户确认的对话框，回调参数同 MessageDialogBuilder::show
+pub fn ask(app: &AppHandle, alert: Alert, on_answer: impl FnOnce(bool) + Send + 'static) {
+    submit(app, alert, Answer::Bool(Box::new(on_answer)));
+}
+
+/// 三个按钮等需要区分选择的对话框，回调参数同 MessageDialogBuilder::show_with_result
+pub fn ask_with_result(app: &AppHandle, alert: Alert, on_answer: impl FnOnce(MessageDialogResult) + Send + 'static) {
+    submit(app, alert, Answer::Result(Box::new(on_answer)));
+}
+
+/// 阻塞等待用户确认，不能在主线程调用。同样的提示已在显示或排队时不再重复询问，返回 false
+pub fn blocking_ask(app: &AppHandle, alert: Alert) -> bool {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    ask(app, alert, move |ok| {
+        let _ = sender.send(ok);
+    });
+    receiver.recv().unwrap_or(false)
+}
+
+/// 系统通知，同样去重并遵守静音设置
+pub fn notify(app: &AppHandle, category: AlertCategory, message: &str) {
+    if crate::headless::enabled(app) {
+        app_log!("{}", message);
+        return;
+    }
+    if is_muted(app, category) {
+        app_log!("Muted {} notification: {}", category.as_str(), message);
+        return;
+    }
+    {
+        let coordinator = app.state::<AlertCoordinator>();
+        let mut inner = coordinator.inner.lock().unwrap();
+        let key = (category, message.to_string());
+        if inner.is_duplicate(&key, false) {
+            app_log!("Suppressed duplicate {} notification: {}", category.as_str(), message);
+            return;
+        }
+        inner.recent.insert(key, Instant::now());
+    }
+    notify_native(app, message);
+}
+
+/// 排队等待显示的提示，按显示顺序排列；不含正在显示的对话框
+#[tauri::command]
+pub fn get_pending_alerts(coordinator: tauri::State<'_, AlertCoordinator>) -> Vec<PendingAlert> {
+    coordinator.inner.lock().unwrap().pending()
+}
+
+/// 前端已在界面内显示某条排队中的提示后调用，不再以原生对话框显示；需要作答的提示不能移除
+#[tauri::command]
+pub fn dismiss_alert(app: AppHandle, id: u64) -> Result<(), String> {
+    let coordinator = app.state::<AlertCoordinator>();
+    let mut inner = coordinator.inner.lock().unwrap();
+    let position = inner
+        .queue
+        .iter()
+        .position(|queued| queued.info.id == id)
+        .ok_or_else(|| format!("No pending alert {}", id))?;
+    if inner.queue[position].info.needs_answer {
+        return Err(format!("Alert {} needs an answer in the dialog", id));
+    }
+    inner.queue.remove(position);
+    let pending = inner.pending();
+    drop(inner);
+    emit_pending(&app, pending);
+    Ok(())
+}
+
+/// 静音某一分类 hours 小时，0 表示取消静音；同时清除已过期的静音
+#[tauri::command]
+pub fn mute_alerts(app: AppHandle, category: AlertCategory, hours: u64) -> Result<(), String> {
+    let now = now_secs();
+    app.state::<crate::config::ConfigState>().update(|config| {
+        config.alert_mutes.retain(|_, until| *until > now);
+        if hours == 0 {
+            config.alert_mutes.remove(category.as_str());
+        } else {
+            config.alert_mutes.insert(category.as_str().to_string(), now + hours.saturating_mul(3600));
+        }
+    })?;
+    app_log!("Alerts in {} muted for {} hours", category.as_str(), hours);
+    Ok(())
+}
@@ -0,0 +1,284 @@
+// 发往前端的应用事件在页面注册监听之前发出就会丢失，冷启动时的 deep link 最明显。这里统一暂存后按顺序补发：
+// - emit_when_ready：需要前端和后端都就绪后才能处理的事件（deep link、打开文件等），就绪前不发送；
+// - emit_to_frontend：后端就绪、崩溃等通知，立即发送（托盘等 Rust 侧监听不受影响），
+//   前端就绪前另存一份，frontend_ready 时只向页面补发。
+// 两类事件的负载都带递增的 seq，前端可据此发现缺口、去掉重复；暂存数量有上限，页面一直没有加载时丢弃最早的。
+// 主窗口页面重新加载时重新开始暂存，直到新页面再次调用 frontend_ready。
+// 页面调用 frontend_ready 时先收到 `app://bootstrap`（系统主题、界面语言、是否处于安全模式），首次绘制即可使用正确的配色。
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime};
+
+const MAX_BUFFERED: usize = 200;
+
+struct Buffered {
+    event: &'static str,
+    payload: serde_json::Value,
+    // true：emit_when_ready 的事件，尚未发送过，还要等后端就绪；false：已发送过，只向页面补发
+    needs_backend: bool,
+}
+
+#[derive(Default)]
+pub struct AppEventQueue {
+    // 前端已注册监听（调用了 frontend_ready），页面重新加载时清除
+    frontend_ready: AtomicBool,
+    // 后端已通过健康检查，或用户在启动画面选择了直接进入
+    backend_ready: AtomicBool,
+    // 最近分配的序号
+    seq: AtomicU64,
+    // 因超出 MAX_BUFFERED 丢弃的事件数
+    dropped: AtomicU64,
+    buffer: Mutex<VecDeque<Buffered>>,
+}
+
+impl AppEventQueue {
+    fn deliverable(&self, needs_backend: bool) -> bool {
+        self.frontend_ready.load(Ordering::SeqCst) && (!needs_backend || self.backend_ready.load(Ordering::SeqCst))
+    }
+
+    // 分配序号并写入负载；负载不是对象时原样发送
+    fn sequenced<S: serde::Serialize>(&self, payload: S) -> (u64, serde_json::Value) {
+        let seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut payload = serde_json::to_value(payload).unwrap_or_default();
+        if let Some(map) = payload.as_object_mut() {
+            map.insert("seq".to_string(), seq.into());
+        }
+        (seq, payload)
+    }
+
+    fn push(&self, buffer: &mut VecDeque<Buffered>, entry: Buffered) {
+        if buffer.len() == MAX_BUFFERED {
+            if let Some(oldest) = buffer.pop_front() {
+                self.dropped.fetch_add(1, Ordering::SeqCst);
+                app_error!("Frontend is not ready, dropping buffered event {}", oldest.event);
+            }
+        }
+        buffer.push_back(entry);
+    }
+
+    // 取出现在可以发送的事件，其余留在队列中
+    fn take_deliverable(&self) -> Vec<Buffered> {
+        let mut buffer = self.buffer.lock().unwrap();
+        let (ready, waiting): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut *buffer)
+            .into_iter()
+            .partition(|entry| self.deliverable(entry.needs_backend));
+        *buffer = waiting;
+        ready.into()
+    }
+}
+
+// 发送暂存的事件；已发送过的只补发给页面，托盘等 Rust 侧监听不会收到第二次
+fn flush<R: Runtime>(app: &AppHandle<R>) -> usize {
+    let ready = app.state::<AppEventQueue>().take_deliverable();
+    let count = ready.len();
+    for entry in ready {
+        if entry.needs_backend {
+            let _ = app.emit(entry.event, entry.payload);
+        } else {
+            let _ = app.emit_filter(entry.event, entry.payload, |target| !matches!(target, EventTarget::App));
+        }
+    }
+    count
+}
+
+/// 前端与后端都就绪时立即发送，否则暂存到就绪后再发送
+pub fn emit_when_ready<R: Runtime, S: serde::Serialize>(app: &AppHandle<R>, event: &'static str, payload: S) {
+    let queue = app.state::<AppEventQueue>();
+    let mut buffer = queue.buffer.lock().unwrap();
+    let (_, payload) = queue.sequenced(payload);
+    if queue.deliverable(true) {
+        drop(buffer);
+        let _ = app.emit(event, payload);
+        return;
+    }
+    queue.push(&mut buffer, Buffered { event, payload, needs_backend: true });
+}
+
+/// 立即发送；前端尚未就绪时另存一份，frontend_ready 时补发给页面
+pub fn emit_to_frontend<R: Runtime, S: serde::Serialize>(app: &AppHandle<R>, event: &'static str, payload: S) {
+    let queue = app.state::<AppEventQueue>();
+    let mut buffer = queue.buffer.lock().unwrap();
+    let (_, payload) = queue.sequenced(payload);
+    if !queue.deliverable(false) {
+        queue.push(&mut buffer, Buffered { event, payload: payload.clone(), needs_backend: false });
+    }
+    drop(buffer);
+    let _ = app.emit(event, payload);
+}
+
+/// 主窗口页面开始（重新）加载：之前注册的监听已失效，重新暂存到新页面调用 frontend_ready
+pub fn frontend_unloaded<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(queue) = app.try_state::<AppEventQueue>() {
+        queue.frontend_ready.store(false, Ordering::SeqCst);
+    }
+}
+
+// `app://bootstrap` 事件负载
+#[derive(Clone, serde::Serialize)]
+struct BootstrapPayload {
+    theme: &'static str,
+    locale: &'static str,
+    safe_mode: bool,
+}
+
+/// `frontend_ready` 返回值
+#[derive(serde::Serialize)]
+pub struct FrontendReadyInfo {
+    /// 本次补发的事件数
+    replayed: usize,
+    /// 至今因暂存已满而丢弃的事件数
+    dropped: u64,
+    /// 最近分配的 seq，之后的事件从 last_seq + 1 开始
+    last_seq: u64,
+}
+
+pub fn mark_backend_ready<R: Runtime>(app: &AppHandle<R>) {
+    if !app.state::<AppEventQueue>().backend_ready.swap(true, Ordering::SeqCst) {
+        flush(app);
+    }
+}
+
+/// 前端注册好 `app://bootstrap`、`app://deep-link` 等事件监听后调用，之后才会收到暂存的事件。
+/// 每个窗口调用时都会向该窗口发送 `app://bootstrap`；页面重新加载后需要再次调用
+#[tauri::command]
+pub async fn frontend_ready(app: AppHandle, window: tauri::WebviewWindow) -> FrontendReadyInfo {
+    let bootstrap = BootstrapPayload {
+        theme: crate::theme::current(&app),
+        locale: crate::i18n::current(),
+        safe_mode: crate::safe_mode::is_active(),
+    };
+    let _ = window.emit("app://bootstrap", bootstrap);
+    let queue = app.state::<AppEventQueue>();
+    let replayed = if queue.frontend_ready.swap(true, Ordering::SeqCst) { 0 } else { flush(&app) };
+    FrontendReadyInfo {
+        replayed,
+        dropped: queue.dropped.load(Ordering::SeqCst),
+        last_seq: queue.seq.load(Ordering::SeqCst),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tauri::test::{mock_app, MockRuntime};
+    use tauri::Listener;
+
+    fn app() -> tauri::App<MockRuntime> {
+        let app = mock_app();
+        app.manage(AppEventQueue::default());
+        app
+    }
+
+    // 记录某个事件的全部负载；any 为 false 时与托盘等一样监听 EventTarget::App
+    fn record(app: &tauri::App<MockRuntime>, event: &str, any: bool) -> Arc<Mutex<Vec<serde_json::Value>>> {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let handler = move |e: tauri::Event| sink.lock().unwrap().push(serde_json::from_str(e.payload()).unwrap());
+        if any {
+            app.listen_any(event, handler);
+        } else {
+            app.listen(event, handler);
+        }
+        received
+    }
+
+    fn seqs(received: &Mutex<Vec<serde_json::Value>>) -> Vec<u64> {
+        received.lock().unwrap().iter().map(|payload| payload["seq"].as_u64().unwrap()).collect()
+    }
+
+    fn set_frontend_ready(app: &AppHandle<MockRuntime>) -> usize {
+        app.state::<AppEventQueue>().frontend_ready.store(true, Ordering::SeqCst);
+        flush(app)
+    }
+
+    #[test]
+    fn pre_ready_events_are_replayed_in_seq_order() {
+        let app = app();
+        let handle = app.handle();
+        let links = record(&app, "app://deep-link", false);
+        let notices = record(&app, "app://notice", true);
+        emit_when_ready(handle, "app://deep-link", serde_json::json!({ "url": "duncrew://a" }));
+        emit_to_frontend(handle, "app://notice", serde_json::json!({ "n": 1 }));
+        emit_when_ready(handle, "app://deep-link", serde_json::json!({ "url": "duncrew://b" }));
+        emit_to_frontend(handle, "app://notice", serde_json::json!({ "n": 2 }));
+        assert!(links.lock().unwrap().is_empty());
+        // 通知立即发送一次
+        assert_eq!(seqs(&notices), [2, 4]);
+
+        // 只有前端就绪：通知补发给页面，deep link 继续等后端
+        assert_eq!(set_frontend_ready(handle), 2);
+        assert_eq!(seqs(&notices), [2, 4, 2, 4]);
+        assert!(links.lock().unwrap().is_empty());
+
+        mark_backend_ready(handle);
+        assert_eq!(seqs(&links), [1, 3]);
+        assert_eq!(links.lock().unwrap()[1]["url"], "duncrew://b");
+        assert!(handle.state::<AppEventQueue>().buffer.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn replayed_notifications_skip_rust_listeners() {
+        let app = app();
+        let handle = app.handle();
+        let tray = record(&app, "backend://ready", false);
+        emit_to_frontend(handle, "backend://ready", serde_json::json!({ "pid": 7 }));
+        assert_eq!(set_frontend_ready(handle), 1);
+        assert_eq!(*tray.lock().unwrap(), [serde_json::json!({ "pid": 7, "seq": 1 })]);
+    }
+
+    #[test]
+    fn full_queue_drops_the_oldest() {
+        let app = app();
+        let handle = app.handle();
+        let links = record(&app, "app://deep-link", false);
+        for i in 0..MAX_BUFFERED + 5 {
+            emit_when_ready(handle, "app://deep-link", serde_json::json!({ "index": i }));
+        }
+        let queue = handle.state::<AppEventQueue>();
+        assert_eq!(queue.dropped.load(Ordering::SeqCst), 5);
+        assert_eq!(queue.buffer.lock().unwrap().len(), MAX_BUFFERED);
+
+        set_frontend_ready(handle);
+        mark_backend_ready(handle);
+        assert_eq!(seqs(&links), (6..=MAX_BUFFERED as u64 + 5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn events_after_ready_bypass_the_queue() {
+        let app = app();
+        let handle = app.handle();
+        let links = record(&app, "app://deep-link", false);
+        let notices = record(&app, "app://notice", false);
+        set_frontend_ready(handle);
+        mark_backend_ready(handle);
+
+        emit_when_ready(handle, "app://deep-link", serde_json::json!({ "url": "duncrew://a" }));
+        emit_to_frontend(handle, "app://notice", serde_json::json!({}));
+        assert_eq!(seqs(&links), [1]);
+        assert_eq!(seqs(&notices), [2]);
+        assert!(handle.state::<AppEventQueue>().buffer.lock().unwrap().is_empty());
+        assert_eq!(flush(handle), 0);
+    }
+
+    #[test]
+    fn reloaded_page_buffers_again() {
+        let app = app();
+        let handle = app.handle();
+        let notices = record(&app, "app://notice", true);
+        set_frontend_ready(handle);
+        frontend_unloaded(handle);
+        emit_to_frontend(handle, "app://notice", serde_json::json!({}));
+        assert_eq!(set_frontend_ready(handle), 1);
+        assert_eq!(seqs(&notices), [1, 1]);
+    }
+
+    #[test]
+    fn non_object_payloads_are_sent_unchanged() {
+        let queue = AppEventQueue::default();
+        assert_eq!(queue.sequenced("duncrew://a"), (1, serde_json::json!("duncrew://a")));
+        assert_eq!(queue.sequenced(serde_json::json!({})).1["seq"], 2);
+    }
+}
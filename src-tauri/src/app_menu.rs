@@ -0,0 +1,314 @@
+// 应用菜单栏：应用（仅 macOS）/ 文件 / 编辑 / 视图 / 后端 / 帮助。各菜单项调用已有命令的实现，不另写一套逻辑。
+// macOS 上设为全局菜单，其他平台只挂在主窗口上（启动画面、辅助窗口不显示菜单栏）。
+// “重启后端”“检查更新”的句柄保存在 AppMenu 中，随 `backend://lifecycle` 等事件更新可用状态；
+// 切换界面语言后由 refresh 整体重建。无界面模式不创建菜单。
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Listener, Manager, WebviewWindow, Wry};
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind};
+
+use crate::alerts::{self, Alert, AlertCategory};
+use crate::backend::{Lifecycle, ServerState};
+use crate::i18n::{t, tf};
+
+// 与托盘菜单的 ID 区分，全局菜单事件也会收到托盘菜单的点击
+const NEW_WINDOW: &str = "menu:new_window";
+const OPEN_DATA_FOLDER: &str = "menu:open_data_folder";
+const RESTART_BACKEND: &str = "menu:restart_backend";
+const SHOW_LOGS: &str = "menu:show_logs";
+const ZOOM_IN: &str = "menu:zoom_in";
+const ZOOM_OUT: &str = "menu:zoom_out";
+const ZOOM_RESET: &str = "menu:zoom_reset";
+const CHECK_UPDATES: &str = "menu:check_updates";
+const TOGGLE_DEVTOOLS: &str = "menu:toggle_devtools";
+const WEBSITE: &str = "menu:website";
+
+const WEBSITE_URL: &str = "https://duncrew.com";
+
+// 会改变菜单项可用状态的事件
+const STATE_EVENTS: [&str; 2] = ["backend://state", "backend://restart-failed"];
+
+static NEXT_WINDOW: AtomicU32 = AtomicU32::new(1);
+
+// 需要随状态启用 / 禁用的菜单项
+struct MenuHandles {
+    restart_backend: MenuItem<Wry>,
+    check_updates: MenuItem<Wry>,
+}
+
+#[derive(Default)]
+pub struct AppMenu {
+    handles: Mutex<Option<MenuHandles>>,
+    checking_updates: AtomicBool,
+}
+
+fn item(app: &AppHandle, id: &str, key: &str, accelerator: Option<&str>) -> tauri::Result<MenuItem<Wry>> {
+    MenuItem::with_id(app, id, t(key), true, accelerator)
+}
+
+fn build(app: &AppHandle) -> tauri::Result<(Menu<Wry>, MenuHandles)> {
+    let restart_backend = item(app, RESTART_BACKEND, "menu.restart_backend", None)?;
+    let check_updates = item(app, CHECK_UPDATES, "menu.check_updates", None)?;
+
+    let menu = Menu::new(app)?;
+    #[cfg(target_os = "macos")]
+    menu.append(&Submenu::with_items(
+        app,
+        "DunCrew",
+        true,
+        &[
+            &PredefinedMenuItem::about(app, None, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::services(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::hide(app, None)?,
+            &PredefinedMenuItem::hide_others(app, None)?,
+            &PredefinedMenuItem::show_all(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::quit(app, None)?,
+        ],
+    )?)?;
+
+    let file = Submenu::with_items(
+        app,
+        t("menu.file"),
+        true,
+        &[
+            &item(app, NEW_WINDOW, "menu.new_window", Some("CmdOrCtrl+Shift+N"))?,
+            &item(app, OPEN_DATA_FOLDER, "menu.open_data_folder", None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::close_window(app, None)?,
+        ],
+    )?;
+    // 其他平台没有应用菜单，退出放在文件菜单末尾
+    #[cfg(not(target_os = "macos"))]
+    {
+        file.append(&PredefinedMenuItem::separator(app)?)?;
+        file.append(&PredefinedMenuItem::quit(app, None)?)?;
+    }
+    menu.append(&file)?;
+
+    // macOS 上复制粘贴等快捷键依赖编辑菜单中的对应项
+    menu.append(&Submenu::with_items(
+        app,
+        t("menu.edit"),
+        true,
+        &[
+            &PredefinedMenuItem::undo(app, None)?,
+            &PredefinedMenuItem::redo(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::cut(app, None)?,
+            &PredefinedMenuItem::copy(app, None)?,
+            &PredefinedMenuItem::paste(app, None)?,
+            &PredefinedMenuItem::select_all(app, None)?,
+        ],
+    )?)?;
+
+    let view = Submenu::with_items(
+        app,
+        t("menu.view"),
+        true,
+        &[
+            &item(app, ZOOM_IN, "menu.zoom_in", Some("CmdOrCtrl+="))?,
+            &item(app, ZOOM_OUT, "menu.zoom_out", Some("CmdOrCtrl+-"))?,
+            &item(app, ZOOM_RESET, "menu.zoom_reset", Some("CmdOrCtrl+0"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::fullscreen(app, None)?,
+        ],
+    )?;
+    // 发布版本只有 enable_devtools 或 --debug 时显示，快捷键随菜单项注册
+    if crate::devtools::allowed(app) {
+        view.append(&item(app, TOGGLE_DEVTOOLS, "menu.toggle_devtools", Some("CmdOrCtrl+Shift+I"))?)?;
+    }
+    menu.append(&view)?;
+
+    menu.append(&Submenu::with_items(
+        app,
+        t("menu.backend"),
+        true,
+        &[
+            &restart_backend,
+            &item(app, SHOW_LOGS, "menu.show_logs", None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &check_updates,
+        ],
+    )?)?;
+
+    menu.append(&Submenu::with_items(app, t("menu.help"), true, &[&item(app, WEBSITE, "menu.website", None)?])?)?;
+
+    Ok((menu, MenuHandles { restart_backend, check_updates }))
+}
+
+// 设置菜单并保存句柄
+fn install(app: &AppHandle) -> tauri::Result<()> {
+    let (menu, handles) = build(app)?;
+    #[cfg(target_os = "macos")]
+    app.set_menu(menu)?;
+    #[cfg(not(target_os = "macos"))]
+    if let Some(window) = app.get_webview_window("main") {
+        window.set_menu(menu)?;
+    }
+    *app.state::<AppMenu>().handles.lock().unwrap() = Some(handles);
+    update_state(app);
+    Ok(())
+}
+
+/// 按后端状态更新菜单项：启动、停止或重启过程中以及连接外部后端时不能重启后端，检查更新期间不能再次检查
+fn update_state(app: &AppHandle) {
+    let (Some(menu), Some(state)) = (app.try_state::<AppMenu>(), app.try_state::<ServerState>()) else {
+        return;
+    };
+    let external = state.process.lock().unwrap().external_url.is_some();
+    let can_restart = !external && !matches!(state.backend.lifecycle(), Lifecycle::Starting | Lifecycle::Stopping);
+    let checking = menu.checking_updates.load(Ordering::SeqCst);
+    let handles = menu.handles.lock().unwrap();
+    if let Some(handles) = handles.as_ref() {
+        let _ = handles.restart_backend.set_enabled(can_restart);
+        let _ = handles.check_updates.set_enabled(!checking);
+    }
+}
+
+/// 创建菜单栏并订阅后端事件
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    app.manage(AppMenu::default());
+    install(app)?;
+    app.on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+    for event in STATE_EVENTS {
+        let handle = app.clone();
+        app.listen(event, move |_| update_state(&handle));
+    }
+    Ok(())
+}
+
+/// 按当前语言重建菜单栏
+pub fn refresh(app: &AppHandle) {
+    if app.try_state::<AppMenu>().is_none() {
+        return;
+    }
+    if let Err(e) = install(app) {
+        app_error!("Failed to rebuild app menu: {}", e);
+    }
+}
+
+// 菜单命令作用的窗口：当前聚焦的窗口，没有时为主窗口
+fn focused_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let focused = app.webview_windows().into_values().find(|window| window.is_focused().unwrap_or(false));
+    focused.or_else(|| app.get_webview_window("main"))
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    let app = app.clone();
+    match id {
+        NEW_WINDOW => {
+            let label = format!("window-{}", NEXT_WINDOW.fetch_add(1, Ordering::SeqCst));
+            if let Err(e) = crate::secondary_windows::open_secondary_window(app, label, "/".to_string(), None) {
+                app_error!("{}", e);
+            }
+        }
+        OPEN_DATA_FOLDER => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::folders::open_data_dir(app).await {
+                    app_error!("{}", e);
+                }
+            });
+        }
+        RESTART_BACKEND => {
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<ServerState>();
+                if let Err(e) = crate::backend::restart_backend_exclusive(&app, &state).await {
+                    app_error!("Failed to restart backend from menu: {}", e);
+                }
+            });
+        }
+        SHOW_LOGS => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::folders::open_logs_dir(app).await {
+                    app_error!("{}", e);
+                }
+            });
+        }
+        ZOOM_IN | ZOOM_OUT => {
+            if let Some(window) = focused_window(&app) {
+                let delta = if id == ZOOM_IN { crate::zoom::ZOOM_STEP } else { -crate::zoom::ZOOM_STEP };
+                crate::zoom::zoom_by(&app, &window, delta);
+            }
+        }
+        ZOOM_RESET => {
+            if let Some(window) = focused_window(&app) {
+                if let Err(e) = crate::zoom::reset_zoom(app.clone(), window) {
+                    app_error!("{}", e);
+                }
+            }
+        }
+        CHECK_UPDATES => {
+            tauri::async_runtime::spawn(check_for_updates(app));
+        }
+        TOGGLE_DEVTOOLS => {
+            if let Some(window) = focused_window(&app) {
+                if let Err(e) = crate::devtools::toggle(&app, &window) {
+                    app_error!("{}", e);
+                }
+            }
+        }
+        WEBSITE => {
+            if let Err(e) = crate::open_external::open_external(app, WEBSITE_URL.to_string()) {
+                app_error!("Failed to open website: {:?}", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn show_message(app: &AppHandle, message: String, kind: MessageDialogKind) {
+    alerts::show(app, Alert::new(AlertCategory::Update, message).kind(kind));
+}
+
+// 检查后端更新并以对话框显示结果，有新版本时询问是否安装
+async fn check_for_updates(app: AppHandle) {
+    let menu = app.state::<AppMenu>();
+    if menu.checking_updates.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    update_state(&app);
+    let result = crate::backend_update::check_backend_update(app.clone()).await;
+    menu.checking_updates.store(false, Ordering::SeqCst);
+    update_state(&app);
+
+    let info = match result {
+        Ok(info) => info,
+        Err(e) => {
+            show_message(&app, tf("dialog.update.check_failed", &[("error", &e)]), MessageDialogKind::Error);
+            return;
+        }
+    };
+    if !info.available {
+        show_message(&app, tf("dialog.update.up_to_date", &[("version", &info.latest_version)]), MessageDialogKind::Info);
+        return;
+    }
+    let current = info.current_version.unwrap_or_else(|| t("common.unknown"));
+    let alert = Alert::new(
+        AlertCategory::Update,
+        tf("dialog.update.available", &[("version", &info.latest_version), ("current", &current)]),
+    )
+    .buttons(MessageDialogButtons::OkCancelCustom(t("button.install"), t("button.later")));
+    alerts::ask(&app, alert, {
+            let app = app.clone();
+            move |install| {
+                if !install {
+                    return;
+                }
+                tauri::async_runtime::spawn(async move {
+                    match crate::backend_update::apply_backend_update(app.clone()).await {
+                        Ok(version) => {
+                            show_message(&app, tf("dialog.update.installed", &[("version", &version)]), MessageDialogKind::Info)
+                        }
+                        Err(e) => {
+                            show_message(&app, tf("dialog.update.install_failed", &[("error", &e)]), MessageDialogKind::Error)
+                        }
+                    }
+                });
+            }
+        });
+}
@@ -0,0 +1,216 @@
+// 应用自更新（tauri-plugin-updater）：按 config.json 的 app_update 检查并安装新版本的完整应用。
+// endpoint 中的 {{channel}} 替换为 stable / beta，{{target}}、{{arch}}、{{current_version}} 由插件替换；
+// stable 通道还会忽略预发布版本（如 1.2.0-beta.1）。更新包必须通过 public_key（minisign）签名校验。
+// 安装前先优雅停止后端，再由插件替换文件并重启应用；Windows 上安装程序接管后应用直接退出。
+// 未配置 endpoint 时更新不可用；启动时的检查只发送系统通知和 `update://available`，不弹对话框。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use semver::Version;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::config::AppUpdateConfig;
+use crate::i18n;
+
+pub const CHANNELS: [&str; 2] = ["stable", "beta"];
+// 启动后等一会再检查，不与后端启动争抢网络和磁盘
+const STARTUP_CHECK_DELAY: Duration = Duration::from_secs(15);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, serde::Serialize)]
+pub struct AppUpdateInfo {
+    pub current_version: String,
+    pub version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+    pub channel: String,
+}
+
+// `update://progress` 事件负载；stage 为 "downloading" / "installing"
+#[derive(Clone, serde::Serialize)]
+struct UpdateProgress {
+    stage: &'static str,
+    bytes_downloaded: u64,
+    total_bytes: Option<u64>,
+}
+
+fn emit_progress(app: &AppHandle, stage: &'static str, bytes_downloaded: u64, total_bytes: Option<u64>) {
+    let _ = app.emit("update://progress", UpdateProgress { stage, bytes_downloaded, total_bytes });
+}
+
+// 签名相关的错误换成用户看得懂的说明，其余保留插件的原始信息
+fn describe(error: tauri_plugin_updater::Error) -> String {
+    use tauri_plugin_updater::Error;
+    match error {
+        Error::Minisign(_)
+        | Error::Base64(_)
+        | Error::SignatureUtf8(_)
+        | Error::SignedVersionMismatch { .. }
+        | Error::MissingSignedVersion => i18n::tf("update.bad_signature", &[("error", &error)]),
+        error => error.to_string(),
+    }
+}
+
+// 替换 {{channel}} 后的更新地址，其余占位符留给插件
+fn endpoint(config: &AppUpdateConfig) -> Result<reqwest::Url, String> {
+    if config.endpoint.trim().is_empty() {
+        return Err("App updates are not configured (app_update.endpoint is empty)".to_string());
+    }
+    let endpoint = config.endpoint.trim().replace("{{channel}}", &config.channel);
+    reqwest::Url::parse(&endpoint).map_err(|e| format!("Invalid app_update.endpoint: {}", e))
+}
+
+// 只提供更高的版本；预发布版本只在 beta 通道提供
+fn offers(current: &Version, release: &Version, channel: &str) -> bool {
+    release > current && (channel == "beta" || release.pre.is_empty())
+}
+
+async fn check(app: &AppHandle) -> Result<Option<(Update, String)>, String> {
+    let config = crate::app_config(app).app_update;
+    let endpoint = endpoint(&config)?;
+    let channel = config.channel.clone();
+    let updater = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(describe)?
+        .pubkey(config.public_key.trim())
+        .timeout(CHECK_TIMEOUT)
+        .version_comparator(move |current, release| offers(&current, &release.version, &channel))
+        .build()
+        .map_err(describe)?;
+    let update = updater.check().await.map_err(describe)?;
+    Ok(update.map(|update| (update, config.channel)))
+}
+
+fn info(update: &Update, channel: String) -> AppUpdateInfo {
+    AppUpdateInfo {
+        current_version: update.current_version.clone(),
+        version: update.version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+        channel,
+    }
+}
+
+/// 启动时检查一次（app_update.check_on_startup），有新版本时发送通知与 `update://available`
+pub fn check_on_startup(app: &AppHandle) {
+    let config = crate::app_config(app).app_update;
+    if !config.check_on_startup || config.endpoint.trim().is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_CHECK_DELAY).await;
+        match check(&app).await {
+            Ok(Some((update, channel))) => {
+                let info = info(&update, channel);
+                app_log!("App update {} is available (installed: {})", info.version, info.current_version);
+                let _ = app.emit("update://available", info.clone());
+                let body = i18n::tf("notify.app_update", &[("version", &info.version)]);
+                crate::alerts::notify(&app, crate::alerts::AlertCategory::Update, &body);
+            }
+            Ok(None) => app_log!("App is up to date"),
+            Err(e) => app_log!("Startup update check failed: {}", e),
+        }
+    });
+}
+
+/// 检查应用更新，没有新版本时返回 null；未配置或签名公钥无效时返回错误
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<AppUpdateInfo>, String> {
+    Ok(check(&app).await?.map(|(update, channel)| info(&update, channel)))
+}
+
+/// 下载、校验并安装更新，过程中发送 `update://progress`。
+/// 签名校验在停止后端之前完成；替换文件前停止后端，安装成功后重启应用，失败时恢复后端并返回错误
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    INSTALLING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .map_err(|_| "An app update is already being installed".to_string())?;
+    let result = download_and_install(&app).await;
+    INSTALLING.store(false, Ordering::SeqCst);
+    if let Err(e) = &result {
+        app_error!("App update failed: {}", e);
+    }
+    result?;
+    app_log!("App update installed, restarting");
+    app.request_restart();
+    Ok(())
+}
+
+async fn download_and_install(app: &AppHandle) -> Result<(), String> {
+    let (update, _) = check(app).await?.ok_or_else(|| "The app is already up to date".to_string())?;
+    app_log!("Downloading app update {}", update.version);
+    let mut bytes_downloaded = 0u64;
+    let bytes = update
+        .download(
+            |chunk, total| {
+                bytes_downloaded += chunk as u64;
+                emit_progress(app, "downloading", bytes_downloaded, total);
+            },
+            || {},
+        )
+        .await
+        .map_err(describe)?;
+
+    emit_progress(app, "installing", bytes.len() as u64, Some(bytes.len() as u64));
+    app_log!("Installing app update {}", update.version);
+    crate::backend::stop_for_app_update(app, move || update.install(bytes).map_err(describe))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(channel: &str, endpoint: &str) -> AppUpdateConfig {
+        AppUpdateConfig {
+            channel: channel.to_string(),
+            endpoint: endpoint.to_string(),
+            public_key: "key".to_string(),
+            ..AppUpdateConfig::default()
+        }
+    }
+
+    fn offered(current: &str, release: &str, channel: &str) -> bool {
+        offers(&Version::parse(current).unwrap(), &Version::parse(release).unwrap(), channel)
+    }
+
+    #[test]
+    fn endpoint_substitutes_only_the_channel() {
+        let url = endpoint(&config("beta", " https://updates.example.com/{{channel}}/{{target}}/{{arch}}/latest.json ")).unwrap();
+        // 其余占位符由插件替换（URL 中花括号会被编码，插件按编码后的形式替换）
+        assert!(url.as_str().starts_with("https://updates.example.com/beta/"), "{}", url);
+        assert!(!url.as_str().contains("channel"));
+        assert!(url.as_str().contains("%7B%7Btarget%7D%7D/%7B%7Barch%7D%7D"), "{}", url);
+    }
+
+    #[test]
+    fn endpoint_must_be_configured_and_valid() {
+        assert!(endpoint(&config("stable", "")).unwrap_err().contains("not configured"));
+        assert!(endpoint(&config("stable", "   ")).unwrap_err().contains("not configured"));
+        assert!(endpoint(&config("stable", "not a url")).unwrap_err().contains("Invalid app_update.endpoint"));
+    }
+
+    #[test]
+    fn stable_channel_skips_prereleases() {
+        assert!(offered("1.2.0", "1.3.0", "stable"));
+        assert!(!offered("1.2.0", "1.3.0-beta.1", "stable"));
+        assert!(!offered("1.2.0", "1.2.0", "stable"));
+        assert!(!offered("1.2.0", "1.1.9", "stable"));
+    }
+
+    #[test]
+    fn beta_channel_offers_newer_prereleases() {
+        assert!(offered("1.2.0", "1.3.0-beta.1", "beta"));
+        assert!(offered("1.3.0-beta.1", "1.3.0-beta.2", "beta"));
+        assert!(offered("1.3.0-beta.2", "1.3.0", "beta"));
+        // 1.2.0 的预发布版本早于 1.2.0 本身
+        assert!(!offered("1.2.0", "1.2.0-beta.3", "beta"));
+    }
+}
@@ -0,0 +1,304 @@
+// 解压归档：后端收到的 zip / tar.gz 由 Rust 侧解压，避免冻结的 Sidecar 中 Python 处理 Windows 路径不稳定。
+// 归档必须位于数据目录内或经 pick_files 选中，目标只能是数据目录下尚不存在（或为空）的子目录。
+// 绝对路径、`..`、盘符或含冒号的条目直接拒绝；符号链接、硬链接等特殊条目跳过。
+// 按实际写入的字节数与文件数限制解压总量（不信任归档头中声明的大小），超出即中止并删除已解压的内容。
+// 解压期间发送 `archive://progress`，完成后返回解压出的文件清单，便于通知后端具体到了哪些文件。
+
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+const MAX_TOTAL_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+const MAX_FILES: usize = 100_000;
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+// 每写入这么多字节发送一次进度
+const PROGRESS_STEP: u64 = 8 * 1024 * 1024;
+
+#[derive(Clone, Copy)]
+enum Format {
+    Zip,
+    TarGz,
+}
+
+/// 解压出的一个文件
+#[derive(serde::Serialize)]
+pub struct ExtractedFile {
+    /// 相对于目标目录，以 / 分隔
+    pub path: String,
+    pub size: u64,
+}
+
+/// `extract_archive` 返回值
+#[derive(serde::Serialize)]
+pub struct ExtractManifest {
+    pub dest: PathBuf,
+    pub files: Vec<ExtractedFile>,
+    pub total_bytes: u64,
+    /// 跳过的符号链接等特殊条目
+    pub skipped: Vec<String>,
+}
+
+// `archive://progress` 事件负载；tar.gz 无法预知解压后大小，total_bytes 为 0
+#[derive(Clone, serde::Serialize)]
+struct ExtractProgress {
+    archive: PathBuf,
+    bytes_written: u64,
+    total_bytes: u64,
+    files: usize,
+}
+
+struct Extractor<'a> {
+    app: &'a AppHandle,
+    archive: &'a Path,
+    dest: &'a Path,
+    total_bytes: u64,
+    buffer: Vec<u8>,
+    next_report: u64,
+    manifest: ExtractManifest,
+}
+
+fn detect_format(archive: &Path) -> Result<Format, String> {
+    let name = archive.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        return Ok(Format::Zip);
+    }
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        return Ok(Format::TarGz);
+    }
+    // 扩展名不明确时看文件头
+    let mut magic = [0u8; 4];
+    let mut file = std::fs::File::open(archive).map_err(|e| format!("Failed to open {:?}: {}", archive, e))?;
+    let n = file.read(&mut magic).map_err(|e| format!("Failed to read {:?}: {}", archive, e))?;
+    match &magic[..n] {
+        [b'P', b'K', 3, 4] => Ok(Format::Zip),
+        [0x1f, 0x8b, ..] => Ok(Format::TarGz),
+        _ => Err(format!("{:?} is not a zip or tar.gz archive", archive)),
+    }
+}
+
+// 条目名转为相对路径；Windows 工具生成的归档可能用反斜杠分隔
+fn entry_path(name: &str) -> Result<Option<PathBuf>, String> {
+    let normalized = name.replace('\\', "/");
+    let mut path = PathBuf::new();
+    for component in Path::new(&normalized).components() {
+        match component {
+            Component::Normal(part) if !part.to_string_lossy().contains(':') => path.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("Archive entry \"{}\" points outside the target folder", name)),
+        }
+    }
+    // 只有 ./ 之类的条目
+    Ok((!path.as_os_str().is_empty()).then_some(path))
+}
+
+// 目标目录：数据目录下的相对路径，不能已有内容；返回规范化后的路径
+fn dest_dir(data_dir: &Path, dest_subdir: &str) -> Result<PathBuf, String> {
+    let Some(relative) = entry_path(dest_subdir).map_err(|_| format!("Invalid destination \"{}\"", dest_subdir))? else {
+        return Err("Destination must be a folder inside the data directory".to_string());
+    };
+    let dest = data_dir.join(relative);
+    if std::fs::read_dir(&dest).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("Destination {:?} already exists and is not empty", dest));
+    }
+    // 中间目录可能是指向别处的符号链接：先检查已有的最深一级，确认在数据目录内再创建，
+    // 避免在数据目录外留下空目录
+    let existing = dest.ancestors().find(|ancestor| ancestor.exists()).unwrap_or(data_dir);
+    let data_dir = crate::folders::canonical(data_dir);
+    if !crate::folders::canonical(existing).starts_with(&data_dir) {
+        return Err(format!("Destination \"{}\" points outside the data directory", dest_subdir));
+    }
+    std::fs::create_dir_all(&dest).map_err(|e| format!("Failed to create {:?}: {}", dest, e))?;
+    let dest = crate::folders::canonical(&dest);
+    if !dest.starts_with(&data_dir) {
+        return Err(format!("Destination \"{}\" points outside the data directory", dest_subdir));
+    }
+    Ok(dest)
+}
+
+// 归档须在数据目录内（后端收到的上传），或是用户在文件对话框中选中的
+fn source_path(app: &AppHandle, data_dir: &Path, archive_path: &str) -> Result<PathBuf, String> {
+    let path = crate::folders::canonical(Path::new(archive_path));
+    if path.starts_with(crate::folders::canonical(data_dir)) && path.is_file() {
+        return Ok(path);
+    }
+    crate::file_picker::ensure_picked(app, archive_path)
+}
+
+impl Extractor<'_> {
+    fn report(&mut self) {
+        let progress = ExtractProgress {
+            archive: self.archive.to_path_buf(),
+            bytes_written: self.manifest.total_bytes,
+            total_bytes: self.total_bytes,
+            files: self.manifest.files.len(),
+        };
+        let _ = self.app.emit("archive://progress", progress);
+    }
+
+    fn write_file(&mut self, relative: &Path, input: &mut impl Read) -> Result<(), String> {
+        if self.manifest.files.len() >= MAX_FILES {
+            return Err(format!("Archive contains more than {} files", MAX_FILES));
+        }
+        let target = self.dest.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut output = std::fs::File::create(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+        let mut size = 0u64;
+        loop {
+            let n = input.read(&mut self.buffer).map_err(|e| format!("Failed to extract {:?}: {}", relative, e))?;
+            if n == 0 {
+                break;
+            }
+            size += n as u64;
+            self.manifest.total_bytes += n as u64;
+            if self.manifest.total_bytes > MAX_TOTAL_BYTES {
+                return Err(format!("Archive expands to more than {} GB", MAX_TOTAL_BYTES / 1024 / 1024 / 1024));
+            }
+            output.write_all(&self.buffer[..n]).map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+            if self.manifest.total_bytes >= self.next_report {
+                self.next_report = self.manifest.total_bytes + PROGRESS_STEP;
+                self.report();
+            }
+        }
+        let path = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+        self.manifest.files.push(ExtractedFile { path, size });
+        Ok(())
+    }
+
+    fn create_dir(&self, relative: &Path) -> Result<(), String> {
+        let target = self.dest.join(relative);
+        std::fs::create_dir_all(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))
+    }
+
+    fn extract_zip(&mut self) -> Result<(), String> {
+        let file = std::fs::File::open(self.archive).map_err(|e| format!("Failed to open {:?}: {}", self.archive, e))?;
+        let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("{:?} is not a valid zip archive: {}", self.archive, e))?;
+        if zip.len() > MAX_FILES {
+            return Err(format!("Archive contains more than {} files", MAX_FILES));
+        }
+        // 先检查全部条目名，有问题时不写入任何文件
+        let mut declared = 0u64;
+        for i in 0..zip.len() {
+            let entry = zip.by_index_raw(i).map_err(|e| e.to_string())?;
+            let name = entry.name().map_err(|e| format!("Invalid entry name in archive: {}", e))?;
+            entry_path(&name)?;
+            declared = declared.saturating_add(entry.size());
+        }
+        self.total_bytes = declared.min(MAX_TOTAL_BYTES);
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+            let name = entry.name().map_err(|e| e.to_string())?.to_string();
+            let Some(relative) = entry_path(&name)? else {
+                continue;
+            };
+            if entry.is_dir() {
+                self.create_dir(&relative)?;
+            } else if entry.is_file() {
+                self.write_file(&relative, &mut entry)?;
+            } else {
+                self.manifest.skipped.push(name);
+            }
+        }
+        Ok(())
+    }
+
+    fn extract_tar_gz(&mut self) -> Result<(), String> {
+        let file = std::fs::File::open(self.archive).map_err(|e| format!("Failed to open {:?}: {}", self.archive, e))?;
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(std::io::BufReader::new(file)));
+        let entries = tar.entries().map_err(|e| format!("{:?} is not a valid tar.gz archive: {}", self.archive, e))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| format!("Failed to read {:?}: {}", self.archive, e))?;
+            let name = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+            let Some(relative) = entry_path(&name)? else {
+                continue;
+            };
+            match entry.header().entry_type() {
+                tar::EntryType::Directory => self.create_dir(&relative)?,
+                tar::EntryType::Regular | tar::EntryType::Continuous => self.write_file(&relative, &mut entry)?,
+                // pax / GNU 长文件名等元数据条目由 tar 处理，其余特殊条目不解压
+                _ => self.manifest.skipped.push(name),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn extract(app: &AppHandle, archive: &Path, dest: &Path) -> Result<ExtractManifest, String> {
+    let format = detect_format(archive)?;
+    let mut extractor = Extractor {
+        app,
+        archive,
+        dest,
+        total_bytes: 0,
+        buffer: vec![0u8; COPY_BUFFER_SIZE],
+        next_report: 0,
+        manifest: ExtractManifest { dest: dest.to_path_buf(), files: Vec::new(), total_bytes: 0, skipped: Vec::new() },
+    };
+    match format {
+        Format::Zip => extractor.extract_zip()?,
+        Format::TarGz => extractor.extract_tar_gz()?,
+    }
+    extractor.report();
+    Ok(extractor.manifest)
+}
+
+/// 把 zip / tar.gz 解压到数据目录下的 `dest_subdir`（不能已有内容），返回解压出的文件清单。
+/// 失败时删除已解压的内容
+#[tauri::command]
+pub async fn extract_archive(app: AppHandle, archive_path: String, dest_subdir: String) -> Result<ExtractManifest, String> {
+    let data_dir = crate::backend_data_dir(&app)?;
+    let archive = source_path(&app, &data_dir, &archive_path)?;
+    let dest = dest_dir(&data_dir, &dest_subdir)?;
+    let spec = crate::jobs::JobSpec::new(crate::jobs::JobKind::Extract, archive_path.clone());
+    let result = crate::jobs::run(&app, spec, |_| async {
+        app_log!("Extracting {:?} into {:?}", archive, dest);
+        let handle = app.clone();
+        let target = dest.clone();
+        tauri::async_runtime::spawn_blocking(move || extract(&handle, &archive, &target))
+            .await
+            .map_err(|e| e.to_string())?
+    })
+    .await;
+    match result {
+        Ok(manifest) => {
+            app_log!("Extracted {} file(s), {} bytes", manifest.files.len(), manifest.total_bytes);
+            Ok(manifest)
+        }
+        Err(e) => {
+            app_error!("Failed to extract {}: {}", archive_path, e);
+            let _ = std::fs::remove_dir_all(&dest);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dest_dir_creates_folder_inside_data_dir() {
+        let data = tempfile::tempdir().unwrap();
+        let dest = dest_dir(data.path(), "imports/新建 文件夹").unwrap();
+        assert!(dest.is_dir());
+        assert!(dest.starts_with(crate::folders::canonical(data.path())));
+    }
+
+    #[test]
+    fn dest_dir_rejects_parent_components() {
+        let data = tempfile::tempdir().unwrap();
+        assert!(dest_dir(data.path(), "../escape").is_err());
+        assert!(dest_dir(data.path(), ".").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dest_dir_checks_symlink_before_creating() {
+        let data = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), data.path().join("link")).unwrap();
+        assert!(dest_dir(data.path(), "link/nested/dest").is_err());
+        assert!(!outside.path().join("nested").exists());
+    }
+}
@@ -0,0 +1,206 @@
+// 数据目录资源协议：`ddos-asset://localhost/<相对路径>`（Windows 上为 `http://ddos-asset.localhost/<相对路径>`）
+// 直接读取数据目录 assets/ 下的缩略图、附件，不再经本机 HTTP 往返，后端端口变化也不受影响。
+// 只提供 assets/ 内的普通文件：含 `..`、反斜杠、盘符的路径返回 403，规范化后（解析符号链接）不在 assets/ 内或不存在返回 404。
+// 支持单个 Range 请求（<video> / <audio> 拖动进度），按 ETag 返回 304；响应附带 sandbox CSP，HTML 等内容不能执行脚本。
+// 不带 Range 的请求整份返回不超过 MAX_FULL_BYTES 的文件，更大的文件只返回开头一段（206），其余由浏览器按 Range 续取。
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::AppHandle;
+
+pub const SCHEME: &str = "ddos-asset";
+/// 数据目录下可以经协议访问的目录
+pub const ASSETS_DIR_NAME: &str = "assets";
+// 一个 Range 响应最多返回这么多，后续部分由浏览器再次请求，避免把整个视频读进内存
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+// 不带 Range 时整份读入内存返回的上限，覆盖常见的图片与 PDF
+const MAX_FULL_BYTES: u64 = 32 * 1024 * 1024;
+
+// 按扩展名确定 Content-Type，未知类型按二进制下载处理
+const MIME_TYPES: [(&str, &str); 24] = [
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("svg", "image/svg+xml"),
+    ("mp4", "video/mp4"),
+    ("m4v", "video/mp4"),
+    ("webm", "video/webm"),
+    ("mov", "video/quicktime"),
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("wav", "audio/wav"),
+    ("ogg", "audio/ogg"),
+    ("flac", "audio/flac"),
+    ("pdf", "application/pdf"),
+    ("json", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("md", "text/markdown; charset=utf-8"),
+    ("csv", "text/csv; charset=utf-8"),
+    ("html", "text/html; charset=utf-8"),
+];
+
+/// 前端拼接资源 URL 的前缀，后接 assets/ 下以 / 分隔、经 URL 编码的相对路径
+pub fn url_base() -> String {
+    if cfg!(any(windows, target_os = "android")) {
+        format!("http://{}.localhost/", SCHEME)
+    } else {
+        format!("{}://localhost/", SCHEME)
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    let ext = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    MIME_TYPES
+        .iter()
+        .find(|(known, _)| *known == ext)
+        .map_or("application/octet-stream", |(_, mime)| mime)
+}
+
+fn status(code: StatusCode) -> Response<Vec<u8>> {
+    Response::builder().status(code).body(Vec::new()).unwrap()
+}
+
+// URL 路径转为 assets/ 下的相对路径；None 表示试图越出目录
+fn relative_path(uri_path: &str) -> Option<PathBuf> {
+    let decoded = percent_encoding::percent_decode_str(uri_path).decode_utf8().ok()?;
+    let mut relative = PathBuf::new();
+    for segment in decoded.split('/').filter(|segment| !segment.is_empty()) {
+        if segment.contains(['\\', ':', '\0']) {
+            return None;
+        }
+        match Path::new(segment).components().next() {
+            Some(Component::Normal(part)) => relative.push(part),
+            _ => return None,
+        }
+    }
+    Some(relative)
+}
+
+// 解析 `bytes=start-end` / `bytes=start-` / `bytes=-suffix`；多个区间只取第一个。范围无效时返回 Err
+fn parse_range(value: &str, len: u64) -> Result<(u64, u64), ()> {
+    let spec = value.trim().strip_prefix("bytes=").ok_or(())?;
+    let (start, end) = spec.split(',').next().unwrap_or_default().trim().split_once('-').ok_or(())?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().map_err(|_| ())?;
+            (len.saturating_sub(suffix), len.saturating_sub(1))
+        }
+        (start, "") => (start.parse().map_err(|_| ())?, len.saturating_sub(1)),
+        (start, end) => (start.parse().map_err(|_| ())?, end.parse::<u64>().map_err(|_| ())?.min(len.saturating_sub(1))),
+    };
+    if len == 0 || start > end || start >= len {
+        return Err(());
+    }
+    Ok((start, end.min(start + MAX_RANGE_BYTES - 1)))
+}
+
+// 响应状态与字节区间 [start, end]；Range 无效时返回 Err，由调用方返回 416
+fn response_range(range: Option<&str>, len: u64) -> Result<(StatusCode, u64, u64), ()> {
+    match range {
+        Some(range) => parse_range(range, len).map(|(start, end)| (StatusCode::PARTIAL_CONTENT, start, end)),
+        None if len > MAX_FULL_BYTES => Ok((StatusCode::PARTIAL_CONTENT, 0, MAX_RANGE_BYTES - 1)),
+        None => Ok((StatusCode::OK, 0, len.saturating_sub(1))),
+    }
+}
+
+fn read_range(path: &Path, start: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut buffer = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn serve(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    if !matches!(*request.method(), tauri::http::Method::GET | tauri::http::Method::HEAD) {
+        return status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let Some(relative) = relative_path(request.uri().path()) else {
+        return status(StatusCode::FORBIDDEN);
+    };
+    let Ok(data_dir) = crate::backend_data_dir(app) else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    let root = crate::folders::canonical(&data_dir.join(ASSETS_DIR_NAME));
+    let Ok(path) = root.join(&relative).canonicalize().map(|path| crate::folders::canonical(&path)) else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    let metadata = match std::fs::metadata(&path) {
+        Ok(metadata) if metadata.is_file() && path.starts_with(&root) => metadata,
+        _ => return status(StatusCode::NOT_FOUND),
+    };
+
+    let len = metadata.len();
+    let modified = metadata.modified().ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map_or(0, |d| d.as_millis());
+    let etag = format!("\"{:x}-{:x}\"", len, modified);
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type(&path))
+        .header(header::CACHE_CONTROL, "private, max-age=60")
+        .header(header::ETAG, &etag)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(header::CONTENT_SECURITY_POLICY, "sandbox");
+    let header_value = |name: header::HeaderName| request.headers().get(name).and_then(|v| v.to_str().ok());
+    if header_value(header::IF_NONE_MATCH).is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag)) {
+        return builder.status(StatusCode::NOT_MODIFIED).body(Vec::new()).unwrap();
+    }
+
+    let Ok((code, start, end)) = response_range(header_value(header::RANGE), len) else {
+        return builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new())
+            .unwrap();
+    };
+    let body_len = if len == 0 { 0 } else { end - start + 1 };
+    let builder = builder.status(code).header(header::CONTENT_LENGTH, body_len);
+    let builder = if code == StatusCode::PARTIAL_CONTENT {
+        builder.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+    } else {
+        builder
+    };
+    if *request.method() == tauri::http::Method::HEAD {
+        return builder.body(Vec::new()).unwrap();
+    }
+    match read_range(&path, start, body_len) {
+        Ok(body) => builder.body(body).unwrap(),
+        Err(e) => {
+            app_error!("Failed to read asset {:?}: {}", path, e);
+            status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 注册到 tauri::Builder；文件读取在阻塞线程池中进行，不占用 WebView 线程
+pub fn register(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
+    builder.register_asynchronous_uri_scheme_protocol(SCHEME, |ctx, request, responder| {
+        let app = ctx.app_handle().clone();
+        tauri::async_runtime::spawn_blocking(move || responder.respond(serve(&app, &request)));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_never_exceed_the_caps() {
+        assert_eq!(response_range(None, 0), Ok((StatusCode::OK, 0, 0)));
+        assert_eq!(response_range(None, 1000), Ok((StatusCode::OK, 0, 999)));
+        assert_eq!(response_range(None, MAX_FULL_BYTES), Ok((StatusCode::OK, 0, MAX_FULL_BYTES - 1)));
+        // 大文件不带 Range 时只给开头一段
+        let len = 2 * 1024 * 1024 * 1024;
+        assert_eq!(response_range(None, len), Ok((StatusCode::PARTIAL_CONTENT, 0, MAX_RANGE_BYTES - 1)));
+        assert_eq!(response_range(Some("bytes=0-"), len), Ok((StatusCode::PARTIAL_CONTENT, 0, MAX_RANGE_BYTES - 1)));
+        assert_eq!(response_range(Some("bytes=-100"), len), Ok((StatusCode::PARTIAL_CONTENT, len - 100, len - 1)));
+        assert_eq!(response_range(Some("bytes=5-2"), len), Err(()));
+    }
+}
@@ -0,0 +1,315 @@
+// 定时自动备份：按 config.json 的 auto_backup 每隔 interval_hours 小时备份一次数据目录，
+// 写入 target_dir（默认“文档/DunCrew Backups”）下的 duncrew-auto-<时间>.zip，只保留最近 keep_last 个自动备份。
+// 上次运行时间记录在数据目录的 auto-backup.json 中，机器休眠或应用未运行而错过的备份在下次启动后补上。
+// 后端正在启动、停止或等待崩溃重启时，以及已有备份、恢复或迁移在进行时跳过（不排队），下次检查再试。
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend::{Lifecycle, ServerState};
+use crate::jobs::{JobHandle, JobKind, JobPriority, JobSpec};
+
+const STATE_FILE_NAME: &str = "auto-backup.json";
+const ARCHIVE_PREFIX: &str = "duncrew-auto-";
+// 手动备份的默认文件名前缀，列出备份历史时一并显示
+const MANUAL_ARCHIVE_PREFIX: &str = "duncrew-backup-";
+const DEFAULT_DIR_NAME: &str = "DunCrew Backups";
+// 启动后先等后端就绪，再补上错过的备份
+const STARTUP_DELAY: Duration = Duration::from_secs(120);
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct AutoBackupState {
+    last_run_at: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct AutoCompletedPayload {
+    path: PathBuf,
+    size_bytes: u64,
+    // 按 keep_last 删除的旧备份
+    pruned: Vec<PathBuf>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct AutoFailedPayload {
+    error: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct BackupArchive {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub size_bytes: u64,
+    /// 文件修改时间（RFC 3339）
+    pub created_at: Option<String>,
+    /// 是否为自动备份
+    pub automatic: bool,
+}
+
+/// 自动备份的目标目录
+pub fn target_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(dir) = crate::app_config(app).auto_backup.target_dir {
+        return Ok(dir);
+    }
+    match dirs::document_dir() {
+        Some(dir) => Ok(dir.join(DEFAULT_DIR_NAME)),
+        // 没有文档目录时放在数据目录旁边，不能放在数据目录里（会被打包进下一次备份）
+        None => {
+            let data_dir = crate::backend_data_dir(app)?;
+            Ok(data_dir.with_file_name(DEFAULT_DIR_NAME))
+        }
+    }
+}
+
+fn state_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(STATE_FILE_NAME))
+}
+
+// 文件不存在或无法解析时视为从未运行
+fn last_run_at(path: &Path) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let state: AutoBackupState = serde_json::from_str(&content).ok()?;
+    chrono::DateTime::parse_from_rfc3339(&state.last_run_at?).ok()
+}
+
+fn record_run(path: &Path, at: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let state = AutoBackupState { last_run_at: Some(at.to_rfc3339()) };
+    let json = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn is_due(
+    last: Option<chrono::DateTime<chrono::FixedOffset>>,
+    now: chrono::DateTime<chrono::Local>,
+    interval_hours: u32,
+) -> bool {
+    match last {
+        Some(last) => now.signed_duration_since(last) >= chrono::Duration::hours(interval_hours.into()),
+        None => true,
+    }
+}
+
+// 目标目录位于数据目录之内时，备份会把之前的备份也打包进去
+fn inside_data_dir(dir: &Path, data_dir: &Path) -> bool {
+    dir.canonicalize().is_ok_and(|d| data_dir.canonicalize().is_ok_and(|data| d.starts_with(data)))
+}
+
+// 目录中的备份文件，按修改时间从新到旧排列
+fn list_archives(dir: &Path) -> Vec<BackupArchive> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut archives: Vec<(Option<std::time::SystemTime>, BackupArchive)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let automatic = file_name.starts_with(ARCHIVE_PREFIX);
+            if !file_name.ends_with(".zip") || !(automatic || file_name.starts_with(MANUAL_ARCHIVE_PREFIX)) {
+                return None;
+            }
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified = metadata.modified().ok();
+            let created_at = modified.map(|time| chrono::DateTime::<chrono::Local>::from(time).to_rfc3339());
+            let archive = BackupArchive { path: entry.path(), file_name, size_bytes: metadata.len(), created_at, automatic };
+            Some((modified, archive))
+        })
+        .collect();
+    archives.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    archives.into_iter().map(|(_, archive)| archive).collect()
+}
+
+// 删除超出 keep_last 的旧自动备份，手动备份不受影响
+fn prune(dir: &Path, keep_last: usize) -> Vec<PathBuf> {
+    list_archives(dir)
+        .into_iter()
+        .filter(|archive| archive.automatic)
+        .skip(keep_last)
+        .filter_map(|archive| match std::fs::remove_file(&archive.path) {
+            Ok(()) => Some(archive.path),
+            Err(e) => {
+                app_error!("Failed to remove old backup {:?}: {}", archive.path, e);
+                None
+            }
+        })
+        .collect()
+}
+
+// 后端正处于启动、停止或等待崩溃重启时不打断它；外部后端不受本应用管理，不影响备份
+fn backend_busy(app: &AppHandle) -> bool {
+    let state = app.state::<ServerState>();
+    let external = state.process.lock().unwrap().external_url.is_some();
+    !external && state.backend.lifecycle() != Lifecycle::Running
+}
+
+async fn run(app: &AppHandle, job: JobHandle) -> Result<(PathBuf, Vec<PathBuf>), String> {
+    let config = crate::app_config(app).auto_backup;
+    let data_dir = crate::backend_data_dir(app)?;
+    let dir = target_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    if inside_data_dir(&dir, &data_dir) {
+        return Err(format!("auto_backup.target_dir {:?} must not be inside the data directory", dir));
+    }
+    if let Some(low) = crate::storage::check_free_space(app, &dir, "backup") {
+        return Err(crate::storage::low_space_message(&low));
+    }
+    let target = dir.join(format!("{}{}.zip", ARCHIVE_PREFIX, chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    crate::backup::run_backup(app, job, data_dir, &target, "auto_backup").await?;
+    Ok((target, prune(&dir, config.keep_last as usize)))
+}
+
+// 到期且条件允许时执行一次
+async fn tick(app: &AppHandle) {
+    let config = crate::app_config(app).auto_backup;
+    let Ok(state_path) = state_path(app) else {
+        return;
+    };
+    if !config.enabled || !is_due(last_run_at(&state_path), chrono::Local::now(), config.interval_hours) {
+        return;
+    }
+    if backend_busy(app) {
+        app_log!("Backend is not running steadily, postponing automatic backup");
+        return;
+    }
+    let spec = JobSpec::new(JobKind::Backup, "automatic backup").priority(JobPriority::Low).cancellable();
+    let job = match crate::jobs::try_start(app, spec) {
+        Ok(job) => job,
+        Err(e) => {
+            app_log!("{}, postponing automatic backup", e);
+            return;
+        }
+    };
+    // 无论成败都记录，失败时不在每次检查时反复重试，等下一个周期
+    let result = run(app, job.handle()).await;
+    job.finish(&result);
+    if let Err(e) = record_run(&state_path, chrono::Local::now()) {
+        app_error!("{}", e);
+    }
+    match result {
+        Ok((path, pruned)) => {
+            let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            app_log!("Automatic backup written to {:?}, removed {} old backup(s)", path, pruned.len());
+            let _ = app.emit("backup://auto-completed", AutoCompletedPayload { path, size_bytes, pruned });
+        }
+        Err(e) => {
+            app_error!("Automatic backup failed: {}", e);
+            let _ = app.emit("backup://auto-failed", AutoFailedPayload { error: e });
+        }
+    }
+}
+
+/// 在 setup 中调用，启动定时检查
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            tick(&app).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 自动备份目录中的备份（含以默认文件名保存在该目录的手动备份），从新到旧
+#[tauri::command]
+pub async fn get_backup_history(app: AppHandle) -> Result<Vec<BackupArchive>, String> {
+    let dir = target_dir(&app)?;
+    tauri::async_runtime::spawn_blocking(move || list_archives(&dir))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    // 写入备份文件并把修改时间设为 age_hours 小时之前
+    fn archive(dir: &Path, name: &str, age_hours: u64) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, b"zip").unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age_hours * 3600);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        path
+    }
+
+    fn names(archives: &[BackupArchive]) -> Vec<&str> {
+        archives.iter().map(|archive| archive.file_name.as_str()).collect()
+    }
+
+    #[test]
+    fn history_lists_backups_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        archive(dir.path(), "duncrew-auto-20240101-000000.zip", 30);
+        archive(dir.path(), "duncrew-backup-manual.zip", 20);
+        archive(dir.path(), "duncrew-auto-20240102-000000.zip", 10);
+        // 其他文件与同名目录不算备份
+        archive(dir.path(), "notes.zip", 1);
+        archive(dir.path(), "duncrew-auto-partial.zip.tmp", 1);
+        std::fs::create_dir(dir.path().join("duncrew-auto-dir.zip")).unwrap();
+
+        let archives = list_archives(dir.path());
+        assert_eq!(
+            names(&archives),
+            ["duncrew-auto-20240102-000000.zip", "duncrew-backup-manual.zip", "duncrew-auto-20240101-000000.zip"]
+        );
+        assert_eq!(archives.iter().map(|a| a.automatic).collect::<Vec<_>>(), [true, false, true]);
+        assert!(list_archives(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn prune_keeps_the_newest_automatic_backups_and_all_manual_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let oldest = archive(dir.path(), "duncrew-auto-1.zip", 40);
+        let older = archive(dir.path(), "duncrew-auto-2.zip", 30);
+        archive(dir.path(), "duncrew-backup-manual.zip", 50);
+        archive(dir.path(), "duncrew-auto-3.zip", 20);
+        archive(dir.path(), "duncrew-auto-4.zip", 10);
+
+        let mut pruned = prune(dir.path(), 2);
+        pruned.sort();
+        assert_eq!(pruned, [oldest, older]);
+        assert_eq!(
+            names(&list_archives(dir.path())),
+            ["duncrew-auto-4.zip", "duncrew-auto-3.zip", "duncrew-backup-manual.zip"]
+        );
+        assert!(prune(dir.path(), 2).is_empty());
+    }
+
+    #[test]
+    fn due_after_the_interval_or_when_never_run() {
+        let now = chrono::Local::now();
+        let ago = |hours| Some((now - chrono::Duration::hours(hours)).fixed_offset());
+        assert!(is_due(None, now, 24));
+        assert!(is_due(ago(24), now, 24));
+        assert!(is_due(ago(100), now, 24));
+        assert!(!is_due(ago(23), now, 24));
+        // 系统时间被调回过去
+        assert!(!is_due(Some((now + chrono::Duration::hours(5)).fixed_offset()), now, 1));
+    }
+
+    #[test]
+    fn last_run_round_trips_through_the_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE_NAME);
+        assert!(last_run_at(&path).is_none());
+        let at = chrono::Local::now();
+        record_run(&path, at).unwrap();
+        assert_eq!(last_run_at(&path).unwrap().timestamp(), at.timestamp());
+
+        std::fs::write(&path, r#"{"last_run_at": "yesterday"}"#).unwrap();
+        assert!(last_run_at(&path).is_none());
+    }
+
+    #[test]
+    fn target_inside_the_data_dir_is_rejected() {
+        let data = tempfile::tempdir().unwrap();
+        let nested = data.path().join("backups");
+        std::fs::create_dir(&nested).unwrap();
+        assert!(inside_data_dir(&nested, data.path()));
+        assert!(inside_data_dir(data.path(), data.path()));
+
+        let other = tempfile::tempdir().unwrap();
+        assert!(!inside_data_dir(other.path(), data.path()));
+    }
+}
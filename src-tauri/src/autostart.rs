@@ -0,0 +1,66 @@
+// 开机自启：注册表 Run 项 / LaunchAgent / .desktop 由 tauri-plugin-autostart 维护，
+// 自启的实例带 --minimized 参数，启动后直接隐藏到托盘。
+// 以托盘方式启动时主窗口创建后保持隐藏，不打开启动画面，后端与托盘照常启动；
+// 点击托盘图标、全局快捷键或再次启动应用时才显示。本次运行中关闭主窗口也只是隐藏回托盘
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+/// 自启动实例附带的参数
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+// 自启动设置失败原因，序列化后作为命令错误返回给前端
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AutostartError {
+    // 例如注册表或 LaunchAgents 目录没有写权限
+    PermissionDenied { operation: &'static str, message: String },
+    Os { operation: &'static str, message: String },
+}
+
+impl AutostartError {
+    fn new(operation: &'static str, error: impl std::fmt::Display) -> Self {
+        let message = error.to_string();
+        let lower = message.to_ascii_lowercase();
+        if lower.contains("denied") || lower.contains("permission") {
+            AutostartError::PermissionDenied { operation, message }
+        } else {
+            AutostartError::Os { operation, message }
+        }
+    }
+}
+
+/// 本次是否以托盘方式启动：--minimized 或 config.json 的 start_minimized，且托盘可用
+/// （托盘不可用时隐藏的窗口无从找回，照常显示）
+pub fn start_minimized(app: &AppHandle) -> bool {
+    let minimized = app.state::<crate::cli::CliArgs>().minimized || crate::app_config(app).start_minimized;
+    minimized && crate::tray::is_available(app)
+}
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_autostart::init(
+        tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+        Some(vec![MINIMIZED_ARG]),
+    )
+}
+
+/// 查询系统中实际的自启动状态（而不是缓存的配置值）
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> Result<bool, AutostartError> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| AutostartError::new("query", e))
+}
+
+/// 开启或关闭开机自启，返回设置后重新查询到的系统状态
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<bool, AutostartError> {
+    let manager = app.autolaunch();
+    if enabled {
+        manager.enable().map_err(|e| AutostartError::new("enable", e))?;
+    } else {
+        manager.disable().map_err(|e| AutostartError::new("disable", e))?;
+    }
+    app_log!("Autostart {}", if enabled { "enabled" } else { "disabled" });
+    get_autostart(app)
+}
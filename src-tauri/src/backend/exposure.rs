@@ -0,0 +1,95 @@
+// 监听地址检查：后端就绪后用本机每个非回环地址连接后端端口，能连上说明后端监听了 0.0.0.0 等对外地址，
+// 局域网内的其他设备可以读取所有文档。有意配置的对外监听（无界面模式的 bind_address 为非回环地址，
+// 且设置了 allow_remote_access）不警告；其他情况发送 `security://exposed-port` 并弹窗警告（每次运行只弹一次）。
+// 检查结果记入 ProcessInfo，get_backend_status 可以查询。外部后端不检查。
+
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::MessageDialogKind;
+
+use super::ServerState;
+use crate::alerts::{self, Alert, AlertCategory};
+use crate::i18n;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 检查结果，也是 `security://exposed-port` 事件负载
+#[derive(Clone, serde::Serialize)]
+pub struct BindingCheck {
+    pub port: u16,
+    /// 启动后端时传入的监听地址
+    pub bind_address: IpAddr,
+    /// 能连上后端端口的非回环地址；为空表示只监听本机
+    pub exposed_on: Vec<IpAddr>,
+    /// 监听地址是按 allow_remote_access 有意配置的非回环地址，为 true 时不警告
+    pub allowed: bool,
+    pub checked_at: String,
+}
+
+// 本机网卡上的非回环地址；IPv6 链路本地地址需要指定网卡，跳过
+pub(super) fn local_addresses() -> Vec<IpAddr> {
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut addresses: Vec<IpAddr> = networks
+        .values()
+        .flat_map(|data| data.ip_networks().iter().map(|network| network.addr))
+        .filter(|addr| !addr.is_loopback() && !addr.is_unspecified())
+        .filter(|addr| !matches!(addr, IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80))
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+fn reachable_on(port: u16) -> Vec<IpAddr> {
+    local_addresses()
+        .into_iter()
+        .filter(|addr| TcpStream::connect_timeout(&SocketAddr::new(*addr, port), CONNECT_TIMEOUT).is_ok())
+        .collect()
+}
+
+fn warn(app: &AppHandle, check: &BindingCheck) {
+    if crate::headless::enabled(app) || WARNED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let addresses = check.exposed_on.iter().map(IpAddr::to_string).collect::<Vec<_>>().join(", ");
+    let args: [(&str, &dyn std::fmt::Display); 2] = [("addresses", &addresses), ("port", &check.port)];
+    alerts::show(app, Alert::new(AlertCategory::Security, i18n::tf("dialog.exposed_port", &args)).kind(MessageDialogKind::Warning));
+}
+
+/// 后端就绪后调用
+pub async fn check(app: &AppHandle) {
+    let (port, bind_address, external) = {
+        let state = app.state::<ServerState>();
+        let process = state.process.lock().unwrap();
+        (process.port, process.bind_address, process.external_url.is_some())
+    };
+    if external {
+        return;
+    }
+    let Ok(exposed_on) = tauri::async_runtime::spawn_blocking(move || reachable_on(port)).await else {
+        return;
+    };
+    let check = BindingCheck {
+        port,
+        bind_address,
+        exposed_on,
+        allowed: !bind_address.is_loopback() && crate::app_config(app).allow_remote_access,
+        checked_at: chrono::Local::now().to_rfc3339(),
+    };
+    app.state::<ServerState>().process.lock().unwrap().binding_check = Some(check.clone());
+    if check.exposed_on.is_empty() {
+        app_log!("Backend port {} is only reachable from this machine", port);
+        return;
+    }
+    if check.allowed {
+        app_log!("Backend port {} is reachable on {:?} (bind_address {} is allowed)", port, check.exposed_on, bind_address);
+        return;
+    }
+    app_error!("Backend port {} is reachable from the network on {:?}", port, check.exposed_on);
+    let _ = app.emit("security://exposed-port", check.clone());
+    warn(app, &check);
+}
@@ -0,0 +1,26 @@
+// 后端进程句柄：BackendManager 只通过 BackendHandle 识别与结束进程，
+// 输出和退出统一经 BackendEvent 事件流送达，不关心背后是真实 Sidecar 还是模拟后端
+
+use tokio::sync::mpsc::Receiver;
+
+pub enum BackendEvent {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    Error(String),
+    // 进程退出，之后事件流关闭；signal 为 Unix 上结束进程的信号
+    Terminated { code: Option<i32>, signal: Option<i32> },
+}
+
+pub trait BackendHandle: Send {
+    fn pid(&self) -> u32;
+    /// 写入进程的标准输入；管道已关闭（进程已退出）时返回错误
+    fn write(&mut self, data: &[u8]) -> Result<(), String>;
+    /// 强制结束进程，事件流随后仍会收到 Terminated
+    fn kill(self: Box<Self>) -> Result<(), String>;
+}
+
+/// 新启动的后端：句柄与它的事件流
+pub struct Spawned {
+    pub handle: Box<dyn BackendHandle>,
+    pub events: Receiver<BackendEvent>,
+}
@@ -0,0 +1,165 @@
+// BackendManager 与应用其余部分之间的接口：拉起进程、等待就绪、发送状态事件、请求优雅退出等。
+// 应用中由 AppHandle 实现（ServerState、shell 插件、健康检查、前端事件）；
+// 集成测试（tests/）实现自己的 Host，用模拟后端驱动生命周期状态机，不需要窗口与事件循环。
+
+use std::future::Future;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::Instrument;
+
+use super::startup::{self, StartSample};
+use super::state::StateTransition;
+use super::{BackendError, BackendHandle, Lifecycle, PortPolicy, ServerState};
+use crate::backend_client::BackendClient;
+
+/// 已拉起的进程。句柄放入 BackendManager 之后才调用 watch 开始处理输出：
+/// 进程立即退出时，Terminated 事件留在通道中，排在句柄放入之后处理
+pub struct Launch {
+    pub handle: Box<dyn BackendHandle>,
+    pub watch: Box<dyn FnOnce() + Send>,
+}
+
+pub trait Host: Send + Sync {
+    /// 就绪时由 wait_ready 产生、交给 started 的数据，应用中为启动耗时记录
+    type Ready: Send;
+
+    /// 连接的外部后端地址，外部后端不由本应用启动或停止
+    fn external_url(&self) -> Option<String>;
+
+    /// 当前进程的 PID；收到其 Terminated 事件后清空，停止流程据此判断进程已退出
+    fn pid(&self) -> Option<u32>;
+
+    /// 生命周期已改变；state 为对外状态的转移，对外状态没有变化时为 None
+    fn lifecycle_changed(&self, lifecycle: Lifecycle, state: Option<StateTransition>);
+
+    /// 开始启动，清除上一次的启动错误等
+    fn starting(&self);
+
+    /// 准备并拉起进程，很快返回
+    fn launch(&self, port_policy: PortPolicy) -> impl Future<Output = Result<Launch, BackendError>> + Send;
+
+    /// 等待刚拉起的进程通过健康检查；进程提前退出或超时返回失败原因
+    fn wait_ready(&self, pid: u32) -> impl Future<Output = Result<Self::Ready, String>> + Send;
+
+    /// 已就绪；elapsed 为从开始启动到就绪的耗时
+    fn started(&self, pid: u32, elapsed: Duration, ready: Self::Ready);
+
+    fn start_failed(&self, error: &BackendError);
+
+    /// 最近的 stderr 输出，记入启动失败的错误
+    fn stderr_tail(&self) -> Vec<String>;
+
+    /// 请求后端自行退出
+    fn request_shutdown(&self) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// 等待指定进程退出（pid 被清空），超时返回 false；graceful 为 true 时是 /shutdown 之后的等待
+    fn wait_for_exit(&self, pid: u32, timeout: Duration, graceful: bool) -> impl Future<Output = bool> + Send;
+
+    /// 进程已结束或已被强制结束：删除 PID 文件、释放数据目录锁
+    fn release(&self);
+}
+
+impl Host for AppHandle {
+    type Ready = StartSample;
+
+    fn external_url(&self) -> Option<String> {
+        self.state::<ServerState>().process.lock().unwrap().external_url.clone()
+    }
+
+    fn pid(&self) -> Option<u32> {
+        self.state::<ServerState>().process.lock().unwrap().pid
+    }
+
+    fn lifecycle_changed(&self, lifecycle: Lifecycle, state: Option<StateTransition>) {
+        let _ = self.emit("backend://lifecycle", super::LifecyclePayload { state: lifecycle });
+        if let Some(transition) = state {
+            let _ = self.emit("backend://state", transition);
+        }
+    }
+
+    fn starting(&self) {
+        let state = self.state::<ServerState>();
+        let mut process = state.process.lock().unwrap();
+        process.start_error = None;
+        process.backend_version = None;
+    }
+
+    async fn launch(&self, port_policy: PortPolicy) -> Result<Launch, BackendError> {
+        // 端口探测、Sidecar 校验、证书生成等同步准备在阻塞线程中进行，期间不持有句柄锁，
+        // kill_now、record_exit 等不会被卡住
+        let prepared = {
+            let app = self.clone();
+            tauri::async_runtime::spawn_blocking(move || super::prepare_spawn(&app, port_policy))
+                .await
+                .map_err(|e| BackendError::from(e.to_string()))??
+        };
+        super::launch(self, prepared)
+    }
+
+    async fn wait_ready(&self, pid: u32) -> Result<StartSample, String> {
+        let timeout = Duration::from_secs(crate::app_config(self).startup_timeout_secs);
+        let pending = startup::begin(self);
+        super::wait_for_ready(self, pid, timeout)
+            .instrument(tracing::info_span!("wait_for_ready", pid))
+            .await?;
+        Ok(pending.finish(self))
+    }
+
+    fn started(&self, pid: u32, elapsed: Duration, sample: StartSample) {
+        let duration_ms = elapsed.as_millis() as u64;
+        crate::telemetry::record(self, crate::telemetry::Event::BackendStart { duration_ms });
+        let (port, base_url) = {
+            let state = self.state::<ServerState>();
+            let process = state.process.lock().unwrap();
+            (process.port, process.base_url())
+        };
+        // 只有通过健康检查后才记录“就绪”；拉起进程时 launch 只记录 PID
+        app_log!(
+            "Backend server ready on {} after {} ms ({} ms since spawn)",
+            base_url, duration_ms, sample.duration_ms
+        );
+        let payload = super::BackendReadyPayload {
+            pid,
+            port,
+            duration_ms: sample.duration_ms,
+            start_kind: sample.kind,
+        };
+        crate::app_events::emit_to_frontend(self, "backend://ready", payload);
+        crate::app_events::mark_backend_ready(self);
+        crate::fs_watch::start(self);
+        let app = self.clone();
+        tauri::async_runtime::spawn(async move {
+            super::version::check(&app).await;
+            super::exposure::check(&app).await;
+        });
+    }
+
+    fn start_failed(&self, error: &BackendError) {
+        self.state::<ServerState>().process.lock().unwrap().start_error = Some(error.clone());
+    }
+
+    fn stderr_tail(&self) -> Vec<String> {
+        self.state::<ServerState>().stderr_tail.lock().unwrap().lines()
+    }
+
+    async fn request_shutdown(&self) -> Result<(), String> {
+        super::request_shutdown(&BackendClient::current(self)).await.map_err(|e| e.to_string())
+    }
+
+    async fn wait_for_exit(&self, pid: u32, timeout: Duration, graceful: bool) -> bool {
+        let state = self.state::<ServerState>();
+        if graceful {
+            super::wait_for_graceful_exit(self, &state, pid, timeout).await
+        } else {
+            super::wait_for_exit(&state, pid, timeout).await
+        }
+    }
+
+    fn release(&self) {
+        let state = self.state::<ServerState>();
+        if let Some(path) = state.process.lock().unwrap().pid_file.take() {
+            crate::pid_file::remove(&path);
+        }
+        super::release_data_lock(&state);
+    }
+}
@@ -0,0 +1,52 @@
+// 后端实例身份：启动 Sidecar 时用 --instance-id 传入数据目录的指纹（规范化路径的 SHA-256），
+// 后端在无需 token 的 GET /instance 上原样返回 {"instance_id": "..."}。
+// 连接到已在监听的端口（外部后端，或启动前发现端口被占用）时据此判断对方是否属于本数据目录：
+// 另一个 DunCrew 版本或另一个数据目录的后端不会被当作自己的后端使用。
+// 旧版后端没有 /instance，instance_id 为 None；/version 与 /instance 都没有响应的监听者不是 DunCrew 后端。
+
+use std::path::Path;
+use std::time::Duration;
+use sha2::{Digest, Sha256};
+
+use crate::backend_client::BackendClient;
+
+const IDENTIFY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 端口上已在运行的 DunCrew 后端
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ListenerIdentity {
+    /// /version 返回的版本，旧版后端不提供时为 None
+    pub version: Option<String>,
+    /// /instance 返回的实例指纹，旧版后端不提供时为 None
+    pub instance_id: Option<String>,
+}
+
+impl ListenerIdentity {
+    /// 是否为本数据目录的后端；对方不报告指纹时无法确认，视为不是
+    pub fn is_ours(&self, fingerprint: &str) -> bool {
+        self.instance_id.as_deref() == Some(fingerprint)
+    }
+}
+
+/// 数据目录的实例指纹；data_dir 须已规范化，与传给后端的 --path 一致
+pub fn fingerprint(data_dir: &Path) -> String {
+    Sha256::digest(data_dir.to_string_lossy().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn sidecar_args(data_dir: &Path) -> Vec<String> {
+    vec!["--instance-id".to_string(), fingerprint(data_dir)]
+}
+
+/// 询问 base_url 上的监听者是谁；不是 DunCrew 后端（两个端点都没有响应）时返回 None。
+/// 不附加会话 token：对方不是本应用启动的进程
+pub async fn identify(base_url: String) -> Option<ListenerIdentity> {
+    let client = BackendClient::unauthenticated(base_url);
+    let version = super::version::fetch(&client).await;
+    let instance_id = client
+        .get_json::<serde_json::Value>("/instance", IDENTIFY_TIMEOUT)
+        .await
+        .ok()
+        .and_then(|value| Some(value.get("instance_id")?.as_str()?.to_string()))
+        .filter(|id| !id.is_empty());
+    (version.is_some() || instance_id.is_some()).then_some(ListenerIdentity { version, instance_id })
+}
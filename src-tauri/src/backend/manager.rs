@@ -0,0 +1,547 @@
+// 后端生命周期管理：Sidecar 句柄放在 tokio Mutex 中，启动 / 停止 / 重启通过操作锁串行执行，
+// 等待后端退出期间既不占用线程也不阻塞事件循环。
+// 生命周期状态机：Stopped → Starting → Running → Stopping → Stopped；
+// 进程拉起后须通过健康检查才算 Running，拉起失败、未在超时内就绪或就绪前退出进入 FailedToStart；
+// 运行中意外退出进入 Failed。只有 Failed / FailedToStart 会被崩溃重启拉起。
+// 进程仍在但健康检查连续超时（死锁等）进入 Hung，与崩溃（Failed）、停止（Stopped）区分；恢复响应后回到 Running。
+// 短时间内反复崩溃达到上限时进入 CrashLoop，不再自动重启，只能由用户手动重启。
+// 状态改变时经 Host 发送 `backend://state`（见 state.rs）与已弃用的 `backend://lifecycle`，应用菜单据此更新可用状态。
+// 同一时间只有一个进程句柄：已在运行时 start 直接返回当前 PID；重启进行中又收到重启请求时，
+// 后到的请求等待进行中的那次完成并返回同一结果，而不是再拉起一个 Sidecar。
+// 独占数据目录的后台任务（备份、恢复、迁移，见 jobs.rs）在整个任务期间持有数据目录锁的写锁，
+// 重启须先取得读锁：任务进行中不会被手动重启、内存超限或崩溃重启打断，重启进行中任务也不会开始。
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard, OwnedRwLockWriteGuard, RwLock, RwLockReadGuard};
+use tracing::Instrument;
+
+use super::state::{BackendState, BackendStateInfo, StateTracker, WaitForBackendError};
+use super::{BackendError, BackendHandle, Host, Launch, PortPolicy};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lifecycle {
+    #[default]
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+    Failed,
+    FailedToStart,
+    Hung,
+    CrashLoop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transition {
+    Start,
+    // 已拉起并通过健康检查
+    Ready,
+    StartFailed,
+    Stop,
+    Stopped,
+    // 强制结束进程，任何状态都直接回到 Stopped
+    Killed,
+    // 当前进程的 Terminated 事件
+    Exited,
+    // 健康检查连续超时 / 超时后恢复响应
+    Hung,
+    Recovered,
+    // 崩溃重启次数达到上限，放弃自动重启
+    CrashLoop,
+}
+
+impl Lifecycle {
+    /// 状态转移，不合法的转移返回 None
+    pub fn apply(self, transition: Transition) -> Option<Lifecycle> {
+        use Lifecycle::*;
+        match (self, transition) {
+            (Stopped | Failed | FailedToStart | CrashLoop, Transition::Start) => Some(Starting),
+            (Starting, Transition::Ready) => Some(Running),
+            (Starting, Transition::StartFailed) => Some(FailedToStart),
+            (Running | Hung, Transition::Stop) => Some(Stopping),
+            (Running, Transition::Hung) => Some(Hung),
+            (Hung, Transition::Recovered) => Some(Running),
+            (Failed | FailedToStart, Transition::CrashLoop) => Some(CrashLoop),
+            // 没有进程在运行时停止只是确认状态，例如崩溃后关闭窗口，等待中的崩溃重启随之取消
+            (Stopped | Failed | FailedToStart | CrashLoop, Transition::Stop) => Some(Stopped),
+            (Stopping, Transition::Stopped) => Some(Stopped),
+            (_, Transition::Killed) => Some(Stopped),
+            (Running | Hung, Transition::Exited) => Some(Failed),
+            // 就绪前退出，由等待就绪的启动流程报告错误
+            (Starting, Transition::Exited) => Some(FailedToStart),
+            // 停止过程中退出是预期的，由停止流程收尾
+            (Stopping | Stopped | Failed | FailedToStart | CrashLoop, Transition::Exited) => Some(self),
+            _ => None,
+        }
+    }
+
+    /// 处于失败状态，可由崩溃重启拉起；CrashLoop 不在此列
+    pub fn is_failed(self) -> bool {
+        matches!(self, Lifecycle::Failed | Lifecycle::FailedToStart)
+    }
+}
+
+fn still_running(pid: u32) -> BackendError {
+    format!("Backend process {} is still running, not starting another", pid).into()
+}
+
+/// 持有期间独占后端的启动 / 停止，start、stop 要求调用方出示
+pub type OperationGuard<'a> = AsyncMutexGuard<'a, ()>;
+
+/// BackendManager::restart 的结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Restart {
+    /// 本次请求停止并重新拉起了后端
+    Started(u32),
+    /// 已有启动进行中，沿用了它的结果
+    Joined(u32),
+}
+
+impl Restart {
+    pub fn pid(self) -> u32 {
+        match self {
+            Restart::Started(pid) | Restart::Joined(pid) => pid,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BackendManager {
+    operation: AsyncMutex<()>,
+    // 数据目录锁：独占任务持有写锁，重启持有读锁；先于 operation 获取
+    data_dir_access: Arc<RwLock<()>>,
+    // 只在存取句柄时短暂持有，不跨越等待
+    child: AsyncMutex<Option<Box<dyn BackendHandle>>>,
+    lifecycle: Mutex<Lifecycle>,
+    state: StateTracker,
+    // 已完成的 start 次数与最近一次的结果，join_start 据此取得进行中那次启动的结果
+    starts: Mutex<(u64, Option<Result<u32, BackendError>>)>,
+}
+
+impl BackendManager {
+    pub fn lifecycle(&self) -> Lifecycle {
+        *self.lifecycle.lock().unwrap()
+    }
+
+    /// 对外状态与上一次转移
+    pub fn state(&self) -> BackendStateInfo {
+        self.state.get()
+    }
+
+    /// 等待后端就绪，见 StateTracker::wait_ready
+    pub async fn wait_ready(&self, timeout: Duration) -> Result<BackendStateInfo, WaitForBackendError> {
+        self.state.wait_ready(timeout).await
+    }
+
+    /// 已连接外部后端
+    pub fn mark_external(&self, app: &AppHandle) {
+        if let Some(transition) = self.state.set(BackendState::External, "external") {
+            let _ = app.emit("backend://state", transition);
+        }
+    }
+
+    fn transition(&self, host: &impl Host, transition: Transition) -> Result<Lifecycle, BackendError> {
+        let (previous, next) = {
+            let mut lifecycle = self.lifecycle.lock().unwrap();
+            let previous = *lifecycle;
+            match previous.apply(transition) {
+                Some(next) => {
+                    *lifecycle = next;
+                    (previous, next)
+                }
+                None => return Err(format!("Backend cannot {:?} while {:?}", transition, previous).into()),
+            }
+        };
+        // 释放锁后再通知，监听方可以读取 lifecycle()
+        if next != previous {
+            let external = host.external_url().is_some();
+            let detail = serde_json::to_value(transition).ok();
+            let detail = detail.as_ref().and_then(|detail| detail.as_str()).unwrap_or_default();
+            let state = self.state.set(BackendState::of(next, external), detail);
+            host.lifecycle_changed(next, state);
+        }
+        Ok(next)
+    }
+
+    /// 健康检查判定后端无响应；只有 Running 可以进入 Hung，返回是否发生了转移
+    pub fn mark_hung(&self, host: &impl Host) -> bool {
+        self.transition(host, Transition::Hung).is_ok()
+    }
+
+    /// 无响应的后端重新通过健康检查
+    pub fn mark_recovered(&self, host: &impl Host) -> bool {
+        self.transition(host, Transition::Recovered).is_ok()
+    }
+
+    /// 崩溃重启达到上限；只有 Failed / FailedToStart 可以进入，返回是否发生了转移
+    pub fn mark_crash_loop(&self, host: &impl Host) -> bool {
+        self.transition(host, Transition::CrashLoop).is_ok()
+    }
+
+    /// 等待正在进行的生命周期操作完成后开始新的操作
+    pub async fn begin(&self) -> OperationGuard<'_> {
+        self.operation.lock().await
+    }
+
+    /// 已有操作进行中时直接失败，用于手动重启等不应排队的请求
+    pub fn try_begin(&self) -> Result<OperationGuard<'_>, BackendError> {
+        self.operation
+            .try_lock()
+            .map_err(|_| "Backend restart already in progress".to_string().into())
+    }
+
+    /// 重启前取得数据目录的读锁；独占任务进行中时直接失败
+    pub fn try_restart_access(&self) -> Result<RwLockReadGuard<'_, ()>, BackendError> {
+        self.data_dir_access
+            .try_read()
+            .map_err(|_| "A backup, restore or data migration is in progress".to_string().into())
+    }
+
+    /// 等待进行中的独占任务结束后取得数据目录的读锁，用于崩溃重启等应当延后而不是放弃的重启
+    pub async fn restart_access(&self) -> RwLockReadGuard<'_, ()> {
+        self.data_dir_access.read().await
+    }
+
+    /// 独占任务的数据目录写锁，等待进行中的重启结束；任务期间自身的停止 / 重启（with_backend_stopped）不再取锁
+    pub async fn exclusive_data_dir(&self) -> OwnedRwLockWriteGuard<()> {
+        self.data_dir_access.clone().write_owned().await
+    }
+
+    /// 不等待地取得独占任务的数据目录写锁
+    pub fn try_exclusive_data_dir(&self) -> Option<OwnedRwLockWriteGuard<()>> {
+        self.data_dir_access.clone().try_write_owned().ok()
+    }
+
+    /// 已完成的 start 次数，传给 join_start
+    pub fn start_count(&self) -> u64 {
+        self.starts.lock().unwrap().0
+    }
+
+    /// 等待进行中的操作结束；其间完成过 start（start_count 不再是 since）时返回该次的结果，
+    /// 否则（只是停止等操作）返回 None
+    pub async fn join_start(&self, since: u64) -> Option<Result<u32, BackendError>> {
+        drop(self.begin().await);
+        let starts = self.starts.lock().unwrap();
+        if starts.0 == since {
+            return None;
+        }
+        starts.1.clone()
+    }
+
+    /// 启动 Sidecar 并等待其通过健康检查，返回新进程 PID；已在运行时直接返回当前 PID。
+    /// 失败原因同时记入 ProcessInfo::start_error，get_backend_status 可以查询
+    pub async fn start(&self, _op: &OperationGuard<'_>, host: &impl Host, port_policy: PortPolicy) -> Result<u32, BackendError> {
+        self.run_start(host, port_policy).instrument(tracing::info_span!("backend_start")).await
+    }
+
+    async fn run_start(&self, host: &impl Host, port_policy: PortPolicy) -> Result<u32, BackendError> {
+        if self.lifecycle() == Lifecycle::Running {
+            if let Some(pid) = self.child.lock().await.as_ref().map(|child| child.pid()) {
+                app_log!("Backend is already running (pid {}), not starting another", pid);
+                return Ok(pid);
+            }
+        }
+        self.transition(host, Transition::Start)?;
+        host.starting();
+        let started = std::time::Instant::now();
+        let result = match self.spawn_and_wait(host, port_policy).await {
+            Ok((pid, ready)) => {
+                self.transition(host, Transition::Ready)?;
+                host.started(pid, started.elapsed(), ready);
+                Ok(pid)
+            }
+            Err(e) => {
+                // 就绪前退出时 Terminated 处理已切到 FailedToStart
+                let _ = self.transition(host, Transition::StartFailed);
+                host.start_failed(&e);
+                Err(e)
+            }
+        };
+        let mut starts = self.starts.lock().unwrap();
+        *starts = (starts.0 + 1, Some(result.clone()));
+        result
+    }
+
+    // 拉起进程并等待就绪，成功时返回 PID 与 Host::wait_ready 的结果
+    async fn spawn_and_wait<H: Host>(&self, host: &H, port_policy: PortPolicy) -> Result<(u32, H::Ready), BackendError> {
+        // 不覆盖仍然存在的句柄，否则旧进程失去管理，直到应用退出都不会被结束
+        if let Some(existing) = self.child.lock().await.as_ref() {
+            return Err(still_running(existing.pid()));
+        }
+        let Launch { handle, watch } = host.launch(port_policy).await?;
+        let pid = handle.pid();
+        {
+            let mut child = self.child.lock().await;
+            // 启动都在操作锁内进行，这里只是保险：放弃新进程，保留原有的句柄
+            if let Some(existing) = child.as_ref() {
+                let existing = existing.pid();
+                drop(child);
+                let _ = handle.kill();
+                watch();
+                return Err(still_running(existing));
+            }
+            *child = Some(handle);
+        }
+        // 句柄放入之后才开始处理输出：进程立即退出时，Terminated 事件能找到句柄
+        watch();
+        match host.wait_ready(pid).await {
+            Ok(ready) => Ok((pid, ready)),
+            Err(reason) => {
+                app_error!("Backend failed to start: {}", reason);
+                // 进程仍在运行（超时）时结束它；迟到的 Terminated 事件找不到句柄，不会按崩溃处理
+                let child = self.child.lock().await.take();
+                if let Some(child) = child {
+                    let _ = child.kill();
+                }
+                host.release();
+                Err(BackendError::FailedToStart { reason, stderr: host.stderr_tail() })
+            }
+        }
+    }
+
+    /// 停止后端：先走 /shutdown 优雅退出，超时或失败再强制 kill
+    pub async fn stop(&self, _op: &OperationGuard<'_>, host: &impl Host, timeout: Duration) {
+        self.run_stop(host, timeout).instrument(tracing::info_span!("backend_stop")).await
+    }
+
+    async fn run_stop(&self, host: &impl Host, timeout: Duration) {
+        if let Some(url) = host.external_url() {
+            app_log!("Backend at {} is external, leaving it running", url);
+            return;
+        }
+        let pid = match (host.pid(), self.transition(host, Transition::Stop)) {
+            (Some(pid), Ok(Lifecycle::Stopping)) => pid,
+            _ => {
+                self.kill(host).await;
+                return;
+            }
+        };
+
+        app_log!("Stopping backend server (pid {})...", pid);
+        match host.request_shutdown().await {
+            Ok(()) => {
+                if host.wait_for_exit(pid, timeout, true).await {
+                    self.child.lock().await.take();
+                    host.release();
+                    let _ = self.transition(host, Transition::Stopped);
+                    app_log!("Backend server stopped gracefully");
+                    return;
+                }
+                app_error!("Backend did not exit within {:?}, force killing", timeout);
+            }
+            Err(e) => {
+                app_error!("Graceful shutdown request failed ({}), force killing", e);
+            }
+        }
+        self.kill(host).await;
+    }
+
+    /// 优雅停止后端并确认进程已退出
+    pub async fn stop_and_wait(&self, op: &OperationGuard<'_>, host: &impl Host, timeout: Duration) -> Result<(), BackendError> {
+        let old_pid = host.pid();
+        self.stop(op, host, timeout).await;
+        if let Some(pid) = old_pid {
+            if !host.wait_for_exit(pid, super::EXIT_WAIT_TIMEOUT, false).await {
+                return Err(format!("Backend process {} did not exit within {:?}", pid, super::EXIT_WAIT_TIMEOUT).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// 手动重启：停止当前进程，等待其真正退出后重新启动，before_start 在停止之后、启动之前调用。
+    /// 独占任务进行中时失败；已有操作进行中时，它会拉起后端则沿用其结果（Joined），只是停止则返回“已在进行中”
+    pub async fn restart(
+        &self,
+        host: &impl Host,
+        stop_timeout: Duration,
+        port_policy: PortPolicy,
+        before_start: impl FnOnce(),
+    ) -> Result<Restart, BackendError> {
+        let _access = self.try_restart_access()?;
+        let since = self.start_count();
+        let op = match self.try_begin() {
+            Ok(op) => op,
+            Err(e) => return self.join_start(since).await.unwrap_or(Err(e)).map(Restart::Joined),
+        };
+        app_log!("Restarting backend server...");
+        self.stop_and_wait(&op, host, stop_timeout).await?;
+        before_start();
+        self.start(&op, host, port_policy).await.map(Restart::Started)
+    }
+
+    /// 崩溃重启：等待 delay 后重启仍处于失败状态的后端。等待期间已被手动重启拉起新进程或已停止时返回 None
+    pub async fn restart_after_crash(
+        &self,
+        host: &impl Host,
+        delay: Duration,
+        port_policy: impl FnOnce() -> PortPolicy,
+    ) -> Option<Result<u32, BackendError>> {
+        tokio::time::sleep(delay).await;
+        // 备份等独占任务进行中时等它结束（任务结束时会自行拉起后端）
+        let _access = self.restart_access().await;
+        // 取得操作锁后再检查
+        let op = self.begin().await;
+        if !self.lifecycle().is_failed() {
+            return None;
+        }
+        Some(self.start(&op, host, port_policy()).await)
+    }
+
+    /// 等待其他操作完成后停止后端，供关闭窗口、退出应用使用。
+    /// 强制结束后同样等到进程确实退出才返回，应用不会在结束请求尚未完成时退出
+    pub async fn shutdown(&self, host: &impl Host, timeout: Duration) {
+        let op = self.begin().await;
+        let pid = host.pid();
+        self.stop(&op, host, timeout).await;
+        if let Some(pid) = pid {
+            if !host.wait_for_exit(pid, super::EXIT_WAIT_TIMEOUT, false).await {
+                app_error!("Backend process {} did not exit within {:?} after being killed", pid, super::EXIT_WAIT_TIMEOUT);
+            }
+        }
+    }
+
+    /// 向当前后端进程的标准输入写入数据；没有运行中的进程时返回错误
+    pub async fn write_stdin(&self, data: &[u8]) -> Result<(), BackendError> {
+        match self.child.lock().await.as_mut() {
+            Some(child) => child.write(data).map_err(|e| format!("Failed to write to backend stdin: {}", e).into()),
+            None => Err("Backend is not running".to_string().into()),
+        }
+    }
+
+    /// 立即强制结束后端进程
+    pub async fn kill(&self, host: &impl Host) {
+        async {
+            let child = self.child.lock().await.take();
+            self.finish_kill(host, child);
+        }
+        .instrument(tracing::info_span!("backend_kill"))
+        .await
+    }
+
+    /// 进程即将退出时兜底，在事件循环线程上同步执行。句柄锁只会被短暂持有，
+    /// 恰好被占用时跳过，由 process_guard 保证 Sidecar 随应用退出
+    pub fn kill_now(&self, host: &impl Host) {
+        match self.child.try_lock() {
+            Ok(mut child) => {
+                let child = child.take();
+                self.finish_kill(host, child);
+            }
+            Err(_) => app_error!("Backend handle is busy, leaving the sidecar to the process guard"),
+        }
+    }
+
+    fn finish_kill(&self, host: &impl Host, child: Option<Box<dyn BackendHandle>>) {
+        let _ = self.transition(host, Transition::Killed);
+        if let Some(child) = child {
+            let _ = child.kill();
+            app_log!("Backend server killed");
+        }
+        host.release();
+    }
+
+    /// 记录进程退出并释放句柄。返回 true 表示运行中的当前进程意外退出（崩溃）；
+    /// 停止流程中的退出、已被替换的旧进程的迟到事件都返回 false
+    pub async fn record_exit(&self, host: &impl Host, pid: u32) -> bool {
+        let mut child = self.child.lock().await;
+        if child.as_ref().map(|c| c.pid()) != Some(pid) {
+            return false;
+        }
+        child.take();
+        let crashed = matches!(self.lifecycle(), Lifecycle::Running | Lifecycle::Hung);
+        let _ = self.transition(host, Transition::Exited);
+        crashed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Lifecycle::*;
+
+    const LIFECYCLES: [Lifecycle; 8] = [Stopped, Starting, Running, Stopping, Failed, FailedToStart, Hung, CrashLoop];
+    const TRANSITIONS: [Transition; 10] = [
+        Transition::Start,
+        Transition::Ready,
+        Transition::StartFailed,
+        Transition::Stop,
+        Transition::Stopped,
+        Transition::Killed,
+        Transition::Exited,
+        Transition::Hung,
+        Transition::Recovered,
+        Transition::CrashLoop,
+    ];
+
+    // 全部合法的转移；不在表中的组合必须被拒绝
+    const LEGAL: &[(Lifecycle, Transition, Lifecycle)] = &[
+        (Stopped, Transition::Start, Starting),
+        (Stopped, Transition::Stop, Stopped),
+        (Stopped, Transition::Killed, Stopped),
+        (Stopped, Transition::Exited, Stopped),
+        (Starting, Transition::Ready, Running),
+        (Starting, Transition::StartFailed, FailedToStart),
+        (Starting, Transition::Killed, Stopped),
+        (Starting, Transition::Exited, FailedToStart),
+        (Running, Transition::Stop, Stopping),
+        (Running, Transition::Killed, Stopped),
+        (Running, Transition::Exited, Failed),
+        (Running, Transition::Hung, Hung),
+        (Stopping, Transition::Stopped, Stopped),
+        (Stopping, Transition::Killed, Stopped),
+        (Stopping, Transition::Exited, Stopping),
+        (Failed, Transition::Start, Starting),
+        (Failed, Transition::Stop, Stopped),
+        (Failed, Transition::Killed, Stopped),
+        (Failed, Transition::Exited, Failed),
+        (Failed, Transition::CrashLoop, CrashLoop),
+        (FailedToStart, Transition::Start, Starting),
+        (FailedToStart, Transition::Stop, Stopped),
+        (FailedToStart, Transition::Killed, Stopped),
+        (FailedToStart, Transition::Exited, FailedToStart),
+        (FailedToStart, Transition::CrashLoop, CrashLoop),
+        (Hung, Transition::Stop, Stopping),
+        (Hung, Transition::Killed, Stopped),
+        (Hung, Transition::Exited, Failed),
+        (Hung, Transition::Recovered, Running),
+        (CrashLoop, Transition::Start, Starting),
+        (CrashLoop, Transition::Stop, Stopped),
+        (CrashLoop, Transition::Killed, Stopped),
+        (CrashLoop, Transition::Exited, CrashLoop),
+    ];
+
+    fn expected(from: Lifecycle, transition: Transition) -> Option<Lifecycle> {
+        LEGAL.iter().find(|(f, t, _)| *f == from && *t == transition).map(|(_, _, to)| *to)
+    }
+
+    #[test]
+    fn apply_matches_the_transition_table() {
+        for from in LIFECYCLES {
+            for transition in TRANSITIONS {
+                assert_eq!(from.apply(transition), expected(from, transition), "{:?} --{:?}-->", from, transition);
+            }
+        }
+    }
+
+    #[test]
+    fn legal_transitions_are_legal_backend_states() {
+        // 内部转移对应的对外状态变化必须在 BackendState 的转移图中，否则 StateTracker 会记错误日志
+        for &(from, transition, to) in LEGAL {
+            let (outer_from, outer_to) = (BackendState::of(from, false), BackendState::of(to, false));
+            assert!(
+                outer_from == outer_to || outer_from.successors().contains(&outer_to),
+                "{:?} --{:?}--> {:?} is {:?} -> {:?}",
+                from, transition, to, outer_from, outer_to
+            );
+        }
+    }
+
+    #[test]
+    fn only_failures_before_crash_loop_are_restartable() {
+        let failed: Vec<_> = LIFECYCLES.into_iter().filter(|l| l.is_failed()).collect();
+        assert_eq!(failed, [Failed, FailedToStart]);
+    }
+}
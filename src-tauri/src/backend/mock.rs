@@ -0,0 +1,199 @@
+// 模拟后端（mock-backend 特性）：在进程内监听端口，响应 /health 与 /shutdown，
+// 不需要 Python 或打包的 Sidecar，便于调试启动、重启、退出流程与前端的后端状态展示。
+// 可以模拟各种异常：环境变量 DUNCREW_MOCK_BACKEND_DELAY_MS 让它启动后先等待再处理请求（就绪超时），
+// 运行中向数据目录写入 mock-backend.json 下达一次性指令，读取后即删除：
+// `{"action": "crash", "code": 3}` 以指定退出码退出，`{"action": "hang"}` 不再响应任何请求直到被结束，
+// `{"action": "print", "stdout": ["..."], "stderr": ["..."]}` 输出指定的行（日志采集、进度上报）。
+// 请求处理与指令循环（serve）也供 tests/mock_server 使用，它以独立进程经真实的 Sidecar 路径启动。
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{BackendEvent, BackendHandle, Spawned};
+
+// 不对应任何真实进程，避免 pid 文件、进程守护和资源采样作用到无关进程上
+static NEXT_PID: AtomicU32 = AtomicU32::new(0xFFFF_0000);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+pub const CONTROL_FILE_NAME: &str = "mock-backend.json";
+const DELAY_ENV: &str = "DUNCREW_MOCK_BACKEND_DELAY_MS";
+
+/// mock-backend.json 中的指令
+#[derive(serde::Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Command {
+    Crash {
+        code: i32,
+    },
+    Hang,
+    Print {
+        #[serde(default)]
+        stdout: Vec<String>,
+        #[serde(default)]
+        stderr: Vec<String>,
+    },
+}
+
+/// 读取并删除指令文件；内容无效时同样删除，避免每次轮询重复报错
+pub fn take_command(path: &Path) -> Option<Result<Command, String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let _ = std::fs::remove_file(path);
+    Some(
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Mock backend ignored invalid {}: {}", CONTROL_FILE_NAME, e)),
+    )
+}
+
+fn startup_delay() -> Option<Duration> {
+    let millis = std::env::var(DELAY_ENV).ok()?.trim().parse().ok()?;
+    Some(Duration::from_millis(millis))
+}
+
+struct MockHandle {
+    pid: u32,
+    killed: Arc<AtomicBool>,
+}
+
+impl BackendHandle for MockHandle {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    // 模拟后端不读取标准输入，控制命令直接视为已送达
+    fn write(&mut self, _data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn kill(self: Box<Self>) -> Result<(), String> {
+        self.killed.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+// 处理一个连接，返回 true 表示收到了 /shutdown
+fn respond(stream: TcpStream, emit: &mut impl FnMut(BackendEvent)) -> bool {
+    let _ = stream.set_nonblocking(false);
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return false;
+    }
+    // 读完请求头；模拟后端不读取请求体
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+        header.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (status, body) = match path {
+        "/health" => ("200 OK", r#"{"status":"ok","version":"mock"}"#),
+        "/shutdown" => ("200 OK", r#"{"status":"shutting_down"}"#),
+        _ => ("404 Not Found", r#"{"error":"not available in the mock backend"}"#),
+    };
+    emit(BackendEvent::Stdout(request_line.trim_end().as_bytes().to_vec()));
+    let mut stream = &stream;
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    path == "/shutdown"
+}
+
+/// 处理请求与指令直到收到 /shutdown、崩溃指令或 stop 被置位，返回退出码（被结束时为 None）。
+/// listener 须为非阻塞；next_command 每次轮询调用一次，输出与请求记录经 emit 送出
+pub fn serve(
+    listener: &TcpListener,
+    stop: &AtomicBool,
+    mut next_command: impl FnMut() -> Option<Result<Command, String>>,
+    mut emit: impl FnMut(BackendEvent),
+) -> Option<i32> {
+    let mut hung = false;
+    loop {
+        if stop.load(Ordering::SeqCst) {
+            return None;
+        }
+        match next_command() {
+            Some(Ok(Command::Crash { code })) => {
+                emit(BackendEvent::Stderr(format!("Mock backend crashing with exit code {}", code).into_bytes()));
+                return Some(code);
+            }
+            Some(Ok(Command::Hang)) => {
+                emit(BackendEvent::Stderr(b"Mock backend stopped responding".to_vec()));
+                hung = true;
+            }
+            Some(Ok(Command::Print { stdout, stderr })) => {
+                for line in stdout {
+                    emit(BackendEvent::Stdout(line.into_bytes()));
+                }
+                for line in stderr {
+                    emit(BackendEvent::Stderr(line.into_bytes()));
+                }
+            }
+            Some(Err(message)) => emit(BackendEvent::Stderr(message.into_bytes())),
+            None => {}
+        }
+        if hung {
+            std::thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if respond(stream, &mut emit) {
+                    return Some(0);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                emit(BackendEvent::Error(e.to_string()));
+                return Some(1);
+            }
+        }
+    }
+}
+
+/// data_dir 为指令文件 mock-backend.json 所在的目录
+pub fn spawn(port: u16, data_dir: &Path) -> Result<Spawned, String> {
+    spawn_with_delay(port, data_dir, startup_delay())
+}
+
+/// 同 spawn，启动后的等待由调用方指定而不读取环境变量
+pub fn spawn_with_delay(port: u16, data_dir: &Path, delay: Option<Duration>) -> Result<Spawned, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+        .map_err(|e| format!("Mock backend failed to listen on port {}: {}", port, e))?;
+    let pid = NEXT_PID.fetch_add(1, Ordering::SeqCst);
+    let killed = Arc::new(AtomicBool::new(false));
+    let (tx, events) = mpsc::channel(64);
+
+    let stop = killed.clone();
+    let control: PathBuf = data_dir.join(CONTROL_FILE_NAME);
+    std::thread::Builder::new()
+        .name("mock-backend".to_string())
+        .spawn(move || {
+            let emit = |event| {
+                let _ = tx.blocking_send(event);
+            };
+            emit(BackendEvent::Stdout(format!("Mock backend listening on 127.0.0.1:{}", port).into_bytes()));
+            // 端口已在监听，但等待期间的请求没有响应，与 Python 仍在导入依赖时相同
+            if let Some(delay) = delay {
+                emit(BackendEvent::Stdout(format!("Mock backend delaying startup by {:?}", delay).into_bytes()));
+                let until = std::time::Instant::now() + delay;
+                while std::time::Instant::now() < until && !stop.load(Ordering::SeqCst) {
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            }
+            let code = serve(&listener, &stop, || take_command(&control), emit);
+            drop(listener);
+            let _ = tx.blocking_send(BackendEvent::Terminated { code, signal: None });
+        })
+        .map_err(|e| format!("Failed to start mock backend: {}", e))?;
+
+    Ok(Spawned { handle: Box::new(MockHandle { pid, killed }), events })
+}
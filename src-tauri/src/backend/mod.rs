@@ -0,0 +1,1994 @@
+// Python 后端 Sidecar：端口选择、启动命令、输出采集、崩溃重启与状态查询。
+// 进程句柄与生命周期由 manager::BackendManager 管理，句柄本身经 handle::BackendHandle 抽象，
+// 默认是 shell 插件启动的 Sidecar（sidecar.rs），mock-backend 特性下换成进程内的模拟后端（mock.rs），
+// test-sidecar 特性下 Sidecar 换成 tests/mock_server 的模拟服务，仍经 shell 插件启动。
+
+mod exposure;
+mod handle;
+mod host;
+mod instance;
+mod manager;
+pub mod mock;
+mod priority;
+pub mod sidecar;
+mod startup;
+mod state;
+mod termination;
+mod tls;
+mod version;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+use tauri_plugin_shell::ShellExt;
+use tauri_plugin_shell::process::Command;
+use tracing::Instrument;
+
+use crate::alerts::{self, Alert, AlertCategory, AlertPriority};
+use crate::backend_client::{BackendClient, BackendClientError};
+use crate::{app_config, backend_data_dir, cache, config, crash_report, data_dir, data_lock, headless, health, i18n, locale, logs, notify, pid_file, process_guard, safe_mode, secrets, sidecar_integrity, storage, telemetry};
+pub use handle::{BackendEvent, BackendHandle, Spawned};
+pub use host::{Host, Launch};
+pub use manager::{BackendManager, Lifecycle, OperationGuard, Restart, Transition};
+pub use priority::LEVELS as PRIORITY_LEVELS;
+pub use state::{BackendState, BackendStateInfo, StateTransition, WaitForBackendError};
+pub use termination::TerminationInfo;
+pub use tls::http_client;
+pub use version::{is_compatible as is_backend_version_compatible, EXPECTED_BACKEND_VERSION};
+
+pub const SIDECAR_NAME: &str = "duncrew-server";
+// 源码模式下的后端入口与解释器
+const BACKEND_SOURCE_SCRIPT: &str = "duncrew-server.py";
+#[cfg(windows)]
+const PYTHON_EXECUTABLE: &str = "python";
+#[cfg(not(windows))]
+const PYTHON_EXECUTABLE: &str = "python3";
+
+// test-sidecar 构建中指定 mock_server 的路径
+const TEST_SIDECAR_ENV: &str = "DUNCREW_TEST_SIDECAR";
+
+// 每次启动后端生成的会话 token，经环境变量传给后端（不出现在命令行参数里），
+// 前端请求时放在 AUTH_TOKEN_HEADER 中
+const AUTH_TOKEN_ENV: &str = "DUNCREW_AUTH_TOKEN";
+pub const AUTH_TOKEN_HEADER: &str = "X-DunCrew-Token";
+
+// 优先使用前端熟悉的 3001，被占用时依次尝试后续端口
+pub const DEFAULT_BACKEND_PORT: u16 = 3001;
+const BACKEND_PORT_RANGE: RangeInclusive<u16> = DEFAULT_BACKEND_PORT..=3020;
+
+// 崩溃自动重启：退避 1s, 2s, 4s ... 上限 30s；窗口期内连续失败超过上限则放弃
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(1);
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+pub const RESTART_MAX_ATTEMPTS: u32 = 5;
+pub const RESTART_WINDOW: Duration = Duration::from_secs(120);
+// 启动失败时弹窗允许重试的次数，用完后只能退出或留在无后端的界面
+const STARTUP_MAX_ATTEMPTS: u32 = 3;
+// 手动重启时等待旧进程退出的最长时间
+const EXIT_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+// 允许经 stdin 发送给后端的控制命令，HTTP 服务本身无响应时也能使用
+// release-cache 由 clear_cache 在删除缓存前发送，请求后端关闭缓存文件
+const CONTROL_VERBS: [&str; 4] = ["reload", "flush", "dump-state", "release-cache"];
+
+// 后端启动失败原因，序列化后可直接作为命令错误返回给前端
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackendError {
+    // 端口已被其他进程监听；pid / process_name 在系统允许时给出
+    PortInUse {
+        port: u16,
+        pid: Option<u32>,
+        process_name: Option<String>,
+    },
+    // 端口上已在运行另一个 DunCrew 后端（其他版本或其他数据目录），实例指纹与本数据目录不符
+    ForeignInstance {
+        port: u16,
+        version: Option<String>,
+        instance_id: Option<String>,
+    },
+    // 数据目录被另一个仍在运行的进程使用
+    DataDirLocked {
+        path: String,
+        pid: Option<u32>,
+    },
+    // 数据目录不存在、无法创建或不可写；issue 区分原因，message 为系统错误
+    DataDirUnavailable {
+        path: String,
+        issue: data_dir::DataDirIssue,
+        message: String,
+    },
+    // Sidecar 二进制与构建时记录的 SHA-256 不一致，通常是被杀毒软件隔离或安装损坏
+    SidecarVerificationFailed {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    // 数据目录所在磁盘剩余空间低于 min_free_space_mb，用户确认后可继续
+    LowDiskSpace {
+        available_bytes: u64,
+        minimum_bytes: u64,
+    },
+    // 无界面模式的 bind_address 不是回环地址，但 config.json 未设置 allow_remote_access
+    RemoteAccessNotAllowed {
+        bind_address: String,
+    },
+    // 进程已拉起，但在 startup_timeout_secs 内没有通过健康检查或提前退出；stderr 为最后几行输出
+    FailedToStart {
+        reason: String,
+        stderr: Vec<String>,
+    },
+    Other {
+        message: String,
+    },
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendError::PortInUse { port, pid, process_name } => {
+                write!(f, "Port {} is in use by another process", port)?;
+                match (process_name, pid) {
+                    (Some(name), Some(pid)) => write!(f, " ({}, PID {})", name, pid),
+                    (None, Some(pid)) => write!(f, " (PID {})", pid),
+                    _ => Ok(()),
+                }
+            }
+            BackendError::ForeignInstance { port, version, .. } => {
+                write!(f, "Port {} is used by another DunCrew backend", port)?;
+                match version {
+                    Some(version) => write!(f, " (version {})", version),
+                    None => Ok(()),
+                }
+            }
+            BackendError::DataDirLocked { path, pid } => {
+                write!(f, "Data directory {} is in use by another DunCrew process", path)?;
+                match pid {
+                    Some(pid) => write!(f, " (PID {})", pid),
+                    None => Ok(()),
+                }
+            }
+            BackendError::DataDirUnavailable { path, message, .. } => {
+                write!(f, "Data directory {} is not available: {}", path, message)
+            }
+            BackendError::SidecarVerificationFailed { path, .. } => {
+                write!(f, "Backend executable {} is damaged or has been modified", path)
+            }
+            BackendError::LowDiskSpace { available_bytes, minimum_bytes } => write!(
+                f,
+                "Only {} MB of disk space left, at least {} MB is recommended",
+                available_bytes / 1024 / 1024,
+                minimum_bytes / 1024 / 1024
+            ),
+            BackendError::RemoteAccessNotAllowed { bind_address } => write!(
+                f,
+                "Refusing to listen on {}: set allow_remote_access to true in config.json to let other machines reach the backend",
+                bind_address
+            ),
+            BackendError::FailedToStart { reason, .. } => f.write_str(reason),
+            BackendError::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for BackendError {
+    fn from(message: String) -> Self {
+        BackendError::Other { message }
+    }
+}
+
+// 端口选择策略：Exact 遇到占用直接报错，Any 自动寻找空闲端口（优先给定端口）
+#[derive(Clone, Copy)]
+pub enum PortPolicy {
+    Exact(u16),
+    Any(u16),
+}
+
+// 后端进程状态
+#[derive(Default)]
+pub struct ServerState {
+    // Sidecar 句柄与生命周期状态，启动 / 停止都经由它串行执行
+    pub backend: BackendManager,
+    // 应用已进入退出流程 / 后端已停止可以真正退出
+    pub exiting: AtomicBool,
+    pub exit_ready: AtomicBool,
+    pub process: Mutex<ProcessInfo>,
+    restarts: Mutex<RestartTracker>,
+    // 每次成功拉起后端 +1
+    pub generation: AtomicU64,
+    // 用户已确认在磁盘空间不足时仍启动后端，本次运行不再拦截
+    pub low_space_acknowledged: AtomicBool,
+    // 本次运行中启动后端连续失败的次数，成功后清零
+    startup_failures: AtomicU32,
+    // 数据目录锁，后端运行期间持有，停止后释放
+    pub data_lock: Mutex<Option<data_lock::DataDirLock>>,
+    // 当前后端进程最近的 stderr 输出，用于启动失败与崩溃报告
+    pub stderr_tail: Mutex<crash_report::StderrTail>,
+}
+
+// 当前/上一个后端进程的运行信息
+pub struct ProcessInfo {
+    // 尚未收到 Terminated 事件的进程 PID
+    pub pid: Option<u32>,
+    // 最近一次启动时选定的端口
+    pub port: u16,
+    // 本应用访问后端使用的地址：后端监听 0.0.0.0 / :: 时为对应的回环地址
+    pub host: IpAddr,
+    // 后端实际监听的地址（--host）；窗口模式与外部后端为 127.0.0.1
+    pub bind_address: IpAddr,
+    pub started_at: Option<SystemTime>,
+    pub last_exit_code: Option<i32>,
+    pub pid_file: Option<PathBuf>,
+    // 外部后端模式下连接的地址，此时不存在 Sidecar 进程
+    pub external_url: Option<String>,
+    // 当前后端进程的会话 token，外部后端模式下为 None
+    pub token: Option<String>,
+    // 最近一次启动失败的原因，下次启动时清除
+    pub start_error: Option<BackendError>,
+    // 当前后端实际使用的日志级别：启动参数，或运行时切换后的级别；外部后端未切换过时未知
+    pub log_level: Option<String>,
+    // 当前后端 /version 返回的版本；尚未检查或旧版后端不提供时为 None
+    pub backend_version: Option<String>,
+    // 后端运行中数据目录变得不可用（驱动器断开）时的原因，下次成功启动前保留
+    pub data_dir_error: Option<String>,
+    // Windows 上创建当前后端进程使用的标志，例如 "CREATE_NO_WINDOW (0x08000000)"
+    pub creation_flags: Option<String>,
+    // 当前后端进程的完整启动参数（不含可执行文件），外部后端为空
+    pub args: Vec<String>,
+    // 就绪后的监听地址检查结果，尚未检查时为 None
+    pub binding_check: Option<exposure::BindingCheck>,
+    // 当前后端以 HTTPS 运行（config.json 的 backend_tls）
+    pub tls: bool,
+    // 当前后端进程的优先级（config.json 的 backend_priority）；外部后端为 None
+    pub priority: Option<String>,
+    // 上一个后端进程的退出信息，尚未退出过时为 None
+    pub termination: Option<TerminationInfo>,
+}
+
+impl ProcessInfo {
+    pub fn base_url(&self) -> String {
+        match &self.external_url {
+            Some(url) => url.clone(),
+            None => {
+                let scheme = if self.tls { "https" } else { "http" };
+                format!("{}://{}", scheme, SocketAddr::new(self.host, self.port))
+            }
+        }
+    }
+}
+
+impl Default for ProcessInfo {
+    fn default() -> Self {
+        Self {
+            pid: None,
+            port: DEFAULT_BACKEND_PORT,
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            started_at: None,
+            last_exit_code: None,
+            pid_file: None,
+            external_url: None,
+            token: None,
+            start_error: None,
+            log_level: None,
+            backend_version: None,
+            data_dir_error: None,
+            creation_flags: None,
+            args: Vec::new(),
+            binding_check: None,
+            tls: false,
+            priority: None,
+            termination: None,
+        }
+    }
+}
+
+// `get_backend_status` 返回值
+#[derive(serde::Serialize)]
+pub struct BackendStatus {
+    // "sidecar"：由本应用启动；"external"：连接已在运行的外部后端
+    mode: &'static str,
+    base_url: String,
+    running: bool,
+    // 生命周期状态："stopped" / "starting" / "running" / "stopping" / "failed" / "failed_to_start" / "hung" / "crash_loop"
+    lifecycle: Lifecycle,
+    // lifecycle 为 "failed_to_start" 时的失败原因
+    start_error: Option<BackendError>,
+    // lifecycle 为 "crash_loop" 时窗口期内的崩溃次数与最后一次退出码
+    crash_loop: Option<CrashLoopInfo>,
+    pid: Option<u32>,
+    started_at: Option<SystemTime>,
+    uptime_secs: Option<u64>,
+    port: u16,
+    // 后端实际监听的地址；非回环地址表示已按 allow_remote_access 允许其他设备访问
+    bind_address: IpAddr,
+    last_exit_code: Option<i32>,
+    // 后端当前的日志级别，可能与 config.json 不同（修改后尚未重启）
+    log_level: Option<String>,
+    app_version: &'static str,
+    // 构建时确定的期望后端版本
+    expected_backend_version: &'static str,
+    // None 表示未知（旧版后端没有 /version）
+    backend_version: Option<String>,
+    // 后端以 --safe-mode 启动，崩溃后不自动重启
+    safe_mode: bool,
+    // 非 None 表示后端因数据目录不可用而停止，而不是普通崩溃
+    data_dir_error: Option<String>,
+    // 自定义数据目录不可用，本次运行临时以只读方式使用默认目录
+    read_only_session: bool,
+    // Windows 上后端进程的创建标志，便于确认是否以无窗口方式启动；其他平台为 None
+    creation_flags: Option<String>,
+    // 后端的启动参数，含 config.json 中的 backend_args / backend_features
+    args: Vec<String>,
+    // 后端端口能否从非回环地址连上；exposed_on 非空且 allowed 为 false 时已警告
+    binding_check: Option<exposure::BindingCheck>,
+    // 后端以 HTTPS 运行，证书信息见 get_tls_info
+    tls: bool,
+    // 后端进程当前的优先级：normal / below_normal / low；设置失败时为实际生效的值
+    priority: Option<String>,
+    // 上一次退出的退出码、信号、原因与 stderr 末尾，同 `backend://stopped` 的负载
+    termination: Option<TerminationInfo>,
+}
+
+/// 崩溃重启的退避策略，默认值见 RESTART_* 常量
+#[derive(Clone, Copy, Debug)]
+pub struct RestartPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub max_attempts: u32,
+    pub window: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: RESTART_BASE_DELAY,
+            max_delay: RESTART_MAX_DELAY,
+            max_attempts: RESTART_MAX_ATTEMPTS,
+            window: RESTART_WINDOW,
+        }
+    }
+}
+
+/// 记录最近 window 内每次崩溃的时间；稳定运行超过窗口期后旧记录过期，计数随之清零
+#[derive(Default)]
+pub struct RestartTracker {
+    policy: RestartPolicy,
+    crashes: VecDeque<Instant>,
+}
+
+impl RestartTracker {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self { policy, crashes: VecDeque::new() }
+    }
+
+    /// 窗口期内的崩溃次数
+    pub fn attempts(&self) -> u32 {
+        self.crashes.len() as u32
+    }
+
+    /// 清空崩溃计数
+    pub fn reset(&mut self) {
+        self.crashes.clear();
+    }
+
+    /// 记录一次崩溃，返回本次重启前的等待时间；超过上限返回 None
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        while self.crashes.front().is_some_and(|t| now.duration_since(*t) > self.policy.window) {
+            self.crashes.pop_front();
+        }
+        self.crashes.push_back(now);
+        let attempts = self.attempts();
+        if attempts > self.policy.max_attempts {
+            return None;
+        }
+        let delay = self.policy.base_delay.checked_mul(2u32.saturating_pow(attempts - 1));
+        Some(delay.map_or(self.policy.max_delay, |delay| delay.min(self.policy.max_delay)))
+    }
+}
+
+// `backend://crash-loop` 事件负载，也用于 get_backend_status
+#[derive(Clone, serde::Serialize)]
+pub struct CrashLoopInfo {
+    attempts: u32,
+    last_exit_code: Option<i32>,
+}
+
+// `backend://restarted` 事件负载；reason 为 "crash"（自动重启）或 "manual"
+#[derive(Clone, serde::Serialize)]
+struct BackendRestartedPayload {
+    reason: &'static str,
+    attempt: u32,
+    pid: u32,
+    // 新进程的会话 token，前端需替换旧值
+    token: Option<String>,
+}
+
+// `backend://ready` 事件负载：后端已通过健康检查；duration_ms 为从拉起进程到就绪的耗时。
+// 作为状态通知已弃用（改用 `backend://state`），启动耗时仍只在这里提供
+#[derive(Clone, serde::Serialize)]
+pub struct BackendReadyPayload {
+    pub pid: u32,
+    pub port: u16,
+    pub duration_ms: u64,
+    pub start_kind: startup::StartKind,
+}
+
+// `backend://lifecycle` 事件负载：生命周期状态已改变。已弃用，改用 `backend://state`
+#[derive(Clone, serde::Serialize)]
+pub struct LifecyclePayload {
+    pub state: Lifecycle,
+}
+
+// `backend://exited` 事件负载；intentional 为 true 表示由应用主动停止
+#[derive(Clone, serde::Serialize)]
+struct BackendExitedPayload {
+    pid: u32,
+    code: Option<i32>,
+    intentional: bool,
+}
+
+
+// `backend://stopping` / `backend://stopping-progress` 事件负载：正在等待后端优雅退出（写完数据），
+// 前端与启动画面据此显示“正在保存数据…（3 秒）”
+#[derive(Clone, serde::Serialize)]
+struct StoppingPayload {
+    pid: u32,
+    elapsed_secs: u64,
+    timeout_secs: u64,
+}
+
+// `backend://shutdown-timeout` 事件负载：后端未在 timeout_secs 内退出，随后被强制结束
+#[derive(Clone, serde::Serialize)]
+struct ShutdownTimeoutPayload {
+    pid: u32,
+    timeout_secs: u64,
+}
+
+// 生成 32 字节随机 token（hex 编码）
+fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate auth token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// ============================================
+// Python Backend Sidecar
+// ============================================
+
+// 端口是否已被占用：Windows 上其他进程监听 0.0.0.0 时仍可绑定 127.0.0.1，
+// 因此除了绑定测试还需做一次连接测试
+fn port_in_use(port: u16) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok()
+        || TcpListener::bind(addr).is_err()
+}
+
+// 查找监听该端口的进程（系统不支持或无权限时返回 None）
+fn find_port_owner(port: u16) -> Option<(u32, String)> {
+    let processes = listeners::get_processes_by_port(port).ok()?;
+    let process = processes.into_iter().next()?;
+    Some((process.pid, process.name))
+}
+
+pub fn check_port_available(port: u16) -> Result<(), BackendError> {
+    if !port_in_use(port) {
+        return Ok(());
+    }
+    let owner = find_port_owner(port);
+    Err(BackendError::PortInUse {
+        port,
+        pid: owner.as_ref().map(|(pid, _)| *pid),
+        process_name: owner.map(|(_, name)| name),
+    })
+}
+
+// 寻找空闲端口：优先 preferred，其次端口范围，全部被占用时交给系统分配
+fn pick_free_port(preferred: u16) -> Result<u16, String> {
+    let candidates = std::iter::once(preferred).chain(BACKEND_PORT_RANGE);
+    for port in candidates {
+        if !port_in_use(port) {
+            return Ok(port);
+        }
+    }
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to find a free port: {}", e))
+}
+
+// 优雅停止：请求 /shutdown 后等待进程自行退出的时间，超时强制 kill
+pub fn shutdown_timeout(app: &AppHandle) -> Duration {
+    Duration::from_secs(app_config(app).shutdown_timeout_secs)
+}
+
+// 后端启动命令：默认为打包的 Sidecar，配置 backend_source 时改为用 Python 运行源码。
+// 两者都返回 shell 插件的 Command，后续输出处理、重启、kill 走同一套逻辑
+fn backend_command(app: &AppHandle, config: &config::AppConfig) -> Result<Command, BackendError> {
+    let shell = app.shell();
+    if cfg!(feature = "test-sidecar") {
+        let path = test_sidecar_path()?;
+        app_log!("Running test sidecar: {:?}", path);
+        return Ok(shell.command(path));
+    }
+    let Some(source_dir) = &config.backend_source else {
+        if !config.skip_sidecar_verification {
+            sidecar_integrity::verify(app)?;
+        }
+        return shell
+            .sidecar(SIDECAR_NAME)
+            .map_err(|e| format!("Failed to create sidecar command: {}", e).into());
+    };
+    if !cfg!(debug_assertions) && !config.allow_source_backend {
+        return Err("backend_source is only honoured in debug builds unless allow_source_backend is set"
+            .to_string()
+            .into());
+    }
+    let script = source_dir.join(BACKEND_SOURCE_SCRIPT);
+    if !script.is_file() {
+        return Err(format!("Backend source {:?} does not exist", script).into());
+    }
+    app_log!("Running backend from source: {:?}", script);
+    Ok(shell
+        .command(PYTHON_EXECUTABLE)
+        .current_dir(source_dir)
+        .args([BACKEND_SOURCE_SCRIPT]))
+}
+
+// test-sidecar 构建拉起的模拟 Sidecar：DUNCREW_TEST_SIDECAR 指定的路径，否则为与应用同目录的 mock_server
+fn test_sidecar_path() -> Result<PathBuf, BackendError> {
+    if let Some(path) = std::env::var_os(TEST_SIDECAR_ENV) {
+        return Ok(PathBuf::from(path));
+    }
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the test sidecar: {}", e))?;
+    Ok(exe.with_file_name(format!("mock_server{}", std::env::consts::EXE_SUFFIX)))
+}
+
+// 确保本进程持有数据目录锁；已持有同一目录的锁时直接返回（崩溃重启沿用）
+fn ensure_data_lock(app: &AppHandle, data_dir: &Path) -> Result<(), BackendError> {
+    let Some(state) = app.try_state::<ServerState>() else {
+        return Ok(());
+    };
+    let mut guard = state.data_lock.lock().unwrap();
+    if guard.as_ref().is_some_and(|lock| lock.data_dir() == data_dir) {
+        return Ok(());
+    }
+    // 数据目录已改变，先释放旧锁
+    guard.take();
+    match data_lock::acquire(data_dir) {
+        Ok(lock) => {
+            *guard = Some(lock);
+            Ok(())
+        }
+        Err(data_lock::LockError::Held { pid }) => Err(BackendError::DataDirLocked {
+            path: data_dir.to_string_lossy().to_string(),
+            pid,
+        }),
+        Err(data_lock::LockError::Io(message)) => Err(BackendError::Other { message }),
+    }
+}
+
+fn release_data_lock(state: &ServerState) {
+    state.data_lock.lock().unwrap().take();
+}
+
+// 数据目录不存在、无法创建或不可写时返回 DataDirUnavailable，不让后端启动后立即因 IO 错误退出。
+// 目录存在时写入测试文件，区分“无法创建”与“存在但只读”
+fn available_data_dir(app: &AppHandle) -> Result<PathBuf, BackendError> {
+    let unavailable = |issue, message| {
+        let path = data_dir::temporary_dir()
+            .or_else(|| crate::configured_data_dir(app).ok())
+            .unwrap_or_default();
+        BackendError::DataDirUnavailable { path: path.to_string_lossy().to_string(), issue, message }
+    };
+    let dir = backend_data_dir(app).map_err(|message| {
+        let missing = crate::configured_data_dir(app)
+            .is_ok_and(|dir| !dir.exists() && data_dir::is_custom_location(app, &dir));
+        let issue = if missing { data_dir::DataDirIssue::Missing } else { data_dir::DataDirIssue::CannotCreate };
+        unavailable(issue, message)
+    })?;
+    data_dir::check_writable(&dir).map_err(|message| unavailable(data_dir::DataDirIssue::NotWritable, message))?;
+    Ok(dir)
+}
+
+// 后端监听的地址：只有无界面模式按 bind_address 监听，窗口模式的后端不对外暴露。
+// 非回环地址必须同时设置 allow_remote_access，且后端有会话 token 保护，否则拒绝启动而不是静默回退到本机
+fn effective_bind_address(app: &AppHandle, config: &config::AppConfig, token: &str) -> Result<IpAddr, BackendError> {
+    if !headless::enabled(app) {
+        return Ok(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+    let bind_address: IpAddr = config
+        .bind_address
+        .trim()
+        .parse()
+        .map_err(|_| format!("bind_address must be an IP address, got \"{}\"", config.bind_address))?;
+    if bind_address.is_loopback() {
+        return Ok(bind_address);
+    }
+    if !config.allow_remote_access {
+        return Err(BackendError::RemoteAccessNotAllowed { bind_address: bind_address.to_string() });
+    }
+    if token.is_empty() {
+        return Err(format!("Refusing to listen on {} without an auth token", bind_address).into());
+    }
+    Ok(bind_address)
+}
+
+/// 传给后端的数据目录与缓存目录参数（--path、--cache-dir）。与 get_app_paths 使用同一规范化，
+/// 路径无法原样表示为 Unicode 时返回 InvalidPath，而不是传一个有损转换后的路径
+pub fn path_args(data_dir: &Path) -> Result<Vec<String>, BackendError> {
+    let data_path = crate::folders::path_arg(&crate::folders::canonical(data_dir))
+        .map_err(|message| BackendError::DataDirUnavailable {
+            path: data_dir.to_string_lossy().to_string(),
+            issue: data_dir::DataDirIssue::InvalidPath,
+            message,
+        })?;
+    let mut args = vec!["--path".to_string(), data_path];
+    args.extend(cache::sidecar_args(data_dir)?);
+    Ok(args)
+}
+
+// 拉起进程前的准备：选端口、检查磁盘空间与数据目录锁、校验 Sidecar、生成 token 与证书、拼出启动参数
+struct PreparedSpawn {
+    data_dir: PathBuf,
+    port: u16,
+    token: String,
+    bind_address: IpAddr,
+    args: Vec<String>,
+    tls: bool,
+    log_level: String,
+    priority: String,
+    console: bool,
+    creation_flags: Option<String>,
+    // mock-backend 构建为 None，拉起进程内的模拟后端
+    command: Option<Command>,
+}
+
+/// 组装后端启动参数所需的输入，由 prepare_spawn 收集。
+/// 每个参数都必须能被 duncrew-server.py 的 parse_args 接受，否则后端以退出码 2 退出并陷入崩溃循环
+struct SidecarLaunch<'a> {
+    /// path_args 的结果：--path、--cache-dir
+    path_args: Vec<String>,
+    port: u16,
+    log_level: &'a str,
+    bind_address: IpAddr,
+    /// tls::sidecar_args 的结果，未启用 TLS 时为空
+    tls_args: Vec<String>,
+    safe_mode: bool,
+    read_only: bool,
+    /// 已规范化的数据目录，用于实例指纹
+    data_dir: &'a Path,
+    locale: &'a locale::LocaleInfo,
+    /// backend_args 与 backend_features，排在最后
+    extra_args: Vec<String>,
+}
+
+impl SidecarLaunch<'_> {
+    fn args(self) -> Vec<String> {
+        let mut args = self.path_args;
+        args.extend([
+            "--port".to_string(),
+            self.port.to_string(),
+            "--log-level".to_string(),
+            self.log_level.to_string(),
+            // 后端自身默认监听 0.0.0.0，始终显式传入：非无界面模式下只监听回环地址
+            "--host".to_string(),
+            self.bind_address.to_string(),
+        ]);
+        args.extend(self.tls_args);
+        args.extend(process_guard::sidecar_args());
+        if self.safe_mode {
+            args.push(safe_mode::SIDECAR_ARG.to_string());
+        }
+        if self.read_only {
+            args.push(data_dir::READ_ONLY_ARG.to_string());
+        }
+        args.extend(instance::sidecar_args(self.data_dir));
+        args.extend(locale::sidecar_args(self.locale));
+        args.extend(self.extra_args);
+        args
+    }
+}
+
+// 含端口探测、哈希校验、证书生成等同步操作，BackendManager::start 在阻塞线程中调用，不持有句柄锁
+fn prepare_spawn(app: &AppHandle, port_policy: PortPolicy) -> Result<PreparedSpawn, BackendError> {
+    let config = app_config(app);
+
+    // 获取用户数据目录
+    let data_dir = available_data_dir(app)?;
+    app.state::<ServerState>().process.lock().unwrap().data_dir_error = None;
+
+    let path_args = path_args(&data_dir)?;
+    let port = match port_policy {
+        PortPolicy::Exact(port) => {
+            check_port_available(port)?;
+            port
+        }
+        PortPolicy::Any(preferred) => pick_free_port(preferred)?,
+    };
+
+    if let Some(low) = storage::check_free_space(app, &data_dir, "start_backend") {
+        let acknowledged = app
+            .try_state::<ServerState>()
+            .is_some_and(|state| state.low_space_acknowledged.load(Ordering::SeqCst));
+        if !acknowledged {
+            return Err(BackendError::LowDiskSpace {
+                available_bytes: low.available_bytes,
+                minimum_bytes: low.minimum_bytes,
+            });
+        }
+    }
+
+    let token = generate_token()?;
+    logs::add_secret(&token);
+    let bind_address = effective_bind_address(app, &config, &token)?;
+    ensure_data_lock(app, &data_dir)?;
+    // 上次清除缓存时被占用的文件，后端启动前不会再被占用
+    cache::delete_pending(&data_dir);
+
+    app_log!("Starting backend server...");
+    app_log!("Data directory: {}", path_args[1]);
+    app_log!("Port: {}", port);
+    let env = config.backend_env();
+    if !env.is_empty() {
+        // 只记录变量名，值可能包含凭据
+        app_log!("Backend env overrides: {}", env.keys().cloned().collect::<Vec<_>>().join(", "));
+    }
+    let secret_env = secrets::backend_env(app);
+    secret_env.values().for_each(|value| logs::add_secret(value));
+    if !secret_env.is_empty() {
+        app_log!("Backend secrets: {}", secret_env.keys().cloned().collect::<Vec<_>>().join(", "));
+    }
+
+    app_log!("Backend bind address: {}", bind_address);
+    // 模拟后端与 test-sidecar 的 mock_server 都不支持 HTTPS
+    let tls_args = if cfg!(any(feature = "mock-backend", feature = "test-sidecar")) {
+        Vec::new()
+    } else {
+        tls::sidecar_args(config.backend_tls, &data_dir, bind_address)?
+    };
+    let tls = !tls_args.is_empty();
+    let system_locale = locale::for_backend(app);
+    let args = SidecarLaunch {
+        path_args,
+        port,
+        log_level: &config.backend_log_level,
+        bind_address,
+        tls_args,
+        safe_mode: safe_mode::is_active(),
+        read_only: data_dir::temporary_dir().is_some(),
+        data_dir: &crate::folders::canonical(&data_dir),
+        locale: &system_locale,
+        extra_args: config.backend_extra_args(),
+    }
+    .args();
+    // 逐个加引号记录，含空格的路径能看出参数边界
+    app_log!("Backend arguments: {:?}", args);
+
+    let console = cfg!(debug_assertions) && config.backend_console;
+    let creation_flags = if cfg!(feature = "mock-backend") { None } else { sidecar::creation_flags(console) };
+    let command = if cfg!(feature = "mock-backend") {
+        None
+    } else {
+        Some(
+            backend_command(app, &config)?
+                .args(&args)
+                .envs(env)
+                .envs(locale::sidecar_env(&system_locale))
+                .envs(secret_env)
+                .env(AUTH_TOKEN_ENV, &token),
+        )
+    };
+    Ok(PreparedSpawn {
+        data_dir,
+        port,
+        token,
+        bind_address,
+        args,
+        tls,
+        log_level: config.backend_log_level,
+        priority: config.backend_priority,
+        console,
+        creation_flags,
+        command,
+    })
+}
+
+// 拉起进程并记录 PID、端口等，很快返回；返回的 watch 开始读取输出
+fn launch(app: &AppHandle, prepared: PreparedSpawn) -> Result<Launch, BackendError> {
+    let PreparedSpawn {
+        data_dir,
+        port,
+        token,
+        bind_address,
+        args,
+        tls,
+        log_level,
+        priority,
+        console,
+        creation_flags,
+        command,
+    } = prepared;
+    if let Some(flags) = &creation_flags {
+        app_log!("Backend creation flags: {}", flags);
+    }
+    let Spawned { handle, events } = match command {
+        Some(command) => sidecar::spawn(command, console)?,
+        None => mock::spawn(port, &data_dir)?,
+    };
+    let pid = handle.pid();
+    process_guard::attach(pid);
+    let priority = if cfg!(feature = "mock-backend") {
+        None
+    } else {
+        Some(apply_priority(pid, &priority).unwrap_or_else(|| "normal".to_string()))
+    };
+    let pid_path = data_dir.join(pid_file::PID_FILE_NAME);
+    pid_file::write(&pid_path, pid);
+    if let Some(state) = app.try_state::<ServerState>() {
+        let mut process = state.process.lock().unwrap();
+        process.pid = Some(pid);
+        process.port = port;
+        process.host = match bind_address {
+            IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            ip => ip,
+        };
+        process.bind_address = bind_address;
+        process.started_at = Some(SystemTime::now());
+        process.pid_file = Some(pid_path);
+        process.token = Some(token);
+        process.log_level = Some(log_level);
+        process.creation_flags = creation_flags;
+        process.args = args;
+        process.binding_check = None;
+        process.tls = tls;
+        process.priority = priority;
+    }
+    let app = app.clone();
+    let watch = Box::new(move || watch_output(&app, pid, port, &data_dir, events));
+    Ok(Launch { handle, watch })
+}
+
+// 异步读取输出，同时写入 logs/backend.log
+fn watch_output(app: &AppHandle, pid: u32, port: u16, data_dir: &Path, mut rx: tokio::sync::mpsc::Receiver<BackendEvent>) {
+    let mut backend_log = logs::RotatingLog::open(
+        data_dir.join(logs::LOGS_DIR_NAME).join(logs::BACKEND_LOG_NAME),
+    );
+    backend_log.write_line("app", &format!("=== backend started (pid {}, port {}) ===", pid, port));
+    let generation = app
+        .try_state::<ServerState>()
+        .map(|state| state.generation.fetch_add(1, Ordering::SeqCst) + 1)
+        .unwrap_or(0);
+    let log_buffer = app.state::<logs::LogBuffer>();
+    if log_buffer.has_history() {
+        let marker = logs::BackendLine::marker(format!("=== backend restarted (pid {}) ===", pid));
+        log_buffer.push(&marker, generation);
+    }
+    if let Some(state) = app.try_state::<ServerState>() {
+        state.stderr_tail.lock().unwrap().clear();
+    }
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                BackendEvent::Stdout(bytes) => {
+                    record_backend_output(&app_handle, &mut backend_log, "stdout", &bytes, generation);
+                }
+                BackendEvent::Stderr(bytes) => {
+                    let line = String::from_utf8_lossy(&bytes);
+                    app_handle.state::<ServerState>().stderr_tail.lock().unwrap().push(line.trim_end_matches(['\r', '\n']));
+                    record_backend_output(&app_handle, &mut backend_log, "stderr", &bytes, generation);
+                }
+                BackendEvent::Error(err) => {
+                    app_error!("Backend process error: {}", err);
+                    backend_log.write_line("error", &err);
+                }
+                BackendEvent::Terminated { code, signal } => {
+                    app_log!("Backend process terminated with code: {:?}, signal: {:?}", code, signal);
+                    backend_log.write_line(
+                        "app",
+                        &format!("=== backend terminated (code {:?}, signal {:?}) ===", code, signal),
+                    );
+                    backend_log.flush();
+                    handle_backend_exit(&app_handle, pid, code, signal).await;
+                    break;
+                }
+            }
+        }
+    });
+
+    app_log!("Backend process spawned (pid {}), waiting for it to become ready", pid);
+}
+
+// 外部后端模式：不启动 Sidecar，只记录地址并验证其能响应健康检查
+pub fn connect_external_backend(app: &AppHandle, url: String) {
+    app_log!("Using external backend at {}", url);
+    // 外部后端通常与应用共用数据目录，同样需要持有锁
+    if let Err(e) = backend_data_dir(app)
+        .map_err(BackendError::from)
+        .and_then(|dir| ensure_data_lock(app, &dir))
+    {
+        app_error!("Not connecting to external backend: {}", e);
+        crate::app_events::emit_to_frontend(app, "backend://start-failed", e);
+        return;
+    }
+    {
+        let state = app.state::<ServerState>();
+        let mut process = state.process.lock().unwrap();
+        if let Some(port) = reqwest::Url::parse(&url).ok().and_then(|u| u.port_or_known_default()) {
+            process.port = port;
+        }
+        process.external_url = Some(url.clone());
+        process.bind_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        process.tls = false;
+    }
+    app.state::<ServerState>().backend.mark_external(app);
+    tls::reset_client();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match health::probe(&BackendClient::current(&app), Duration::from_secs(2)).await {
+            Ok(_) => {
+                let port = app.state::<ServerState>().process.lock().unwrap().port;
+                if let Some(identity) = foreign_instance(&app, url.clone()).await {
+                    let error = BackendError::ForeignInstance {
+                        port,
+                        version: identity.version,
+                        instance_id: identity.instance_id,
+                    };
+                    app_error!("Not using external backend at {}: {}", url, error);
+                    crate::app_events::emit_to_frontend(&app, "backend://start-failed", error.clone());
+                    let message = i18n::tf("dialog.foreign_instance.external", &[("url", &url), ("error", &error)]);
+                    alerts::show(&app, Alert::new(AlertCategory::Instance, message).kind(MessageDialogKind::Warning));
+                    return;
+                }
+                app.state::<ServerState>().process.lock().unwrap().started_at = Some(SystemTime::now());
+                app_log!("External backend at {} is reachable", url);
+                version::check(&app).await;
+            }
+            Err(e) => {
+                // 健康检查循环会继续探测，外部后端之后启动也能连上
+                let error = BackendError::from(format!("External backend at {} is not reachable: {}", url, e));
+                app_error!("{}", error);
+                crate::app_events::emit_to_frontend(&app, "backend://start-failed", error);
+            }
+        }
+    });
+}
+
+// 处理一行后端输出：控制台（经 tracing）+ backend.log + 内存缓冲，结构化错误日志额外通知前端
+fn record_backend_output(
+    app: &AppHandle,
+    backend_log: &mut logs::RotatingLog,
+    stream: &'static str,
+    bytes: &[u8],
+    generation: u64,
+) {
+    let raw = String::from_utf8_lossy(bytes);
+    if stream == "stdout" && crate::progress::handle_backend_line(app, &raw) {
+        return;
+    }
+    let line = logs::BackendLine::parse(stream, raw.trim_end_matches(['\r', '\n']));
+    line.emit();
+    backend_log.write_line(&format!("{}/{}", stream, line.level.as_str()), &line.display());
+    let entry = app.state::<logs::LogBuffer>().push(&line, generation);
+    if line.structured && line.level >= logs::LogLevel::Error {
+        let _ = app.emit("backend://error", entry);
+    }
+}
+
+// 后端进程退出：清理句柄，非主动停止时写崩溃报告并按退避策略重启
+async fn handle_backend_exit(app: &AppHandle, pid: u32, code: Option<i32>, signal: Option<i32>) {
+    let Some(state) = app.try_state::<ServerState>() else {
+        return;
+    };
+    // 必须在清除 pid 之前记录：停止流程等到 pid 清空才会把状态切回 Stopped
+    let intentional = !state.backend.record_exit(app, pid).await;
+    let termination =
+        TerminationInfo::new(pid, code, signal, intentional, state.stderr_tail.lock().unwrap().lines());
+    if let Some(reason) = &termination.reason {
+        app_log!("Backend process {} exited: {}", pid, reason);
+    }
+    let mut started_at = None;
+    {
+        let mut process = state.process.lock().unwrap();
+        if process.pid == Some(pid) {
+            started_at = process.started_at;
+            process.pid = None;
+            process.started_at = None;
+            process.token = None;
+            process.last_exit_code = code;
+            process.termination = Some(termination.clone());
+            if let Some(path) = process.pid_file.take() {
+                pid_file::remove(&path);
+            }
+        }
+    }
+    crate::progress::clear_backend_jobs(app);
+    crate::app_events::emit_to_frontend(app, "backend://exited", BackendExitedPayload { pid, code, intentional });
+    // 已弃用的状态别名（见 state.rs），退出详情仍随它与 get_backend_status 提供
+    crate::app_events::emit_to_frontend(app, "backend://stopped", termination);
+    if intentional {
+        return;
+    }
+    // 数据目录所在驱动器断开导致的退出不算崩溃：重启也会失败，等用户重新连接后手动重启
+    if let Err(BackendError::DataDirUnavailable { path, issue, message }) = available_data_dir(app) {
+        app_error!("Backend exited (code {:?}) because data directory {} is unavailable: {}", code, path, message);
+        state.process.lock().unwrap().data_dir_error = Some(message.clone());
+        let _ = app.emit("backend://data-dir-unavailable", BackendError::DataDirUnavailable { path: path.clone(), issue, message });
+        notify::backend_failure(app, &i18n::tf("notify.data_dir_unavailable", &[("path", &path)]), true);
+        return;
+    }
+    telemetry::record(app, telemetry::Event::BackendCrash { exit_code: code });
+    let restarting = schedule_crash_restart(app, code);
+    if code != Some(0) {
+        let stderr = state.stderr_tail.lock().unwrap().lines();
+        match crash_report::write(
+            app,
+            &crash_report::Crash { kind: crash_report::ReportKind::Crash, pid, code, started_at, stderr, snapshot: Vec::new() },
+        ) {
+            Ok(report) => {
+                app_error!("Backend crash report written to {:?}", report);
+                // 崩溃循环已有单独的提示
+                if state.backend.lifecycle() != Lifecycle::CrashLoop {
+                    crash_report::show_dialog(app, report, code, restarting);
+                }
+            }
+            Err(e) => app_error!("Failed to write crash report: {}", e),
+        }
+    }
+}
+
+// 按退避策略安排崩溃重启，返回是否已安排；重启前状态不再是 Failed（已手动重启或已停止）时放弃
+fn schedule_crash_restart(app: &AppHandle, code: Option<i32>) -> bool {
+    let state = app.state::<ServerState>();
+    let exit_code = code.map_or_else(|| i18n::t("common.unknown"), |c| c.to_string());
+    if !app_config(app).auto_restart || safe_mode::is_active() {
+        app_error!("Backend exited unexpectedly (code {:?}), auto-restart is disabled", code);
+        notify::backend_failure(
+            app,
+            &i18n::tf("notify.crashed", &[("code", &exit_code)]),
+            true,
+        );
+        return false;
+    }
+
+    let delay = state.restarts.lock().unwrap().next_delay();
+    let Some(delay) = delay else {
+        let attempts = state.restarts.lock().unwrap().attempts();
+        app_error!("Backend crashed {} times within {:?}, giving up auto-restart", attempts, RESTART_WINDOW);
+        enter_crash_loop(app, CrashLoopInfo { attempts, last_exit_code: code });
+        return false;
+    };
+    notify::backend_failure(
+        app,
+        &i18n::tf("notify.crashed.restarting", &[("code", &exit_code)]),
+        false,
+    );
+    spawn_crash_restart(app, delay);
+    true
+}
+
+// 崩溃循环：停止自动重启并询问用户。反复崩溃多半是插件或缓存损坏，提供以安全模式重启；
+// 无界面模式下无法询问，直接以安全模式再启动一次
+fn enter_crash_loop(app: &AppHandle, info: CrashLoopInfo) {
+    if !app.state::<ServerState>().backend.mark_crash_loop(app) {
+        return;
+    }
+    let _ = app.emit("backend://restart-failed", info.attempts);
+    // 已弃用的状态别名，改用 `backend://state`
+    crate::app_events::emit_to_frontend(app, "backend://crash-loop", info.clone());
+    notify::backend_failure(app, &i18n::t("notify.crash_loop"), true);
+    if headless::enabled(app) {
+        restart_in_safe_mode(app);
+        return;
+    }
+    let (safe_mode_label, open_logs, quit) =
+        (i18n::t("button.restart_safe_mode"), i18n::t("button.open_logs"), i18n::t("button.quit"));
+    let exit_code = info.last_exit_code.map_or_else(|| i18n::t("common.unknown"), |c| c.to_string());
+    let args: [(&str, &dyn fmt::Display); 2] = [("attempts", &info.attempts), ("code", &exit_code)];
+    let alert = Alert::new(AlertCategory::Crash, i18n::tf("dialog.crash_loop", &args))
+        .kind(MessageDialogKind::Error)
+        .priority(AlertPriority::High)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(safe_mode_label.clone(), open_logs.clone(), quit.clone()));
+    alerts::ask_with_result(app, alert, {
+            let app = app.clone();
+            move |result| {
+                let MessageDialogResult::Custom(choice) = result else {
+                    return;
+                };
+                if choice == safe_mode_label {
+                    restart_in_safe_mode(&app);
+                } else if choice == open_logs {
+                    if let Err(e) = crate::folders::logs_dir(&app).and_then(|dir| crate::folders::open(&dir)) {
+                        app_error!("{}", e);
+                    }
+                } else if choice == quit {
+                    crate::session::set_exit_reason(crate::session::SessionEnd::BackendCrashLoop);
+                    app.exit(0);
+                }
+            }
+        });
+}
+
+fn restart_in_safe_mode(app: &AppHandle) {
+    safe_mode::enter(app, "crash_loop");
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<ServerState>();
+        if let Err(e) = restart_backend_exclusive(&app, &state).await {
+            app_error!("Failed to restart backend in safe mode: {}", e);
+        }
+    });
+}
+
+// 等待 delay 后重启仍处于 Failed 状态的后端
+fn spawn_crash_restart(app: &AppHandle, delay: Duration) {
+    let app = app.clone();
+    let task = async move {
+        app_error!("Backend exited unexpectedly, restarting in {:?}", delay);
+        let state = app.state::<ServerState>();
+        let port = || PortPolicy::Any(state.process.lock().unwrap().port);
+        // 等待期间可能已被手动重启拉起新进程，或应用已停止后端
+        let Some(result) = state.backend.restart_after_crash(&app, delay, port).await else {
+            return;
+        };
+        match result {
+            Ok(pid) => {
+                let attempt = state.restarts.lock().unwrap().attempts();
+                telemetry::record(&app, telemetry::Event::BackendRestart { attempt });
+                let payload = BackendRestartedPayload {
+                    reason: "crash",
+                    attempt,
+                    pid,
+                    token: state.process.lock().unwrap().token.clone(),
+                };
+                crate::app_events::emit_to_frontend(&app, "backend://restarted", payload);
+            }
+            Err(e) => {
+                // 启动本身失败不会产生 Terminated 事件，需要在这里继续退避
+                app_error!("Failed to restart backend: {}", e);
+                schedule_crash_restart(&app, None);
+            }
+        }
+    };
+    tauri::async_runtime::spawn(task.instrument(restart_span("crash")));
+}
+
+// 请求后端自行退出，让 Python 有机会关闭 SQLite 等资源
+async fn request_shutdown(client: &BackendClient) -> Result<(), BackendClientError> {
+    client.post("/shutdown", Duration::from_secs(2)).await?;
+    Ok(())
+}
+
+// 轮询健康端点直到刚拉起的进程就绪；进程提前退出或超时返回失败原因
+async fn wait_for_ready(app: &AppHandle, pid: u32, timeout: Duration) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    let deadline = Instant::now() + timeout;
+    loop {
+        let (current, exit_code) = {
+            let process = state.process.lock().unwrap();
+            (process.pid, process.last_exit_code)
+        };
+        if current != Some(pid) {
+            return Err(format!(
+                "Backend exited during startup (exit code {})",
+                exit_code.map_or_else(|| "unknown".to_string(), |c| c.to_string())
+            ));
+        }
+        if health::probe(&BackendClient::current(app), Duration::from_secs(1)).await.is_ok() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("Backend did not become ready within {}s", timeout.as_secs()));
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
+
+// 通过后端的 POST /log-level 运行时切换日志级别；旧版后端没有该端点时返回错误
+async fn request_log_level(client: &BackendClient, level: &str) -> Result<(), BackendClientError> {
+    client.post_json("/log-level", &serde_json::json!({ "level": level }), Duration::from_secs(2)).await?;
+    Ok(())
+}
+
+// 等待指定进程的 Terminated 事件，超时返回 false
+async fn wait_for_exit(state: &ServerState, pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while state.process.lock().unwrap().pid == Some(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    true
+}
+
+// 等待 /shutdown 之后的退出：先发送 backend://stopping，之后每秒发送 backend://stopping-progress；
+// 超时发送 backend://shutdown-timeout（记入生命周期历史）并返回 false
+async fn wait_for_graceful_exit(app: &AppHandle, state: &ServerState, pid: u32, timeout: Duration) -> bool {
+    let timeout_secs = timeout.as_secs();
+    let started = Instant::now();
+    let _ = app.emit("backend://stopping", StoppingPayload { pid, elapsed_secs: 0, timeout_secs });
+    let mut reported = 0;
+    while state.process.lock().unwrap().pid == Some(pid) {
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            let _ = app.emit("backend://shutdown-timeout", ShutdownTimeoutPayload { pid, timeout_secs });
+            return false;
+        }
+        if elapsed.as_secs() > reported {
+            reported = elapsed.as_secs();
+            let _ = app.emit("backend://stopping-progress", StoppingPayload { pid, elapsed_secs: reported, timeout_secs });
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    true
+}
+
+/// 重启后端：停止当前进程，等待其真正退出后重新启动。
+///
+/// 成功返回新进程 PID，并广播 `backend://restarted`
+/// （payload: `{ reason: "manual", attempt: 0, pid }`）；
+/// 已有重启在进行中时等待其完成并返回同一结果，不会拉起第二个 Sidecar。
+/// `safe_mode` 为 false 时退出安全模式正常启动，为 true 时进入安全模式，省略则保持当前模式。
+#[tauri::command]
+pub async fn restart_backend(
+    app: AppHandle,
+    state: tauri::State<'_, ServerState>,
+    safe_mode: Option<bool>,
+) -> Result<u32, BackendError> {
+    match safe_mode {
+        Some(true) => safe_mode::enter(&app, "manual"),
+        Some(false) => safe_mode::exit(&app),
+        None => {}
+    }
+    restart_backend_exclusive(&app, &state).await
+}
+
+static NEXT_RESTART_SPAN: AtomicU64 = AtomicU64::new(1);
+
+// 一次重启的 span：其中的停止、强制结束、健康检查与启动日志都带上同一个 op，在 app.log 中可以关联
+fn restart_span(reason: &'static str) -> tracing::Span {
+    tracing::info_span!("backend_restart", op = NEXT_RESTART_SPAN.fetch_add(1, Ordering::SeqCst), reason)
+}
+
+// 带防重入保护的重启，命令与健康检查共用
+pub async fn restart_backend_exclusive(app: &AppHandle, state: &ServerState) -> Result<u32, BackendError> {
+    async {
+        if let Some(url) = &state.process.lock().unwrap().external_url {
+            return Err(format!("Backend at {} is external and must be restarted where it runs", url).into());
+        }
+        let port = state.process.lock().unwrap().port;
+        // 手动重启视为新的开始，清空崩溃计数
+        let reset = || state.restarts.lock().unwrap().reset();
+        match state.backend.restart(app, shutdown_timeout(app), PortPolicy::Any(port), reset).await? {
+            Restart::Started(pid) => {
+                emit_restarted(app, state, "manual", pid);
+                Ok(pid)
+            }
+            // 沿用进行中那次启动的结果，重启通知由它发送
+            Restart::Joined(pid) => Ok(pid),
+        }
+    }
+    .instrument(restart_span("manual"))
+    .await
+}
+
+// `set_backend_log_level` 返回值
+#[derive(serde::Serialize)]
+pub struct SetLogLevelResult {
+    level: String,
+    // 后端已通过运行时接口切换到新级别
+    applied: bool,
+    // 后端不支持运行时切换，重启后才生效
+    restart_required: bool,
+    // 已按 restart 参数重启后端
+    restarted: bool,
+}
+
+/// 修改后端日志级别（error / warn / info / debug）并写入 config.json。
+/// 后端正在运行时先尝试运行时切换；后端不支持时 restart_required 为 true，
+/// `restart` 为 true 则立即重启后端使其生效
+#[tauri::command]
+pub async fn set_backend_log_level(
+    app: AppHandle,
+    state: tauri::State<'_, ServerState>,
+    level: String,
+    restart: Option<bool>,
+) -> Result<SetLogLevelResult, BackendError> {
+    let level = level.trim().to_lowercase();
+    app.state::<config::ConfigState>()
+        .update(|config| config.backend_log_level = level.clone())?;
+    app_log!("Backend log level set to {}", level);
+    let mut result = SetLogLevelResult { level: level.clone(), applied: false, restart_required: false, restarted: false };
+
+    let (current, external) = {
+        let process = state.process.lock().unwrap();
+        (process.log_level.clone(), process.external_url.is_some())
+    };
+    if !external && state.backend.lifecycle() != Lifecycle::Running {
+        // 没有运行中的后端，下次启动时使用新级别
+        return Ok(result);
+    }
+    if current.as_deref() == Some(level.as_str()) {
+        result.applied = true;
+        return Ok(result);
+    }
+    match request_log_level(&BackendClient::current(&app), &level).await {
+        Ok(()) => {
+            state.process.lock().unwrap().log_level = Some(level);
+            result.applied = true;
+        }
+        Err(e) => {
+            app_log!("Backend cannot change its log level at runtime ({}), a restart is required", e);
+            result.restart_required = true;
+            if restart.unwrap_or(false) && !external {
+                restart_backend_exclusive(&app, &state).await?;
+                result.restart_required = false;
+                result.restarted = true;
+            }
+        }
+    }
+    Ok(result)
+}
+
+// 设置后端进程优先级，返回生效的级别；失败只记录警告
+fn apply_priority(pid: u32, level: &str) -> Option<String> {
+    match priority::apply(pid, level) {
+        Ok(()) => {
+            app_log!("Backend priority set to {}", level);
+            Some(level.to_string())
+        }
+        Err(e) => {
+            app_error!("Warning: failed to set backend priority to {}: {}", level, e);
+            None
+        }
+    }
+}
+
+/// 对运行中的后端应用 config.json 的 backend_priority；set_config 修改该项后调用
+pub fn reapply_priority(app: &AppHandle) -> bool {
+    let level = app_config(app).backend_priority;
+    let state = app.state::<ServerState>();
+    let mut process = state.process.lock().unwrap();
+    let Some(pid) = process.pid.filter(|_| process.priority.is_some()) else {
+        return false;
+    };
+    if process.priority.as_deref() == Some(level.as_str()) {
+        return true;
+    }
+    match apply_priority(pid, &level) {
+        Some(level) => {
+            process.priority = Some(level);
+            true
+        }
+        None => false,
+    }
+}
+
+// `set_backend_priority` 返回值
+#[derive(serde::Serialize)]
+pub struct SetPriorityResult {
+    level: String,
+    // 已对运行中的后端生效；后端未运行或设置失败时为 false，下次启动时使用新级别
+    applied: bool,
+}
+
+/// 修改后端进程优先级（normal / below_normal / low）并写入 config.json；后端运行中时立即生效
+#[tauri::command]
+pub fn set_backend_priority(app: AppHandle, level: String) -> Result<SetPriorityResult, BackendError> {
+    let level = level.trim().to_lowercase();
+    if !PRIORITY_LEVELS.contains(&level.as_str()) {
+        return Err(BackendError::from(format!("level must be one of {:?}, got \"{}\"", PRIORITY_LEVELS, level)));
+    }
+    app.state::<config::ConfigState>()
+        .update(|config| config.backend_priority = level.clone())?;
+    let applied = reapply_priority(&app);
+    Ok(SetPriorityResult { level, applied })
+}
+
+// `set_backend_args` 返回值
+#[derive(serde::Serialize)]
+pub struct SetBackendArgsResult {
+    // 新配置下追加的全部参数（backend_args 与 backend_features）
+    args: Vec<String>,
+    // 当前运行的后端使用的是旧参数，需调用 restart_backend 生效
+    restart_required: bool,
+}
+
+/// 修改追加的后端启动参数（及可选的功能开关）并写入 config.json，不会自动重启；
+/// 设置页“应用并重启”在此之后调用 restart_backend
+#[tauri::command]
+pub fn set_backend_args(
+    app: AppHandle,
+    state: tauri::State<'_, ServerState>,
+    args: Vec<String>,
+    features: Option<BTreeMap<String, bool>>,
+) -> Result<SetBackendArgsResult, BackendError> {
+    let config_state = app.state::<config::ConfigState>();
+    let old = app_config(&app);
+    config_state.update(|config| {
+        config.backend_args = args;
+        if let Some(features) = features {
+            config.backend_features = features;
+        }
+    })?;
+    let config = app_config(&app);
+    let changed = !config.restart_required_fields(&old).is_empty();
+    let running = state.process.lock().unwrap().pid.is_some();
+    let args = config.backend_extra_args();
+    app_log!("Backend extra arguments set to: {}", args.join(" "));
+    Ok(SetBackendArgsResult { args, restart_required: changed && running })
+}
+
+/// 经标准输入向后端发送一行控制命令（reload / flush / dump-state / release-cache，可带参数），自动追加换行。
+/// 外部后端或后端未运行时返回错误
+#[tauri::command]
+pub async fn send_backend_command(state: tauri::State<'_, ServerState>, line: String) -> Result<(), BackendError> {
+    let line = line.trim();
+    let verb = line.split_whitespace().next().unwrap_or_default();
+    if !CONTROL_VERBS.contains(&verb) {
+        return Err(format!("Unknown backend control command \"{}\" (expected one of: {})", verb, CONTROL_VERBS.join(", ")).into());
+    }
+    if line.chars().any(char::is_control) {
+        return Err("Backend control commands must be a single line".to_string().into());
+    }
+    if let Some(url) = &state.process.lock().unwrap().external_url {
+        return Err(format!("Backend at {} is external and does not accept control commands", url).into());
+    }
+    app_log!("Sending control command to backend: {}", line);
+    let result = state.backend.write_stdin(format!("{}\n", line).as_bytes()).await;
+    if let Err(e) = &result {
+        app_error!("Backend control command \"{}\" failed: {}", line, e);
+    }
+    result
+}
+
+// 优雅停止后端并确认进程已退出
+async fn stop_and_wait(op: &OperationGuard<'_>, app: &AppHandle, state: &ServerState) -> Result<(), BackendError> {
+    state.backend.stop_and_wait(op, app, shutdown_timeout(app)).await
+}
+
+async fn start_after_stop(
+    op: &OperationGuard<'_>,
+    app: &AppHandle,
+    state: &ServerState,
+    reason: &'static str,
+    reset_crash_count: bool,
+) -> Result<u32, BackendError> {
+    // 手动重启视为新的开始，清空崩溃计数
+    if reset_crash_count {
+        state.restarts.lock().unwrap().reset();
+    }
+    let port = state.process.lock().unwrap().port;
+    let pid = state.backend.start(op, app, PortPolicy::Any(port)).await?;
+    emit_restarted(app, state, reason, pid);
+    Ok(pid)
+}
+
+fn emit_restarted(app: &AppHandle, state: &ServerState, reason: &'static str, pid: u32) {
+    let token = state.process.lock().unwrap().token.clone();
+    crate::app_events::emit_to_frontend(
+        app,
+        "backend://restarted",
+        BackendRestartedPayload { reason, attempt: 0, pid, token },
+    );
+}
+
+/// 内存超限后的重启。与崩溃重启共用计数：窗口期内反复超限达到上限后不再重启，只通知
+pub async fn restart_for_memory_limit(app: &AppHandle) -> Result<u32, BackendError> {
+    let state = app.state::<ServerState>();
+    async {
+        let _access = state.backend.try_restart_access()?;
+        let op = state.backend.try_begin()?;
+        // 拿到操作锁后才计数：另一项操作正在进行而未重启时不占用重启次数
+        if state.restarts.lock().unwrap().next_delay().is_none() {
+            return Err(format!(
+                "Backend exceeded its memory limit too often ({} restarts within {:?}), not restarting again",
+                RESTART_MAX_ATTEMPTS, RESTART_WINDOW
+            )
+            .into());
+        }
+        stop_and_wait(&op, app, &state).await?;
+        start_after_stop(&op, app, &state, "memory_limit", false).await
+    }
+    .instrument(restart_span("memory_limit"))
+    .await
+}
+
+/// 无响应（Hung）的后端不会处理 /shutdown，直接强制结束后重启
+pub async fn kill_and_restart(app: &AppHandle) -> Result<u32, BackendError> {
+    let state = app.state::<ServerState>();
+    async {
+        let _access = state.backend.try_restart_access()?;
+        let op = state.backend.try_begin()?;
+        let old_pid = state.process.lock().unwrap().pid;
+        app_log!("Killing unresponsive backend...");
+        state.backend.kill(app).await;
+        if let Some(pid) = old_pid {
+            if !wait_for_exit(&state, pid, EXIT_WAIT_TIMEOUT).await {
+                return Err(format!("Backend process {} did not exit within {:?}", pid, EXIT_WAIT_TIMEOUT).into());
+            }
+        }
+        start_after_stop(&op, app, &state, "hung", false).await
+    }
+    .instrument(restart_span("hung"))
+    .await
+}
+
+/// 停止后端执行维护操作（备份、恢复等），完成后重新启动，重启通知的 reason 为 `reason`。
+/// 期间占用操作锁，手动重启和健康检查不会同时拉起进程；外部后端不受本应用管理，直接执行。
+/// 备份、恢复等独占数据目录的任务调用时已持有数据目录锁，这里不再取读锁
+pub async fn with_backend_stopped<T, F>(app: &AppHandle, reason: &'static str, work: F) -> Result<T, BackendError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let state = app.state::<ServerState>();
+    let external = state.process.lock().unwrap().external_url.is_some();
+    if external {
+        return tauri::async_runtime::spawn_blocking(work).await.map_err(|e| e.to_string().into());
+    }
+    async {
+        let op = state.backend.try_begin()?;
+        stop_and_wait(&op, app, &state).await?;
+        let output = tauri::async_runtime::spawn_blocking(work).await.map_err(|e| e.to_string());
+        // 维护操作失败也要把后端拉起来
+        start_after_stop(&op, app, &state, reason, true).await?;
+        Ok(output?)
+    }
+    .instrument(restart_span(reason))
+    .await
+}
+
+/// 应用自更新替换文件前停止后端并执行 `work`。成功后不再拉起后端（应用随即重启），
+/// 失败时重新启动后端，重启通知的 reason 为 `app_update`
+pub async fn stop_for_app_update<F>(app: &AppHandle, work: F) -> Result<(), BackendError>
+where
+    F: FnOnce() -> Result<(), String> + Send + 'static,
+{
+    let state = app.state::<ServerState>();
+    let external = state.process.lock().unwrap().external_url.is_some();
+    if external {
+        let output = tauri::async_runtime::spawn_blocking(work).await.map_err(|e| e.to_string())?;
+        return Ok(output?);
+    }
+    let op = state.backend.try_begin()?;
+    stop_and_wait(&op, app, &state).await?;
+    let output = tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|output| output);
+    if let Err(e) = output {
+        // 先记下更新失败的原因，重启也失败时两者一并返回
+        app_error!("App update failed, restarting backend: {}", e);
+        if let Err(restart) = start_after_stop(&op, app, &state, "app_update", true).await {
+            return Err(format!("{}; restarting the backend also failed: {}", e, restart).into());
+        }
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// 查询后端进程状态。只读取内存中的记录，不做 HTTP 探测，可供状态指示器高频轮询。
+#[tauri::command]
+pub fn get_backend_status(state: tauri::State<'_, ServerState>) -> BackendStatus {
+    backend_status(&state)
+}
+
+/// 后端的对外状态（stopped / starting / running / stopping / crashed / crash_loop / hung / external）
+/// 与进入该状态的那次转移；状态变化时另有 `backend://state` 事件
+#[tauri::command]
+pub fn get_backend_state(state: tauri::State<'_, ServerState>) -> BackendStateInfo {
+    state.backend.state()
+}
+
+/// 等待后端就绪（Running，或已连接外部后端）后返回当前状态，已就绪时立即返回，供前端在启动时代替轮询。
+/// 超过 timeout_ms 或后端进入 crash_loop / 启动失败时返回错误，包含当时的状态与最近一次的启动错误
+#[tauri::command]
+pub async fn wait_for_backend(
+    state: tauri::State<'_, ServerState>,
+    timeout_ms: u64,
+) -> Result<BackendStateInfo, WaitForBackendError> {
+    state.backend.wait_ready(Duration::from_millis(timeout_ms)).await.map_err(|mut failure| {
+        failure.error = state.process.lock().unwrap().start_error.clone();
+        failure
+    })
+}
+
+/// 后端 HTTPS 证书的指纹与有效期；证书在启用 backend_tls 后首次启动后端时生成，到期前自动更新
+#[tauri::command]
+pub fn get_tls_info(app: AppHandle) -> Result<tls::TlsInfo, String> {
+    let data_dir = backend_data_dir(&app)?;
+    Ok(tls::TlsInfo {
+        enabled: crate::app_config(&app).backend_tls,
+        active: app.state::<ServerState>().process.lock().unwrap().tls,
+        certificate: tls::read_info(&data_dir),
+    })
+}
+
+/// 最近 20 次后端启动（从拉起进程到通过健康检查）的耗时、中位数与 p95，以及最近一次是冷启动还是重启
+#[tauri::command]
+pub fn get_startup_metrics(app: AppHandle) -> startup::StartupMetrics {
+    startup::metrics(&app)
+}
+
+pub fn backend_status(state: &ServerState) -> BackendStatus {
+    let process = state.process.lock().unwrap();
+    let uptime_secs = process
+        .started_at
+        .and_then(|t| t.elapsed().ok())
+        .map(|d| d.as_secs());
+    let lifecycle = state.backend.lifecycle();
+    let crash_loop = (lifecycle == Lifecycle::CrashLoop).then(|| CrashLoopInfo {
+        attempts: state.restarts.lock().unwrap().attempts(),
+        last_exit_code: process.last_exit_code,
+    });
+    BackendStatus {
+        mode: if process.external_url.is_some() { "external" } else { "sidecar" },
+        base_url: process.base_url(),
+        running: process.pid.is_some() || (process.external_url.is_some() && process.started_at.is_some()),
+        lifecycle,
+        start_error: process.start_error.clone(),
+        crash_loop,
+        pid: process.pid,
+        started_at: process.started_at,
+        uptime_secs,
+        port: process.port,
+        bind_address: process.bind_address,
+        last_exit_code: process.last_exit_code,
+        log_level: process.log_level.clone(),
+        app_version: version::APP_VERSION,
+        expected_backend_version: version::EXPECTED_BACKEND_VERSION,
+        backend_version: process.backend_version.clone(),
+        safe_mode: safe_mode::is_active(),
+        data_dir_error: process.data_dir_error.clone(),
+        read_only_session: data_dir::temporary_dir().is_some(),
+        creation_flags: process.creation_flags.clone(),
+        args: process.args.clone(),
+        binding_check: process.binding_check.clone(),
+        tls: process.tls,
+        priority: process.priority.clone(),
+        termination: process.termination.clone(),
+    }
+}
+
+/// 当前后端的会话 token，前端需在每个请求的 `X-DunCrew-Token` 头中携带。
+/// 后端重启后 token 会变化，新值随 `backend://restarted` 事件下发。
+/// 命令只对应用自身加载的页面开放（Tauri 不向远程页面暴露 IPC）。
+#[tauri::command]
+pub fn get_backend_token(state: tauri::State<'_, ServerState>) -> Option<String> {
+    state.process.lock().unwrap().token.clone()
+}
+
+/// 返回后端实际监听的端口，前端据此拼接 base URL 而不是假设 3001
+#[tauri::command]
+pub fn get_backend_port(state: tauri::State<'_, ServerState>) -> u16 {
+    state.process.lock().unwrap().port
+}
+
+// 首次启动时默认端口被占用：弹窗说明占用者，由用户决定是否改用其他端口
+fn prompt_port_conflict(app: &AppHandle, error: BackendError) {
+    crate::app_events::emit_to_frontend(app, "backend://start-failed", error.clone());
+    let alert = Alert::new(AlertCategory::Startup, i18n::tf("dialog.port_conflict", &[("error", &error)]))
+        .kind(MessageDialogKind::Warning)
+        .priority(AlertPriority::High)
+        .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("button.use_other_port"), i18n::t("button.cancel")));
+    alerts::ask(app, alert, {
+            let app = app.clone();
+            move |use_other_port| {
+                if !use_other_port {
+                    return;
+                }
+                tauri::async_runtime::spawn(async move {
+                    start_initial_backend(&app, PortPolicy::Any(app_config(&app).port)).await;
+                });
+            }
+        });
+}
+
+// base_url 上是不属于本数据目录的 DunCrew 后端时返回其身份；不是 DunCrew 后端或正是本数据目录的后端时为 None
+async fn foreign_instance(app: &AppHandle, base_url: String) -> Option<instance::ListenerIdentity> {
+    let data_dir = crate::folders::canonical(&backend_data_dir(app).ok()?);
+    let identity = instance::identify(base_url).await?;
+    (!identity.is_ours(&instance::fingerprint(&data_dir))).then_some(identity)
+}
+
+// 配置的端口被另一个 DunCrew 后端占用：不使用它，改用空闲端口启动本应用自己的后端，并说明对方的版本
+fn start_beside_foreign_instance(app: &AppHandle, port: u16, identity: instance::ListenerIdentity) {
+    let version = identity.version.clone().unwrap_or_else(|| i18n::t("common.unknown"));
+    let error = BackendError::ForeignInstance { port, version: identity.version, instance_id: identity.instance_id };
+    app_log!("{}, starting the backend on another port", error);
+    crate::app_events::emit_to_frontend(app, "backend://foreign-instance", error);
+    let message = i18n::tf("dialog.foreign_instance", &[("port", &port), ("version", &version)]);
+    alerts::show(app, Alert::new(AlertCategory::Instance, message).priority(AlertPriority::Low));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        start_initial_backend(&app, PortPolicy::Any(port)).await;
+    });
+}
+
+// 数据目录不可用：重试、另选位置（写入指针文件，之后一直使用），第三项在自定义位置未连接时为
+// 本次运行以只读方式使用默认目录，其他情况（无法创建、不可写、默认目录本身不可用）为退出
+fn prompt_data_dir_unavailable(app: &AppHandle, error: BackendError, port_policy: PortPolicy) {
+    crate::app_events::emit_to_frontend(app, "backend://start-failed", error.clone());
+    let BackendError::DataDirUnavailable { path, issue, message } = &error else {
+        return;
+    };
+    let (retry, choose, use_default, quit) = (
+        i18n::t("button.retry"),
+        i18n::t("button.choose_location"),
+        i18n::t("button.use_default_temporarily"),
+        i18n::t("button.quit"),
+    );
+    let is_default = app.path().app_data_dir().is_ok_and(|dir| dir.to_string_lossy() == path.as_str());
+    let offer_default = *issue == data_dir::DataDirIssue::Missing && !is_default && data_dir::temporary_dir().is_none();
+    let third = if offer_default { use_default.clone() } else { quit.clone() };
+    let key = match issue {
+        data_dir::DataDirIssue::Missing => "dialog.data_dir_unavailable",
+        data_dir::DataDirIssue::CannotCreate => "dialog.data_dir_unavailable.cannot_create",
+        data_dir::DataDirIssue::NotWritable => "dialog.data_dir_unavailable.not_writable",
+        data_dir::DataDirIssue::InvalidPath => "dialog.data_dir_unavailable.invalid_path",
+    };
+    let alert = Alert::new(AlertCategory::Data, i18n::tf(key, &[("path", path), ("error", message)]))
+        .kind(MessageDialogKind::Warning)
+        .priority(AlertPriority::High)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(retry.clone(), choose.clone(), third));
+    alerts::ask_with_result(app, alert, {
+            let app = app.clone();
+            move |result| {
+                let choice = match result {
+                    MessageDialogResult::Custom(label) => label,
+                    MessageDialogResult::Ok | MessageDialogResult::Yes => retry.clone(),
+                    _ => return,
+                };
+                if choice == quit {
+                    app_log!("Quitting because the data directory is unavailable");
+                    app.exit(1);
+                } else if choice == retry {
+                    tauri::async_runtime::spawn(async move {
+                        start_initial_backend(&app, port_policy).await;
+                    });
+                } else if choice == choose {
+                    choose_data_dir(app, port_policy);
+                } else if choice == use_default {
+                    match data_dir::use_default_temporarily(&app) {
+                        Ok(()) => {
+                            tauri::async_runtime::spawn(async move {
+                                start_initial_backend(&app, port_policy).await;
+                            });
+                        }
+                        Err(e) => app_error!("{}", e),
+                    }
+                }
+            }
+        });
+}
+
+// 选择新的数据目录后启动；取消选择或新位置无效时回到上一个对话框
+fn choose_data_dir(app: AppHandle, port_policy: PortPolicy) {
+    app.dialog().file().pick_folder({
+        let app = app.clone();
+        move |folder| {
+            let result = match folder.map(|folder| folder.into_path()) {
+                Some(Ok(dir)) => data_dir::switch_unavailable(&app, &dir),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Err("No folder selected".to_string()),
+            };
+            if let Err(e) = &result {
+                app_error!("Cannot use the selected data directory: {}", e);
+            }
+            tauri::async_runtime::spawn(async move {
+                start_initial_backend(&app, port_policy).await;
+            });
+        }
+    });
+}
+
+fn prompt_low_disk_space(app: &AppHandle, error: BackendError, port_policy: PortPolicy) {
+    crate::app_events::emit_to_frontend(app, "backend://start-failed", error.clone());
+    let message = match backend_data_dir(app) {
+        Ok(dir) => i18n::tf("dialog.low_disk.on_drive", &[("error", &error), ("path", &dir.display())]),
+        Err(_) => format!("{}.", error),
+    };
+    let alert = Alert::new(AlertCategory::LowDisk, i18n::tf("dialog.low_disk.start", &[("message", &message)]))
+        .kind(MessageDialogKind::Warning)
+        .priority(AlertPriority::High)
+        .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("button.start_anyway"), i18n::t("button.cancel")));
+    alerts::ask(app, alert, {
+            let app = app.clone();
+            move |start_anyway| {
+                if !start_anyway {
+                    return;
+                }
+                app.state::<ServerState>().low_space_acknowledged.store(true, Ordering::SeqCst);
+                tauri::async_runtime::spawn(async move {
+                    start_initial_backend(&app, port_policy).await;
+                });
+            }
+        });
+}
+
+// 其他启动失败：弹窗显示错误，可重试（有次数上限）或退出应用
+fn prompt_startup_failure(app: &AppHandle, error: BackendError, port_policy: PortPolicy) {
+    crate::app_events::emit_to_frontend(app, "backend://start-failed", error.clone());
+    let failures = app.state::<ServerState>().startup_failures.fetch_add(1, Ordering::SeqCst) + 1;
+    let can_retry = failures < STARTUP_MAX_ATTEMPTS;
+    let (message, buttons) = if can_retry {
+        (
+            i18n::tf("dialog.startup_failed", &[("error", &error)]),
+            MessageDialogButtons::OkCancelCustom(i18n::t("button.retry"), i18n::t("button.quit")),
+        )
+    } else {
+        (
+            i18n::tf("dialog.startup_failed.final", &[("attempts", &failures), ("error", &error)]),
+            MessageDialogButtons::OkCancelCustom(i18n::t("button.keep_open"), i18n::t("button.quit")),
+        )
+    };
+    let alert = Alert::new(AlertCategory::Startup, message)
+        .kind(MessageDialogKind::Error)
+        .priority(AlertPriority::High)
+        .buttons(buttons);
+    alerts::ask(app, alert, {
+            let app = app.clone();
+            move |ok| {
+                if !ok {
+                    app_log!("Quitting after backend startup failure");
+                    crate::session::set_exit_reason(crate::session::SessionEnd::BackendStartupFailed);
+                    app.exit(1);
+                    return;
+                }
+                if !can_retry {
+                    return;
+                }
+                app_log!("Retrying backend startup (attempt {})", failures + 1);
+                tauri::async_runtime::spawn(async move {
+                    start_initial_backend(&app, port_policy).await;
+                });
+            }
+        });
+}
+
+// 启动时拉起后端；端口冲突、磁盘空间不足等可由用户处理的错误弹窗询问，确认后再次调用本函数
+pub async fn start_initial_backend(app: &AppHandle, port_policy: PortPolicy) {
+    let state = app.state::<ServerState>();
+    let op = state.backend.begin().await;
+    let result = state.backend.start(&op, app, port_policy).await;
+    drop(op);
+    match result {
+        Ok(_) => {
+            state.startup_failures.store(0, Ordering::SeqCst);
+            app_log!("Application started successfully");
+        }
+        // 无界面模式没有人能回应对话框：以非零状态退出，交给 systemd 等服务管理器重启
+        Err(e) if headless::enabled(app) => {
+            app_error!("Failed to start backend: {}, exiting", e);
+            crate::app_events::emit_to_frontend(app, "backend://start-failed", e);
+            app.exit(1);
+        }
+        Err(e @ BackendError::PortInUse { port, .. }) => {
+            app_error!("Failed to start backend: {}", e);
+            match foreign_instance(app, format!("http://127.0.0.1:{}", port)).await {
+                Some(identity) => start_beside_foreign_instance(app, port, identity),
+                None => prompt_port_conflict(app, e),
+            }
+        }
+        Err(e @ BackendError::LowDiskSpace { .. }) => {
+            app_error!("Failed to start backend: {}", e);
+            prompt_low_disk_space(app, e, port_policy);
+        }
+        Err(e @ BackendError::DataDirUnavailable { .. }) => {
+            app_error!("Failed to start backend: {}", e);
+            prompt_data_dir_unavailable(app, e, port_policy);
+        }
+        Err(e @ BackendError::SidecarVerificationFailed { .. }) => {
+            crate::app_events::emit_to_frontend(app, "backend://start-failed", e.clone());
+            let alert = Alert::new(AlertCategory::Security, i18n::tf("dialog.sidecar_verification", &[("error", &e)]))
+                .kind(MessageDialogKind::Error)
+                .priority(AlertPriority::High);
+            alerts::show(app, alert);
+        }
+        Err(e) => {
+            app_error!("Failed to start backend: {}", e);
+            // ServerState 已在 setup 中注册，放弃重试后前端仍可调用 restart_backend
+            prompt_startup_failure(app, e, port_policy);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 在 duncrew-server.py 中加载模块并调用 parse_args，解析结果以 JSON 打印在 ARGS 行
+    const PARSE_SCRIPT: &str = "import importlib.util, json, sys\n\
+        spec = importlib.util.spec_from_file_location('duncrew_server', sys.argv[1])\n\
+        module = importlib.util.module_from_spec(spec)\n\
+        spec.loader.exec_module(module)\n\
+        print('ARGS ' + json.dumps(vars(module.parse_args(sys.argv[2:]))))\n";
+
+    fn python() -> String {
+        std::env::var("DUNCREW_TEST_PYTHON").unwrap_or_else(|_| if cfg!(windows) { "python" } else { "python3" }.to_string())
+    }
+
+    /// 用后端自己的参数解析器解析 args，返回解析结果
+    fn parse_with_server(args: &[String]) -> serde_json::Value {
+        let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("../duncrew-server.py");
+        let output = std::process::Command::new(python())
+            .arg("-c")
+            .arg(PARSE_SCRIPT)
+            .arg(&script)
+            .args(args)
+            .output()
+            .unwrap_or_else(|e| panic!("failed to run {} (set DUNCREW_TEST_PYTHON): {}", python(), e));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "server rejected {:?} ({}):\n{}",
+            args,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let line = stdout.lines().find_map(|line| line.strip_prefix("ARGS ")).expect("parse_args output");
+        serde_json::from_str(line).unwrap()
+    }
+
+    #[test]
+    fn server_accepts_every_sidecar_arg() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = crate::folders::canonical(dir.path());
+        let mut config = config::AppConfig::default();
+        config.backend_features.insert("vision".to_string(), false);
+        let system_locale = locale::LocaleInfo {
+            locale: Some("zh-CN".to_string()),
+            languages: vec!["zh-CN".to_string(), "en-US".to_string()],
+            timezone: Some("Asia/Shanghai".to_string()),
+            utc_offset_minutes: 480,
+        };
+        let cert_path = data_dir.join("tls").join("cert.pem");
+        let key_path = data_dir.join("tls").join("key.pem");
+        let args = SidecarLaunch {
+            path_args: path_args(&data_dir).unwrap(),
+            port: 3001,
+            log_level: &config.backend_log_level,
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            tls_args: vec![
+                "--tls-cert".to_string(),
+                cert_path.to_string_lossy().into_owned(),
+                "--tls-key".to_string(),
+                key_path.to_string_lossy().into_owned(),
+            ],
+            safe_mode: true,
+            read_only: true,
+            data_dir: &data_dir,
+            locale: &system_locale,
+            extra_args: config.backend_extra_args(),
+        }
+        .args();
+
+        let parsed = parse_with_server(&args);
+        assert_eq!(parsed["path"], args[1].as_str());
+        assert_eq!(parsed["cache_dir"], args[3].as_str());
+        assert_eq!(parsed["port"], 3001);
+        assert_eq!(parsed["host"], "127.0.0.1");
+        assert_eq!(parsed["log_level"], config.backend_log_level.as_str());
+        assert_eq!(parsed["tls_key"], key_path.to_string_lossy().as_ref());
+        assert_eq!(parsed["safe_mode"], true);
+        assert_eq!(parsed["read_only"], true);
+        assert_eq!(parsed["instance_id"], instance::fingerprint(&data_dir).as_str());
+        assert_eq!(parsed["languages"], "zh-CN,en-US");
+        assert_eq!(parsed["timezone"], "Asia/Shanghai");
+        assert_eq!(parsed["features"], serde_json::json!({ "vision": false }));
+        if cfg!(unix) {
+            assert_eq!(parsed["parent_pid"], std::process::id());
+        }
+    }
+}
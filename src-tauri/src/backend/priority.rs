@@ -0,0 +1,59 @@
+// 后端进程优先级：config.json 的 backend_priority，后端为大型资料库建索引时不至于占满 CPU 让整个桌面卡顿。
+// 每次拉起 Sidecar 后按配置设置，set_backend_priority 可对运行中的进程立即修改。
+// Windows 使用 SetPriorityClass，Unix 使用 setpriority（nice 值）；之后由后端创建的子进程继承同样的优先级。
+// 权限不足等原因设置失败时只记录警告，不影响后端启动。Unix 上普通用户不能把 nice 值调低，
+// 从 low 改回 normal 通常会失败，需重启后端才能生效。
+
+pub const LEVELS: [&str; 3] = ["normal", "below_normal", "low"];
+
+/// 设置进程优先级；level 须是 LEVELS 之一（config 校验保证）
+pub fn apply(pid: u32, level: &str) -> Result<(), String> {
+    imp::apply(pid, level)
+}
+
+#[cfg(windows)]
+mod imp {
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError};
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+        PROCESS_SET_INFORMATION,
+    };
+
+    pub fn apply(pid: u32, level: &str) -> Result<(), String> {
+        let class = match level {
+            "below_normal" => BELOW_NORMAL_PRIORITY_CLASS,
+            "low" => IDLE_PRIORITY_CLASS,
+            _ => NORMAL_PRIORITY_CLASS,
+        };
+        unsafe {
+            let process = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+            if process.is_null() {
+                return Err(format!("OpenProcess({}) failed: {}", pid, GetLastError()));
+            }
+            let ok = SetPriorityClass(process, class);
+            let err = GetLastError();
+            CloseHandle(process);
+            if ok == 0 {
+                return Err(format!("SetPriorityClass failed: {}", err));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    pub fn apply(pid: u32, level: &str) -> Result<(), String> {
+        let nice = match level {
+            "below_normal" => 10,
+            "low" => 19,
+            _ => 0,
+        };
+        // SAFETY: 只传入整数参数
+        let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) };
+        if result != 0 {
+            return Err(format!("setpriority({}, {}) failed: {}", pid, nice, std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+// 真实后端：shell 插件启动的 Sidecar 或源码模式下的 Python 进程。
+// Windows 上 shell 插件以 CREATE_NO_WINDOW 创建进程并用管道接收 stdout / stderr，控制台程序不会弹出黑窗口；
+// 后端的优雅停止走 HTTP /shutdown，不依赖控制台的 Ctrl 事件，没有控制台也不受影响。
+// 调试构建可用 config.json 的 backend_console 改为 CREATE_NEW_CONSOLE，输出直接显示在控制台，不再写入 backend.log。
+
+use tauri_plugin_shell::process::{Command, CommandChild, CommandEvent};
+use tokio::sync::mpsc;
+
+use super::{BackendEvent, BackendHandle, Spawned};
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+#[cfg(windows)]
+const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+#[cfg(windows)]
+const WAIT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+struct SidecarHandle(CommandChild);
+
+impl BackendHandle for SidecarHandle {
+    fn pid(&self) -> u32 {
+        self.0.pid()
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        self.0.write(data).map_err(|e| e.to_string())
+    }
+
+    fn kill(self: Box<Self>) -> Result<(), String> {
+        self.0.kill().map_err(|e| e.to_string())
+    }
+}
+
+/// 本次启动使用的进程创建标志，记入日志与 get_backend_status；非 Windows 平台返回 None
+pub fn creation_flags(console: bool) -> Option<String> {
+    #[cfg(windows)]
+    {
+        let (name, value) = if console {
+            ("CREATE_NEW_CONSOLE", CREATE_NEW_CONSOLE)
+        } else {
+            ("CREATE_NO_WINDOW", CREATE_NO_WINDOW)
+        };
+        Some(format!("{} (0x{:08X})", name, value))
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = console;
+        None
+    }
+}
+
+/// `console` 为 true 时（仅调试构建、Windows）为后端打开控制台窗口
+pub fn spawn(command: Command, console: bool) -> Result<Spawned, String> {
+    #[cfg(windows)]
+    if console {
+        return spawn_with_console(command);
+    }
+    #[cfg(not(windows))]
+    let _ = console;
+    let (mut rx, child) = command.spawn().map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+    let (tx, events) = mpsc::channel(64);
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let event = match event {
+                CommandEvent::Stdout(bytes) => BackendEvent::Stdout(bytes),
+                CommandEvent::Stderr(bytes) => BackendEvent::Stderr(bytes),
+                CommandEvent::Error(err) => BackendEvent::Error(err),
+                CommandEvent::Terminated(payload) => {
+                    BackendEvent::Terminated { code: payload.code, signal: payload.signal }
+                }
+                _ => continue,
+            };
+            if tx.send(event).await.is_err() {
+                break;
+            }
+        }
+    });
+    Ok(Spawned { handle: Box::new(SidecarHandle(child)), events })
+}
+
+#[cfg(windows)]
+struct ConsoleHandle {
+    pid: u32,
+    child: std::sync::Arc<std::sync::Mutex<std::process::Child>>,
+    stdin: Option<std::process::ChildStdin>,
+}
+
+#[cfg(windows)]
+impl BackendHandle for ConsoleHandle {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        use std::io::Write;
+        let stdin = self.stdin.as_mut().ok_or_else(|| "Backend stdin is closed".to_string())?;
+        stdin.write_all(data).and_then(|()| stdin.flush()).map_err(|e| e.to_string())
+    }
+
+    fn kill(self: Box<Self>) -> Result<(), String> {
+        self.child.lock().unwrap().kill().map_err(|e| e.to_string())
+    }
+}
+
+// 输出留在控制台，事件流只有退出事件；等待线程轮询，以便 kill 时能同时访问子进程
+#[cfg(windows)]
+fn spawn_with_console(command: Command) -> Result<Spawned, String> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Stdio;
+
+    let mut command = std::process::Command::from(command);
+    command
+        .creation_flags(CREATE_NEW_CONSOLE)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    let mut child = command.spawn().map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+    let pid = child.id();
+    let stdin = child.stdin.take();
+    let child = std::sync::Arc::new(std::sync::Mutex::new(child));
+    let (tx, events) = mpsc::channel(4);
+    let waiter = child.clone();
+    std::thread::spawn(move || loop {
+        let status = waiter.lock().unwrap().try_wait();
+        match status {
+            Ok(Some(status)) => {
+                let _ = tx.blocking_send(BackendEvent::Terminated { code: status.code(), signal: None });
+                break;
+            }
+            Ok(None) => std::thread::sleep(WAIT_POLL_INTERVAL),
+            Err(e) => {
+                let _ = tx.blocking_send(BackendEvent::Error(e.to_string()));
+                let _ = tx.blocking_send(BackendEvent::Terminated { code: None, signal: None });
+                break;
+            }
+        }
+    });
+    Ok(Spawned { handle: Box::new(ConsoleHandle { pid, child, stdin }), events })
+}
@@ -0,0 +1,167 @@
+// 后端启动耗时：从拉起进程到首次通过健康检查的时间。每次启动的耗时随 `backend://ready`（duration_ms、start_kind）
+// 写入生命周期历史，内存中保留最近 MAX_SAMPLES 次，首次使用时从历史中补齐，供 get_startup_metrics 查询中位数与 p95。
+// 本次运行第一次成功的启动为冷启动（cold_boot），之后的为重启（restart）。
+// 已有至少 MIN_SAMPLES 次记录时，等待就绪超过中位数的 SLOW_FACTOR 倍即发送 `backend://slow-start`，
+// 启动画面据此改为“比平时慢”的提示并给出诊断入口；该事件在等待期间发送，不必等到启动结束。
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::AppHandle;
+
+const MAX_SAMPLES: usize = 20;
+const MIN_SAMPLES: usize = 3;
+const SLOW_FACTOR: u32 = 3;
+// 补齐历史时读取的生命周期事件数
+const HISTORY_EVENTS: usize = 500;
+
+static SAMPLES: Mutex<Option<VecDeque<StartSample>>> = Mutex::new(None);
+// 本次运行是否已经成功启动过
+static BOOTED: AtomicBool = AtomicBool::new(false);
+// 正在等待就绪的启动编号，0 表示没有
+static ACTIVE: AtomicU64 = AtomicU64::new(0);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartKind {
+    ColdBoot,
+    Restart,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct StartSample {
+    pub duration_ms: u64,
+    pub kind: StartKind,
+    pub at: SystemTime,
+}
+
+/// `get_startup_metrics` 返回值；还没有任何记录时各项为 None
+#[derive(serde::Serialize)]
+pub struct StartupMetrics {
+    pub last_ms: Option<u64>,
+    pub last_kind: Option<StartKind>,
+    pub median_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    /// 从旧到新
+    pub samples: Vec<StartSample>,
+}
+
+// `backend://slow-start` 事件负载
+#[derive(Clone, serde::Serialize)]
+struct SlowStartPayload {
+    elapsed_ms: u64,
+    median_ms: u64,
+    kind: StartKind,
+}
+
+// 生命周期历史中最近的 ready 记录（旧版本写入的记录没有 duration_ms，跳过）
+fn from_history(app: &AppHandle) -> VecDeque<StartSample> {
+    let Ok(data_dir) = crate::backend_data_dir(app) else {
+        return VecDeque::new();
+    };
+    let mut samples: VecDeque<StartSample> = crate::lifecycle_history::read(&data_dir, HISTORY_EVENTS, None)
+        .into_iter()
+        .filter(|event| event["event"] == "ready")
+        .filter_map(|event| {
+            let data = &event["data"];
+            Some(StartSample {
+                duration_ms: data["duration_ms"].as_u64()?,
+                kind: serde_json::from_value(data["start_kind"].clone()).ok()?,
+                at: SystemTime::UNIX_EPOCH + Duration::from_millis(event["ts"].as_u64()?),
+            })
+        })
+        .take(MAX_SAMPLES)
+        .collect();
+    // read 从新到旧返回
+    samples.make_contiguous().reverse();
+    samples
+}
+
+fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    let index = ((sorted.len() as f64 * p).ceil() as usize).saturating_sub(1);
+    sorted.get(index).copied()
+}
+
+fn sorted_durations(samples: &VecDeque<StartSample>) -> Vec<u64> {
+    let mut durations: Vec<u64> = samples.iter().map(|sample| sample.duration_ms).collect();
+    durations.sort_unstable();
+    durations
+}
+
+fn with_samples<T>(app: &AppHandle, action: impl FnOnce(&mut VecDeque<StartSample>) -> T) -> T {
+    let mut samples = SAMPLES.lock().unwrap();
+    action(samples.get_or_insert_with(|| from_history(app)))
+}
+
+/// 一次正在等待就绪的启动；没有调用 finish 就丢弃（启动失败）时不记录
+pub struct PendingStart {
+    id: u64,
+    kind: StartKind,
+    spawned: Instant,
+}
+
+/// 进程已拉起、开始等待就绪时调用
+pub fn begin(app: &AppHandle) -> PendingStart {
+    let spawned = Instant::now();
+    let kind = if BOOTED.load(Ordering::SeqCst) { StartKind::Restart } else { StartKind::ColdBoot };
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    ACTIVE.store(id, Ordering::SeqCst);
+    let median_ms = with_samples(app, |samples| {
+        (samples.len() >= MIN_SAMPLES).then(|| percentile(&sorted_durations(samples), 0.5)).flatten()
+    });
+    if let Some(median_ms) = median_ms.filter(|median| *median > 0) {
+        let app = app.clone();
+        let threshold = Duration::from_millis(median_ms) * SLOW_FACTOR;
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(threshold).await;
+            if ACTIVE.load(Ordering::SeqCst) != id {
+                return;
+            }
+            let elapsed_ms = threshold.as_millis() as u64;
+            app_log!("Backend start is taking longer than usual ({} ms, median {} ms)", elapsed_ms, median_ms);
+            let payload = SlowStartPayload { elapsed_ms, median_ms, kind };
+            crate::app_events::emit_to_frontend(&app, "backend://slow-start", payload);
+        });
+    }
+    PendingStart { id, kind, spawned }
+}
+
+impl PendingStart {
+    /// 通过健康检查：记录本次耗时
+    pub fn finish(self, app: &AppHandle) -> StartSample {
+        BOOTED.store(true, Ordering::SeqCst);
+        let sample = StartSample {
+            duration_ms: self.spawned.elapsed().as_millis() as u64,
+            kind: self.kind,
+            at: SystemTime::now(),
+        };
+        with_samples(app, |samples| {
+            if samples.len() == MAX_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(sample.clone());
+        });
+        sample
+    }
+}
+
+impl Drop for PendingStart {
+    fn drop(&mut self) {
+        let _ = ACTIVE.compare_exchange(self.id, 0, Ordering::SeqCst, Ordering::SeqCst);
+    }
+}
+
+pub fn metrics(app: &AppHandle) -> StartupMetrics {
+    with_samples(app, |samples| {
+        let sorted = sorted_durations(samples);
+        StartupMetrics {
+            last_ms: samples.back().map(|sample| sample.duration_ms),
+            last_kind: samples.back().map(|sample| sample.kind),
+            median_ms: percentile(&sorted, 0.5),
+            p95_ms: percentile(&sorted, 0.95),
+            samples: samples.iter().cloned().collect(),
+        }
+    })
+}
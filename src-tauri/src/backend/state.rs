@@ -0,0 +1,286 @@
+// 对外的后端状态：前端只需订阅 `backend://state`，每次转移发送 { from, to, at, detail }，
+// get_backend_state 返回当前状态与上一次转移。内部的 Lifecycle 区分更细（Failed / FailedToStart），
+// 对外合并为 Crashed，由 detail 区分；连接外部后端时为 External。
+//
+// 转移图（successors 列出全部合法的后继状态）：
+//
+//   Stopped ──start──▶ Starting ──ready──▶ Running ──stop──▶ Stopping ──stopped──▶ Stopped
+//      │                  │                 │  ▲
+//      │             start_failed /     hung│  │recovered
+//      │                exited              ▼  │
+//      │                  ▼               Hung ─┘ （Hung 也可以 stop / exited）
+//      │               Crashed ◀──exited── Running / Hung
+//      │                  │ ▲
+//      │         crash_loop│ │
+//      │                  ▼ │
+//      │               CrashLoop ──start──▶ Starting（Crashed 同样可以 start）
+//      └──connect──▶ External
+//
+// 任何运行中的状态被强制结束（killed）都回到 Stopped。
+//
+// 状态保存在 watch 通道中，wait_for_backend 的所有等待方由同一次转移唤醒，不必自行探测后端。
+//
+// 已弃用：`backend://lifecycle`、`backend://ready`、`backend://stopped`、`backend://crash-loop` 作为状态通知
+// 继续发送一个版本，新代码应订阅 `backend://state`；启动耗时、退出详情等负载目前仍只在这些事件中提供。
+
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+use super::{BackendError, Lifecycle};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendState {
+    #[default]
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+    /// 意外退出或启动失败，detail 为 exited / start_failed
+    Crashed,
+    /// 反复崩溃，已停止自动重启
+    CrashLoop,
+    /// 进程仍在，但健康检查连续超时
+    Hung,
+    /// 连接的是本应用之外运行的后端
+    External,
+}
+
+impl BackendState {
+    /// 由内部生命周期得到对外状态；新增 Lifecycle 时这里必须处理
+    pub fn of(lifecycle: Lifecycle, external: bool) -> Self {
+        if external {
+            return BackendState::External;
+        }
+        match lifecycle {
+            Lifecycle::Stopped => BackendState::Stopped,
+            Lifecycle::Starting => BackendState::Starting,
+            Lifecycle::Running => BackendState::Running,
+            Lifecycle::Stopping => BackendState::Stopping,
+            Lifecycle::Failed | Lifecycle::FailedToStart => BackendState::Crashed,
+            Lifecycle::Hung => BackendState::Hung,
+            Lifecycle::CrashLoop => BackendState::CrashLoop,
+        }
+    }
+
+    /// 合法的后继状态，即上面的转移图；新增状态时这里必须处理
+    pub fn successors(self) -> &'static [BackendState] {
+        use BackendState::*;
+        match self {
+            Stopped => &[Starting, External],
+            Starting => &[Running, Crashed, Stopped],
+            Running => &[Stopping, Hung, Crashed, Stopped],
+            Stopping => &[Stopped],
+            Crashed => &[Starting, CrashLoop, Stopped],
+            CrashLoop => &[Starting, Stopped],
+            Hung => &[Running, Stopping, Crashed, Stopped],
+            External => &[],
+        }
+    }
+}
+
+/// 一次状态转移，`backend://state` 事件负载
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct StateTransition {
+    pub from: BackendState,
+    pub to: BackendState,
+    pub at: SystemTime,
+    /// 引起转移的原因，例如 ready、exited、start_failed、killed、external
+    pub detail: Option<String>,
+}
+
+/// `get_backend_state` 返回值
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct BackendStateInfo {
+    pub state: BackendState,
+    /// 进入当前状态的那次转移，应用启动后还没有转移时为 None
+    pub previous: Option<StateTransition>,
+}
+
+impl BackendStateInfo {
+    /// 可以向后端发送请求；外部后端不由本应用管理，连接后即视为就绪
+    pub fn is_ready(&self) -> bool {
+        matches!(self.state, BackendState::Running | BackendState::External)
+    }
+
+    /// 不会自行恢复的失败：CrashLoop，或启动失败（从 Starting 进入 Crashed，即内部的 FailedToStart）
+    pub fn is_terminal_failure(&self) -> bool {
+        match self.state {
+            BackendState::CrashLoop => true,
+            BackendState::Crashed => self.previous.as_ref().is_some_and(|t| t.from == BackendState::Starting),
+            _ => false,
+        }
+    }
+}
+
+/// `wait_for_backend` 失败时的返回值
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct WaitForBackendError {
+    /// 超时或失败时的状态与进入该状态的转移
+    #[serde(flatten)]
+    pub state: BackendStateInfo,
+    /// true 表示等待超时，false 表示后端进入了不会自行恢复的失败
+    pub timed_out: bool,
+    /// 最近一次启动失败的原因
+    pub error: Option<BackendError>,
+}
+
+pub struct StateTracker {
+    current: watch::Sender<BackendStateInfo>,
+}
+
+impl Default for StateTracker {
+    fn default() -> Self {
+        Self { current: watch::Sender::new(BackendStateInfo::default()) }
+    }
+}
+
+impl StateTracker {
+    pub fn get(&self) -> BackendStateInfo {
+        self.current.borrow().clone()
+    }
+
+    /// 状态有变化时记录并返回这次转移，由调用方发送 `backend://state`
+    pub fn set(&self, to: BackendState, detail: &str) -> Option<StateTransition> {
+        let mut transition = None;
+        self.current.send_if_modified(|current| {
+            let from = current.state;
+            if from == to {
+                return false;
+            }
+            if !from.successors().contains(&to) {
+                app_error!("Unexpected backend state transition {:?} -> {:?} ({})", from, to, detail);
+            }
+            let next = StateTransition { from, to, at: SystemTime::now(), detail: Some(detail.to_string()) };
+            *current = BackendStateInfo { state: to, previous: Some(next.clone()) };
+            transition = Some(next);
+            true
+        });
+        transition
+    }
+
+    /// 等待后端就绪，已就绪时立即返回；超时或进入不会自行恢复的失败时返回当时的状态（error 由调用方填写）
+    pub async fn wait_ready(&self, timeout: Duration) -> Result<BackendStateInfo, WaitForBackendError> {
+        let mut receiver = self.current.subscribe();
+        let settled = tokio::time::timeout(
+            timeout,
+            receiver.wait_for(|info| info.is_ready() || info.is_terminal_failure()),
+        )
+        .await;
+        match settled {
+            // 发送端属于 StateTracker 自身，等待期间不会关闭
+            Ok(Ok(info)) if info.is_ready() => Ok(info.clone()),
+            Ok(Ok(info)) => Err(WaitForBackendError { state: info.clone(), timed_out: false, error: None }),
+            Ok(Err(_)) | Err(_) => Err(WaitForBackendError { state: self.get(), timed_out: true, error: None }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tauri::async_runtime::{block_on, spawn};
+
+    fn tracker_at(path: &[BackendState]) -> Arc<StateTracker> {
+        let tracker = Arc::new(StateTracker::default());
+        for &to in path {
+            tracker.set(to, "test");
+        }
+        tracker
+    }
+
+    // 另开任务等待，稍后在当前任务中转移状态
+    fn wait_in_task(
+        tracker: &Arc<StateTracker>,
+        timeout: Duration,
+    ) -> tauri::async_runtime::JoinHandle<Result<BackendStateInfo, WaitForBackendError>> {
+        let tracker = tracker.clone();
+        spawn(async move { tracker.wait_ready(timeout).await })
+    }
+
+    #[test]
+    fn times_out_while_starting() {
+        block_on(async {
+            let tracker = tracker_at(&[BackendState::Starting]);
+            let error = tracker.wait_ready(Duration::from_millis(50)).await.unwrap_err();
+            assert!(error.timed_out);
+            assert_eq!(error.state.state, BackendState::Starting);
+        });
+    }
+
+    #[test]
+    fn already_running_returns_immediately() {
+        block_on(async {
+            let tracker = tracker_at(&[BackendState::Starting, BackendState::Running]);
+            let info = tracker.wait_ready(Duration::ZERO).await.unwrap();
+            assert_eq!(info.state, BackendState::Running);
+            assert_eq!(info.previous.unwrap().from, BackendState::Starting);
+
+            let tracker = tracker_at(&[BackendState::External]);
+            assert_eq!(tracker.wait_ready(Duration::ZERO).await.unwrap().state, BackendState::External);
+        });
+    }
+
+    #[test]
+    fn waiters_wake_when_the_backend_becomes_ready() {
+        block_on(async {
+            let tracker = tracker_at(&[BackendState::Starting]);
+            let waiters: Vec<_> = (0..3).map(|_| wait_in_task(&tracker, Duration::from_secs(5))).collect();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tracker.set(BackendState::Running, "ready");
+            for waiter in waiters {
+                assert_eq!(waiter.await.unwrap().unwrap().state, BackendState::Running);
+            }
+        });
+    }
+
+    #[test]
+    fn start_failure_before_ready_ends_the_wait() {
+        block_on(async {
+            let tracker = tracker_at(&[BackendState::Starting]);
+            let waiter = wait_in_task(&tracker, Duration::from_secs(5));
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tracker.set(BackendState::Crashed, "start_failed");
+            let error = waiter.await.unwrap().unwrap_err();
+            assert!(!error.timed_out);
+            assert_eq!(error.state.state, BackendState::Crashed);
+            assert_eq!(error.state.previous.unwrap().detail.as_deref(), Some("start_failed"));
+        });
+    }
+
+    #[test]
+    fn crash_after_running_keeps_waiting_for_the_restart() {
+        block_on(async {
+            let tracker = tracker_at(&[BackendState::Starting, BackendState::Running, BackendState::Crashed]);
+            let waiter = wait_in_task(&tracker, Duration::from_secs(5));
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert!(!waiter.inner().is_finished());
+            tracker.set(BackendState::Starting, "restart");
+            tracker.set(BackendState::Running, "ready");
+            assert_eq!(waiter.await.unwrap().unwrap().state, BackendState::Running);
+        });
+    }
+
+    #[test]
+    fn crash_loop_is_a_terminal_failure() {
+        block_on(async {
+            let tracker = tracker_at(&[BackendState::Starting, BackendState::Running, BackendState::Crashed]);
+            let waiter = wait_in_task(&tracker, Duration::from_secs(5));
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tracker.set(BackendState::CrashLoop, "crash_loop");
+            let error = waiter.await.unwrap().unwrap_err();
+            assert!(!error.timed_out);
+            assert_eq!(error.state.state, BackendState::CrashLoop);
+        });
+    }
+
+    #[test]
+    fn set_reports_only_changes() {
+        let tracker = tracker_at(&[BackendState::Starting]);
+        assert!(tracker.set(BackendState::Starting, "again").is_none());
+        let transition = tracker.set(BackendState::Running, "ready").unwrap();
+        assert_eq!((transition.from, transition.to), (BackendState::Starting, BackendState::Running));
+        assert_eq!(tracker.get().previous.unwrap().detail.as_deref(), Some("ready"));
+    }
+}
@@ -0,0 +1,107 @@
+// 后端退出信息：退出码、Unix 上结束进程的信号、是否由应用主动停止，以及退出前的 stderr 末尾。
+// 由 Terminated 事件生成，记入 ProcessInfo 供 get_backend_status 查询，同时随 `backend://stopped` 下发，
+// 前端据此区分“用户停止”“以代码 1 崩溃”“被系统因内存不足结束”并给出相应提示。
+// reason 是可读的说明：Unix 上为信号名，Windows 上解码常见的 NTSTATUS 退出码（如 0xC0000005）。
+
+use std::time::SystemTime;
+
+#[derive(Clone, serde::Serialize)]
+pub struct TerminationInfo {
+    pub pid: u32,
+    pub code: Option<i32>,
+    /// Unix 上结束进程的信号，正常退出或 Windows 上为 None
+    pub signal: Option<i32>,
+    /// 信号名，例如 "SIGKILL"
+    pub signal_name: Option<&'static str>,
+    /// 可读的退出原因；普通退出码没有说明时为 None
+    pub reason: Option<String>,
+    pub intentional: bool,
+    pub at: SystemTime,
+    pub stderr_tail: Vec<String>,
+}
+
+impl TerminationInfo {
+    pub fn new(pid: u32, code: Option<i32>, signal: Option<i32>, intentional: bool, stderr_tail: Vec<String>) -> Self {
+        let signal_name = signal.and_then(signal_name);
+        let reason = match (signal, signal_name) {
+            (Some(_), Some(name)) => Some(format!("{}: {}", name, signal_description(name))),
+            (Some(signal), None) => Some(format!("Terminated by signal {}", signal)),
+            (None, _) => code.and_then(describe_code),
+        };
+        Self { pid, code, signal, signal_name, reason, intentional, at: SystemTime::now(), stderr_tail }
+    }
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> Option<&'static str> {
+    let name = match signal {
+        libc::SIGHUP => "SIGHUP",
+        libc::SIGINT => "SIGINT",
+        libc::SIGQUIT => "SIGQUIT",
+        libc::SIGILL => "SIGILL",
+        libc::SIGTRAP => "SIGTRAP",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGALRM => "SIGALRM",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGXCPU => "SIGXCPU",
+        libc::SIGXFSZ => "SIGXFSZ",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(not(unix))]
+fn signal_name(_signal: i32) -> Option<&'static str> {
+    None
+}
+
+fn signal_description(name: &str) -> &'static str {
+    match name {
+        "SIGKILL" => "killed (for example by the out-of-memory killer)",
+        "SIGSEGV" => "segmentation fault",
+        "SIGBUS" => "bus error",
+        "SIGABRT" => "aborted",
+        "SIGILL" => "illegal instruction",
+        "SIGFPE" => "arithmetic error",
+        "SIGTERM" => "terminated",
+        "SIGINT" => "interrupted",
+        "SIGHUP" => "hangup",
+        "SIGPIPE" => "broken pipe",
+        "SIGXCPU" => "CPU time limit exceeded",
+        "SIGXFSZ" => "file size limit exceeded",
+        _ => "terminated by signal",
+    }
+}
+
+// Windows 上进程因未处理的异常结束时，退出码是对应的 NTSTATUS
+#[cfg(windows)]
+fn describe_code(code: i32) -> Option<String> {
+    let status = code as u32;
+    let (name, description) = match status {
+        0xC000_0005 => ("STATUS_ACCESS_VIOLATION", "access violation"),
+        0xC000_0017 => ("STATUS_NO_MEMORY", "out of memory"),
+        0xC000_001D => ("STATUS_ILLEGAL_INSTRUCTION", "illegal instruction"),
+        0xC000_0094 => ("STATUS_INTEGER_DIVIDE_BY_ZERO", "integer division by zero"),
+        0xC000_00FD => ("STATUS_STACK_OVERFLOW", "stack overflow"),
+        0xC000_0135 => ("STATUS_DLL_NOT_FOUND", "a required DLL was not found"),
+        0xC000_0139 => ("STATUS_ENTRYPOINT_NOT_FOUND", "a DLL entry point was not found"),
+        0xC000_013A => ("STATUS_CONTROL_C_EXIT", "interrupted by Ctrl+C or console close"),
+        0xC000_0142 => ("STATUS_DLL_INIT_FAILED", "a DLL failed to initialize"),
+        0xC000_0374 => ("STATUS_HEAP_CORRUPTION", "heap corruption"),
+        0xC000_0409 => ("STATUS_STACK_BUFFER_OVERRUN", "fail-fast exception or stack buffer overrun"),
+        0x8000_0003 => ("STATUS_BREAKPOINT", "breakpoint"),
+        _ if status & 0xC000_0000 == 0xC000_0000 => return Some(format!("Unhandled exception 0x{:08X}", status)),
+        _ => return None,
+    };
+    Some(format!("{} (0x{:08X}): {}", name, status, description))
+}
+
+#[cfg(not(windows))]
+fn describe_code(_code: i32) -> Option<String> {
+    None
+}
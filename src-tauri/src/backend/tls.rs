@@ -0,0 +1,236 @@
+// 后端 HTTPS（可选）：config.json 设置 backend_tls 后，启动后端时在数据目录 tls/ 下生成自签名证书与私钥（rcgen），
+// 以 --tls-cert / --tls-key 传给 Sidecar。本应用访问后端的 HTTP 客户端（代理、健康检查、关闭请求等）不使用系统根证书，
+// 只信任这一张证书。证书有效期一年，剩余不足 30 天或文件缺失时在下次启动后端前重新生成。
+// 默认关闭：只监听本机时流量不出本机，仍走 HTTP。
+// 与更新器一样使用系统 TLS（native-tls）；证书用纯 Rust 的 p256 签名，不引入需要 C 工具链的 ring。
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use p256::ecdsa::signature::Signer;
+use p256::pkcs8::EncodePrivateKey;
+use sha2::{Digest, Sha256};
+
+const TLS_DIR_NAME: &str = "tls";
+const CERT_FILE_NAME: &str = "backend-cert.pem";
+const KEY_FILE_NAME: &str = "backend-key.pem";
+// 证书指纹、有效期等，供 get_tls_info 读取而不必解析证书
+const INFO_FILE_NAME: &str = "backend-cert.json";
+const VALIDITY_DAYS: i64 = 365;
+const RENEW_BEFORE_DAYS: i64 = 30;
+// 空闲连接略早于后端关闭（uvicorn 默认 5 秒）前丢弃，避免复用服务端正在关闭的连接
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(4);
+const POOL_MAX_IDLE: usize = 8;
+
+// 访问后端使用的客户端；启用 TLS 时只信任当前证书，每次启动后端时按配置重建
+static CLIENT: Mutex<Option<reqwest::Client>> = Mutex::new(None);
+
+/// 证书信息，`get_tls_info` 返回值的一部分
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct CertificateInfo {
+    /// DER 编码的 SHA-256，冒号分隔的大写十六进制
+    pub fingerprint_sha256: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub subject_alt_names: Vec<String>,
+    #[serde(skip_deserializing)]
+    pub cert_path: PathBuf,
+}
+
+/// `get_tls_info` 返回值
+#[derive(serde::Serialize)]
+pub struct TlsInfo {
+    /// config.json 的 backend_tls
+    pub enabled: bool,
+    /// 当前后端是否以 HTTPS 运行
+    pub active: bool,
+    /// 尚未生成证书时为 None
+    pub certificate: Option<CertificateInfo>,
+}
+
+// 后端在本机，保留少量空闲连接复用，健康检查与代理请求不必每次重新建立连接
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder().pool_idle_timeout(POOL_IDLE_TIMEOUT).pool_max_idle_per_host(POOL_MAX_IDLE)
+}
+
+/// 访问后端的 HTTP 客户端；请求经 backend_client 发送
+pub fn http_client() -> reqwest::Client {
+    CLIENT
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| client_builder().build().unwrap_or_default())
+        .clone()
+}
+
+/// 连接外部后端时调用：外部后端的证书由系统根证书验证
+pub fn reset_client() {
+    *CLIENT.lock().unwrap() = None;
+}
+
+fn tls_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(TLS_DIR_NAME)
+}
+
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der).iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+// 证书覆盖的名称：回环地址、主机名、本机网卡地址，以及无界面模式下指定的监听地址
+fn subject_alt_names(bind_address: IpAddr) -> Vec<String> {
+    let mut names = vec!["localhost".to_string()];
+    names.extend(sysinfo::System::host_name().filter(|name| !name.is_empty()));
+    let mut addresses = vec![IpAddr::V4(Ipv4Addr::LOCALHOST), IpAddr::V6(Ipv6Addr::LOCALHOST)];
+    if !bind_address.is_unspecified() {
+        addresses.push(bind_address);
+    }
+    addresses.extend(super::exposure::local_addresses());
+    names.extend(addresses.iter().map(IpAddr::to_string));
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+    names
+}
+
+pub fn read_info(data_dir: &Path) -> Option<CertificateInfo> {
+    let dir = tls_dir(data_dir);
+    let content = std::fs::read_to_string(dir.join(INFO_FILE_NAME)).ok()?;
+    let mut info: CertificateInfo = serde_json::from_str(&content).ok()?;
+    info.cert_path = crate::folders::canonical(&dir.join(CERT_FILE_NAME));
+    Some(info)
+}
+
+// 证书或私钥缺失、信息不可读、即将过期或不覆盖当前监听地址时需要重新生成
+fn needs_renewal(data_dir: &Path, bind_address: IpAddr) -> bool {
+    let dir = tls_dir(data_dir);
+    if !dir.join(CERT_FILE_NAME).is_file() || !dir.join(KEY_FILE_NAME).is_file() {
+        return true;
+    }
+    let Some(info) = read_info(data_dir) else {
+        return true;
+    };
+    let expiring = chrono::DateTime::parse_from_rfc3339(&info.not_after).map_or(true, |not_after| {
+        not_after.signed_duration_since(chrono::Local::now()) < chrono::Duration::days(RENEW_BEFORE_DAYS)
+    });
+    let uncovered = !bind_address.is_unspecified() && !info.subject_alt_names.contains(&bind_address.to_string());
+    expiring || uncovered
+}
+
+// 交给 rcgen 签名的 ECDSA P-256 私钥
+struct SigningKey {
+    key: p256::ecdsa::SigningKey,
+    public_key: Vec<u8>,
+}
+
+impl SigningKey {
+    fn generate() -> Result<Self, String> {
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate TLS key: {}", e))?;
+        let key = p256::ecdsa::SigningKey::from_slice(&bytes).map_err(|e| format!("Failed to generate TLS key: {}", e))?;
+        let public_key = key.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+        Ok(Self { key, public_key })
+    }
+}
+
+impl rcgen::RemoteKeyPair for SigningKey {
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, rcgen::Error> {
+        let signature: p256::ecdsa::Signature = self.key.try_sign(msg).map_err(|_| rcgen::Error::RemoteKeyError)?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+
+    fn algorithm(&self) -> &'static rcgen::SignatureAlgorithm {
+        &rcgen::PKCS_ECDSA_P256_SHA256
+    }
+}
+
+fn write_private(path: &Path, content: &str) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {:?}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+fn generate(data_dir: &Path, bind_address: IpAddr) -> Result<(), String> {
+    let dir = tls_dir(data_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    let names = subject_alt_names(bind_address);
+    let now = chrono::Local::now();
+    // 提前一天生效，避免与后端所在机器的时钟略有偏差时被拒绝
+    let not_before = now - chrono::Duration::days(1);
+    let not_after = now + chrono::Duration::days(VALIDITY_DAYS);
+    let date = |time: chrono::DateTime<chrono::Local>| {
+        use chrono::Datelike;
+        rcgen::date_time_ymd(time.year(), time.month() as u8, time.day() as u8)
+    };
+
+    let mut params = rcgen::CertificateParams::new(names.clone()).map_err(|e| e.to_string())?;
+    params.distinguished_name.push(rcgen::DnType::CommonName, "DunCrew backend");
+    params.not_before = date(not_before);
+    params.not_after = date(not_after);
+    // 没有启用 rcgen 自带的加密后端时需要自行提供序列号：随机 16 字节，最高位清零保证为正数
+    let mut serial = [0u8; 16];
+    getrandom::fill(&mut serial).map_err(|e| format!("Failed to generate certificate serial: {}", e))?;
+    serial[0] &= 0x7f;
+    params.serial_number = Some(rcgen::SerialNumber::from_slice(&serial));
+    let signing_key = SigningKey::generate()?;
+    let key_pem = signing_key
+        .key
+        .to_pkcs8_pem(p256::pkcs8::LineEnding::LF)
+        .map_err(|e| format!("Failed to encode TLS key: {}", e))?;
+    let key_pair = rcgen::KeyPair::from_remote(Box::new(signing_key)).map_err(|e| e.to_string())?;
+    let cert = params.self_signed(&key_pair).map_err(|e| format!("Failed to generate certificate: {}", e))?;
+
+    write_private(&dir.join(KEY_FILE_NAME), &key_pem)?;
+    let cert_path = dir.join(CERT_FILE_NAME);
+    std::fs::write(&cert_path, cert.pem()).map_err(|e| format!("Failed to write {:?}: {}", cert_path, e))?;
+    let info = CertificateInfo {
+        fingerprint_sha256: fingerprint(cert.der()),
+        not_before: not_before.to_rfc3339(),
+        not_after: not_after.to_rfc3339(),
+        subject_alt_names: names,
+        cert_path: PathBuf::new(),
+    };
+    let info_path = dir.join(INFO_FILE_NAME);
+    let json = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    std::fs::write(&info_path, json).map_err(|e| format!("Failed to write {:?}: {}", info_path, e))?;
+    app_log!("Generated backend TLS certificate {} (valid until {})", info.fingerprint_sha256, info.not_after);
+    Ok(())
+}
+
+fn pinned_client(cert_path: &Path) -> Result<reqwest::Client, String> {
+    let pem = std::fs::read(cert_path).map_err(|e| format!("Failed to read {:?}: {}", cert_path, e))?;
+    let certificate = reqwest::Certificate::from_pem(&pem).map_err(|e| format!("Invalid certificate: {}", e))?;
+    client_builder()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(certificate)
+        .build()
+        .map_err(|e| format!("Failed to build HTTPS client: {}", e))
+}
+
+/// 启动后端之前调用：按配置准备证书与客户端，返回追加到 Sidecar 启动参数的 --tls-cert / --tls-key；未启用时为空
+pub fn sidecar_args(enabled: bool, data_dir: &Path, bind_address: IpAddr) -> Result<Vec<String>, String> {
+    if !enabled {
+        reset_client();
+        return Ok(Vec::new());
+    }
+    if needs_renewal(data_dir, bind_address) {
+        generate(data_dir, bind_address)?;
+    }
+    let dir = tls_dir(data_dir);
+    let cert_path = crate::folders::canonical(&dir.join(CERT_FILE_NAME));
+    let key_path = crate::folders::canonical(&dir.join(KEY_FILE_NAME));
+    *CLIENT.lock().unwrap() = Some(pinned_client(&cert_path)?);
+    Ok(vec![
+        "--tls-cert".to_string(),
+        crate::folders::path_arg(&cert_path)?,
+        "--tls-key".to_string(),
+        crate::folders::path_arg(&key_path)?,
+    ])
+}
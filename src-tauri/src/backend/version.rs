@@ -0,0 +1,155 @@
+// 应用与后端的版本握手：后端通过健康检查后请求 GET /version，与构建时确定的期望版本比较。
+// 期望版本默认与应用版本相同，可在构建时用 DUNCREW_EXPECTED_BACKEND_VERSION 指定。
+// 主版本号不同（0.x 时为次版本号不同）视为不兼容，发送 `backend://version-mismatch` 并弹窗说明哪一方过旧；
+// 旧版后端没有 /version 时版本记为未知，不影响启动。
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::MessageDialogKind;
+
+use super::ServerState;
+use crate::alerts::{self, Alert, AlertCategory};
+use crate::backend_client::BackendClient;
+use crate::i18n;
+
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const EXPECTED_BACKEND_VERSION: &str = match option_env!("DUNCREW_EXPECTED_BACKEND_VERSION") {
+    Some(version) => version,
+    None => APP_VERSION,
+};
+const VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+
+// 已提示过的后端版本，自动重启等情况下同一版本只弹一次窗
+static WARNED: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Clone, serde::Serialize)]
+struct VersionMismatchPayload {
+    app_version: &'static str,
+    expected_backend_version: &'static str,
+    backend_version: String,
+    // "backend"：后端版本过旧；"app"：应用（含前端）版本过旧
+    stale: &'static str,
+}
+
+// 后端可能报告 v1.2.3 这样带前缀的版本
+fn numbers(version: &str) -> Vec<u64> {
+    crate::backup::version_numbers(version.trim().trim_start_matches(['v', 'V']))
+}
+
+// 主版本号相同即兼容；0.x 阶段次版本号也须相同
+fn compatibility_key(version: &str) -> (u64, u64) {
+    let numbers = numbers(version);
+    let major = numbers.first().copied().unwrap_or(0);
+    let minor = numbers.get(1).copied().unwrap_or(0);
+    (major, if major == 0 { minor } else { 0 })
+}
+
+fn compatible_with(version: &str, expected: &str) -> bool {
+    compatibility_key(version) == compatibility_key(expected)
+}
+
+/// 后端版本是否与本应用兼容
+pub fn is_compatible(version: &str) -> bool {
+    compatible_with(version, EXPECTED_BACKEND_VERSION)
+}
+
+// 兼容 {"version": "1.2.3"} 与纯文本两种返回；没有版本时返回 None
+fn parse(body: &str) -> Option<String> {
+    let version = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(value) => value.get("version")?.as_str()?.trim().to_string(),
+        Err(_) => body.trim().to_string(),
+    };
+    (!version.is_empty()).then_some(version)
+}
+
+// 返回 None 表示后端不提供版本（404、旧版后端或请求失败）
+pub(super) async fn fetch(client: &BackendClient) -> Option<String> {
+    parse(&client.get_text("/version", VERSION_TIMEOUT).await.ok()?)
+}
+
+/// 后端就绪后调用：记录后端版本，不兼容时通知前端并弹窗
+pub async fn check(app: &AppHandle) {
+    let version = fetch(&BackendClient::current(app)).await;
+    app.state::<ServerState>().process.lock().unwrap().backend_version = version.clone();
+    let Some(version) = version else {
+        app_log!("Backend does not report its version, skipping version check");
+        return;
+    };
+    if is_compatible(&version) {
+        app_log!("Backend version {} is compatible", version);
+        return;
+    }
+
+    let backend_is_older = numbers(&version) < numbers(EXPECTED_BACKEND_VERSION);
+    let stale = if backend_is_older { "backend" } else { "app" };
+    app_error!(
+        "Backend version {} is incompatible with this app (expects {}), {} is out of date",
+        version, EXPECTED_BACKEND_VERSION, stale
+    );
+    let payload = VersionMismatchPayload {
+        app_version: APP_VERSION,
+        expected_backend_version: EXPECTED_BACKEND_VERSION,
+        backend_version: version.clone(),
+        stale,
+    };
+    crate::app_events::emit_to_frontend(app, "backend://version-mismatch", payload);
+
+    if crate::headless::enabled(app) || WARNED.lock().unwrap().replace(version.clone()).as_deref() == Some(&version) {
+        return;
+    }
+    let args: [(&str, &dyn std::fmt::Display); 3] =
+        [("backend", &version), ("app", &APP_VERSION), ("expected", &EXPECTED_BACKEND_VERSION)];
+    let key = if backend_is_older { "dialog.version_mismatch.backend" } else { "dialog.version_mismatch.app" };
+    alerts::show(app, Alert::new(AlertCategory::VersionMismatch, i18n::tf(key, &args)).kind(MessageDialogKind::Warning));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_major_version_is_compatible() {
+        assert!(compatible_with("1.4.2", "1.0.0"));
+        assert!(compatible_with("1.0.0-beta.2", "1.9.9"));
+        assert!(compatible_with("v1.2.3", "1.0.0"));
+        assert!(!compatible_with("2.0.0", "1.9.9"));
+        assert!(!compatible_with("1.0.0", "2.0.0"));
+    }
+
+    #[test]
+    fn zero_major_versions_must_share_the_minor_version() {
+        assert!(compatible_with("0.3.1", "0.3.7"));
+        assert!(!compatible_with("0.4.0", "0.3.7"));
+        assert!(!compatible_with("0.3.1", "1.3.1"));
+    }
+
+    #[test]
+    fn malformed_versions_are_incompatible_with_releases() {
+        assert!(!compatible_with("garbage", "1.0.0"));
+        assert!(!compatible_with("", "1.0.0"));
+    }
+
+    #[test]
+    fn older_backend_is_detected() {
+        assert!(numbers("1.9.0") < numbers("2.0.0"));
+        assert!(numbers("v2.1.0") > numbers("2.0.5"));
+        assert!(numbers("0.10.0") > numbers("0.9.0"));
+    }
+
+    #[test]
+    fn version_body_accepts_json_and_plain_text() {
+        assert_eq!(parse(r#"{"version": "1.2.3"}"#).as_deref(), Some("1.2.3"));
+        assert_eq!(parse(r#"{"version": "1.2.3", "build": "abc"}"#).as_deref(), Some("1.2.3"));
+        assert_eq!(parse("1.2.3\n").as_deref(), Some("1.2.3"));
+        assert_eq!(parse(r#"{"name": "duncrew-server"}"#), None);
+        assert_eq!(parse(r#"{"version": 3}"#), None);
+        assert_eq!(parse(r#"{"version": " "}"#), None);
+        assert_eq!(parse("   "), None);
+    }
+
+    #[test]
+    fn expected_version_is_compatible_with_itself() {
+        assert!(is_compatible(EXPECTED_BACKEND_VERSION));
+    }
+}
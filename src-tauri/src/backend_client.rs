@@ -0,0 +1,286 @@
+// 访问后端的 HTTP 客户端：健康检查、版本握手、关闭请求、请求代理、流式响应与上传等对后端的调用都经这里发送。
+// 共用 backend::http_client 的连接池（启用 TLS 时只信任当前证书），自动附加会话 token，每次调用自带超时。
+// 幂等的 GET / HEAD 在连接被拒绝或后端返回 502/503/504 时按带抖动的指数退避重试，超时不重试（超时本身已等待够久）。
+// 失败统一为 BackendClientError，区分连接被拒绝、超时、HTTP 状态与响应解析失败。
+// 请求数、失败数与延迟分位数记入客户端侧指标，随 `get_backend_metrics` 返回。
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::backend::{ServerState, AUTH_TOKEN_HEADER};
+
+// 幂等请求最多发送的次数（含第一次）
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+const RETRY_STATUSES: [u16; 3] = [502, 503, 504];
+// 计算延迟分位数时保留的最近样本数
+const LATENCY_SAMPLES: usize = 500;
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics::new());
+
+/// 调用后端失败的原因
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackendClientError {
+    /// 后端未在监听（连接被拒绝或无法建立连接）
+    ConnectionRefused { base_url: String },
+    Timeout { timeout_ms: u64 },
+    /// 后端返回了非 2xx 状态
+    Http { status: u16 },
+    /// 响应体无法读取或解析
+    Decode { message: String },
+    /// 请求本身无效（地址、请求头等），没有发出
+    InvalidRequest { message: String },
+    Other { message: String },
+}
+
+impl BackendClientError {
+    fn kind(&self) -> &'static str {
+        match self {
+            BackendClientError::ConnectionRefused { .. } => "connection_refused",
+            BackendClientError::Timeout { .. } => "timeout",
+            BackendClientError::Http { .. } => "http",
+            BackendClientError::Decode { .. } => "decode",
+            BackendClientError::InvalidRequest { .. } => "invalid_request",
+            BackendClientError::Other { .. } => "other",
+        }
+    }
+}
+
+impl fmt::Display for BackendClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendClientError::ConnectionRefused { base_url } => write!(f, "Backend at {} is not reachable", base_url),
+            BackendClientError::Timeout { timeout_ms } => write!(f, "Request timed out after {} ms", timeout_ms),
+            BackendClientError::Http { status } => write!(f, "HTTP {}", status),
+            BackendClientError::Decode { message } => write!(f, "Invalid response: {}", message),
+            BackendClientError::InvalidRequest { message } | BackendClientError::Other { message } => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+struct Metrics {
+    requests: u64,
+    errors: u64,
+    retries: u64,
+    errors_by_kind: BTreeMap<&'static str, u64>,
+    // 收到响应头的耗时（毫秒）
+    latencies: VecDeque<u32>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self { requests: 0, errors: 0, retries: 0, errors_by_kind: BTreeMap::new(), latencies: VecDeque::new() }
+    }
+
+    fn error(&mut self, error: &BackendClientError) {
+        self.errors += 1;
+        *self.errors_by_kind.entry(error.kind()).or_default() += 1;
+    }
+}
+
+/// 客户端侧指标，`get_backend_metrics` 返回值的 client 字段
+#[derive(Clone, serde::Serialize)]
+pub struct ClientMetrics {
+    pub requests: u64,
+    /// 连接失败、超时、5xx 响应与解析失败的次数
+    pub errors: u64,
+    pub retries: u64,
+    pub errors_by_kind: BTreeMap<&'static str, u64>,
+    /// 最近 LATENCY_SAMPLES 次请求的延迟分位数，还没有请求时为 None
+    pub latency_p50_ms: Option<u32>,
+    pub latency_p95_ms: Option<u32>,
+    pub latency_samples: usize,
+}
+
+/// 应用启动以来的客户端侧指标
+pub fn metrics() -> ClientMetrics {
+    let metrics = METRICS.lock().unwrap();
+    let mut latencies: Vec<u32> = metrics.latencies.iter().copied().collect();
+    latencies.sort_unstable();
+    let percentile = |p: f64| {
+        let index = ((latencies.len() as f64 * p).ceil() as usize).saturating_sub(1);
+        latencies.get(index).copied()
+    };
+    ClientMetrics {
+        requests: metrics.requests,
+        errors: metrics.errors,
+        retries: metrics.retries,
+        errors_by_kind: metrics.errors_by_kind.clone(),
+        latency_p50_ms: percentile(0.5),
+        latency_p95_ms: percentile(0.95),
+        latency_samples: latencies.len(),
+    }
+}
+
+// 第 attempt 次失败后的等待：指数增长，取其中一半到全部之间的随机值，避免多个请求同时重试
+fn backoff(attempt: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+    let mut random = [0u8; 2];
+    let fraction = match getrandom::fill(&mut random) {
+        Ok(()) => f64::from(u16::from_le_bytes(random)) / f64::from(u16::MAX),
+        Err(_) => 0.5,
+    };
+    delay.mul_f64(0.5 + fraction / 2.0)
+}
+
+fn decode_error(error: impl fmt::Display) -> BackendClientError {
+    let error = BackendClientError::Decode { message: error.to_string() };
+    METRICS.lock().unwrap().error(&error);
+    error
+}
+
+/// 某一时刻的后端地址与会话 token；后端重启后端口与 token 会变化，每组调用前重新获取
+pub struct BackendClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl BackendClient {
+    /// 当前后端；取客户端放在读取地址之后，启用 TLS 时客户端随后端启动重建
+    pub fn current(app: &AppHandle) -> Self {
+        let (base_url, token) = {
+            let state = app.state::<ServerState>();
+            let process = state.process.lock().unwrap();
+            (process.base_url(), process.token.clone())
+        };
+        Self { client: crate::backend::http_client(), base_url, token }
+    }
+
+    /// 不附加 token 的客户端，用于询问不是本应用启动的后端（例如端口上已在运行的其他实例）
+    pub fn unauthenticated(base_url: String) -> Self {
+        Self { client: crate::backend::http_client(), base_url, token: None }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 构造发往 `<base_url><path>` 的请求并附加会话 token；用 send / send_idempotent 发送
+    pub fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.header(AUTH_TOKEN_HEADER, token),
+            None => request,
+        }
+    }
+
+    /// 把 reqwest 的错误归类；读取响应体时出错也用它转换
+    pub fn error(&self, error: reqwest::Error, timeout: Option<Duration>) -> BackendClientError {
+        if error.is_connect() {
+            BackendClientError::ConnectionRefused { base_url: self.base_url.clone() }
+        } else if error.is_timeout() {
+            BackendClientError::Timeout { timeout_ms: timeout.map_or(0, |timeout| timeout.as_millis() as u64) }
+        } else if error.is_builder() {
+            BackendClientError::InvalidRequest { message: error.to_string() }
+        } else if error.is_decode() {
+            BackendClientError::Decode { message: error.to_string() }
+        } else {
+            BackendClientError::Other { message: error.to_string() }
+        }
+    }
+
+    /// 发送一次，不检查状态码；timeout 为 None 时不限时（流式响应由调用方控制结束）
+    pub async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        timeout: Option<Duration>,
+    ) -> Result<reqwest::Response, BackendClientError> {
+        let request = match timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+        let started = Instant::now();
+        let result = request.send().await.map_err(|e| self.error(e, timeout));
+        let mut metrics = METRICS.lock().unwrap();
+        metrics.requests += 1;
+        match &result {
+            Ok(response) => {
+                if metrics.latencies.len() == LATENCY_SAMPLES {
+                    metrics.latencies.pop_front();
+                }
+                metrics.latencies.push_back(started.elapsed().as_millis() as u32);
+                if response.status().is_server_error() {
+                    metrics.error(&BackendClientError::Http { status: response.status().as_u16() });
+                }
+            }
+            Err(e) => metrics.error(e),
+        }
+        result
+    }
+
+    /// 发送幂等请求（GET / HEAD），连接被拒绝或 502/503/504 时退避后重试；不检查其他状态码
+    pub async fn send_idempotent(
+        &self,
+        request: reqwest::RequestBuilder,
+        timeout: Duration,
+    ) -> Result<reqwest::Response, BackendClientError> {
+        let mut attempt = 1;
+        loop {
+            // 最后一次或请求体不可复制时直接发送原请求
+            let Some(retry) = request.try_clone().filter(|_| attempt < MAX_ATTEMPTS) else {
+                return self.send(request, Some(timeout)).await;
+            };
+            match self.send(retry, Some(timeout)).await {
+                Ok(response) if !RETRY_STATUSES.contains(&response.status().as_u16()) => return Ok(response),
+                Err(e) if !matches!(e, BackendClientError::ConnectionRefused { .. }) => return Err(e),
+                _ => {
+                    METRICS.lock().unwrap().retries += 1;
+                    tokio::time::sleep(backoff(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// GET，失败时按重试策略重试，非 2xx 返回 Http 错误
+    pub async fn get(&self, path: &str, timeout: Duration) -> Result<reqwest::Response, BackendClientError> {
+        let response = self.send_idempotent(self.request(reqwest::Method::GET, path), timeout).await?;
+        check_status(response)
+    }
+
+    /// GET 一次，不重试；健康检查用，连续失败的次数本身就是信号
+    pub async fn get_once(&self, path: &str, timeout: Duration) -> Result<reqwest::Response, BackendClientError> {
+        let response = self.send(self.request(reqwest::Method::GET, path), Some(timeout)).await?;
+        check_status(response)
+    }
+
+    pub async fn get_text(&self, path: &str, timeout: Duration) -> Result<String, BackendClientError> {
+        self.get(path, timeout).await?.text().await.map_err(decode_error)
+    }
+
+    pub async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, timeout: Duration) -> Result<T, BackendClientError> {
+        let body = self.get(path, timeout).await?.bytes().await.map_err(decode_error)?;
+        serde_json::from_slice(&body).map_err(decode_error)
+    }
+
+    /// 不带请求体的 POST，不重试，非 2xx 返回 Http 错误
+    pub async fn post(&self, path: &str, timeout: Duration) -> Result<reqwest::Response, BackendClientError> {
+        let response = self.send(self.request(reqwest::Method::POST, path), Some(timeout)).await?;
+        check_status(response)
+    }
+
+    /// POST JSON，不重试，非 2xx 返回 Http 错误
+    pub async fn post_json<S: serde::Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &S,
+        timeout: Duration,
+    ) -> Result<reqwest::Response, BackendClientError> {
+        let response = self.send(self.request(reqwest::Method::POST, path).json(body), Some(timeout)).await?;
+        check_status(response)
+    }
+}
+
+fn check_status(response: reqwest::Response) -> Result<reqwest::Response, BackendClientError> {
+    if !response.status().is_success() {
+        return Err(BackendClientError::Http { status: response.status().as_u16() });
+    }
+    Ok(response)
+}
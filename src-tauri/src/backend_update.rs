@@ -0,0 +1,341 @@
+// 后端 Sidecar 独立更新：Python 后端比应用壳更新频繁，不必每次都发完整安装包。
+// 从配置的地址获取 minisign 签名的清单（版本、各平台下载地址与 SHA-256），下载到数据目录的
+// updates/ 下并校验，停止后端后替换二进制再启动；新版本首次健康检查不通过时换回旧二进制。
+// 默认关闭，需在 config.json 的 backend_update 中启用。
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::config::BackendUpdateConfig;
+
+// 记录已安装的更新版本与哈希，供 sidecar_integrity 校验。二进制是所有档案共用的，记录放在它旁边
+const RECORD_FILE_NAME: &str = "backend-update.json";
+const UPDATES_DIR_NAME: &str = "updates";
+// 新版本启动后等待健康检查通过的时间，超时即回滚
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+#[derive(serde::Deserialize)]
+struct Manifest {
+    version: String,
+    notes: Option<String>,
+    // 键为 `<os>-<arch>`，例如 windows-x86_64
+    platforms: HashMap<String, PlatformAsset>,
+}
+
+#[derive(Clone, serde::Deserialize)]
+struct PlatformAsset {
+    url: String,
+    sha256: String,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct InstalledRecord {
+    version: String,
+    sha256: String,
+    installed_at: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct BackendUpdateInfo {
+    pub current_version: Option<String>,
+    pub latest_version: String,
+    pub available: bool,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct DownloadProgress {
+    bytes_downloaded: u64,
+    total_bytes: Option<u64>,
+}
+
+// `backend-update://applied` 事件负载：新版本已通过健康检查
+#[derive(Clone, serde::Serialize)]
+struct UpdateAppliedPayload {
+    version: String,
+}
+
+struct UpdateGuard(());
+
+impl UpdateGuard {
+    fn acquire() -> Result<Self, String> {
+        IN_PROGRESS
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map_err(|_| "A backend update is already in progress".to_string())?;
+        Ok(UpdateGuard(()))
+    }
+}
+
+impl Drop for UpdateGuard {
+    fn drop(&mut self) {
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+fn platform_key() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+fn record_path() -> Result<PathBuf, String> {
+    Ok(crate::sidecar_integrity::sidecar_path()?.with_file_name(RECORD_FILE_NAME))
+}
+
+// 旧版本把记录写在当前档案的数据目录中，读不到新位置时仍认这份记录
+fn legacy_record_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(RECORD_FILE_NAME))
+}
+
+fn read_record(app: &AppHandle) -> Option<InstalledRecord> {
+    let content = [record_path(), legacy_record_path(app)]
+        .into_iter()
+        .flatten()
+        .find_map(|path| std::fs::read_to_string(path).ok())?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_record(app: &AppHandle, record: Option<&InstalledRecord>) -> Result<(), String> {
+    let path = record_path()?;
+    if let Ok(legacy) = legacy_record_path(app) {
+        let _ = std::fs::remove_file(legacy);
+    }
+    match record {
+        Some(record) => {
+            let json = serde_json::to_string_pretty(record).map_err(|e| e.to_string())?;
+            std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+        }
+        None => {
+            let _ = std::fs::remove_file(&path);
+            Ok(())
+        }
+    }
+}
+
+/// 通过更新安装的 Sidecar 的 SHA-256
+pub fn installed_sha256(app: &AppHandle) -> Option<String> {
+    read_record(app).map(|record| record.sha256)
+}
+
+fn update_config(app: &AppHandle) -> Result<BackendUpdateConfig, String> {
+    let config = crate::app_config(app);
+    if !config.backend_update.enabled {
+        return Err("Backend updates are disabled".to_string());
+    }
+    if config.backend_source.is_some() || config.external_backend_url().is_some() {
+        return Err("Backend updates are only available for the bundled backend".to_string());
+    }
+    Ok(config.backend_update)
+}
+
+// 下载清单与签名，签名校验通过后才解析
+async fn fetch_manifest(client: &reqwest::Client, config: &BackendUpdateConfig) -> Result<Manifest, String> {
+    let url = config.manifest_url.trim();
+    let get = |url: String| async move {
+        let response = client
+            .get(&url)
+            .timeout(MANIFEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()));
+        }
+        response.bytes().await.map_err(|e| format!("Failed to fetch {}: {}", url, e))
+    };
+    let manifest_bytes = get(url.to_string()).await?;
+    let signature_bytes = get(format!("{}.sig", url)).await?;
+
+    let public_key = minisign_verify::PublicKey::from_base64(config.public_key.trim())
+        .map_err(|e| format!("Invalid backend_update.public_key: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&String::from_utf8_lossy(&signature_bytes))
+        .map_err(|e| format!("Invalid update manifest signature: {}", e))?;
+    public_key
+        .verify(&manifest_bytes, &signature, false)
+        .map_err(|e| format!("Update manifest signature verification failed: {}", e))?;
+    serde_json::from_slice(&manifest_bytes).map_err(|e| format!("Invalid update manifest: {}", e))
+}
+
+// 正在运行的后端版本（/health 返回），取不到时用更新记录中的版本
+async fn current_version(app: &AppHandle) -> Option<String> {
+    let client = crate::backend_client::BackendClient::current(app);
+    let from_health = async {
+        let body: serde_json::Value = client.get_json("/health", Duration::from_secs(2)).await.ok()?;
+        body.get("version")?.as_str().map(str::to_string)
+    };
+    match from_health.await {
+        Some(version) => Some(version),
+        None => read_record(app).map(|record| record.version),
+    }
+}
+
+fn is_newer(latest: &str, current: Option<&str>) -> bool {
+    match current {
+        Some(current) => crate::backup::version_numbers(latest) > crate::backup::version_numbers(current),
+        None => true,
+    }
+}
+
+/// 检查后端更新；未启用时返回错误
+#[tauri::command]
+pub async fn check_backend_update(app: AppHandle) -> Result<BackendUpdateInfo, String> {
+    let config = update_config(&app)?;
+    let client = reqwest::Client::new();
+    let manifest = fetch_manifest(&client, &config).await?;
+    let current_version = current_version(&app).await;
+    let available = manifest.platforms.contains_key(&platform_key())
+        && is_newer(&manifest.version, current_version.as_deref());
+    Ok(BackendUpdateInfo {
+        current_version,
+        latest_version: manifest.version,
+        available,
+        notes: manifest.notes,
+    })
+}
+
+// 下载到 target，边下载边计算哈希，发送 `backend-update://progress`
+async fn download(app: &AppHandle, client: &reqwest::Client, url: &str, target: &Path) -> Result<String, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download {}: HTTP {}", url, response.status()));
+    }
+    let total_bytes = response.content_length();
+    let mut file = std::fs::File::create(target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+    let mut hasher = Sha256::new();
+    let mut bytes_downloaded = 0u64;
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download interrupted: {}", e))? {
+        file.write_all(&chunk).map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+        hasher.update(&chunk);
+        bytes_downloaded += chunk.len() as u64;
+        let _ = app.emit("backend-update://progress", DownloadProgress { bytes_downloaded, total_bytes });
+    }
+    file.flush().map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// 在 Sidecar 旁边的路径，例如 duncrew-server.exe.old；与 Sidecar 同目录才能原子改名
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}", name, suffix))
+}
+
+// 替换二进制：Windows 上运行中的 exe 不能覆盖但可以改名，因此先把旧文件改名再放入新文件
+fn swap(sidecar: &Path, staged: &Path, previous: &Path) -> Result<(), String> {
+    let _ = std::fs::remove_file(previous);
+    std::fs::rename(sidecar, previous).map_err(|e| format!("Failed to move {:?} aside: {}", sidecar, e))?;
+    if let Err(e) = std::fs::rename(staged, sidecar) {
+        let _ = std::fs::rename(previous, sidecar);
+        return Err(format!("Failed to install new backend: {}", e));
+    }
+    Ok(())
+}
+
+fn roll_back(sidecar: &Path, previous: &Path) -> Result<(), String> {
+    let failed = sibling(sidecar, "failed");
+    let _ = std::fs::remove_file(&failed);
+    let _ = std::fs::rename(sidecar, &failed);
+    std::fs::rename(previous, sidecar).map_err(|e| format!("Failed to restore previous backend: {}", e))
+}
+
+/// 下载并安装后端更新，成功返回新版本号。新版本在 30 秒内未通过健康检查时自动换回旧版本并返回错误
+#[tauri::command]
+pub async fn apply_backend_update(app: AppHandle) -> Result<String, String> {
+    let _guard = UpdateGuard::acquire()?;
+    let config = update_config(&app)?;
+    let client = reqwest::Client::new();
+    let manifest = fetch_manifest(&client, &config).await?;
+    let asset = manifest
+        .platforms
+        .get(&platform_key())
+        .cloned()
+        .ok_or_else(|| format!("No backend update for {}", platform_key()))?;
+    if !is_newer(&manifest.version, current_version(&app).await.as_deref()) {
+        return Err(format!("Backend is already up to date ({})", manifest.version));
+    }
+
+    let updates_dir = crate::backend_data_dir(&app)?.join(UPDATES_DIR_NAME);
+    std::fs::create_dir_all(&updates_dir).map_err(|e| format!("Failed to create {:?}: {}", updates_dir, e))?;
+    let download_path = updates_dir.join(format!("duncrew-server-{}.download", manifest.version));
+    app_log!("Downloading backend {} from {}", manifest.version, asset.url);
+    let sha256 = download(&app, &client, &asset.url, &download_path).await;
+    let sha256 = match sha256 {
+        Ok(hash) if hash.eq_ignore_ascii_case(asset.sha256.trim()) => hash,
+        Ok(hash) => {
+            let _ = std::fs::remove_file(&download_path);
+            return Err(format!("Downloaded backend hash mismatch: expected {}, got {}", asset.sha256, hash));
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&download_path);
+            return Err(e);
+        }
+    };
+
+    let sidecar = crate::sidecar_integrity::sidecar_path()?;
+    let staged = sibling(&sidecar, "new");
+    let previous = sibling(&sidecar, "old");
+    std::fs::copy(&download_path, &staged).map_err(|e| format!("Failed to stage new backend next to {:?}: {}", sidecar, e))?;
+    let _ = std::fs::remove_file(&download_path);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755));
+    }
+
+    let previous_record = read_record(&app);
+    let record = InstalledRecord {
+        version: manifest.version.clone(),
+        sha256,
+        installed_at: chrono::Local::now().to_rfc3339(),
+    };
+    let install = {
+        let (app, sidecar, previous) = (app.clone(), sidecar.clone(), previous.clone());
+        move || -> Result<(), String> {
+            swap(&sidecar, &staged, &previous)?;
+            // 没有记录时 sidecar_integrity 会把新二进制当作被篡改，换回旧版本
+            if let Err(e) = write_record(&app, Some(&record)) {
+                roll_back(&sidecar, &previous).map_err(|rollback| format!("{} ({})", e, rollback))?;
+                return Err(e);
+            }
+            crate::sidecar_integrity::invalidate();
+            Ok(())
+        }
+    };
+    app_log!("Installing backend {}", manifest.version);
+    let installed = crate::backend::with_backend_stopped(&app, "update", install).await;
+    match installed {
+        Ok(Err(e)) => return Err(e),
+        Ok(Ok(())) if crate::health::wait_until_ready(&app, READY_TIMEOUT).await => {
+            app_log!("Backend updated to {}", manifest.version);
+            let _ = app.emit("backend-update://applied", UpdateAppliedPayload { version: manifest.version.clone() });
+            return Ok(manifest.version);
+        }
+        Ok(Ok(())) => app_error!("Backend {} did not pass its health check, rolling back", manifest.version),
+        Err(e) => app_error!("Backend {} failed to start ({}), rolling back", manifest.version, e),
+    }
+
+    let rollback = {
+        let app = app.clone();
+        move || -> Result<(), String> {
+            roll_back(&sidecar, &previous)?;
+            write_record(&app, previous_record.as_ref())?;
+            crate::sidecar_integrity::invalidate();
+            Ok(())
+        }
+    };
+    crate::backend::with_backend_stopped(&app, "update-rollback", rollback)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+        .map_err(|e| format!("Backend update failed and rollback failed: {}", e))?;
+    Err(format!("Backend {} failed its first health check, the previous version was restored", manifest.version))
+}
@@ -0,0 +1,450 @@
+// 数据目录备份与恢复：停止后端保证 SQLite 等文件不在写入中，把数据目录（不含 logs、cache）打包成 zip，
+// 完成后重新启动后端。压缩包根目录有 manifest.json，数据文件位于 data/ 下，供恢复时校验。
+// 打包 / 解压在阻塞线程池中进行，期间发送 `backup://progress`，可通过 `cancel_backup` 或 `cancel_job` 取消。
+// 备份与恢复都是独占数据目录的后台任务（jobs.rs），同一时间只有一个，后到的排队。
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+use zip::write::SimpleFileOptions;
+
+use crate::jobs::{self, JobHandle, JobKind, JobPriority, JobSpec};
+
+pub const MANIFEST_NAME: &str = "manifest.json";
+pub const ARCHIVE_DATA_PREFIX: &str = "data/";
+pub const BACKUP_FORMAT: &str = "duncrew-backup";
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+// 不备份的顶层目录：日志与缓存可再生，体积又大
+const EXCLUDED_DIRS: [&str; 2] = [crate::logs::LOGS_DIR_NAME, crate::folders::CACHE_DIR_NAME];
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+const PROGRESS_STEP: u64 = 8 * 1024 * 1024;
+// zip 单文件超过 4GB 需要 ZIP64
+const ZIP64_THRESHOLD: u64 = u32::MAX as u64;
+
+#[derive(Clone, serde::Serialize)]
+pub struct ProgressPayload {
+    pub operation: &'static str,
+    pub bytes_processed: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    // 备份时数据目录的顶层条目，恢复时用于粗略判断内容是否匹配
+    pub contents: Vec<String>,
+    pub total_bytes: u64,
+}
+
+// 收集要备份的文件（相对路径、大小），跳过符号链接与无法读取的条目
+fn collect_files(data_dir: &Path, exclude: &Path) -> (Vec<(PathBuf, u64)>, Vec<String>) {
+    let mut files = Vec::new();
+    let mut top_level = Vec::new();
+    let mut stack = vec![data_dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path == exclude {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let Ok(relative) = path.strip_prefix(data_dir) else {
+                continue;
+            };
+            if dir == data_dir {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if file_type.is_dir() && EXCLUDED_DIRS.contains(&name.as_str()) {
+                    continue;
+                }
+                top_level.push(name);
+            }
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if file_type.is_file() {
+                if let Ok(meta) = entry.metadata() {
+                    files.push((relative.to_path_buf(), meta.len()));
+                }
+            }
+        }
+    }
+    top_level.sort();
+    (files, top_level)
+}
+
+// zip 内部统一使用 / 分隔
+fn archive_name(relative: &Path) -> String {
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    format!("{}{}", ARCHIVE_DATA_PREFIX, parts.join("/"))
+}
+
+fn write_archive(app: &AppHandle, job: &JobHandle, data_dir: &Path, target: &Path) -> Result<(), String> {
+    let (files, contents) = collect_files(data_dir, target);
+    let total_bytes: u64 = files.iter().map(|(_, size)| size).sum();
+    let manifest = Manifest {
+        format: BACKUP_FORMAT.to_string(),
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: app.package_info().version.to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        contents,
+        total_bytes,
+    };
+
+    let file = std::fs::File::create(target)
+        .map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(MANIFEST_NAME, options).map_err(|e| e.to_string())?;
+    let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.write_all(manifest_json.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut bytes_processed = 0u64;
+    let mut next_report = 0u64;
+    for (relative, size) in &files {
+        let source = data_dir.join(relative);
+        // 文件可能在收集之后被删除，跳过即可
+        let Ok(mut input) = std::fs::File::open(&source) else {
+            app_error!("Skipping unreadable file {:?} during backup", source);
+            continue;
+        };
+        let file_options = options.large_file(*size >= ZIP64_THRESHOLD);
+        zip.start_file(archive_name(relative), file_options).map_err(|e| e.to_string())?;
+        loop {
+            if job.is_cancelled() {
+                return Err("cancelled".to_string());
+            }
+            let n = input.read(&mut buffer).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+            if n == 0 {
+                break;
+            }
+            zip.write_all(&buffer[..n]).map_err(|e| e.to_string())?;
+            bytes_processed += n as u64;
+            if bytes_processed >= next_report {
+                next_report = bytes_processed + PROGRESS_STEP;
+                job.set_progress(bytes_processed, total_bytes);
+                let _ = app.emit(
+                    "backup://progress",
+                    ProgressPayload { operation: "backup", bytes_processed, total_bytes },
+                );
+            }
+        }
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    let _ = app.emit(
+        "backup://progress",
+        ProgressPayload { operation: "backup", bytes_processed: total_bytes, total_bytes },
+    );
+    Ok(())
+}
+
+/// 备份数据目录。未指定 target 时弹出保存对话框，指定的 target 须是 pick_save_path 返回的；
+/// 用户取消对话框或调用 `cancel_backup` 都返回错误 "cancelled"，未完成的压缩包会被删除。成功返回压缩包路径。
+#[tauri::command]
+pub async fn create_backup(app: AppHandle, target: Option<String>) -> Result<String, String> {
+    let data_dir = crate::backend_data_dir(&app)?;
+    let target = match target {
+        Some(path) => crate::file_picker::ensure_picked(&app, &path)?,
+        None => {
+            let default_name = format!("duncrew-backup-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            let mut dialog = app.dialog().file().set_file_name(&default_name).add_filter("Zip", &["zip"]);
+            if let Some(dir) = dirs::document_dir() {
+                dialog = dialog.set_directory(dir);
+            }
+            dialog
+                .blocking_save_file()
+                .ok_or_else(|| "cancelled".to_string())?
+                .into_path()
+                .map_err(|e| e.to_string())?
+        }
+    };
+
+    if !crate::storage::confirm_free_space(&app, &target, "backup") {
+        return Err("cancelled".to_string());
+    }
+    let label = target.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let spec = JobSpec::new(JobKind::Backup, label).cancellable();
+    jobs::run(&app, spec, |job| run_backup(&app, job, data_dir, &target, "backup")).await?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// 停止后端后把数据目录打包到 target，完成后重新启动后端（重启通知的 reason 为 `reason`）。
+/// 失败时删除未完成的压缩包；须在 Backup 任务中调用
+pub async fn run_backup(
+    app: &AppHandle,
+    job: JobHandle,
+    data_dir: PathBuf,
+    target: &Path,
+    reason: &'static str,
+) -> Result<(), String> {
+    app_log!("Creating backup of {:?} at {:?}", data_dir, target);
+    let app_handle = app.clone();
+    let archive = target.to_path_buf();
+    let result = crate::backend::with_backend_stopped(app, reason, move || {
+        write_archive(&app_handle, &job, &data_dir, &archive)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+
+    match result {
+        Ok(()) => {
+            app_log!("Backup written to {:?}", target);
+            Ok(())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(target);
+            app_error!("Backup failed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// 取消排队中或正在进行的备份 / 恢复
+#[tauri::command]
+pub fn cancel_backup(app: AppHandle) {
+    app.state::<jobs::JobManager>().cancel_kinds(&app, &[JobKind::Backup, JobKind::Restore]);
+}
+
+#[derive(Clone, serde::Serialize)]
+struct RestoreFinishedPayload {
+    success: bool,
+    error: Option<String>,
+    // 恢复前数据的安全副本，成功后由用户决定是否删除
+    safety_copy: Option<PathBuf>,
+}
+
+pub(crate) fn version_numbers(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-', '+'])
+        .take(3)
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+// 校验清单与所有条目，任何条目越出数据目录都拒绝整个压缩包，校验通过前不改动数据目录
+fn validate_archive(app: &AppHandle, archive: &Path) -> Result<Manifest, String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open {:?}: {}", archive, e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("{:?} is not a valid zip archive: {}", archive, e))?;
+    let manifest: Manifest = {
+        let entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| format!("{:?} is not a DunCrew backup (missing {})", archive, MANIFEST_NAME))?;
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid backup manifest: {}", e))?
+    };
+    if manifest.format != BACKUP_FORMAT {
+        return Err(format!("Unsupported backup format \"{}\"", manifest.format));
+    }
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "Backup format version {} is newer than supported version {}",
+            manifest.format_version, BACKUP_FORMAT_VERSION
+        ));
+    }
+    let app_version = app.package_info().version.to_string();
+    if version_numbers(&manifest.app_version) > version_numbers(&app_version) {
+        return Err(format!(
+            "Backup was created by DunCrew {}, please update from {} before restoring",
+            manifest.app_version, app_version
+        ));
+    }
+
+    for i in 0..zip.len() {
+        let entry = zip.by_index_raw(i).map_err(|e| e.to_string())?;
+        let name = entry.name().map_err(|e| format!("Invalid entry name in backup: {}", e))?.to_string();
+        if name == MANIFEST_NAME {
+            continue;
+        }
+        let escapes = match entry.enclosed_name() {
+            Some(path) => !name.starts_with(ARCHIVE_DATA_PREFIX) || path.strip_prefix(ARCHIVE_DATA_PREFIX).is_err(),
+            None => true,
+        };
+        if escapes {
+            return Err(format!("Backup contains an invalid entry \"{}\"", name));
+        }
+    }
+    Ok(manifest)
+}
+
+// 顶层条目中不参与恢复的部分：日志目录正被 app 日志占用，保持原地
+fn is_kept_in_place(name: &std::ffi::OsStr) -> bool {
+    name == crate::logs::LOGS_DIR_NAME
+}
+
+// 把数据目录中的现有内容移到安全副本目录（逐个移动顶层条目，日志目录除外）
+fn move_aside(data_dir: &Path, safety: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(safety).map_err(|e| format!("Failed to create {:?}: {}", safety, e))?;
+    let entries = std::fs::read_dir(data_dir).map_err(|e| format!("Failed to read {:?}: {}", data_dir, e))?;
+    for entry in entries.flatten() {
+        if is_kept_in_place(&entry.file_name()) {
+            continue;
+        }
+        std::fs::rename(entry.path(), safety.join(entry.file_name()))
+            .map_err(|e| format!("Failed to move {:?} aside: {}", entry.path(), e))?;
+    }
+    Ok(())
+}
+
+// 回滚：删除已解压的内容，把安全副本移回原处
+fn roll_back(data_dir: &Path, safety: &Path) -> Result<(), String> {
+    if let Ok(entries) = std::fs::read_dir(data_dir) {
+        for entry in entries.flatten() {
+            if is_kept_in_place(&entry.file_name()) {
+                continue;
+            }
+            let path = entry.path();
+            let _ = if path.is_dir() { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+        }
+    }
+    let entries = std::fs::read_dir(safety).map_err(|e| format!("Failed to read {:?}: {}", safety, e))?;
+    for entry in entries.flatten() {
+        std::fs::rename(entry.path(), data_dir.join(entry.file_name()))
+            .map_err(|e| format!("Failed to move {:?} back: {}", entry.path(), e))?;
+    }
+    let _ = std::fs::remove_dir(safety);
+    Ok(())
+}
+
+fn extract(app: &AppHandle, job: &JobHandle, archive: &Path, data_dir: &Path, total_bytes: u64) -> Result<(), String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open {:?}: {}", archive, e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut bytes_processed = 0u64;
+    let mut next_report = 0u64;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        // validate_archive 已检查过，这里再取一次相对路径，不信任原始名称
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|p| p.strip_prefix(ARCHIVE_DATA_PREFIX).ok().map(Path::to_path_buf))
+        else {
+            continue;
+        };
+        let target = data_dir.join(&relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut output = std::fs::File::create(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+        loop {
+            if job.is_cancelled() {
+                return Err("cancelled".to_string());
+            }
+            let n = entry.read(&mut buffer).map_err(|e| format!("Failed to extract {:?}: {}", relative, e))?;
+            if n == 0 {
+                break;
+            }
+            output.write_all(&buffer[..n]).map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+            bytes_processed += n as u64;
+            if bytes_processed >= next_report {
+                next_report = bytes_processed + PROGRESS_STEP;
+                job.set_progress(bytes_processed, total_bytes);
+                let _ = app.emit(
+                    "backup://progress",
+                    ProgressPayload { operation: "restore", bytes_processed, total_bytes },
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn restore_into(
+    app: &AppHandle,
+    job: &JobHandle,
+    archive: &Path,
+    data_dir: &Path,
+    total_bytes: u64,
+) -> Result<PathBuf, String> {
+    let dir_name = data_dir.file_name().unwrap_or_default().to_string_lossy();
+    let safety = data_dir.with_file_name(format!(
+        "{}.pre-restore-{}",
+        dir_name,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    if let Err(e) = move_aside(data_dir, &safety) {
+        // 部分条目可能已移走，同样需要移回
+        roll_back(data_dir, &safety).map_err(|r| format!("{}; rollback failed: {}", e, r))?;
+        return Err(e);
+    }
+    if let Err(e) = extract(app, job, archive, data_dir, total_bytes) {
+        app_error!("Restore failed, rolling back: {}", e);
+        roll_back(data_dir, &safety).map_err(|r| format!("{}; rollback failed, previous data is in {:?}: {}", e, safety, r))?;
+        return Err(e);
+    }
+    // 恢复出的 config.json 要在后端重新启动前生效
+    if let Err(e) = app.state::<crate::config::ConfigState>().reload() {
+        app_error!("Restored config could not be loaded, keeping current settings: {}", e);
+    }
+    Ok(safety)
+}
+
+// 备份历史中的压缩包（自动备份目录内）可直接恢复，其他位置须是用户在文件对话框中选中的
+fn restore_source(app: &AppHandle, archive_path: &str) -> Result<PathBuf, String> {
+    let path = crate::folders::canonical(Path::new(archive_path));
+    let in_history = crate::auto_backup::target_dir(app)
+        .is_ok_and(|dir| path.starts_with(crate::folders::canonical(&dir)));
+    if in_history && path.is_file() {
+        return Ok(path);
+    }
+    crate::file_picker::ensure_picked(app, archive_path)
+}
+
+/// 从备份恢复数据目录：校验清单后停止后端，把现有数据移到 `<数据目录>.pre-restore-<时间>`，
+/// 解压并重新启动后端。任一步失败都会移回原数据；结果另以 `backup://restore-finished` 广播。
+/// archive_path 须在备份历史中，或是 pick_files 返回的。成功返回安全副本路径。
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, archive_path: String) -> Result<String, String> {
+    let archive = restore_source(&app, &archive_path)?;
+    let data_dir = crate::backend_data_dir(&app)?;
+    // 压缩包在数据目录里会被一起移到安全副本中
+    if archive.canonicalize().is_ok_and(|p| data_dir.canonicalize().is_ok_and(|d| p.starts_with(d))) {
+        return Err("Move the backup archive out of the data directory before restoring".to_string());
+    }
+
+    // 用户正在等待恢复结果，排在排队中的备份之前
+    let label = archive.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let spec = JobSpec::new(JobKind::Restore, label).priority(JobPriority::High).cancellable();
+    let result = jobs::run(&app, spec, |job| async {
+        let manifest = validate_archive(&app, &archive)?;
+        app_log!("Restoring backup {:?} created {}", archive, manifest.created_at);
+        let (app_handle, archive) = (app.clone(), archive.clone());
+        crate::backend::with_backend_stopped(&app, "restore", move || {
+            restore_into(&app_handle, &job, &archive, &data_dir, manifest.total_bytes)
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+    })
+    .await;
+
+    let payload = match &result {
+        Ok(safety) => RestoreFinishedPayload { success: true, error: None, safety_copy: Some(safety.clone()) },
+        Err(e) => RestoreFinishedPayload { success: false, error: Some(e.clone()), safety_copy: None },
+    };
+    let _ = app.emit("backup://restore-finished", payload);
+    match result {
+        Ok(safety) => {
+            app_log!("Backup restored, previous data kept in {:?}", safety);
+            Ok(safety.to_string_lossy().to_string())
+        }
+        Err(e) => {
+            app_error!("Restore failed: {}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,240 @@
+// 缓存目录管理：后端的缩略图、向量等缓存统一放在数据目录下的 cache/，经 --cache-dir 传给 Sidecar，备份时排除。
+// clear_cache 按分类（cache/ 下的一级子目录）删除：先经 stdin 控制通道请求后端释放文件，删除时发送 `cache://progress`。
+// 分类名只能是单个目录名，规范化后必须仍在缓存根目录内，符号链接只删除链接本身。
+// 被运行中的后端占用而删不掉的文件记入 cache/.pending-delete.json，下次启动后端之前删除，不让整个操作失败。
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backup::ProgressPayload;
+
+const PENDING_FILE_NAME: &str = ".pending-delete.json";
+// 发送释放请求后等待后端关闭文件句柄的时间
+const RELEASE_WAIT: Duration = Duration::from_millis(500);
+// 每删除这么多字节发送一次进度
+const PROGRESS_STEP: u64 = 8 * 1024 * 1024;
+
+/// `clear_cache` 返回值
+#[derive(serde::Serialize)]
+pub struct ClearCacheResult {
+    pub bytes_freed: u64,
+    pub files_deleted: u64,
+    /// 被占用的文件数，下次启动后端前删除
+    pub scheduled: u64,
+    pub categories: Vec<String>,
+}
+
+#[derive(Default)]
+struct Outcome {
+    bytes_freed: u64,
+    files_deleted: u64,
+    // 相对于缓存根目录
+    pending: Vec<PathBuf>,
+}
+
+/// 缓存根目录，已创建并规范化
+pub fn cache_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::folders::cache_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    Ok(crate::folders::canonical(&dir))
+}
+
+/// 追加到 Sidecar 启动参数
+pub fn sidecar_args(data_dir: &Path) -> Result<Vec<String>, String> {
+    let dir = data_dir.join(crate::folders::CACHE_DIR_NAME);
+    // 先创建，后端可以直接写入，路径也能规范化
+    let _ = std::fs::create_dir_all(&dir);
+    Ok(vec!["--cache-dir".to_string(), crate::folders::path_arg(&crate::folders::canonical(&dir))?])
+}
+
+// 校验分类名并返回其目录；确保不会删到缓存根目录之外
+fn category_dir(root: &Path, category: &str) -> Result<PathBuf, String> {
+    let valid = !category.is_empty()
+        && !category.starts_with('.')
+        && category.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid cache category \"{}\"", category));
+    }
+    let dir = root.join(category);
+    let Ok(metadata) = std::fs::symlink_metadata(&dir) else {
+        return Ok(dir);
+    };
+    if metadata.file_type().is_symlink() || !crate::folders::canonical(&dir).starts_with(root) {
+        return Err(format!("Cache category \"{}\" points outside the cache directory", category));
+    }
+    Ok(dir)
+}
+
+// 现有的全部分类
+fn all_categories(root: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut categories: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    categories.sort();
+    categories
+}
+
+// 深度优先删除；不进入符号链接，删不掉的文件记入 pending，其余照常删除
+fn delete_tree(root: &Path, dir: &Path, outcome: &mut Outcome, progress: &mut impl FnMut(u64)) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            delete_tree(root, &path, outcome, progress);
+            continue;
+        }
+        let size = if file_type.is_file() { entry.metadata().map_or(0, |m| m.len()) } else { 0 };
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                outcome.bytes_freed += size;
+                outcome.files_deleted += 1;
+                progress(outcome.bytes_freed);
+            }
+            Err(e) => {
+                app_log!("Cache file {:?} is in use ({}), deleting it after the next restart", path, e);
+                if let Ok(relative) = path.strip_prefix(root) {
+                    outcome.pending.push(relative.to_path_buf());
+                }
+            }
+        }
+    }
+    // 仍有被占用的文件时目录不为空，留到下次
+    let _ = std::fs::remove_dir(dir);
+}
+
+fn read_pending(root: &Path) -> Vec<PathBuf> {
+    std::fs::read_to_string(root.join(PENDING_FILE_NAME))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_pending(root: &Path, pending: &[PathBuf]) -> Result<(), String> {
+    let path = root.join(PENDING_FILE_NAME);
+    if pending.is_empty() {
+        let _ = std::fs::remove_file(&path);
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(pending).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// 启动后端之前调用：删除上次被占用的缓存文件。记录中的路径同样限制在缓存根目录内
+pub fn delete_pending(data_dir: &Path) {
+    let root = crate::folders::canonical(&data_dir.join(crate::folders::CACHE_DIR_NAME));
+    let pending = read_pending(&root);
+    if pending.is_empty() {
+        return;
+    }
+    let remaining: Vec<PathBuf> = pending
+        .into_iter()
+        .filter(|relative| {
+            let path = root.join(relative);
+            let inside = relative.is_relative()
+                && relative.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+            if !inside {
+                app_error!("Ignoring pending cache deletion outside the cache directory: {:?}", relative);
+                return false;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => false,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+                Err(e) => {
+                    app_error!("Failed to delete cache file {:?}: {}", path, e);
+                    true
+                }
+            }
+        })
+        .collect();
+    // 清掉因此变空的分类目录
+    for category in all_categories(&root) {
+        let _ = std::fs::remove_dir(root.join(category));
+    }
+    if let Err(e) = write_pending(&root, &remaining) {
+        app_error!("{}", e);
+    }
+}
+
+fn clear(app: &AppHandle, root: &Path, dirs: &[PathBuf]) -> Result<Outcome, String> {
+    let total_bytes: u64 = dirs.iter().map(|dir| crate::storage::scan(dir).total_bytes).sum();
+    let mut last_reported = 0u64;
+    let mut progress = |bytes_processed: u64| {
+        if bytes_processed - last_reported >= PROGRESS_STEP {
+            last_reported = bytes_processed;
+            let _ = app.emit("cache://progress", ProgressPayload { operation: "clear_cache", bytes_processed, total_bytes });
+        }
+    };
+    let mut outcome = Outcome::default();
+    for dir in dirs {
+        delete_tree(root, dir, &mut outcome, &mut progress);
+    }
+    let _ = app.emit(
+        "cache://progress",
+        ProgressPayload { operation: "clear_cache", bytes_processed: outcome.bytes_freed, total_bytes },
+    );
+    let mut pending = read_pending(root);
+    pending.extend(outcome.pending.iter().cloned());
+    pending.sort();
+    pending.dedup();
+    write_pending(root, &pending)?;
+    Ok(outcome)
+}
+
+/// 删除选定的缓存分类；categories 为空时清除全部。运行中的后端先收到 `release-cache` 控制命令
+#[tauri::command]
+pub async fn clear_cache(app: AppHandle, categories: Vec<String>) -> Result<ClearCacheResult, String> {
+    let root = cache_root(&app)?;
+    let categories = if categories.is_empty() { all_categories(&root) } else { categories };
+    let dirs = categories
+        .iter()
+        .map(|category| category_dir(&root, category))
+        .collect::<Result<Vec<_>, _>>()?;
+    let spec = crate::jobs::JobSpec::new(crate::jobs::JobKind::ClearCache, categories.join(", "));
+    crate::jobs::run(&app, spec, |_| clear_categories(&app, root, categories, dirs)).await
+}
+
+async fn clear_categories(
+    app: &AppHandle,
+    root: PathBuf,
+    categories: Vec<String>,
+    dirs: Vec<PathBuf>,
+) -> Result<ClearCacheResult, String> {
+    let state = app.state::<crate::backend::ServerState>();
+    let external = state.process.lock().unwrap().external_url.is_some();
+    if !external && state.process.lock().unwrap().pid.is_some() {
+        let line = format!("release-cache {}\n", categories.join(" "));
+        match state.backend.write_stdin(line.as_bytes()).await {
+            Ok(()) => tokio::time::sleep(RELEASE_WAIT).await,
+            Err(e) => app_log!("Could not ask the backend to release cache files: {}", e),
+        }
+    }
+
+    app_log!("Clearing cache: {}", categories.join(", "));
+    let handle = app.clone();
+    let outcome = tauri::async_runtime::spawn_blocking(move || clear(&handle, &root, &dirs))
+        .await
+        .map_err(|e| e.to_string())??;
+    app_log!(
+        "Cache cleared: {} MB freed, {} file(s) scheduled for deletion after restart",
+        outcome.bytes_freed / 1024 / 1024,
+        outcome.pending.len()
+    );
+    Ok(ClearCacheResult {
+        bytes_freed: outcome.bytes_freed,
+        files_deleted: outcome.files_deleted,
+        scheduled: outcome.pending.len() as u64,
+        categories,
+    })
+}
@@ -0,0 +1,110 @@
+// 命令行参数：覆盖 config.json 中的对应配置，便于测试或自行运行后端时使用
+
+use std::path::PathBuf;
+
+use crate::config::AppConfig;
+
+const USAGE: &str = "\
+Usage: DunCrew [OPTIONS] [LINK_OR_FILE]...
+
+Options:
+      --port <PORT>            Backend port (overrides config.json)
+      --data-dir <DIR>         Data directory (default: the app data dir)
+      --profile <NAME>         Use the named profile for this run (ignored with --data-dir)
+      --no-backend             Do not spawn the backend; connect to one already on --port
+      --backend-source <DIR>   Run the backend from duncrew-server.py in DIR (debug builds)
+      --minimized              Start hidden in the system tray
+      --headless               Run only the backend and its supervisor, without any window
+      --safe-mode              Start the backend with plugins and caches disabled (same as holding Shift)
+      --debug                  Allow opening DevTools in release builds
+  -h, --help                   Print this help";
+
+#[derive(Clone, Default)]
+pub struct CliArgs {
+    pub port: Option<u16>,
+    pub data_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub no_backend: bool,
+    pub backend_source: Option<PathBuf>,
+    /// 开机自启时附带，启动后隐藏到托盘
+    pub minimized: bool,
+    /// 不创建窗口，只运行后端；收到 SIGINT / SIGTERM 时退出
+    pub headless: bool,
+    /// 以安全模式启动后端
+    pub safe_mode: bool,
+    /// 发布版本中允许打开 DevTools
+    pub debug: bool,
+    /// 非选项参数：深度链接（由 deep-link 插件处理）或要打开的文件
+    pub positional: Vec<String>,
+}
+
+impl CliArgs {
+    /// 解析进程参数；参数错误时打印用法并以非零状态退出
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(Some(args)) => args,
+            Ok(None) => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("error: {}\n\n{}", e, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// 返回 Ok(None) 表示用户请求了 --help
+    fn parse(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut result = Self::default();
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            // 支持 --flag=value 与 --flag value 两种写法
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} requires a value", flag))
+            };
+            match flag.as_str() {
+                "--port" => {
+                    let raw = value()?;
+                    let port = raw
+                        .parse::<u16>()
+                        .ok()
+                        .filter(|p| *p >= 1024)
+                        .ok_or_else(|| format!("invalid --port \"{}\" (expected 1024-65535)", raw))?;
+                    result.port = Some(port);
+                }
+                "--data-dir" => result.data_dir = Some(PathBuf::from(value()?)),
+                "--profile" => result.profile = Some(value()?),
+                "--backend-source" => result.backend_source = Some(PathBuf::from(value()?)),
+                "--no-backend" => result.no_backend = true,
+                "--headless" => result.headless = true,
+                "--safe-mode" => result.safe_mode = true,
+                "--debug" => result.debug = true,
+                crate::autostart::MINIMIZED_ARG => result.minimized = true,
+                "-h" | "--help" => return Ok(None),
+                // macOS 旧版 Finder 启动时附带的进程序列号
+                _ if flag.starts_with("-psn_") => {}
+                _ if !flag.starts_with('-') => result.positional.push(arg),
+                _ => return Err(format!("unknown argument \"{}\"", arg)),
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// 用命令行参数覆盖配置文件中的值
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(port) = self.port {
+            config.port = port;
+        }
+        if let Some(dir) = &self.backend_source {
+            config.backend_source = Some(dir.clone());
+        }
+    }
+}
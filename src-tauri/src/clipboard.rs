@@ -0,0 +1,125 @@
+// 剪贴板：前端“复制为 Markdown / 复制为图片”经这里写入系统剪贴板，不受 WebView 剪贴板 API 对图片与大内容的限制。
+// 图片可以直接给出数据目录内的 PNG 路径（后端把图表渲染成文件后前端直接复制，不必经 base64 往返），也可以给 base64。
+// 任何内容超过 MAX_BYTES 都拒绝。读取剪贴板属于敏感操作，只有 config.json 的 allow_clipboard_read 打开时才允许。
+
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use base64::Engine;
+use tauri::AppHandle;
+
+const MAX_BYTES: usize = 20 * 1024 * 1024;
+
+// Linux 上剪贴板内容由写入方进程提供，剪贴板对象须一直保留，否则写入的内容随之消失
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// `clipboard_write_image` 的图片来源
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    /// 数据目录内的 PNG 文件，绝对路径或相对数据目录的路径
+    Path { path: String },
+    Base64 { data: String },
+}
+
+fn with_clipboard<T>(action: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>) -> Result<T, String> {
+    let mut guard = CLIPBOARD.lock().unwrap();
+    if guard.is_none() {
+        *guard = Some(arboard::Clipboard::new().map_err(|e| format!("Clipboard is not available: {}", e))?);
+    }
+    action(guard.as_mut().unwrap()).map_err(|e| format!("Clipboard operation failed: {}", e))
+}
+
+fn check_size(len: usize, what: &str) -> Result<(), String> {
+    if len > MAX_BYTES {
+        return Err(format!(
+            "{} is {} MB, larger than the {} MB clipboard limit",
+            what,
+            len / 1024 / 1024,
+            MAX_BYTES / 1024 / 1024
+        ));
+    }
+    Ok(())
+}
+
+// 规范化后（解析符号链接）必须仍在数据目录内
+fn data_dir_file(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let data_dir = crate::folders::canonical(&crate::backend_data_dir(app)?);
+    let path = data_dir.join(path);
+    let resolved = path
+        .canonicalize()
+        .map(|path| crate::folders::canonical(&path))
+        .map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    if !resolved.starts_with(&data_dir) || !resolved.is_file() {
+        return Err(format!("{:?} is not a file inside the data folder", path));
+    }
+    Ok(resolved)
+}
+
+// 解码 PNG 为 RGBA
+fn decode_png(bytes: &[u8]) -> Result<arboard::ImageData<'static>, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8() | png::Transformations::ALPHA);
+    let mut reader = decoder.read_info().map_err(|e| format!("Invalid PNG: {}", e))?;
+    let mut buffer = vec![0; reader.output_buffer_size().ok_or("PNG is too large")?];
+    let info = reader.next_frame(&mut buffer).map_err(|e| format!("Invalid PNG: {}", e))?;
+    let pixels = &buffer[..info.buffer_size()];
+    let rgba: Vec<u8> = match info.color_type {
+        png::ColorType::Rgba => pixels.to_vec(),
+        png::ColorType::Rgb => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => pixels.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return Err("Unsupported PNG color type".to_string()),
+    };
+    Ok(arboard::ImageData { width: info.width as usize, height: info.height as usize, bytes: Cow::Owned(rgba) })
+}
+
+/// 写入纯文本
+#[tauri::command]
+pub fn clipboard_write_text(text: String) -> Result<(), String> {
+    check_size(text.len(), "Text")?;
+    with_clipboard(|clipboard| clipboard.set_text(text))
+}
+
+/// 写入 HTML，不支持富文本的程序粘贴时得到 plain_fallback
+#[tauri::command]
+pub fn clipboard_write_html(html: String, plain_fallback: String) -> Result<(), String> {
+    check_size(html.len() + plain_fallback.len(), "HTML")?;
+    with_clipboard(|clipboard| clipboard.set_html(html, Some(plain_fallback)))
+}
+
+/// 写入 PNG 图片
+#[tauri::command]
+pub async fn clipboard_write_image(app: AppHandle, image: ImageSource) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let bytes = match image {
+            ImageSource::Path { path } => {
+                let path = data_dir_file(&app, &path)?;
+                let len = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+                check_size(usize::try_from(len).unwrap_or(usize::MAX), "Image")?;
+                std::fs::read(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?
+            }
+            ImageSource::Base64 { data } => {
+                // 先按编码长度估算，避免解码超大的输入
+                check_size(data.len() / 4 * 3, "Image")?;
+                base64::engine::general_purpose::STANDARD
+                    .decode(data.trim())
+                    .map_err(|e| format!("Invalid base64 image: {}", e))?
+            }
+        };
+        let image = decode_png(&bytes)?;
+        with_clipboard(|clipboard| clipboard.set_image(image))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 读取剪贴板中的文本；需在设置中打开 allow_clipboard_read
+#[tauri::command]
+pub fn clipboard_read_text(app: AppHandle) -> Result<String, String> {
+    if !crate::app_config(&app).allow_clipboard_read {
+        return Err("Reading the clipboard is disabled in settings (allow_clipboard_read)".to_string());
+    }
+    with_clipboard(|clipboard| clipboard.get_text())
+}
@@ -0,0 +1,974 @@
+// 应用配置：<app_data_dir>/config.json，缺失时按默认值创建
+//
+// 写入是原子的：先写同目录下的临时文件并 fsync，再改名覆盖原文件，进程中途退出不会留下半个文件；
+// 覆盖前把上一版（能解析且通过校验时）保存为 config.json.bak。读取时原文件无法解析或校验失败，
+// 改用备份并把损坏的文件另存为 config.json.corrupt，随后向前端发送 `config://recovered`。
+// schema_version 记录文件格式版本，旧版本文件读取时按 MIGRATIONS 依次升级后写回。
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const CONFIG_FILE_NAME: &str = "config.json";
+const BACKUP_SUFFIX: &str = ".bak";
+const CORRUPT_SUFFIX: &str = ".corrupt";
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// 当前的配置文件格式版本；没有 schema_version 字段的文件视为版本 1
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
+
+// 从版本 n 升级到 n + 1，第 n - 1 项对应版本 n
+const MIGRATIONS: [fn(&mut serde_json::Map<String, serde_json::Value>); 1] = [migrate_v1];
+
+// 启动时从备份恢复了配置，等 AppEventQueue 注册后由 notify_recovered 发送
+static RECOVERED: Mutex<Option<ConfigRecovered>> = Mutex::new(None);
+
+const LOG_LEVELS: [&str; 4] = ["error", "warn", "info", "debug"];
+// 由本应用决定的后端参数，backend_args 不能覆盖
+const RESERVED_BACKEND_ARGS: [&str; 4] = ["--path", "--port", "--tls-cert", "--tls-key"];
+
+// 设置后连接该地址的后端而不启动 Sidecar，优先级高于 config.json
+const EXTERNAL_BACKEND_ENV: [&str; 2] = ["DUNCREW_EXTERNAL_BACKEND", "DDOS_EXTERNAL_BACKEND"];
+
+/// 外部后端：开发时自行运行 `python duncrew-server.py`，应用只负责连接
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExternalBackendConfig {
+    pub enabled: bool,
+    /// 例如 http://localhost:8000
+    pub base_url: String,
+}
+
+// 子进程会读取的代理变量，大小写两种写法都设置（Python 各库读取习惯不同）
+const PROXY_ENV_VARS: [&str; 6] = ["HTTP_PROXY", "HTTPS_PROXY", "NO_PROXY", "http_proxy", "https_proxy", "no_proxy"];
+
+/// 后端代理设置，会作为 HTTP_PROXY / HTTPS_PROXY / NO_PROXY 传给 Sidecar
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: Option<String>,
+}
+
+/// 后端 Sidecar 独立更新，默认关闭
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BackendUpdateConfig {
+    pub enabled: bool,
+    /// 更新清单地址（https），签名位于同名 .sig 文件
+    pub manifest_url: String,
+    /// 校验清单签名的 minisign 公钥（base64）
+    pub public_key: String,
+}
+
+/// 后端内存上限：RSS 连续超过 limit_mb 达到 samples 次采样（每 5 秒一次）时触发
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MemoryLimitConfig {
+    /// 0 表示不限制
+    pub limit_mb: u64,
+    pub samples: u32,
+    /// "notify"：只通知；"restart"：通知并优雅重启后端
+    pub action: String,
+}
+
+impl Default for MemoryLimitConfig {
+    fn default() -> Self {
+        Self { limit_mb: 2048, samples: 6, action: "notify".to_string() }
+    }
+}
+
+/// 无响应检测：健康检查连续超时（连接被拒绝不算）达到 hung_threshold 次时判定后端 Hung
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub hung_threshold: u32,
+    /// "notify"：只通知；"restart"：通知并强制结束、重启后端
+    pub action: String,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self { hung_threshold: 3, action: "notify".to_string() }
+    }
+}
+
+/// 数据目录中的外部变更监视（见 fs_watch）
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FsWatchConfig {
+    /// 数据目录下要监视的子目录，例如 ["imports"]；为空表示不监视。不能包含 logs 与 cache
+    pub paths: Vec<String>,
+    /// 非空时把变更同时 POST 给后端的这个端点（须在 /api/ 下）
+    pub forward_endpoint: String,
+    /// 系统文件通知不可用（例如网络驱动器）时改为轮询的间隔
+    pub poll_interval_secs: u64,
+}
+
+impl Default for FsWatchConfig {
+    fn default() -> Self {
+        Self {
+            paths: vec![crate::imports::IMPORTS_DIR_NAME.to_string()],
+            forward_endpoint: String::new(),
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+/// 定时自动备份（见 auto_backup），默认关闭
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AutoBackupConfig {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    /// 保留最近几个自动备份
+    pub keep_last: u32,
+    /// 为空时使用“文档/DunCrew Backups”，不能位于数据目录内
+    pub target_dir: Option<PathBuf>,
+}
+
+impl Default for AutoBackupConfig {
+    fn default() -> Self {
+        Self { enabled: false, interval_hours: 24, keep_last: 7, target_dir: None }
+    }
+}
+
+/// 网络连通性检测（见 network）
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkCheckConfig {
+    /// 默认关闭：开启后才会定期向 url 发请求，隔离网络保持关闭
+    pub enabled: bool,
+    /// 探测地址，收到任何 HTTP 响应即视为在线
+    pub url: String,
+    pub interval_secs: u64,
+}
+
+impl Default for NetworkCheckConfig {
+    fn default() -> Self {
+        Self { enabled: false, url: "https://duncrew.com/".to_string(), interval_secs: 60 }
+    }
+}
+
+/// 应用自更新（见 app_update）
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AppUpdateConfig {
+    /// "stable" 或 "beta"
+    pub channel: String,
+    /// 启动时检查一次，有新版本时发送通知
+    pub check_on_startup: bool,
+    /// 更新地址（https），可包含 {{channel}}、{{target}}、{{arch}}、{{current_version}}；为空表示不检查更新
+    pub endpoint: String,
+    /// 校验更新包签名的 minisign 公钥（tauri signer 生成）
+    pub public_key: String,
+}
+
+impl Default for AppUpdateConfig {
+    fn default() -> Self {
+        Self {
+            channel: "stable".to_string(),
+            check_on_startup: true,
+            endpoint: String::new(),
+            public_key: String::new(),
+        }
+    }
+}
+
+/// 匿名使用统计（见 telemetry），默认关闭
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// 批量上报地址（https）；为空时只在本地记录，不上传
+    pub endpoint: String,
+}
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// 配置文件格式版本，由本应用维护
+    pub schema_version: u32,
+    /// 后端首选端口，被占用时提示用户改用其他端口
+    pub port: u16,
+    /// 无界面模式下后端监听的地址（传给后端的 --host），例如 0.0.0.0 允许局域网访问；
+    /// 非回环地址需同时设置 allow_remote_access，否则拒绝启动。窗口模式始终只监听本机
+    pub bind_address: String,
+    /// 允许后端监听非回环地址；有意对外监听时不再警告
+    pub allow_remote_access: bool,
+    /// 后端改用 HTTPS：自动生成自签名证书，本应用只信任该证书。默认关闭，只监听本机时无需开启
+    pub backend_tls: bool,
+    /// 传给后端的 --log-level：error / warn / info / debug
+    pub backend_log_level: String,
+    /// 本应用自身日志（app.log、控制台、日志面板中的 app 行）的级别过滤，语法同 RUST_LOG，
+    /// 例如 "info,duncrew_lib::backend=debug"；修改后立即生效，设置了 DUNCREW_LOG 环境变量时以其为准
+    pub app_log_filter: String,
+    /// 后端进程优先级：normal / below_normal / low，修改后对运行中的后端立即生效
+    pub backend_priority: String,
+    /// 追加到后端启动参数末尾，例如 ["--workers", "4"]；不能包含 --path / --port
+    pub backend_args: Vec<String>,
+    /// 后端功能开关：true 传 --enable-<名称>，false 传 --disable-<名称>，排在 backend_args 之后
+    pub backend_features: BTreeMap<String, bool>,
+    /// 仅调试构建、Windows：为后端打开控制台窗口便于排查，输出不再写入 backend.log
+    pub backend_console: bool,
+    /// 后端意外退出时是否自动重启
+    pub auto_restart: bool,
+    /// 优雅停止等待时间（1 到 60 秒），期间发送 backend://stopping-progress，超时后强制结束
+    pub shutdown_timeout_secs: u64,
+    /// 后端拉起后等待其通过健康检查的时间，超时视为启动失败
+    pub startup_timeout_secs: u64,
+    pub external_backend: ExternalBackendConfig,
+    /// 开发用：从该目录下的 duncrew-server.py 源码启动后端，而不是打包的 Sidecar
+    pub backend_source: Option<PathBuf>,
+    /// 允许 release 构建使用 backend_source
+    pub allow_source_backend: bool,
+    pub proxy: ProxyConfig,
+    /// 额外传给后端的环境变量，优先级高于 proxy
+    pub env: HashMap<String, String>,
+    /// 关闭窗口时隐藏到托盘，后端继续运行
+    pub close_to_tray: bool,
+    /// 启动时只显示托盘图标，不显示主窗口（同 --minimized，开机自启的实例总是如此）
+    pub start_minimized: bool,
+    /// 主窗口不带系统边框，由前端绘制标题栏（见 window_controls）
+    pub custom_titlebar: bool,
+    /// 后端崩溃或不健康时发送系统通知
+    pub notify_on_backend_failure: bool,
+    /// 静音的提示分类（见 alerts::AlertCategory，如 "low_disk"）→ 静音截止时间（Unix 秒），由 mute_alerts 写入
+    pub alert_mutes: BTreeMap<String, u64>,
+    /// 上次会话异常结束（崩溃、被强杀、因后端故障退出）时在启动后弹窗说明；`app://previous-session-abnormal` 总会发送
+    pub prompt_after_abnormal_exit: bool,
+    /// 允许拖放导入的扩展名（不含点），为空表示不限制
+    pub import_extensions: Vec<String>,
+    /// 数据目录所在磁盘的最低剩余空间（MB），低于此值启动后端、备份、导入前会警告；0 表示不检查
+    pub min_free_space_mb: u64,
+    /// 开发用：本地替换了 Sidecar 二进制时跳过 SHA-256 校验
+    pub skip_sidecar_verification: bool,
+    pub backend_update: BackendUpdateConfig,
+    pub memory_limit: MemoryLimitConfig,
+    pub watchdog: WatchdogConfig,
+    /// open_external 允许打开的主机；"*.example.com" 匹配子域名，"*" 允许任意主机
+    pub external_url_allowlist: Vec<String>,
+    /// 显示/隐藏主窗口的全局快捷键，例如 CmdOrCtrl+Shift+D；为空表示不注册
+    pub global_shortcut: String,
+    /// 原生菜单、对话框与通知的语言：en / zh；为空表示跟随系统语言
+    pub locale: String,
+    /// 允许 capture_window_screenshot 截取主窗口并附到诊断包；关闭后已暂存的截图随即删除
+    pub allow_screenshots: bool,
+    /// 允许 clipboard_read_text 读取剪贴板；剪贴板可能含有密码等敏感内容，默认关闭
+    pub allow_clipboard_read: bool,
+    /// 发布版本中允许打开 DevTools（toggle_devtools、CmdOrCtrl+Shift+I），供现场排查；调试版本始终允许
+    pub enable_devtools: bool,
+    pub telemetry: TelemetryConfig,
+    pub app_update: AppUpdateConfig,
+    pub auto_backup: AutoBackupConfig,
+    pub fs_watch: FsWatchConfig,
+    pub network_check: NetworkCheckConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            schema_version: CONFIG_SCHEMA_VERSION,
+            port: crate::backend::DEFAULT_BACKEND_PORT,
+            bind_address: "127.0.0.1".to_string(),
+            allow_remote_access: false,
+            backend_tls: false,
+            backend_log_level: "info".to_string(),
+            app_log_filter: crate::logs::DEFAULT_LOG_FILTER.to_string(),
+            backend_priority: "normal".to_string(),
+            backend_args: Vec::new(),
+            backend_features: BTreeMap::new(),
+            backend_console: false,
+            auto_restart: true,
+            shutdown_timeout_secs: 5,
+            startup_timeout_secs: 30,
+            external_backend: ExternalBackendConfig::default(),
+            backend_source: None,
+            allow_source_backend: false,
+            proxy: ProxyConfig::default(),
+            env: HashMap::new(),
+            close_to_tray: false,
+            start_minimized: false,
+            custom_titlebar: false,
+            notify_on_backend_failure: true,
+            alert_mutes: BTreeMap::new(),
+            prompt_after_abnormal_exit: true,
+            import_extensions: ["ddos", "json", "md", "txt", "csv", "pdf", "docx", "xlsx", "zip"]
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
+            min_free_space_mb: 500,
+            skip_sidecar_verification: false,
+            backend_update: BackendUpdateConfig::default(),
+            memory_limit: MemoryLimitConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            external_url_allowlist: ["github.com", "*.github.com", "duncrew.com", "*.duncrew.com"]
+                .iter()
+                .map(|host| host.to_string())
+                .collect(),
+            global_shortcut: crate::shortcut::DEFAULT_SHORTCUT.to_string(),
+            locale: String::new(),
+            allow_screenshots: true,
+            allow_clipboard_read: false,
+            enable_devtools: false,
+            telemetry: TelemetryConfig::default(),
+            app_update: AppUpdateConfig::default(),
+            auto_backup: AutoBackupConfig::default(),
+            fs_watch: FsWatchConfig::default(),
+            network_check: NetworkCheckConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err(format!("port must be between 1024 and 65535, got {}", self.port));
+        }
+        if self.bind_address.trim().parse::<std::net::IpAddr>().is_err() {
+            return Err(format!("bind_address must be an IP address, got \"{}\"", self.bind_address));
+        }
+        if !LOG_LEVELS.contains(&self.backend_log_level.as_str()) {
+            return Err(format!(
+                "backend_log_level must be one of {:?}, got \"{}\"",
+                LOG_LEVELS, self.backend_log_level
+            ));
+        }
+        if let Err(e) = crate::logs::validate_filter(&self.app_log_filter) {
+            return Err(format!("app_log_filter \"{}\" is invalid: {}", self.app_log_filter, e));
+        }
+        if !crate::backend::PRIORITY_LEVELS.contains(&self.backend_priority.as_str()) {
+            return Err(format!(
+                "backend_priority must be one of {:?}, got \"{}\"",
+                crate::backend::PRIORITY_LEVELS,
+                self.backend_priority
+            ));
+        }
+        if let Some(arg) = self.backend_args.iter().find(|arg| {
+            RESERVED_BACKEND_ARGS.iter().any(|reserved| {
+                arg.as_str() == *reserved || arg.strip_prefix(reserved).is_some_and(|rest| rest.starts_with('='))
+            })
+        }) {
+            return Err(format!("backend_args must not contain \"{}\", it is set by DunCrew", arg));
+        }
+        if let Some(name) = self.backend_features.keys().find(|name| {
+            name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        }) {
+            return Err(format!(
+                "backend_features name \"{}\" may only contain letters, digits, \"-\" and \"_\"",
+                name
+            ));
+        }
+        if !(1..=60).contains(&self.shutdown_timeout_secs) {
+            return Err(format!(
+                "shutdown_timeout_secs must be between 1 and 60, got {}",
+                self.shutdown_timeout_secs
+            ));
+        }
+        if !(5..=600).contains(&self.startup_timeout_secs) {
+            return Err(format!(
+                "startup_timeout_secs must be between 5 and 600, got {}",
+                self.startup_timeout_secs
+            ));
+        }
+        if self.external_backend.enabled {
+            validate_base_url(&self.external_backend.base_url)
+                .map_err(|e| format!("external_backend.base_url {}", e))?;
+        }
+        if !["notify", "restart"].contains(&self.memory_limit.action.as_str()) {
+            return Err(format!(
+                "memory_limit.action must be \"notify\" or \"restart\", got \"{}\"",
+                self.memory_limit.action
+            ));
+        }
+        if self.memory_limit.samples == 0 {
+            return Err("memory_limit.samples must be at least 1".to_string());
+        }
+        if !["notify", "restart"].contains(&self.watchdog.action.as_str()) {
+            return Err(format!(
+                "watchdog.action must be \"notify\" or \"restart\", got \"{}\"",
+                self.watchdog.action
+            ));
+        }
+        if self.watchdog.hung_threshold == 0 {
+            return Err("watchdog.hung_threshold must be at least 1".to_string());
+        }
+        crate::shortcut::parse(&self.global_shortcut).map_err(|e| format!("global_shortcut: {}", e))?;
+        if !self.locale.trim().is_empty() && crate::i18n::normalize(&self.locale).is_none() {
+            return Err(format!("locale must be \"en\", \"zh\" or empty, got \"{}\"", self.locale));
+        }
+        let endpoint = self.telemetry.endpoint.trim();
+        if !endpoint.is_empty() && !endpoint.starts_with("https://") {
+            return Err(format!("telemetry.endpoint must be an https URL, got \"{}\"", self.telemetry.endpoint));
+        }
+        if !(1..=24 * 30).contains(&self.auto_backup.interval_hours) {
+            return Err(format!(
+                "auto_backup.interval_hours must be between 1 and 720, got {}",
+                self.auto_backup.interval_hours
+            ));
+        }
+        if self.auto_backup.keep_last == 0 {
+            return Err("auto_backup.keep_last must be at least 1".to_string());
+        }
+        let url = self.network_check.url.trim();
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(format!("network_check.url must be an http(s) URL, got \"{}\"", self.network_check.url));
+        }
+        if !(5..=3600).contains(&self.network_check.interval_secs) {
+            return Err(format!(
+                "network_check.interval_secs must be between 5 and 3600, got {}",
+                self.network_check.interval_secs
+            ));
+        }
+        for path in &self.fs_watch.paths {
+            crate::fs_watch::validate_path(path).map_err(|e| format!("fs_watch.paths: {}", e))?;
+        }
+        let endpoint = self.fs_watch.forward_endpoint.trim();
+        if !endpoint.is_empty() && crate::proxy::validate_path(endpoint).is_err() {
+            return Err(format!("fs_watch.forward_endpoint must be a path under /api/, got \"{}\"", endpoint));
+        }
+        if self.fs_watch.poll_interval_secs == 0 {
+            return Err("fs_watch.poll_interval_secs must be at least 1".to_string());
+        }
+        if !crate::app_update::CHANNELS.contains(&self.app_update.channel.as_str()) {
+            return Err(format!(
+                "app_update.channel must be one of {:?}, got \"{}\"",
+                crate::app_update::CHANNELS, self.app_update.channel
+            ));
+        }
+        let endpoint = self.app_update.endpoint.trim();
+        if !endpoint.is_empty() {
+            if !endpoint.starts_with("https://") {
+                return Err(format!("app_update.endpoint must be an https URL, got \"{}\"", self.app_update.endpoint));
+            }
+            if self.app_update.public_key.trim().is_empty() {
+                return Err("app_update.public_key is required when app_update.endpoint is set".to_string());
+            }
+        }
+        if self.backend_update.enabled {
+            if !self.backend_update.manifest_url.trim().starts_with("https://") {
+                return Err(format!(
+                    "backend_update.manifest_url must be an https URL, got \"{}\"",
+                    self.backend_update.manifest_url
+                ));
+            }
+            if self.backend_update.public_key.trim().is_empty() {
+                return Err("backend_update.public_key is required when backend updates are enabled".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// 外部后端地址：环境变量优先，其次是启用的 external_backend 配置
+    pub fn external_backend_url(&self) -> Option<String> {
+        let from_env = EXTERNAL_BACKEND_ENV
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.trim().is_empty()));
+        match from_env {
+            Some(url) => match validate_base_url(&url) {
+                Ok(()) => Some(url.trim().trim_end_matches('/').to_string()),
+                Err(e) => {
+                    app_error!("Ignoring external backend from environment: {}", e);
+                    None
+                }
+            },
+            None if self.external_backend.enabled => {
+                Some(self.external_backend.base_url.trim_end_matches('/').to_string())
+            }
+            None => None,
+        }
+    }
+
+    /// 启动后端时追加的环境变量（在继承父进程环境的基础上覆盖）
+    pub fn backend_env(&self) -> BTreeMap<String, String> {
+        let mut env = BTreeMap::new();
+        let proxies = [
+            ("HTTP_PROXY", &self.proxy.http),
+            ("HTTPS_PROXY", &self.proxy.https),
+            ("NO_PROXY", &self.proxy.no_proxy),
+        ];
+        for (name, value) in proxies {
+            if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+                env.insert(name.to_string(), value.to_string());
+                env.insert(name.to_ascii_lowercase(), value.to_string());
+            }
+        }
+        env.extend(self.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        env
+    }
+
+    /// 追加在内置参数之后的后端参数：backend_args，然后是 backend_features 对应的开关
+    pub fn backend_extra_args(&self) -> Vec<String> {
+        let features = self.backend_features.iter().map(|(name, enabled)| {
+            format!("--{}-{}", if *enabled { "enable" } else { "disable" }, name)
+        });
+        self.backend_args.iter().cloned().chain(features).collect()
+    }
+
+    /// 与旧配置相比，哪些已修改的字段需要重启后端才能生效
+    pub fn restart_required_fields(&self, old: &AppConfig) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.port != old.port {
+            fields.push("port");
+        }
+        if self.bind_address != old.bind_address {
+            fields.push("bind_address");
+        }
+        if self.backend_tls != old.backend_tls {
+            fields.push("backend_tls");
+        }
+        if self.backend_log_level != old.backend_log_level {
+            fields.push("backend_log_level");
+        }
+        if self.backend_args != old.backend_args {
+            fields.push("backend_args");
+        }
+        if self.backend_features != old.backend_features {
+            fields.push("backend_features");
+        }
+        if self.backend_console != old.backend_console {
+            fields.push("backend_console");
+        }
+        if self.external_backend != old.external_backend {
+            fields.push("external_backend");
+        }
+        if self.backend_source != old.backend_source {
+            fields.push("backend_source");
+        }
+        if self.proxy != old.proxy {
+            fields.push("proxy");
+        }
+        if self.env != old.env {
+            fields.push("env");
+        }
+        fields
+    }
+}
+
+fn validate_base_url(url: &str) -> Result<(), String> {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.host().is_some() => Ok(()),
+        _ => Err(format!("must be an http(s) URL, got \"{}\"", url)),
+    }
+}
+
+// 隐藏敏感变量的值，以及代理 URL 中的密码
+fn mask_env_value(key: &str, value: &str) -> String {
+    if crate::diagnostics::is_sensitive_key(key) {
+        return "***".to_string();
+    }
+    crate::diagnostics::mask_url_password(value)
+}
+
+pub fn config_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(CONFIG_FILE_NAME)
+}
+
+// config.json → config.json.bak
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+// 版本 1 → 2：早期版本接受 "warning" / "critical" 日志级别与 "localhost" 监听地址，现在校验不再通过
+fn migrate_v1(config: &mut serde_json::Map<String, serde_json::Value>) {
+    if let Some(level) = config.get_mut("backend_log_level") {
+        match level.as_str() {
+            Some("warning") => *level = "warn".into(),
+            Some("critical" | "fatal") => *level = "error".into(),
+            _ => {}
+        }
+    }
+    if let Some(address) = config.get_mut("bind_address") {
+        if address.as_str() == Some("localhost") {
+            *address = "127.0.0.1".into();
+        }
+    }
+}
+
+// 解析、升级并校验；返回配置与文件原来的格式版本
+fn parse(content: &str) -> Result<(AppConfig, u32), String> {
+    let mut json: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let object = json.as_object_mut().ok_or("config must be a JSON object")?;
+    let version = object.get("schema_version").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+    for (index, migrate) in MIGRATIONS.iter().enumerate() {
+        if version <= index as u32 + 1 {
+            migrate(object);
+        }
+    }
+    let mut config: AppConfig = serde_json::from_value(json).map_err(|e| e.to_string())?;
+    // 更新的版本写入的文件：读取认识的字段，未知字段忽略
+    config.schema_version = CONFIG_SCHEMA_VERSION;
+    config.validate()?;
+    Ok((config, version))
+}
+
+/// `config://recovered` 事件负载
+#[derive(Clone, serde::Serialize)]
+pub struct ConfigRecovered {
+    pub path: PathBuf,
+    /// 损坏的文件另存在这里
+    pub corrupt_path: PathBuf,
+    pub error: String,
+    /// 损坏的文件仍是 JSON 时，与备份取值不同的顶层字段，这些设置可能已回退；无法解析时为空
+    pub reverted_fields: Vec<String>,
+    /// 备份的写入时间，之后修改的设置可能已回退
+    pub backup_modified_at: Option<String>,
+}
+
+// 损坏文件与备份中取值不同的顶层字段
+fn changed_fields(corrupt: &str, backup: &str) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(corrupt)), Ok(serde_json::Value::Object(backup))) =
+        (serde_json::from_str::<serde_json::Value>(corrupt), serde_json::from_str::<serde_json::Value>(backup))
+    else {
+        return Vec::new();
+    };
+    let mut keys: Vec<String> = corrupt
+        .keys()
+        .chain(backup.keys())
+        .filter(|key| corrupt.get(*key) != backup.get(*key))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+// 原文件无法使用：改用备份，损坏的文件另存后用备份内容覆盖
+fn recover(path: &Path, content: &str, error: String) -> Result<AppConfig, String> {
+    let backup_path = sibling(path, BACKUP_SUFFIX);
+    let backup = std::fs::read_to_string(&backup_path)
+        .map_err(|_| format!("Invalid config {:?}: {}", path, error))?;
+    let (config, _) = parse(&backup)
+        .map_err(|e| format!("Invalid config {:?}: {} (backup is invalid too: {})", path, error, e))?;
+    app_error!("Config {:?} is corrupt ({}), restored the previous version from {:?}", path, error, backup_path);
+    let corrupt_path = sibling(path, CORRUPT_SUFFIX);
+    let _ = std::fs::write(&corrupt_path, content);
+    // 备份本身就是恢复来源，不用刚恢复出的内容覆盖它
+    write(path, &config, false)?;
+    let backup_modified_at = std::fs::metadata(&backup_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Local>::from(time).to_rfc3339());
+    *RECOVERED.lock().unwrap() = Some(ConfigRecovered {
+        path: path.to_path_buf(),
+        corrupt_path,
+        reverted_fields: changed_fields(content, &backup),
+        error,
+        backup_modified_at,
+    });
+    Ok(config)
+}
+
+/// 读取配置；文件不存在时写入默认配置。文件损坏时改用 config.json.bak，
+/// 备份也不可用时返回带文件路径的错误。旧格式的文件升级后写回
+pub fn load(path: &Path) -> Result<AppConfig, String> {
+    if !path.exists() {
+        let config = AppConfig::default();
+        save(path, &config)?;
+        return Ok(config);
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let (config, version) = match parse(&content) {
+        Ok(parsed) => parsed,
+        Err(e) => return recover(path, &content, e),
+    };
+    if version < CONFIG_SCHEMA_VERSION {
+        app_log!("Upgrading config {:?} from schema version {} to {}", path, version, CONFIG_SCHEMA_VERSION);
+        save(path, &config)?;
+    } else if version > CONFIG_SCHEMA_VERSION {
+        app_error!(
+            "Config {:?} was written by a newer version (schema {}), settings it added are ignored",
+            path, version
+        );
+    }
+    Ok(config)
+}
+
+/// 原子写入：临时文件 fsync 后改名覆盖；原文件能解析且通过校验时先复制为 config.json.bak
+pub fn save(path: &Path, config: &AppConfig) -> Result<(), String> {
+    write(path, config, true)
+}
+
+fn write(path: &Path, config: &AppConfig, back_up: bool) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    let tmp = sibling(path, TEMP_SUFFIX);
+    let write = || -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()
+    };
+    write().map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+    // 损坏或校验不通过的原文件不覆盖备份，备份始终是最后一个完好的版本
+    let current_is_valid = back_up && std::fs::read_to_string(path).is_ok_and(|content| parse(&content).is_ok());
+    if current_is_valid {
+        let backup = sibling(path, BACKUP_SUFFIX);
+        if let Err(e) = std::fs::copy(path, &backup) {
+            app_error!("Failed to back up {:?} to {:?}: {}", path, backup, e);
+        }
+    }
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to write {:?}: {}", path, e)
+    })?;
+    // 改名本身也要落盘，否则断电后目录中可能仍是旧文件
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::File::open(dir).and_then(|dir| dir.sync_all());
+    }
+    Ok(())
+}
+
+/// 启动时若从备份恢复了配置，发送 `config://recovered`；在 AppEventQueue 注册之后调用
+pub fn notify_recovered(app: &tauri::AppHandle) {
+    if let Some(recovered) = RECOVERED.lock().unwrap().take() {
+        crate::app_events::emit_to_frontend(app, "config://recovered", recovered);
+    }
+}
+
+/// 运行期配置，供各处读取当前值
+pub struct ConfigState {
+    // 数据目录迁移后会改变
+    path: Mutex<PathBuf>,
+    config: Mutex<AppConfig>,
+}
+
+impl ConfigState {
+    pub fn new(path: PathBuf, config: AppConfig) -> Self {
+        Self { path: Mutex::new(path), config: Mutex::new(config) }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.path.lock().unwrap().clone()
+    }
+
+    pub fn get(&self) -> AppConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// 从磁盘重新读取（例如恢复备份之后）；读取失败时保留内存中的配置
+    pub fn reload(&self) -> Result<(), String> {
+        let config = load(&self.path())?;
+        *self.config.lock().unwrap() = config;
+        Ok(())
+    }
+
+    /// 修改部分字段，校验后写入磁盘；校验或写入失败时内存中的配置不变
+    pub fn update(&self, apply: impl FnOnce(&mut AppConfig)) -> Result<(), String> {
+        let mut current = self.config.lock().unwrap();
+        let mut config = current.clone();
+        apply(&mut config);
+        config.validate()?;
+        save(&self.path(), &config)?;
+        *current = config;
+        Ok(())
+    }
+
+    /// 改用新位置的配置文件（数据目录迁移后）
+    pub fn relocate(&self, path: PathBuf) -> Result<(), String> {
+        *self.path.lock().unwrap() = path;
+        self.reload()
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SetConfigResult {
+    /// 需要重启后端才能生效的字段，为空表示已全部生效
+    pub restart_required: Vec<&'static str>,
+}
+
+/// 后端实际收到的代理及自定义环境变量（敏感值已隐藏），包括从父进程继承的代理变量
+#[tauri::command]
+pub fn get_effective_backend_env(app: tauri::AppHandle) -> BTreeMap<String, String> {
+    let mut env: BTreeMap<String, String> = PROXY_ENV_VARS
+        .iter()
+        .filter_map(|name| std::env::var(name).ok().map(|v| (name.to_string(), v)))
+        .collect();
+    env.extend(crate::app_config(&app).backend_env());
+    env.into_iter()
+        .map(|(k, v)| {
+            let masked = mask_env_value(&k, &v);
+            (k, masked)
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_config(state: tauri::State<'_, ConfigState>) -> AppConfig {
+    state.get()
+}
+
+/// 校验并保存配置。返回值说明哪些修改需要重启后端（可配合 restart_backend）
+#[tauri::command]
+pub fn set_config(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ConfigState>,
+    config: AppConfig,
+) -> Result<SetConfigResult, String> {
+    config.validate()?;
+    save(&state.path(), &config)?;
+    let mut current = state.config.lock().unwrap();
+    let restart_required = config.restart_required_fields(&current);
+    let shortcut_changed = config.global_shortcut != current.global_shortcut;
+    let locale_changed = config.locale != current.locale;
+    let titlebar_changed = config.custom_titlebar != current.custom_titlebar;
+    let fs_watch_changed = config.fs_watch != current.fs_watch;
+    let priority_changed = config.backend_priority != current.backend_priority;
+    let log_filter_changed = config.app_log_filter != current.app_log_filter;
+    let telemetry_disabled = current.telemetry.enabled && !config.telemetry.enabled;
+    let screenshots_disabled = current.allow_screenshots && !config.allow_screenshots;
+    let devtools_changed = config.enable_devtools != current.enable_devtools;
+    let custom_titlebar = config.custom_titlebar;
+    let app_log_filter = config.app_log_filter.clone();
+    *current = config;
+    drop(current);
+    if devtools_changed {
+        // 菜单中的 DevTools 项随之显示或隐藏
+        crate::app_menu::refresh(&app);
+    }
+    if log_filter_changed {
+        crate::logs::apply_filter(&app_log_filter);
+    }
+    app_log!("Config updated (restart required for: {:?})", restart_required);
+    if shortcut_changed {
+        crate::shortcut::init(&app);
+    }
+    if locale_changed {
+        crate::i18n::apply(&app);
+    }
+    if telemetry_disabled {
+        crate::telemetry::clear(&app);
+    }
+    if screenshots_disabled {
+        crate::screenshot::clear_pending();
+    }
+    if fs_watch_changed {
+        crate::fs_watch::start(&app);
+    }
+    if priority_changed {
+        crate::backend::reapply_priority(&app);
+    }
+    if titlebar_changed {
+        if let Some(window) = tauri::Manager::get_webview_window(&app, "main") {
+            let _ = window.set_decorations(!custom_titlebar);
+        }
+    }
+    Ok(SetConfigResult { restart_required })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    // 以另一份配置为基础改写若干顶层字段，得到仍是 JSON 的文件内容
+    fn edited(config: &AppConfig, fields: serde_json::Value) -> String {
+        let mut json = serde_json::to_value(config).unwrap();
+        for (key, value) in fields.as_object().unwrap() {
+            json[key] = value.clone();
+        }
+        serde_json::to_string_pretty(&json).unwrap()
+    }
+
+    #[test]
+    fn parse_rejects_invalid_values() {
+        assert!(parse("[]").is_err());
+        assert!(parse("{").is_err());
+        assert!(parse(r#"{"backend_log_level": "verbose"}"#).is_err());
+        let (config, version) = parse(r#"{"schema_version": 2, "port": 4000}"#).unwrap();
+        assert_eq!((config.port, version), (4000, 2));
+    }
+
+    #[test]
+    fn save_replaces_atomically_and_keeps_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = config_path(dir.path());
+        let first = AppConfig::default();
+        save(&path, &first).unwrap();
+        assert!(!sibling(&path, BACKUP_SUFFIX).exists());
+
+        let second = AppConfig { port: 4000, ..first.clone() };
+        save(&path, &second).unwrap();
+        assert!(parse(&read(&path)).unwrap().0 == second);
+        assert!(parse(&read(&sibling(&path, BACKUP_SUFFIX))).unwrap().0 == first);
+        assert!(!sibling(&path, TEMP_SUFFIX).exists());
+    }
+
+    #[test]
+    fn save_does_not_back_up_an_unusable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = config_path(dir.path());
+        let good = AppConfig::default();
+        save(&path, &good).unwrap();
+        save(&path, &good).unwrap();
+        let backup = read(&sibling(&path, BACKUP_SUFFIX));
+
+        // 不是 JSON，以及是 JSON 但校验不通过，都不能顶替备份
+        for content in ["{ not json".to_string(), edited(&good, serde_json::json!({ "backend_log_level": "verbose" }))] {
+            std::fs::write(&path, content).unwrap();
+            save(&path, &good).unwrap();
+            assert_eq!(read(&sibling(&path, BACKUP_SUFFIX)), backup);
+        }
+    }
+
+    #[test]
+    fn load_migrates_v1_and_writes_it_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = config_path(dir.path());
+        let v1 = r#"{"backend_log_level": "warning", "bind_address": "localhost"}"#;
+        std::fs::write(&path, v1).unwrap();
+
+        let config = load(&path).unwrap();
+        assert_eq!(config.backend_log_level, "warn");
+        assert_eq!(config.bind_address, "127.0.0.1");
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        let (written, version) = parse(&read(&path)).unwrap();
+        assert!(written == config);
+        assert_eq!(version, CONFIG_SCHEMA_VERSION);
+        // 升级前的原文件留作备份
+        assert_eq!(read(&sibling(&path, BACKUP_SUFFIX)), v1);
+    }
+
+    #[test]
+    fn load_recovers_from_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = config_path(dir.path());
+        let good = AppConfig::default();
+        save(&path, &good).unwrap();
+        save(&path, &good).unwrap();
+        let backup = read(&sibling(&path, BACKUP_SUFFIX));
+        let corrupt = edited(&good, serde_json::json!({ "port": 4000, "backend_log_level": "verbose" }));
+        std::fs::write(&path, &corrupt).unwrap();
+
+        let config = load(&path).unwrap();
+        assert!(config == good);
+        assert!(parse(&read(&path)).unwrap().0 == good);
+        assert_eq!(read(&sibling(&path, CORRUPT_SUFFIX)), corrupt);
+        assert_eq!(read(&sibling(&path, BACKUP_SUFFIX)), backup);
+        let recovered = RECOVERED.lock().unwrap().take().unwrap();
+        assert_eq!(recovered.corrupt_path, sibling(&path, CORRUPT_SUFFIX));
+        assert_eq!(recovered.reverted_fields, ["backend_log_level", "port"]);
+        assert!(recovered.backup_modified_at.is_some());
+    }
+
+    #[test]
+    fn load_fails_when_backup_is_unusable_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = config_path(dir.path());
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(load(&path).err().unwrap().contains("Invalid config"));
+
+        std::fs::write(sibling(&path, BACKUP_SUFFIX), "[]").unwrap();
+        assert!(load(&path).err().unwrap().contains("backup is invalid too"));
+        // 两份都不可用时不改动任何文件
+        assert_eq!(read(&path), "{ not json");
+        assert!(!sibling(&path, CORRUPT_SUFFIX).exists());
+    }
+}
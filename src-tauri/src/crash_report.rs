@@ -0,0 +1,251 @@
+// 后端崩溃报告：输出循环在 ServerState 中保留最近的 stderr 行，后端非主动停止且以非零码退出时，
+// 将退出码、运行时长、stderr 末尾与应用版本写入 <data_dir>/crashes/backend-<时间>.txt，
+// 并弹窗提供“重启后端”和“打开崩溃报告”。设置页通过 list/read 命令查看历史记录。
+// 健康检查判定后端无响应（进程仍在）时写入 backend-<时间>-hang.txt，附带资源占用与最近的日志。
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind};
+
+use crate::alerts::{self, Alert, AlertCategory};
+use crate::i18n::{t, tf};
+
+pub const CRASHES_DIR_NAME: &str = "crashes";
+const REPORT_PREFIX: &str = "backend-";
+const REPORT_EXTENSION: &str = ".txt";
+const HANG_SUFFIX: &str = "-hang";
+const STDERR_TAIL_LINES: usize = 50;
+// 只保留最近的报告
+const MAX_REPORTS: usize = 20;
+
+/// 当前后端进程最近的 stderr 行，超过上限时丢弃最早的
+#[derive(Default)]
+pub struct StderrTail {
+    lines: VecDeque<String>,
+}
+
+impl StderrTail {
+    pub fn push(&mut self, line: &str) {
+        if self.lines.len() == STDERR_TAIL_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_string());
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    /// 进程意外退出
+    Crash,
+    /// 进程仍在运行但不再响应
+    Hang,
+}
+
+/// 写入报告所需的退出信息
+pub struct Crash {
+    pub kind: ReportKind,
+    pub pid: u32,
+    pub code: Option<i32>,
+    pub started_at: Option<SystemTime>,
+    pub stderr: Vec<String>,
+    /// 附加的诊断信息（资源占用、最近的日志等），为空时不写这一节
+    pub snapshot: Vec<String>,
+}
+
+/// `list_crash_reports` 返回值，按时间从新到旧
+#[derive(serde::Serialize)]
+pub struct CrashReportInfo {
+    name: String,
+    kind: ReportKind,
+    size: u64,
+    created_at: Option<String>,
+}
+
+fn crashes_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(CRASHES_DIR_NAME))
+}
+
+fn is_report_name(name: &str) -> bool {
+    name.starts_with(REPORT_PREFIX)
+        && name.ends_with(REPORT_EXTENSION)
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
+
+// 报告文件，文件名中的时间戳保证按名称排序即按时间排序
+fn report_files(dir: &Path) -> Vec<(String, std::fs::Metadata)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            is_report_name(&name).then_some((name, metadata))
+        })
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files
+}
+
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    format!("{}h {:02}m {:02}s", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// 写入崩溃报告并清理超出数量的旧报告，返回报告路径
+pub fn write(app: &AppHandle, crash: &Crash) -> Result<PathBuf, String> {
+    let dir = crashes_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    let uptime = crash
+        .started_at
+        .and_then(|started| SystemTime::now().duration_since(started).ok())
+        .map_or_else(|| "unknown".to_string(), format_uptime);
+    let mut content = String::new();
+    content.push_str(match crash.kind {
+        ReportKind::Crash => "DunCrew backend crash report\n",
+        ReportKind::Hang => "DunCrew backend hang report\n",
+    });
+    content.push_str(&format!("Time: {}\n", crate::logs::timestamp()));
+    content.push_str(&format!("App version: {}\n", app.package_info().version));
+    content.push_str(&format!("OS: {} {}\n", std::env::consts::OS, std::env::consts::ARCH));
+    content.push_str(&format!("PID: {}\n", crash.pid));
+    let exit_code = match (crash.kind, crash.code) {
+        (ReportKind::Hang, _) => "none (still running, not responding)".to_string(),
+        (ReportKind::Crash, Some(code)) => code.to_string(),
+        (ReportKind::Crash, None) => "none (terminated by signal)".to_string(),
+    };
+    content.push_str(&format!("Exit code: {}\n", exit_code));
+    content.push_str(&format!("Uptime: {}\n", uptime));
+    content.push_str(&format!("\nLast {} stderr lines:\n", crash.stderr.len()));
+    for line in &crash.stderr {
+        content.push_str(line);
+        content.push('\n');
+    }
+    content.push_str("\nSystem:\n");
+    for line in crate::system_info::collect(app).report_lines() {
+        content.push_str(&line);
+        content.push('\n');
+    }
+    if !crash.snapshot.is_empty() {
+        content.push_str("\nDiagnostic snapshot:\n");
+        for line in &crash.snapshot {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+
+    // 后缀放在时间之后，按名称排序仍是时间顺序
+    let suffix = if crash.kind == ReportKind::Hang { HANG_SUFFIX } else { "" };
+    let path = dir.join(format!(
+        "{}{}{}{}",
+        REPORT_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f"),
+        suffix,
+        REPORT_EXTENSION
+    ));
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    for (name, _) in report_files(&dir).into_iter().skip(MAX_REPORTS) {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+    Ok(path)
+}
+
+/// 提示后端崩溃。`restarting` 为 true 表示已安排自动重启，此时只提供打开报告
+pub fn show_dialog(app: &AppHandle, report: PathBuf, code: Option<i32>, restarting: bool) {
+    // 无界面模式下报告路径已写入日志
+    if crate::headless::enabled(app) {
+        return;
+    }
+    let exit_code = code.map_or_else(|| t("common.unknown"), |c| c.to_string());
+    let (message, restart_label) = if restarting {
+        (tf("dialog.crash.restarting", &[("code", &exit_code)]), t("button.ok"))
+    } else {
+        (tf("dialog.crash.stopped", &[("code", &exit_code)]), t("button.restart_backend"))
+    };
+    let alert = Alert::new(AlertCategory::Crash, message)
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(restart_label, t("button.open_crash_report")));
+    alerts::ask(app, alert, {
+            let app = app.clone();
+            move |restart| {
+                if !restart {
+                    if let Err(e) = crate::folders::open(&report) {
+                        app_error!("{}", e);
+                    }
+                    return;
+                }
+                if restarting {
+                    return;
+                }
+                tauri::async_runtime::spawn(async move {
+                    let state = app.state::<crate::backend::ServerState>();
+                    if let Err(e) = crate::backend::restart_backend_exclusive(&app, &state).await {
+                        app_error!("Failed to restart backend: {}", e);
+                    }
+                });
+            }
+        });
+}
+
+/// data_dir 中在 since 之后写入的报告文件名，最新的在前
+pub fn written_since(data_dir: &Path, since: SystemTime) -> Vec<String> {
+    report_files(&data_dir.join(CRASHES_DIR_NAME))
+        .into_iter()
+        .filter(|(_, metadata)| metadata.modified().is_ok_and(|modified| modified >= since))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// 最新一份报告的文件名与修改时间；没有报告时为 None
+pub fn latest(app: &AppHandle) -> Option<(String, SystemTime)> {
+    let dir = crashes_dir(app).ok()?;
+    report_files(&dir)
+        .into_iter()
+        .next()
+        .and_then(|(name, metadata)| Some((name, metadata.modified().ok()?)))
+}
+
+/// 已保存的崩溃报告，最新的在前
+#[tauri::command]
+pub fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReportInfo>, String> {
+    let dir = crashes_dir(&app)?;
+    Ok(report_files(&dir)
+        .into_iter()
+        .map(|(name, metadata)| CrashReportInfo {
+            kind: if name.ends_with(&format!("{}{}", HANG_SUFFIX, REPORT_EXTENSION)) {
+                ReportKind::Hang
+            } else {
+                ReportKind::Crash
+            },
+            name,
+            size: metadata.len(),
+            created_at: metadata
+                .modified()
+                .ok()
+                .map(|time| chrono::DateTime::<chrono::Local>::from(time).to_rfc3339()),
+        })
+        .collect())
+}
+
+/// 读取一份崩溃报告；`name` 为 list_crash_reports 返回的文件名
+#[tauri::command]
+pub fn read_crash_report(app: AppHandle, name: String) -> Result<String, String> {
+    if !is_report_name(&name) {
+        return Err(format!("\"{}\" is not a crash report", name));
+    }
+    let path = crashes_dir(&app)?.join(&name);
+    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))
+}
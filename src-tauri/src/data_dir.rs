@@ -0,0 +1,318 @@
+// 数据目录位置：默认为 app_data_dir()，用户可改到其他磁盘。新位置记录在默认数据目录旁的
+// 指针文件中，启动时由 backend_data_dir 读取；迁移失败不会写入指针，旧位置继续有效。
+// 自定义位置所在的移动硬盘或网络驱动器未连接时，可以临时改用默认目录：只在本次运行有效，
+// 后端以 --read-only 启动，指针文件不变，下次启动仍使用自定义位置。
+// 启动时数据目录不可用的原因分为三类（DataDirIssue），对话框按原因给出不同的说明与补救方式。
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+const POINTER_FILE_SUFFIX: &str = ".data-location.json";
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+const PROGRESS_STEP: u64 = 8 * 1024 * 1024;
+// 除数据本身外至少保留的剩余空间
+const FREE_SPACE_MARGIN: u64 = 100 * 1024 * 1024;
+/// 临时使用默认目录时追加到 Sidecar 启动参数
+pub const READ_ONLY_ARG: &str = "--read-only";
+
+// 本次运行临时使用的数据目录（自定义位置不可用时的默认目录）
+static TEMPORARY_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// 数据目录不可用的原因
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDirIssue {
+    /// 自定义位置不存在，多半是所在驱动器未连接
+    Missing,
+    /// 目录不存在且无法创建，例如上级目录只读或被组策略禁止（漫游配置文件常见）
+    CannotCreate,
+    /// 目录存在但无法写入
+    NotWritable,
+    /// 路径无法作为参数传给后端
+    InvalidPath,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Pointer {
+    data_dir: PathBuf,
+}
+
+// 例如 %APPDATA%/com.duncrew.app.data-location.json
+fn pointer_path(default_dir: &Path) -> PathBuf {
+    let name = default_dir.file_name().unwrap_or_default().to_string_lossy();
+    default_dir.with_file_name(format!("{}{}", name, POINTER_FILE_SUFFIX))
+}
+
+/// 指针文件记录的数据目录；文件不存在、格式错误或路径不是绝对路径时返回 None，使用默认目录
+pub fn read_pointer(default_dir: &Path) -> Option<PathBuf> {
+    let path = pointer_path(default_dir);
+    let content = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Pointer>(&content) {
+        Ok(pointer) if pointer.data_dir.is_absolute() => Some(pointer.data_dir),
+        Ok(pointer) => {
+            app_error!("Ignoring relative data dir {:?} in {:?}", pointer.data_dir, path);
+            None
+        }
+        Err(e) => {
+            app_error!("Ignoring invalid data dir pointer {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+// 新位置等于默认目录时删除指针文件
+pub(crate) fn write_pointer(default_dir: &Path, data_dir: &Path) -> Result<(), String> {
+    let path = pointer_path(default_dir);
+    if data_dir == default_dir {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {:?}: {}", path, e)),
+            _ => Ok(()),
+        };
+    }
+    let content = serde_json::to_string_pretty(&Pointer { data_dir: data_dir.to_path_buf() })
+        .map_err(|e| e.to_string())?;
+    // 先写临时文件再改名，避免写到一半断电留下损坏的指针
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+// 目标可能还不存在，取最近一个存在的上级目录规范化后再拼回剩余部分
+fn normalize(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+    let mut normalized = existing.canonicalize().unwrap_or_else(|_| existing.to_path_buf());
+    normalized.extend(rest.iter().rev());
+    normalized
+}
+
+/// 指针文件记录的自定义位置；backend_data_dir 不会自动创建它，避免驱动器未连接时把数据写到别处
+pub fn is_custom_location<R: Runtime>(app: &AppHandle<R>, data_dir: &Path) -> bool {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .and_then(|default_dir| read_pointer(&default_dir))
+        .is_some_and(|dir| dir == data_dir)
+}
+
+/// 写入并删除一个测试文件，确认目录可写
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(".duncrew-write-test");
+    std::fs::write(&probe, b"ok").map_err(|e| format!("{:?} is not writable: {}", dir, e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+pub fn temporary_dir() -> Option<PathBuf> {
+    TEMPORARY_DIR.lock().unwrap().clone()
+}
+
+/// 本次运行改用默认数据目录，配置随之切换
+pub fn use_default_temporarily(app: &AppHandle) -> Result<(), String> {
+    let default_dir = app.path().app_data_dir().map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    std::fs::create_dir_all(&default_dir).map_err(|e| format!("Failed to create {:?}: {}", default_dir, e))?;
+    app_log!("Using {:?} for this session only (read-only)", default_dir);
+    *TEMPORARY_DIR.lock().unwrap() = Some(default_dir.clone());
+    let config_state = app.state::<crate::config::ConfigState>();
+    if let Err(e) = config_state.relocate(crate::config::config_path(&default_dir)) {
+        app_error!("{}, keeping current settings", e);
+    }
+    init_session_logs(app, &default_dir);
+    Ok(())
+}
+
+// 启动时数据目录不可用，setup 没能打开 app.log；改用新目录后补上
+fn init_session_logs(app: &AppHandle, data_dir: &Path) {
+    crate::logs::init_app_log(&data_dir.join(crate::logs::LOGS_DIR_NAME));
+    crate::logs::apply_filter(&app.state::<crate::config::ConfigState>().get().app_log_filter);
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    std::fs::read_dir(path).map(|mut entries| entries.next().is_none()).unwrap_or(false)
+}
+
+fn validate_target(old_dir: &Path, new_dir: &Path, migrate: bool) -> Result<u64, String> {
+    if !new_dir.is_absolute() {
+        return Err(format!("{:?} is not an absolute path", new_dir));
+    }
+    let old = normalize(old_dir);
+    let new = normalize(new_dir);
+    if new == old {
+        return Err("The new data directory is the current one".to_string());
+    }
+    if new.starts_with(&old) || old.starts_with(&new) {
+        return Err("The new data directory must not be inside the current one or contain it".to_string());
+    }
+    if new_dir.exists() && !new_dir.is_dir() {
+        return Err(format!("{:?} is not a directory", new_dir));
+    }
+    if migrate && new_dir.exists() && !is_empty_dir(new_dir) {
+        return Err(format!("{:?} is not empty", new_dir));
+    }
+
+    std::fs::create_dir_all(new_dir).map_err(|e| format!("Failed to create {:?}: {}", new_dir, e))?;
+    check_writable(new_dir)?;
+
+    let required = if migrate {
+        crate::storage::scan(old_dir).total_bytes
+    } else {
+        0
+    };
+    if let Some(available) = crate::storage::available_space(&new) {
+        if available < required + FREE_SPACE_MARGIN {
+            return Err(format!(
+                "Not enough free space on the target drive: {} MB needed, {} MB available",
+                (required + FREE_SPACE_MARGIN) / 1024 / 1024,
+                available / 1024 / 1024
+            ));
+        }
+    }
+    Ok(required)
+}
+
+// 递归复制（不跟随符号链接），跳过 `skip` 中的顶层条目；每复制约 8 MB 以已复制字节数调用一次 `progress`
+pub(crate) fn copy_contents(from: &Path, to: &Path, skip: &[&str], mut progress: impl FnMut(u64)) -> Result<(), String> {
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut bytes_processed = 0u64;
+    let mut next_report = 0u64;
+    let mut stack = vec![PathBuf::new()];
+    while let Some(relative) = stack.pop() {
+        let source_dir = from.join(&relative);
+        let entries = std::fs::read_dir(&source_dir).map_err(|e| format!("Failed to read {:?}: {}", source_dir, e))?;
+        std::fs::create_dir_all(to.join(&relative)).map_err(|e| format!("Failed to create {:?}: {}", to.join(&relative), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read {:?}: {}", source_dir, e))?;
+            let file_type = entry.file_type().map_err(|e| format!("Failed to read {:?}: {}", entry.path(), e))?;
+            if relative.as_os_str().is_empty() && skip.iter().any(|name| entry.file_name() == *name) {
+                continue;
+            }
+            let child = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                stack.push(child);
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let source = from.join(&child);
+            let target = to.join(&child);
+            let mut input = std::fs::File::open(&source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+            let mut output = std::fs::File::create(&target).map_err(|e| format!("Failed to create {:?}: {}", target, e))?;
+            loop {
+                let n = input.read(&mut buffer).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+                if n == 0 {
+                    break;
+                }
+                output.write_all(&buffer[..n]).map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+                bytes_processed += n as u64;
+                if bytes_processed >= next_report {
+                    next_report = bytes_processed + PROGRESS_STEP;
+                    progress(bytes_processed);
+                }
+            }
+            output.flush().map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+        }
+    }
+    Ok(())
+}
+
+// 复制失败时清除已复制的内容，目录本身由 validate_target 创建，一并删除（非空时会失败，留着即可）
+fn discard_partial_copy(new_dir: &Path) {
+    if let Ok(entries) = std::fs::read_dir(new_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let _ = if path.is_dir() { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+        }
+    }
+    let _ = std::fs::remove_dir(new_dir);
+}
+
+fn switch_to(app: &AppHandle, old_dir: &Path, new_dir: &Path, migrate: bool, total_bytes: u64) -> Result<(), String> {
+    let default_dir = app.path().app_data_dir().map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    if migrate {
+        let progress = |bytes_processed| {
+            let _ = app.emit(
+                "data-dir://progress",
+                crate::backup::ProgressPayload { operation: "migrate", bytes_processed, total_bytes },
+            );
+        };
+        if let Err(e) = copy_contents(old_dir, new_dir, &[], progress) {
+            discard_partial_copy(new_dir);
+            return Err(e);
+        }
+    }
+    write_pointer(&default_dir, new_dir)?;
+    // 配置文件跟随数据目录；新位置没有 config.json 时按默认值创建
+    let config_state = app.state::<crate::config::ConfigState>();
+    if let Err(e) = config_state.relocate(crate::config::config_path(new_dir)) {
+        app_error!("{}, keeping current settings", e);
+    }
+    Ok(())
+}
+
+fn check_movable(app: &AppHandle) -> Result<(), String> {
+    if app.state::<crate::cli::CliArgs>().data_dir.is_some() {
+        return Err("The data directory is set by --data-dir and cannot be changed here".to_string());
+    }
+    if crate::profiles::active_name(app) != crate::profiles::DEFAULT_PROFILE {
+        return Err("Only the default profile's data directory can be moved; switch to it first".to_string());
+    }
+    Ok(())
+}
+
+/// 启动时数据目录不可用，用户选择了另一个位置：不复制数据，直接改用新位置（后端尚未启动）
+pub fn switch_unavailable(app: &AppHandle, new_dir: &Path) -> Result<(), String> {
+    check_movable(app)?;
+    let old_dir = crate::configured_data_dir(app)?;
+    validate_target(&old_dir, new_dir, false)?;
+    switch_to(app, &old_dir, new_dir, false, 0)?;
+    *TEMPORARY_DIR.lock().unwrap() = None;
+    init_session_logs(app, new_dir);
+    app_log!("Data dir is now {:?}", new_dir);
+    Ok(())
+}
+
+/// 修改数据目录位置：校验目标（绝对路径、可写、空间足够、与当前目录互不包含），停止后端，
+/// `migrate` 为 true 时复制现有数据，然后写入指针文件并用新 `--path` 重新启动后端。
+/// 旧目录中的数据保留不删；日志在应用重启前仍写入旧目录。
+#[tauri::command]
+pub async fn set_data_dir(app: AppHandle, new_path: String, migrate: bool) -> Result<String, String> {
+    check_movable(&app)?;
+    let new_dir = PathBuf::from(new_path.trim());
+    let spec = crate::jobs::JobSpec::new(crate::jobs::JobKind::Migration, new_dir.to_string_lossy());
+    crate::jobs::run(&app, spec, |_| move_data_dir(&app, new_dir.clone(), migrate)).await?;
+    app_log!("Data dir is now {:?}", new_dir);
+    Ok(new_dir.to_string_lossy().to_string())
+}
+
+async fn move_data_dir(app: &AppHandle, new_dir: PathBuf, migrate: bool) -> Result<(), String> {
+    let old_dir = crate::backend_data_dir(app)?;
+    let validated = {
+        let (old_dir, new_dir) = (old_dir.clone(), new_dir.clone());
+        tauri::async_runtime::spawn_blocking(move || validate_target(&old_dir, &new_dir, migrate))
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    let total_bytes = validated?;
+
+    app_log!("Moving data dir from {:?} to {:?} (migrate: {})", old_dir, new_dir, migrate);
+    let app_handle = app.clone();
+    crate::backend::with_backend_stopped(app, "data-dir", move || {
+        switch_to(&app_handle, &old_dir, &new_dir, migrate, total_bytes)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r)
+    .inspect_err(|e| app_error!("Failed to change data dir: {}", e))
+}
@@ -0,0 +1,117 @@
+// 数据目录锁：<data_dir>/.ddos.lock 记录持有者 PID 与时间，并用系统文件锁保护，
+// 防止单实例检查的空档或外部后端模式下两个进程同时使用同一数据目录。
+// 锁由应用进程持有（而不是 Sidecar），进程退出时系统自动释放，因此遗留的锁文件不会挡住下次启动。
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+pub const LOCK_FILE_NAME: &str = ".ddos.lock";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LockRecord {
+    pid: u32,
+    acquired_at: String,
+}
+
+/// 锁文件中记录的持有者
+pub struct LockHolder {
+    pub pid: u32,
+    pub acquired_at: String,
+    /// 该进程是否仍在运行；已退出说明是遗留的锁文件，下次获取时直接接管
+    pub alive: bool,
+}
+
+pub enum LockError {
+    // 被仍在运行的进程持有；Windows 上加锁期间其他进程读不到内容，pid 可能为 None
+    Held { pid: Option<u32> },
+    Io(String),
+}
+
+/// 持有期间数据目录归本进程使用，drop 时解锁并删除锁文件
+pub struct DataDirLock {
+    file: File,
+    path: PathBuf,
+    data_dir: PathBuf,
+}
+
+impl DataDirLock {
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+}
+
+impl Drop for DataDirLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn read_record(file: &mut File) -> Option<LockRecord> {
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn process_alive(pid: u32) -> bool {
+    let mut system = System::new();
+    let sys_pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[sys_pid]), true, ProcessRefreshKind::nothing());
+    system.process(sys_pid).is_some()
+}
+
+fn write_record(file: &mut File) -> std::io::Result<()> {
+    let record = LockRecord { pid: std::process::id(), acquired_at: crate::logs::timestamp() };
+    file.set_len(0)?;
+    file.rewind()?;
+    file.write_all(serde_json::to_string(&record).unwrap_or_default().as_bytes())?;
+    file.flush()
+}
+
+/// 读取锁文件记录的持有者，不加锁；没有锁文件或读不到内容（Windows 上被加锁时）返回 None
+pub fn holder(data_dir: &Path) -> Option<LockHolder> {
+    let mut file = File::open(data_dir.join(LOCK_FILE_NAME)).ok()?;
+    let record = read_record(&mut file)?;
+    let alive = record.pid == std::process::id() || process_alive(record.pid);
+    Some(LockHolder { pid: record.pid, acquired_at: record.acquired_at, alive })
+}
+
+/// 获取数据目录锁。被存活进程持有时返回 Held；持有者已退出的遗留锁直接接管
+pub fn acquire(data_dir: &Path) -> Result<DataDirLock, LockError> {
+    let path = data_dir.join(LOCK_FILE_NAME);
+    let io_error = |e: std::io::Error| LockError::Io(format!("Failed to lock {:?}: {}", path, e));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(io_error)?;
+
+    match file.try_lock() {
+        Ok(()) => {
+            if let Some(stale) = read_record(&mut file) {
+                if stale.pid != std::process::id() {
+                    app_log!("Breaking stale data dir lock left by pid {} at {}", stale.pid, stale.acquired_at);
+                }
+            }
+        }
+        Err(TryLockError::WouldBlock) => {
+            return Err(LockError::Held { pid: read_record(&mut file).map(|r| r.pid) });
+        }
+        // 部分网络文件系统不支持文件锁，退回到检查记录中的 PID 是否存活
+        Err(TryLockError::Error(e)) if e.kind() == std::io::ErrorKind::Unsupported => {
+            if let Some(record) = read_record(&mut file) {
+                if record.pid != std::process::id() && process_alive(record.pid) {
+                    return Err(LockError::Held { pid: Some(record.pid) });
+                }
+            }
+        }
+        Err(TryLockError::Error(e)) => return Err(io_error(e)),
+    }
+
+    write_record(&mut file).map_err(io_error)?;
+    Ok(DataDirLock { file, path, data_dir: data_dir.to_path_buf() })
+}
@@ -0,0 +1,92 @@
+// duncrew:// / ddos:// 深度链接：解析校验后以 `app://deep-link` 事件发给前端。
+// 已有实例运行时链接经 single-instance 转交到这里；冷启动时先暂存，等前端和后端就绪再发送。
+
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+use tauri_plugin_deep_link::DeepLinkExt;
+
+const SCHEMES: [&str; 2] = ["duncrew", "ddos"];
+const MAX_URL_LEN: usize = 2048;
+const MAX_PARAM_LEN: usize = 512;
+const MAX_PARAMS: usize = 16;
+
+/// `app://deep-link` 事件负载，例如 ddos://open?doc=123 解析为
+/// `{ action: "open", path: "", params: { doc: "123" } }`
+#[derive(Clone, serde::Serialize)]
+pub struct DeepLink {
+    pub url: String,
+    pub action: String,
+    pub path: String,
+    pub params: BTreeMap<String, String>,
+}
+
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 校验并解析链接；格式不符、过长或含控制字符的链接直接拒绝
+pub fn parse(raw: &str) -> Result<DeepLink, String> {
+    if raw.len() > MAX_URL_LEN {
+        return Err(format!("deep link is longer than {} bytes", MAX_URL_LEN));
+    }
+    if raw.chars().any(char::is_control) {
+        return Err("deep link contains control characters".to_string());
+    }
+    let url = reqwest::Url::parse(raw).map_err(|e| format!("invalid deep link: {}", e))?;
+    if !SCHEMES.contains(&url.scheme()) {
+        return Err(format!("unsupported scheme \"{}\"", url.scheme()));
+    }
+    let action = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    if !is_identifier(&action) {
+        return Err(format!("invalid deep link action \"{}\"", action));
+    }
+
+    let mut params = BTreeMap::new();
+    for (key, value) in url.query_pairs() {
+        if params.len() >= MAX_PARAMS {
+            return Err(format!("deep link has more than {} parameters", MAX_PARAMS));
+        }
+        if !is_identifier(&key) || value.len() > MAX_PARAM_LEN {
+            return Err(format!("invalid deep link parameter \"{}\"", key));
+        }
+        params.insert(key.into_owned(), value.into_owned());
+    }
+
+    Ok(DeepLink {
+        url: url.to_string(),
+        action,
+        path: url.path().trim_start_matches('/').to_string(),
+        params,
+    })
+}
+
+fn deliver(app: &AppHandle, raw: &str) {
+    match parse(raw) {
+        Ok(link) => {
+            app_log!("Deep link received: {}", link.action);
+            crate::app_events::emit_when_ready(app, "app://deep-link", link);
+        }
+        Err(e) => app_error!("Ignoring deep link: {}", e),
+    }
+}
+
+/// 处理冷启动链接并订阅后续链接
+pub fn init(app: &AppHandle) {
+    // 安装包会注册协议；开发构建手动注册，便于本地测试
+    #[cfg(all(debug_assertions, any(windows, target_os = "linux")))]
+    if let Err(e) = app.deep_link().register_all() {
+        app_error!("Failed to register deep link schemes: {}", e);
+    }
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            deliver(app, url.as_str());
+        }
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            deliver(&handle, url.as_str());
+        }
+    });
+}
@@ -0,0 +1,76 @@
+// 现场排查用的开发者工具与调试信息。
+// 调试构建中 DevTools 始终可用；发布构建只有 config.json 设置了 enable_devtools 或以 --debug 启动时才可用，
+// 否则 toggle_devtools 返回“已被策略禁用”，菜单中也不显示该项（快捷键 CmdOrCtrl+Shift+I 随菜单项注册）。
+// get_debug_info 把后端状态、最近的生命周期事件与生效配置（敏感值已隐藏）汇总为一份，支持人员只需一张截图。
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+// get_debug_info 附带的生命周期事件数
+const RECENT_EVENTS: usize = 20;
+
+/// 当前是否允许打开 DevTools
+pub fn allowed(app: &AppHandle) -> bool {
+    cfg!(debug_assertions) || crate::app_config(app).enable_devtools || app.state::<crate::cli::CliArgs>().debug
+}
+
+/// 打开或关闭窗口的 DevTools，策略不允许时返回错误
+pub fn toggle(app: &AppHandle, window: &WebviewWindow) -> Result<(), String> {
+    if !allowed(app) {
+        return Err("DevTools are disabled by policy (set enable_devtools in config.json or start with --debug)".to_string());
+    }
+    if window.is_devtools_open() {
+        window.close_devtools();
+    } else {
+        app_log!("Opening DevTools for window {}", window.label());
+        window.open_devtools();
+    }
+    Ok(())
+}
+
+/// 打开 / 关闭调用方窗口的 DevTools
+#[tauri::command]
+pub fn toggle_devtools(app: AppHandle, window: WebviewWindow) -> Result<(), String> {
+    toggle(&app, &window)
+}
+
+/// `get_debug_info` 返回值
+#[derive(serde::Serialize)]
+pub struct DebugInfo {
+    pub app_version: &'static str,
+    pub debug_build: bool,
+    pub devtools_allowed: bool,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// 同 get_backend_status
+    pub backend: serde_json::Value,
+    /// 最近的生命周期事件，从新到旧
+    pub lifecycle_events: Vec<serde_json::Value>,
+    /// 生效的配置（含命令行覆盖），敏感字段为 ***
+    pub config: serde_json::Value,
+}
+
+// 敏感字段按名称隐藏，会话 token 与凭据值按内容隐藏
+fn redacted(value: impl serde::Serialize) -> serde_json::Value {
+    let mut json = serde_json::to_value(value).unwrap_or_default();
+    crate::diagnostics::redact_json(&mut json);
+    let text = crate::logs::redact(&json.to_string()).into_owned();
+    serde_json::from_str(&text).unwrap_or(json)
+}
+
+/// 汇总排查问题所需的信息
+#[tauri::command]
+pub fn get_debug_info(app: AppHandle) -> DebugInfo {
+    let lifecycle_events = crate::backend_data_dir(&app)
+        .map(|dir| crate::lifecycle_history::read(&dir, RECENT_EVENTS, None))
+        .unwrap_or_default();
+    DebugInfo {
+        app_version: env!("CARGO_PKG_VERSION"),
+        debug_build: cfg!(debug_assertions),
+        devtools_allowed: allowed(&app),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        backend: redacted(crate::backend::backend_status(&app.state::<crate::backend::ServerState>())),
+        lifecycle_events: lifecycle_events.into_iter().map(redacted).collect(),
+        config: redacted(crate::app_config(&app)),
+    }
+}
@@ -0,0 +1,319 @@
+// 后端自检：run_backend_doctor 依次检查 Sidecar、数据目录、磁盘空间、端口、健康端点、后端版本、
+// config.json、数据目录锁与最近的崩溃报告，前端据此渲染诊断页，诊断包中也附带同一份报告（doctor.json）。
+// 每项检查在单独的任务中并行执行并各自限时，某项探测卡住时记为 fail，整份报告至多等待最长的那个时限。
+
+use std::future::Future;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::pin::Pin;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+
+use crate::backend::ServerState;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+// 首次校验要读完整个 Sidecar 二进制（100MB 以上）
+const SIDECAR_TIMEOUT: Duration = Duration::from_secs(20);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+// 这段时间内有崩溃报告时给出警告
+const RECENT_CRASH: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// 一项检查的结果
+#[derive(Clone, serde::Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// `run_backend_doctor` 返回值；status 为各项中最差的结果
+#[derive(Clone, serde::Serialize)]
+pub struct DoctorReport {
+    pub status: CheckStatus,
+    pub generated_at: String,
+    pub checks: Vec<Check>,
+}
+
+type Outcome = (CheckStatus, String);
+type CheckFuture = Pin<Box<dyn Future<Output = Check> + Send>>;
+
+fn pass(detail: impl Into<String>) -> Outcome {
+    (CheckStatus::Pass, detail.into())
+}
+
+fn warn(detail: impl Into<String>) -> Outcome {
+    (CheckStatus::Warn, detail.into())
+}
+
+fn fail(detail: impl Into<String>) -> Outcome {
+    (CheckStatus::Fail, detail.into())
+}
+
+// 限时执行一项异步检查
+fn timed(name: &'static str, timeout: Duration, check: impl Future<Output = Outcome> + Send + 'static) -> CheckFuture {
+    Box::pin(async move {
+        let started = Instant::now();
+        let (status, detail) = match tokio::time::timeout(timeout, check).await {
+            Ok(outcome) => outcome,
+            Err(_) => fail(format!("Did not finish within {}s", timeout.as_secs())),
+        };
+        Check { name, status, detail, duration_ms: started.elapsed().as_millis() as u64 }
+    })
+}
+
+// 限时执行一项阻塞检查（文件 IO、连接测试）；超时后线程继续跑完，结果丢弃
+fn blocking(
+    name: &'static str,
+    timeout: Duration,
+    app: &AppHandle,
+    check: impl FnOnce(&AppHandle) -> Outcome + Send + 'static,
+) -> CheckFuture {
+    let app = app.clone();
+    timed(name, timeout, async move {
+        tauri::async_runtime::spawn_blocking(move || check(&app))
+            .await
+            .unwrap_or_else(|e| fail(e.to_string()))
+    })
+}
+
+// 后端当前是否在运行（与 get_backend_status 的 running 一致）
+fn backend_running(app: &AppHandle) -> bool {
+    let state = app.state::<ServerState>();
+    let process = state.process.lock().unwrap();
+    process.pid.is_some() || (process.external_url.is_some() && process.started_at.is_some())
+}
+
+fn not_running(app: &AppHandle) -> String {
+    let lifecycle = serde_json::to_value(app.state::<ServerState>().backend.lifecycle()).unwrap_or_default();
+    format!("Backend is not running (lifecycle: {})", lifecycle.as_str().unwrap_or("unknown"))
+}
+
+fn check_sidecar(app: &AppHandle) -> Outcome {
+    if cfg!(feature = "mock-backend") {
+        return pass("Using the built-in mock backend");
+    }
+    if app.state::<ServerState>().process.lock().unwrap().external_url.is_some() {
+        return pass("Not used: connected to an external backend");
+    }
+    let config = crate::app_config(app);
+    if let Some(source) = &config.backend_source {
+        return pass(format!("Running the backend from source {:?}", source));
+    }
+    let path = match crate::sidecar_integrity::sidecar_path() {
+        Ok(path) => path,
+        Err(e) => return fail(e),
+    };
+    if !path.is_file() {
+        return fail(format!("{:?} does not exist; reinstall the app", path));
+    }
+    if !crate::sidecar_integrity::required(app) {
+        return warn(format!("{:?} exists, verification is disabled (skip_sidecar_verification)", path));
+    }
+    if crate::sidecar_integrity::EXPECTED_SIDECAR_SHA256.is_none() {
+        return warn(format!("{:?} exists, no hash was embedded at build time", path));
+    }
+    match crate::sidecar_integrity::verify(app) {
+        Ok(()) => pass(format!("{:?} matches the expected SHA-256", path)),
+        Err(e) => fail(e.to_string()),
+    }
+}
+
+fn check_data_dir(app: &AppHandle) -> Outcome {
+    let dir = match crate::backend_data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => return fail(e),
+    };
+    match crate::data_dir::check_writable(&dir) {
+        Ok(()) if crate::data_dir::temporary_dir().is_some() => {
+            warn(format!("Using {:?} for this session because the configured folder is unavailable", dir))
+        }
+        Ok(()) => pass(format!("{:?} is writable", dir)),
+        Err(e) => fail(e),
+    }
+}
+
+fn check_disk_space(app: &AppHandle) -> Outcome {
+    let dir = match crate::backend_data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => return fail(e),
+    };
+    let Some(available) = crate::storage::available_space(&dir) else {
+        return warn(format!("Could not read the free space of the volume of {:?}", dir));
+    };
+    let minimum = crate::app_config(app).min_free_space_mb * 1024 * 1024;
+    let detail = format!("{} MB free (minimum {} MB)", available / 1024 / 1024, minimum / 1024 / 1024);
+    if available < minimum {
+        fail(detail)
+    } else if available < minimum.saturating_mul(2) {
+        warn(detail)
+    } else {
+        pass(detail)
+    }
+}
+
+// 后端地址的 host:port；外部后端按 URL 解析
+fn backend_addr(app: &AppHandle) -> Option<SocketAddr> {
+    let state = app.state::<ServerState>();
+    let process = state.process.lock().unwrap();
+    let Some(url) = &process.external_url else {
+        return Some(SocketAddr::new(process.host, process.port));
+    };
+    let url = reqwest::Url::parse(url).ok()?;
+    let port = url.port_or_known_default()?;
+    (url.host_str()?, port).to_socket_addrs().ok()?.next()
+}
+
+fn check_port(app: &AppHandle) -> Outcome {
+    if backend_running(app) {
+        let Some(addr) = backend_addr(app) else {
+            return fail("Could not resolve the backend address");
+        };
+        return match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(_) => pass(format!("{} is accepting connections", addr)),
+            Err(e) => fail(format!("Cannot connect to {}: {}", addr, e)),
+        };
+    }
+    let port = crate::app_config(app).port;
+    match crate::backend::check_port_available(port) {
+        Ok(()) => pass(format!("Port {} is free", port)),
+        Err(e) => warn(format!("{}; another port will be used on start", e)),
+    }
+}
+
+async fn check_health(app: AppHandle) -> Outcome {
+    if !backend_running(&app) {
+        return fail(not_running(&app));
+    }
+    let client = crate::backend_client::BackendClient::current(&app);
+    let base_url = client.base_url();
+    match crate::health::probe(&client, CHECK_TIMEOUT).await {
+        Ok(latency) => pass(format!("{}/health responded in {} ms", base_url, latency.as_millis())),
+        Err(e) => fail(format!("{}/health: {}", base_url, e)),
+    }
+}
+
+fn check_version(app: &AppHandle) -> Outcome {
+    let expected = crate::backend::EXPECTED_BACKEND_VERSION;
+    let version = app.state::<ServerState>().process.lock().unwrap().backend_version.clone();
+    match version {
+        Some(version) if crate::backend::is_backend_version_compatible(&version) => {
+            pass(format!("Backend {} is compatible (expects {})", version, expected))
+        }
+        Some(version) => fail(format!("Backend {} is incompatible with this app (expects {})", version, expected)),
+        None if backend_running(app) => warn("Backend does not report its version"),
+        None => warn(not_running(app)),
+    }
+}
+
+fn check_config(app: &AppHandle) -> Outcome {
+    let path = app.state::<crate::config::ConfigState>().path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return warn(format!("{:?} does not exist, default settings are used", path));
+        }
+        Err(e) => return fail(format!("Failed to read {:?}: {}", path, e)),
+    };
+    let parsed = serde_json::from_str::<crate::config::AppConfig>(&content)
+        .map_err(|e| e.to_string())
+        .and_then(|config| config.validate());
+    match parsed {
+        Ok(()) => pass(format!("{:?} is valid", path)),
+        Err(e) => fail(format!("{:?} is invalid, default settings are used: {}", path, e)),
+    }
+}
+
+fn check_lock(app: &AppHandle) -> Outcome {
+    let dir = match crate::backend_data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => return fail(e),
+    };
+    let held = app
+        .state::<ServerState>()
+        .data_lock
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|lock| lock.data_dir() == dir);
+    if held {
+        return pass("The data folder lock is held by this app");
+    }
+    if !dir.join(crate::data_lock::LOCK_FILE_NAME).exists() {
+        return pass("No lock file");
+    }
+    match crate::data_lock::holder(&dir) {
+        Some(holder) if holder.alive => {
+            warn(format!("The data folder is in use by PID {} since {}", holder.pid, holder.acquired_at))
+        }
+        Some(holder) => warn(format!(
+            "Stale lock file left by PID {} at {}; it is taken over on the next start",
+            holder.pid, holder.acquired_at
+        )),
+        None => warn("A lock file exists but its owner could not be read"),
+    }
+}
+
+fn check_crashes(app: &AppHandle) -> Outcome {
+    let Some((name, modified)) = crate::crash_report::latest(app) else {
+        return pass("No crash reports");
+    };
+    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+    let hours = age.as_secs() / 3600;
+    let detail = format!("Latest report {} is {}h {:02}m old", name, hours, age.as_secs() / 60 % 60);
+    if age < RECENT_CRASH {
+        warn(detail)
+    } else {
+        pass(detail)
+    }
+}
+
+/// 执行全部检查；至多等待 SIDECAR_TIMEOUT
+pub async fn run(app: &AppHandle) -> DoctorReport {
+    let checks: Vec<CheckFuture> = vec![
+        blocking("sidecar", SIDECAR_TIMEOUT, app, check_sidecar),
+        blocking("data_dir", CHECK_TIMEOUT, app, check_data_dir),
+        blocking("disk_space", CHECK_TIMEOUT, app, check_disk_space),
+        blocking("port", CHECK_TIMEOUT, app, check_port),
+        timed("health", CHECK_TIMEOUT + Duration::from_secs(1), check_health(app.clone())),
+        blocking("backend_version", CHECK_TIMEOUT, app, check_version),
+        blocking("config", CHECK_TIMEOUT, app, check_config),
+        blocking("data_lock", CHECK_TIMEOUT, app, check_lock),
+        blocking("crash_reports", CHECK_TIMEOUT, app, check_crashes),
+    ];
+    // 先全部启动再按顺序收集，各项并行执行
+    let tasks: Vec<_> = checks.into_iter().map(tauri::async_runtime::spawn).collect();
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        if let Ok(check) = task.await {
+            results.push(check);
+        }
+    }
+    let status = if results.iter().any(|c| c.status == CheckStatus::Fail) {
+        CheckStatus::Fail
+    } else if results.iter().any(|c| c.status == CheckStatus::Warn) {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    let failed: Vec<_> = results.iter().filter(|c| c.status != CheckStatus::Pass).map(|c| c.name).collect();
+    if failed.is_empty() {
+        app_log!("Backend doctor: all checks passed");
+    } else {
+        app_log!("Backend doctor: issues in {}", failed.join(", "));
+    }
+    DoctorReport { status, generated_at: crate::logs::timestamp(), checks: results }
+}
+
+/// 运行后端自检，返回每项检查的 pass / warn / fail 与说明，供诊断页展示
+#[tauri::command]
+pub async fn run_backend_doctor(app: AppHandle) -> Result<DoctorReport, String> {
+    Ok(run(&app).await)
+}
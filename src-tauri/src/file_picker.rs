@@ -0,0 +1,115 @@
+// 原生文件选择：浏览器的 <input type=file> 拿不到真实路径，后端导入大文件时又不应经 WebView 复制一遍。
+// 选中的路径校验后记入短期允许列表（PickedPaths），import_file 等接受路径的命令只处理列表中的路径，
+// 前端不能借此让应用读写任意文件。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+// 选中后多久内可以交给其他命令使用
+const PICK_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 对话框的扩展名过滤器，例如 `{ name: "Markdown", extensions: ["md"] }`
+#[derive(serde::Deserialize)]
+pub struct FileFilter {
+    name: String,
+    extensions: Vec<String>,
+}
+
+/// 用户通过对话框选中的路径（规范化后）及选中时间
+#[derive(Default)]
+pub struct PickedPaths {
+    paths: Mutex<HashMap<PathBuf, Instant>>,
+}
+
+impl PickedPaths {
+    fn insert(&self, path: PathBuf) {
+        let mut paths = self.paths.lock().unwrap();
+        paths.retain(|_, picked_at| picked_at.elapsed() < PICK_TTL);
+        paths.insert(path, Instant::now());
+    }
+}
+
+// 规范化路径；保存路径对应的文件可能还不存在，此时规范化其所在目录
+fn normalize(path: &Path) -> Result<PathBuf, String> {
+    if let Ok(path) = path.canonicalize() {
+        return Ok(path);
+    }
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(format!("Invalid path {}", path.display()));
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| format!("Folder {} is not accessible: {}", parent.display(), e))?;
+    Ok(parent.join(name))
+}
+
+/// 确认路径是 pick_files / pick_save_path 在有效期内返回过的，返回规范化后的路径
+pub fn ensure_picked(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let normalized = normalize(Path::new(path))?;
+    let state = app.state::<PickedPaths>();
+    let paths = state.paths.lock().unwrap();
+    match paths.get(&normalized) {
+        Some(picked_at) if picked_at.elapsed() < PICK_TTL => Ok(normalized),
+        _ => Err(format!("{} was not selected in a file dialog", path)),
+    }
+}
+
+fn dialog_with_filters(app: &AppHandle, filters: &[FileFilter]) -> tauri_plugin_dialog::FileDialogBuilder<tauri::Wry> {
+    let mut dialog = app.dialog().file();
+    for filter in filters {
+        let extensions: Vec<&str> = filter.extensions.iter().map(|ext| ext.trim_start_matches('.')).collect();
+        dialog = dialog.add_filter(&filter.name, &extensions);
+    }
+    dialog
+}
+
+// 确认选中的文件存在且可读，并记入允许列表
+fn accept_file(app: &AppHandle, path: tauri_plugin_dialog::FilePath) -> Result<String, String> {
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    let path = path.canonicalize().map_err(|e| format!("{} does not exist: {}", path.display(), e))?;
+    if !path.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    std::fs::File::open(&path).map_err(|e| format!("{} is not readable: {}", path.display(), e))?;
+    app.state::<PickedPaths>().insert(path.clone());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 弹出打开文件对话框，返回选中文件的绝对路径；用户取消时返回 null
+#[tauri::command]
+pub async fn pick_files(app: AppHandle, filters: Vec<FileFilter>, multiple: bool) -> Result<Option<Vec<String>>, String> {
+    let dialog = dialog_with_filters(&app, &filters);
+    let picked = if multiple {
+        dialog.blocking_pick_files()
+    } else {
+        dialog.blocking_pick_file().map(|path| vec![path])
+    };
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let paths = picked
+        .into_iter()
+        .map(|path| accept_file(&app, path))
+        .collect::<Result<Vec<_>, _>>()?;
+    app_log!("User picked {} file(s)", paths.len());
+    Ok(Some(paths))
+}
+
+/// 弹出保存文件对话框，返回目标绝对路径（文件可能尚不存在）；用户取消时返回 null
+#[tauri::command]
+pub async fn pick_save_path(app: AppHandle, default_name: Option<String>, filters: Vec<FileFilter>) -> Result<Option<String>, String> {
+    let mut dialog = dialog_with_filters(&app, &filters);
+    if let Some(name) = default_name.as_deref().filter(|name| !name.is_empty()) {
+        dialog = dialog.set_file_name(name);
+    }
+    let Some(picked) = dialog.blocking_save_file() else {
+        return Ok(None);
+    };
+    let path = normalize(&picked.into_path().map_err(|e| e.to_string())?)?;
+    app.state::<PickedPaths>().insert(path.clone());
+    Ok(Some(path.to_string_lossy().to_string()))
+}
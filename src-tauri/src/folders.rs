@@ -0,0 +1,278 @@
+// 在系统文件管理器中打开已知目录。命令不接受路径参数，前端无法借此打开任意位置。
+// get_app_paths 返回这些已知目录的实际位置，与启动后端时使用的是同一组函数。
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// 数据目录下的缓存目录，备份时排除
+pub const CACHE_DIR_NAME: &str = "cache";
+
+/// `get_app_paths` 返回值，目录均已创建并规范化为绝对路径
+#[derive(serde::Serialize)]
+pub struct AppPaths {
+    pub data_dir: PathBuf,
+    pub config_path: PathBuf,
+    pub logs_dir: PathBuf,
+    pub cache_dir: PathBuf,
+    pub imports_dir: PathBuf,
+    /// 可经 ddos-asset 协议读取的目录，见 asset_url_base
+    pub assets_dir: PathBuf,
+    /// assets_dir 中文件的 URL 前缀：macOS / Linux 为 `ddos-asset://localhost/`，Windows 为 `http://ddos-asset.localhost/`；
+    /// 后接以 / 分隔、逐段 URL 编码的相对路径，例如 `thumbnails/a%20b.png`。支持 Range 请求
+    pub asset_url_base: String,
+    /// 自动备份目录
+    pub backups_dir: PathBuf,
+    pub current_profile: String,
+}
+
+/// 用系统文件管理器打开目录；打开失败（例如精简 Linux 环境没有文件管理器）时返回带路径的错误，
+/// 前端可以改为直接显示路径
+pub fn reveal(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    open(dir)
+}
+
+/// 用系统默认程序打开文件或目录
+pub fn open(path: &Path) -> Result<(), String> {
+    // explorer.exe 即使成功也会返回非零退出码，只能检查能否启动
+    #[cfg(windows)]
+    let result = std::process::Command::new("explorer").arg(path).spawn().map(|_| ());
+    #[cfg(not(windows))]
+    let result = {
+        #[cfg(target_os = "macos")]
+        let opener = "open";
+        #[cfg(not(target_os = "macos"))]
+        let opener = "xdg-open";
+        std::process::Command::new(opener).arg(path).status().and_then(|status| {
+            if status.success() {
+                Ok(())
+            } else {
+                Err(std::io::Error::other(format!("{} exited with {}", opener, status)))
+            }
+        })
+    };
+    result.map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+pub fn logs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(crate::logs::LOGS_DIR_NAME))
+}
+
+pub fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(CACHE_DIR_NAME))
+}
+
+// Windows 上 canonicalize 返回 \\?\C:\... 形式，普通盘符路径去掉该前缀，UNC 路径保持不变
+#[cfg(windows)]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    // 含无法转为 UTF-8 的字符时保留原样，不做有损转换
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    match text.strip_prefix(r"\\?\") {
+        Some(rest) if !rest.starts_with(r"UNC\") => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+#[cfg(not(windows))]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// 规范化为绝对路径；路径不存在等无法规范化时原样返回
+pub fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().map(strip_verbatim).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// 作为启动参数传给后端的路径。参数按原样逐个传递（不经 shell，空格与中文无需转义），
+/// 但 Python 后端按 Unicode 解析参数：路径含无法表示为 Unicode 的字符时返回错误，
+/// 而不是像 to_string_lossy 那样替换成 U+FFFD，让后端在另一个目录里建库
+pub fn path_arg(path: &Path) -> Result<String, String> {
+    match path.to_str() {
+        Some(text) if Path::new(text).as_os_str() == path.as_os_str() => Ok(text.to_string()),
+        _ => Err(format!(
+            "Path {:?} contains characters that cannot be passed to the backend; choose a different folder",
+            path
+        )),
+    }
+}
+
+// 按需创建目录后规范化
+fn ensure_dir(dir: PathBuf) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    Ok(canonical(&dir))
+}
+
+fn app_paths(app: &AppHandle) -> Result<AppPaths, String> {
+    let data_dir = crate::backend_data_dir(app)?;
+    let config_path = app.state::<crate::config::ConfigState>().path();
+    let config_path = match (config_path.parent(), config_path.file_name()) {
+        (Some(parent), Some(name)) => ensure_dir(parent.to_path_buf())?.join(name),
+        _ => config_path,
+    };
+    Ok(AppPaths {
+        config_path,
+        logs_dir: ensure_dir(logs_dir(app)?)?,
+        cache_dir: ensure_dir(cache_dir(app)?)?,
+        imports_dir: ensure_dir(data_dir.join(crate::imports::IMPORTS_DIR_NAME))?,
+        assets_dir: ensure_dir(data_dir.join(crate::assets::ASSETS_DIR_NAME))?,
+        asset_url_base: crate::assets::url_base(),
+        backups_dir: ensure_dir(crate::auto_backup::target_dir(app)?)?,
+        data_dir: ensure_dir(data_dir)?,
+        current_profile: crate::profiles::active_name(app),
+    })
+}
+
+async fn reveal_in_background(dir: PathBuf) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || reveal(&dir))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 打开数据目录
+#[tauri::command]
+pub async fn open_data_dir(app: AppHandle) -> Result<(), String> {
+    reveal_in_background(crate::backend_data_dir(&app)?).await
+}
+
+/// 打开日志目录
+#[tauri::command]
+pub async fn open_logs_dir(app: AppHandle) -> Result<(), String> {
+    reveal_in_background(logs_dir(&app)?).await
+}
+
+/// 应用使用的各个目录。data_dir 与传给后端的 --path 一致
+#[tauri::command]
+pub async fn get_app_paths(app: AppHandle) -> Result<AppPaths, String> {
+    tauri::async_runtime::spawn_blocking(move || app_paths(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_arg_keeps_spaces_and_unicode() {
+        for name in ["with space", "数据 目录", "🚀 emoji 👩‍💻", "\u{e9}t\u{e9}"] {
+            let path = std::env::temp_dir().join(name).join("DunCrew");
+            assert_eq!(path_arg(&path).unwrap(), path.to_str().unwrap());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_arg_rejects_paths_that_are_not_unicode() {
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/tmp/data-\xff"));
+        assert!(path_arg(path).is_err());
+    }
+
+    // 默认数据目录、指针文件、配置目录都以唯一的 identifier 隔离在测试自己的位置，结束时删除
+    struct TestApp {
+        app: tauri::App<tauri::test::MockRuntime>,
+        cleanup: Vec<PathBuf>,
+    }
+
+    impl TestApp {
+        fn new() -> Self {
+            use std::sync::atomic::{AtomicU32, Ordering};
+            static NEXT: AtomicU32 = AtomicU32::new(0);
+            let mut context = tauri::test::mock_context(tauri::test::noop_assets());
+            context.config_mut().identifier =
+                format!("com.duncrew.test-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::SeqCst));
+            let app = tauri::test::mock_builder().build(context).unwrap();
+            app.manage(crate::cli::CliArgs::default());
+            let default_dir = app.path().app_data_dir().unwrap();
+            let name = default_dir.file_name().unwrap().to_string_lossy().to_string();
+            let cleanup = vec![
+                default_dir.with_file_name(format!("{}.data-location.json", name)),
+                default_dir.with_file_name(format!("{}.profiles", name)),
+                app.path().app_config_dir().unwrap(),
+                default_dir,
+            ];
+            Self { app, cleanup }
+        }
+
+        fn default_dir(&self) -> PathBuf {
+            self.app.path().app_data_dir().unwrap()
+        }
+
+        // 与启动时相同：先选择配置档案，再解析后端数据目录
+        fn backend_data_dir(&self) -> PathBuf {
+            crate::profiles::init(self.app.handle());
+            crate::backend_data_dir(self.app.handle()).unwrap()
+        }
+    }
+
+    impl Drop for TestApp {
+        fn drop(&mut self) {
+            for path in &self.cleanup {
+                let _ = std::fs::remove_file(path);
+                let _ = std::fs::remove_dir_all(path);
+            }
+        }
+    }
+
+    // prepare_spawn 传给后端的 --path 与 app_paths 返回的 data_dir 都来自 backend_data_dir，各自规范化后须一致
+    fn assert_backend_path(data_dir: &Path, expected: &Path) {
+        let args = crate::backend::path_args(data_dir).unwrap();
+        assert_eq!(args[0], "--path");
+        assert_eq!(args[1], ensure_dir(data_dir.to_path_buf()).unwrap().to_str().unwrap(), "for {:?}", data_dir);
+        assert_eq!(Path::new(&args[1]), expected.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn backend_path_follows_the_pointer_file() {
+        let test = TestApp::new();
+        assert_backend_path(&test.backend_data_dir(), &test.default_dir());
+
+        // 指针文件中的路径未规范化
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("elsewhere")).unwrap();
+        std::fs::create_dir_all(root.path().join("Custom Data")).unwrap();
+        let custom = root.path().join("elsewhere").join("..").join("Custom Data");
+        crate::data_dir::write_pointer(&test.default_dir(), &custom).unwrap();
+        let data_dir = test.backend_data_dir();
+        assert!(crate::data_dir::is_custom_location(test.app.handle(), &data_dir));
+        assert_backend_path(&data_dir, &root.path().join("Custom Data"));
+    }
+
+    #[test]
+    fn backend_path_uses_the_active_profile() {
+        let test = TestApp::new();
+        let profile_dir = test.default_dir().with_file_name(format!(
+            "{}.profiles",
+            test.default_dir().file_name().unwrap().to_string_lossy()
+        ));
+        let profile_dir = profile_dir.join("work 工作");
+        let profiles = serde_json::json!({
+            "active": "work 工作",
+            "profiles": [{ "name": "work 工作", "data_dir": profile_dir }],
+        });
+        let config_dir = test.app.path().app_config_dir().unwrap();
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(config_dir.join(crate::profiles::PROFILES_FILE_NAME), profiles.to_string()).unwrap();
+
+        let data_dir = test.backend_data_dir();
+        assert_backend_path(&data_dir, &profile_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn backend_path_resolves_symlinked_custom_location() {
+        let test = TestApp::new();
+        let root = tempfile::tempdir().unwrap();
+        let target = root.path().join("external drive");
+        std::fs::create_dir_all(&target).unwrap();
+        let link = root.path().join("linked");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        crate::data_dir::write_pointer(&test.default_dir(), &link).unwrap();
+
+        let data_dir = test.backend_data_dir();
+        assert_eq!(data_dir, link);
+        assert_backend_path(&data_dir, &target);
+    }
+}
@@ -0,0 +1,250 @@
+// 数据目录监视：用户直接在资源管理器 / 访达中把文件放进 imports 等目录时通知前端。
+// 后端就绪后监视 config.json 中 fs_watch.paths 列出的子目录（logs、cache 由本应用自己写入，不允许监视以免形成回路），
+// 原始事件在 DEBOUNCE 内合并后按文件发送 `fs://created` / `fs://modified` / `fs://removed`（数据目录下的相对路径），
+// 配置了 forward_endpoint 时同时 POST 给后端。系统文件通知不可用（常见于网络驱动器）或运行中出错时改为定时轮询。
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+use ::notify::event::{EventKind, ModifyKind, RenameMode};
+use ::notify::{RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend_client::{BackendClient, BackendClientError};
+
+// 最后一个事件之后这么久没有新事件才发送，复制大文件时的连续写入只通知一次
+const DEBOUNCE: Duration = Duration::from_millis(500);
+const EXCLUDED_DIRS: [&str; 2] = [crate::logs::LOGS_DIR_NAME, crate::folders::CACHE_DIR_NAME];
+
+// 当前的监视器；替换或清空时旧监视器随之停止，其合并线程在通道关闭后退出
+static WATCHER: Mutex<Option<Box<dyn Watcher + Send>>> = Mutex::new(None);
+// 当前的监视方式，见 WatchStatus::mode
+static MODE: Mutex<&'static str> = Mutex::new("off");
+
+#[derive(Clone, Copy, PartialEq)]
+enum Change {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// `fs://created` / `fs://modified` / `fs://removed` 事件负载
+#[derive(Clone, Default, serde::Serialize)]
+struct FsChangePayload {
+    paths: Vec<String>,
+}
+
+// 转发给后端的请求体
+#[derive(Default, serde::Serialize)]
+struct FsChanges {
+    created: Vec<String>,
+    modified: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// `set_watched_paths` 返回值
+#[derive(serde::Serialize)]
+pub struct WatchStatus {
+    pub paths: Vec<String>,
+    /// "native"：系统文件通知；"poll"：定时轮询；"off"：未在监视
+    pub mode: &'static str,
+}
+
+/// 校验 fs_watch.paths 中的一项：数据目录下的相对路径，不能是 logs / cache
+pub fn validate_path(path: &str) -> Result<(), String> {
+    let relative = Path::new(path);
+    let normal = !path.trim().is_empty() && relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if !normal {
+        return Err(format!("\"{}\" must be a relative folder inside the data directory", path));
+    }
+    if relative.components().next().is_some_and(|first| EXCLUDED_DIRS.iter().any(|dir| first.as_os_str() == *dir)) {
+        return Err(format!("\"{}\" cannot be watched", path));
+    }
+    Ok(())
+}
+
+fn relative_path(data_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(data_dir).ok()?;
+    let first = relative.components().next()?;
+    if EXCLUDED_DIRS.iter().any(|dir| first.as_os_str() == *dir) {
+        return None;
+    }
+    Some(relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+}
+
+// 同一文件在一批中的多个事件合并为一个结果；None 表示先创建后删除，不通知
+fn merge(previous: Option<Change>, next: Change) -> Option<Change> {
+    match (previous, next) {
+        (None, change) => Some(change),
+        (Some(Change::Created), Change::Removed) => None,
+        (Some(Change::Created), _) => Some(Change::Created),
+        (Some(Change::Removed), Change::Created) => Some(Change::Modified),
+        (Some(_), change) => Some(change),
+    }
+}
+
+fn changes_of(event: &::notify::Event) -> Vec<(PathBuf, Change)> {
+    let change = match event.kind {
+        EventKind::Create(_) => Change::Created,
+        EventKind::Remove(_) => Change::Removed,
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => Change::Removed,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => Change::Created,
+        // 重命名：paths 为 [原路径, 新路径]
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            return vec![(event.paths[0].clone(), Change::Removed), (event.paths[1].clone(), Change::Created)];
+        }
+        EventKind::Modify(ModifyKind::Metadata(_)) | EventKind::Access(_) => return Vec::new(),
+        EventKind::Modify(_) => Change::Modified,
+        _ => return Vec::new(),
+    };
+    event.paths.iter().map(|path| (path.clone(), change)).collect()
+}
+
+fn emit(app: &AppHandle, batch: BTreeMap<String, Change>) {
+    let mut changes = FsChanges::default();
+    for (path, change) in batch {
+        match change {
+            Change::Created => changes.created.push(path),
+            Change::Modified => changes.modified.push(path),
+            Change::Removed => changes.removed.push(path),
+        }
+    }
+    for (event, paths) in [
+        ("fs://created", &changes.created),
+        ("fs://modified", &changes.modified),
+        ("fs://removed", &changes.removed),
+    ] {
+        if !paths.is_empty() {
+            let _ = app.emit(event, FsChangePayload { paths: paths.clone() });
+        }
+    }
+    let endpoint = crate::app_config(app).fs_watch.forward_endpoint.trim().to_string();
+    if endpoint.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client = BackendClient::current(&app);
+        match client.post_json(&endpoint, &changes, Duration::from_secs(10)).await {
+            Ok(_) => {}
+            Err(BackendClientError::Http { status }) => {
+                app_error!("Backend rejected file changes at {}: HTTP {}", endpoint, status)
+            }
+            Err(e) => app_error!("Failed to forward file changes to {}: {}", endpoint, e),
+        }
+    });
+}
+
+// 合并线程：收到第一个事件后等到 DEBOUNCE 内不再有新事件再统一发送；监视器停止（通道关闭）后退出
+fn debounce(app: AppHandle, data_dir: PathBuf, rx: Receiver<::notify::Result<::notify::Event>>, polling: bool) {
+    let mut batch: BTreeMap<String, Change> = BTreeMap::new();
+    loop {
+        let received = if batch.is_empty() {
+            rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            rx.recv_timeout(DEBOUNCE)
+        };
+        match received {
+            Ok(Ok(event)) => {
+                for (path, change) in changes_of(&event) {
+                    let Some(relative) = relative_path(&data_dir, &path) else {
+                        continue;
+                    };
+                    match merge(batch.get(&relative).copied(), change) {
+                        Some(change) => batch.insert(relative, change),
+                        None => batch.remove(&relative),
+                    };
+                }
+            }
+            Ok(Err(e)) if !polling => {
+                app_error!("File watcher failed ({}), switching to polling", e);
+                let handle = app.clone();
+                // 替换监视器会关闭本线程的通道，放到别的线程进行
+                std::thread::spawn(move || start_watching(&handle, true));
+            }
+            Ok(Err(e)) => app_error!("File watcher error: {}", e),
+            Err(RecvTimeoutError::Timeout) => emit(&app, std::mem::take(&mut batch)),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+fn watch_all(watcher: &mut dyn Watcher, dirs: &[PathBuf]) -> ::notify::Result<()> {
+    for dir in dirs {
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+    }
+    Ok(())
+}
+
+// 按当前配置（重新）开始监视；polling 为 true 时直接使用轮询
+fn start_watching(app: &AppHandle, polling: bool) {
+    let mut current = WATCHER.lock().unwrap();
+    *current = None;
+    *MODE.lock().unwrap() = "off";
+    let config = crate::app_config(app).fs_watch;
+    let Ok(data_dir) = crate::backend_data_dir(app) else {
+        return;
+    };
+    if config.paths.is_empty() {
+        return;
+    }
+    let data_dir = crate::folders::canonical(&data_dir);
+    let dirs: Vec<PathBuf> = config
+        .paths
+        .iter()
+        .map(|path| data_dir.join(path))
+        .filter(|dir| std::fs::create_dir_all(dir).is_ok())
+        .collect();
+
+    let mut polling = polling;
+    loop {
+        let (tx, rx) = mpsc::channel();
+        let watcher: ::notify::Result<Box<dyn Watcher + Send>> = if polling {
+            let interval = Duration::from_secs(config.poll_interval_secs);
+            ::notify::PollWatcher::new(tx, ::notify::Config::default().with_poll_interval(interval))
+                .map(|watcher| Box::new(watcher) as Box<dyn Watcher + Send>)
+        } else {
+            ::notify::recommended_watcher(tx).map(|watcher| Box::new(watcher) as Box<dyn Watcher + Send>)
+        };
+        let result = watcher.and_then(|mut watcher| watch_all(watcher.as_mut(), &dirs).map(|_| watcher));
+        match result {
+            Ok(watcher) => {
+                let mode = if polling { "poll" } else { "native" };
+                app_log!("Watching {} for changes ({})", config.paths.join(", "), mode);
+                let handle = app.clone();
+                let root = data_dir.clone();
+                std::thread::spawn(move || debounce(handle, root, rx, polling));
+                *current = Some(watcher);
+                *MODE.lock().unwrap() = mode;
+                return;
+            }
+            Err(e) if !polling => {
+                app_log!("Native file watching is unavailable ({}), polling every {} s", e, config.poll_interval_secs);
+                polling = true;
+            }
+            Err(e) => {
+                app_error!("Failed to watch data directory: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// 后端就绪后调用；每次就绪都按当前配置与数据目录重新开始
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || start_watching(&app, false));
+}
+
+/// 设置数据目录下要监视的子目录并立即生效；空列表表示停止监视
+#[tauri::command]
+pub async fn set_watched_paths(app: AppHandle, paths: Vec<String>) -> Result<WatchStatus, String> {
+    app.state::<crate::config::ConfigState>().update(|config| config.fs_watch.paths = paths)?;
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || start_watching(&handle, false))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(WatchStatus { paths: crate::app_config(&app).fs_watch.paths, mode: *MODE.lock().unwrap() })
+}
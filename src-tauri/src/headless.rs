@@ -0,0 +1,37 @@
+// 无界面模式（--headless）：只运行后端及其守护（自动重启、日志、健康检查、更新），不创建窗口、托盘和全局快捷键，
+// 供家庭服务器等场景由其他机器上的浏览器访问。系统通知改为写日志，启动失败时不弹窗而是以非零状态退出；
+// 收到 SIGINT / SIGTERM（Windows 为 Ctrl+C）时走正常退出流程，优雅停止后端。
+// Linux 上 Tauri 仍需初始化 GTK，没有图形会话时需在 Xvfb 等虚拟显示下运行。
+
+use tauri::{AppHandle, Manager};
+
+pub fn enabled(app: &AppHandle) -> bool {
+    app.try_state::<crate::cli::CliArgs>().is_some_and(|args| args.headless)
+}
+
+/// 收到终止信号时请求退出，ExitRequested 处理会先停止后端
+pub fn exit_on_signal(app: &AppHandle) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            app_log!("Received interrupt signal, shutting down");
+            handle.exit(0);
+        }
+    });
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match signal(SignalKind::terminate()) {
+                Ok(mut terminate) => {
+                    if terminate.recv().await.is_some() {
+                        app_log!("Received SIGTERM, shutting down");
+                        handle.exit(0);
+                    }
+                }
+                Err(e) => app_error!("Failed to listen for SIGTERM: {}", e),
+            }
+        });
+    }
+}
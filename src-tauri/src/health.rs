@@ -0,0 +1,230 @@
+// 后端健康检查：定期探测 HTTP /health 端点并向前端广播状态。
+// 探测超时（进程还在但不响应）与连接被拒绝分开计数：连续超时达到 config.json 的 watchdog.hung_threshold 次时
+// 判定后端 Hung，写入附带资源占用与最近日志的无响应报告，发送 `backend://hung`，按 watchdog.action 只通知或强制重启。
+
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend::{Lifecycle, ServerState};
+use crate::backend_client::{BackendClient, BackendClientError};
+
+/// 健康检查参数
+#[derive(Clone)]
+pub struct HealthCheckConfig {
+    pub interval: Duration,
+    pub timeout: Duration,
+    /// 连续失败达到该次数时广播 `backend://unhealthy`
+    pub failure_threshold: u32,
+    /// 进程启动后的宽限期，Python 导入依赖期间的失败不计数
+    pub startup_grace: Duration,
+    /// 判定不健康后是否自动重启后端
+    pub restart_on_unhealthy: bool,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(2),
+            failure_threshold: 3,
+            startup_grace: Duration::from_secs(30),
+            restart_on_unhealthy: false,
+        }
+    }
+}
+
+// 无响应报告中附带的最近日志行数
+const HUNG_SNAPSHOT_LOG_LINES: usize = 50;
+
+// `backend://health` / `backend://unhealthy` 事件负载
+#[derive(Clone, serde::Serialize)]
+struct HealthPayload {
+    healthy: bool,
+    latency_ms: u32,
+    consecutive_failures: u32,
+}
+
+// `backend://hung` 事件负载；action 为 "notify" / "restart"
+#[derive(Clone, serde::Serialize)]
+struct HungPayload {
+    pid: Option<u32>,
+    consecutive_timeouts: u32,
+    action: &'static str,
+    report: Option<String>,
+}
+
+/// 探测一次后端健康端点，成功返回响应耗时；超时（端口仍在监听但后端没有响应）为 Timeout
+pub async fn probe(client: &BackendClient, timeout: Duration) -> Result<Duration, BackendClientError> {
+    let started = Instant::now();
+    client.get_once("/health", timeout).await?;
+    Ok(started.elapsed())
+}
+
+// 无响应报告的诊断部分：最近一次资源采样与最近的日志
+fn hung_snapshot(app: &AppHandle, pid: Option<u32>) -> Vec<String> {
+    let mut lines = Vec::new();
+    match pid.and_then(|pid| app.state::<crate::metrics::MetricsState>().latest(pid)) {
+        Some(sample) => lines.push(format!(
+            "Memory: {} MB, CPU: {}, sampled at {}",
+            sample.rss_bytes / 1024 / 1024,
+            sample.cpu_percent.map_or_else(|| "n/a".to_string(), |cpu| format!("{:.1}%", cpu)),
+            sample.sampled_at
+        )),
+        None => lines.push("Memory: no sample".to_string()),
+    }
+    lines.push(String::new());
+    lines.push(format!("Recent log (last {} lines):", HUNG_SNAPSHOT_LOG_LINES));
+    for entry in app.state::<crate::logs::LogBuffer>().recent(HUNG_SNAPSHOT_LOG_LINES, None) {
+        lines.push(format!("[{}] {} {}", entry.stream, entry.level.as_str(), entry.line));
+    }
+    lines
+}
+
+// 判定为 Hung 之后：写报告、广播事件并按配置通知或强制重启
+fn handle_hung(app: &AppHandle, consecutive_timeouts: u32) {
+    let state = app.state::<ServerState>();
+    let (pid, started_at) = {
+        let process = state.process.lock().unwrap();
+        (process.pid, process.started_at)
+    };
+    let restart = crate::app_config(app).watchdog.action == "restart";
+    app_error!("Backend is hung: {} health checks in a row timed out", consecutive_timeouts);
+    let crash = crate::crash_report::Crash {
+        kind: crate::crash_report::ReportKind::Hang,
+        pid: pid.unwrap_or(0),
+        code: None,
+        started_at,
+        stderr: state.stderr_tail.lock().unwrap().lines(),
+        snapshot: hung_snapshot(app, pid),
+    };
+    let report = match crate::crash_report::write(app, &crash) {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            app_error!("Failed to write hang report: {}", e);
+            None
+        }
+    };
+    let payload = HungPayload { pid, consecutive_timeouts, action: if restart { "restart" } else { "notify" }, report };
+    // 状态本身见 `backend://state`；这里附带无响应报告与处理方式
+    crate::app_events::emit_to_frontend(app, "backend://hung", payload);
+    if !restart {
+        crate::notify::backend_failure(app, &crate::i18n::t("notify.hung"), false);
+        return;
+    }
+    crate::notify::backend_failure(app, &crate::i18n::t("notify.hung.restarting"), false);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::backend::kill_and_restart(&app).await {
+            app_error!("Failed to restart hung backend: {}", e);
+        }
+    });
+}
+
+/// 轮询健康端点直到后端就绪或超时，启动画面据此切换到主窗口
+pub async fn wait_until_ready(app: &AppHandle, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        // 每次重新获取：启用 TLS 时客户端随后端启动重建
+        if probe(&BackendClient::current(app), Duration::from_secs(1)).await.is_ok() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    false
+}
+
+/// 启动后台健康检查循环
+pub fn spawn(app: AppHandle, config: HealthCheckConfig) {
+    tauri::async_runtime::spawn(async move {
+        let mut consecutive_failures = 0u32;
+        let mut consecutive_timeouts = 0u32;
+        loop {
+            tokio::time::sleep(config.interval).await;
+
+            let client = BackendClient::current(&app);
+            let state = app.state::<ServerState>();
+            // 主动停止或进程不存在时不探测，避免关闭 / 重启过程中刷错误
+            let (started_at, external) = {
+                let process = state.process.lock().unwrap();
+                (process.started_at, process.external_url.is_some())
+            };
+            // 外部后端启动时未连上：持续探测，连上后补记连接时间
+            if external && started_at.is_none() {
+                if probe(&client, config.timeout).await.is_ok() {
+                    state.process.lock().unwrap().started_at = Some(SystemTime::now());
+                    app_log!("External backend at {} is reachable", client.base_url());
+                }
+                continue;
+            }
+            let lifecycle = state.backend.lifecycle();
+            let running = matches!(lifecycle, Lifecycle::Running | Lifecycle::Hung);
+            let Some(started_at) = started_at.filter(|_| running || external) else {
+                consecutive_failures = 0;
+                consecutive_timeouts = 0;
+                continue;
+            };
+            // 系统挂起中或刚唤醒：睡眠时间不计入连续失败，唤醒后的探测与重启由 power 模块负责
+            if crate::power::health_checks_paused() {
+                consecutive_failures = 0;
+                consecutive_timeouts = 0;
+                continue;
+            }
+
+            let payload = match probe(&client, config.timeout).await {
+                Ok(latency) => {
+                    consecutive_failures = 0;
+                    consecutive_timeouts = 0;
+                    if lifecycle == Lifecycle::Hung && state.backend.mark_recovered(&app) {
+                        app_log!("Hung backend is responding again");
+                    }
+                    crate::app_events::mark_backend_ready(&app);
+                    HealthPayload {
+                        healthy: true,
+                        latency_ms: latency.as_millis() as u32,
+                        consecutive_failures,
+                    }
+                }
+                Err(e) => {
+                    let in_grace = started_at
+                        .elapsed()
+                        .map(|d| d < config.startup_grace)
+                        .unwrap_or(false);
+                    if in_grace {
+                        continue;
+                    }
+                    consecutive_failures += 1;
+                    // 只有连续的超时才说明进程卡住，连接被拒绝等其他失败中断计数
+                    let timed_out = matches!(e, BackendClientError::Timeout { .. });
+                    consecutive_timeouts = if timed_out { consecutive_timeouts + 1 } else { 0 };
+                    app_error!(
+                        "Health check failed ({} in a row): {}",
+                        consecutive_failures, e
+                    );
+                    HealthPayload {
+                        healthy: false,
+                        latency_ms: config.timeout.as_millis() as u32,
+                        consecutive_failures,
+                    }
+                }
+            };
+
+            let _ = app.emit("backend://health", payload.clone());
+            if !external
+                && consecutive_timeouts == crate::app_config(&app).watchdog.hung_threshold
+                && state.backend.mark_hung(&app)
+            {
+                handle_hung(&app, consecutive_timeouts);
+            }
+            if consecutive_failures == config.failure_threshold {
+                app_error!("Backend is unhealthy");
+                let _ = app.emit("backend://unhealthy", payload);
+                crate::notify::backend_failure(&app, &crate::i18n::t("notify.unresponsive"), false);
+                if config.restart_on_unhealthy {
+                    if let Err(e) = crate::backend::restart_backend_exclusive(&app, &state).await {
+                        app_error!("Failed to restart unhealthy backend: {}", e);
+                    }
+                }
+            }
+        }
+    });
+}
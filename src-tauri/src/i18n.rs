@@ -0,0 +1,92 @@
+// 原生界面文字（对话框、托盘菜单、系统通知、启动画面状态）的本地化。
+// 文案在 locales/<lang>.json 中，编译时用 include_str! 嵌入；当前语言缺少的键回退到英文，英文也没有时显示键名。
+// 语言取 config.json 的 locale，为空时跟随系统语言（sys_locale），系统语言不受支持时用英文。
+// 日志与返回给前端的错误信息不翻译。
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Manager};
+
+const FALLBACK: &str = "en";
+
+const BUNDLES: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.json")),
+    ("zh", include_str!("../locales/zh.json")),
+];
+
+static CURRENT: RwLock<&'static str> = RwLock::new(FALLBACK);
+
+fn bundles() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static PARSED: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        BUNDLES
+            .iter()
+            .map(|(lang, json)| {
+                let strings = serde_json::from_str(json).unwrap_or_else(|e| {
+                    app_error!("Invalid locale bundle {}: {}", lang, e);
+                    HashMap::new()
+                });
+                (*lang, strings)
+            })
+            .collect()
+    })
+}
+
+/// 把 zh-CN、en_US.UTF-8 之类的写法归一为受支持的语言代码，不支持时返回 None
+pub fn normalize(lang: &str) -> Option<&'static str> {
+    let primary = lang.trim().split(['-', '_', '.']).next()?.to_ascii_lowercase();
+    BUNDLES.iter().map(|(code, _)| *code).find(|code| *code == primary)
+}
+
+/// 配置为空时跟随系统语言
+fn resolve(configured: &str) -> &'static str {
+    normalize(configured)
+        .or_else(|| sys_locale::get_locale().as_deref().and_then(normalize))
+        .unwrap_or(FALLBACK)
+}
+
+pub fn current() -> &'static str {
+    *CURRENT.read().unwrap()
+}
+
+/// 按配置设置当前语言，返回生效的语言；启动时及 locale 配置改变时调用
+pub fn init(app: &AppHandle) -> &'static str {
+    let lang = resolve(&crate::app_config(app).locale);
+    *CURRENT.write().unwrap() = lang;
+    lang
+}
+
+/// 重新读取配置中的语言，并按新语言重建托盘菜单和菜单栏
+pub fn apply(app: &AppHandle) -> &'static str {
+    let lang = init(app);
+    crate::tray::refresh(app);
+    crate::app_menu::refresh(app);
+    lang
+}
+
+/// 当前语言下 `key` 对应的文案
+pub fn t(key: &str) -> String {
+    let bundles = bundles();
+    [current(), FALLBACK]
+        .iter()
+        .find_map(|lang| bundles.get(lang).and_then(|strings| strings.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// 带参数的文案，`{name}` 替换为对应的值
+pub fn tf(key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter()
+        .fold(t(key), |text, (name, value)| text.replace(&format!("{{{}}}", name), &value.to_string()))
+}
+
+/// 切换原生界面语言并写入配置；`lang` 为空表示跟随系统。返回生效的语言。
+/// 托盘菜单与菜单栏立即按新语言重建，已弹出的对话框不受影响
+#[tauri::command]
+pub fn set_locale(app: AppHandle, lang: String) -> Result<String, String> {
+    let lang = lang.trim().to_string();
+    app.state::<crate::config::ConfigState>().update(|config| config.locale = lang.clone())?;
+    let effective = apply(&app);
+    app_log!("Locale set to \"{}\" (effective: {})", lang, effective);
+    Ok(effective.to_string())
+}
@@ -0,0 +1,221 @@
+// 拖放导入：文件拖到窗口上（或经 import_file 导入文件对话框选中的文件）时在 Rust 侧拿到真实路径，按扩展名过滤后复制到
+// <app_data_dir>/imports/<uuid>/，完成后以 `import://files` 通知前端交给后端导入。
+// 复制在阻塞线程池中进行，期间发送 `import://progress`，失败发送 `import://error`。
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+pub const IMPORTS_DIR_NAME: &str = "imports";
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+// 每复制这么多字节发送一次进度
+const PROGRESS_STEP: u64 = 8 * 1024 * 1024;
+
+#[derive(Clone, serde::Serialize)]
+struct ImportedFile {
+    path: PathBuf,
+    original_path: PathBuf,
+    size: u64,
+}
+
+// `import://files` 事件负载；skipped 为扩展名不在允许列表中的文件
+#[derive(Clone, serde::Serialize)]
+struct ImportFilesPayload {
+    import_id: String,
+    files: Vec<ImportedFile>,
+    skipped: Vec<PathBuf>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ImportProgressPayload {
+    import_id: String,
+    file: PathBuf,
+    bytes_copied: u64,
+    total_bytes: u64,
+}
+
+// 导入失败原因，作为 `import://error` 事件负载
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImportError {
+    PermissionDenied { path: PathBuf, message: String },
+    DiskFull { path: PathBuf, message: String },
+    NotFound { path: PathBuf, message: String },
+    Other { path: PathBuf, message: String },
+}
+
+impl ImportError {
+    fn from_io(path: &Path, error: std::io::Error) -> Self {
+        let path = path.to_path_buf();
+        let message = error.to_string();
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => ImportError::PermissionDenied { path, message },
+            std::io::ErrorKind::StorageFull => ImportError::DiskFull { path, message },
+            std::io::ErrorKind::NotFound => ImportError::NotFound { path, message },
+            _ => ImportError::Other { path, message },
+        }
+    }
+}
+
+// 随机 UUID v4，作为本次导入的目录名
+fn new_import_id() -> String {
+    let mut bytes = [0u8; 16];
+    let _ = getrandom::fill(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+fn is_allowed(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    let Some(ext) = path.extension() else {
+        return false;
+    };
+    let ext = ext.to_string_lossy();
+    extensions.iter().any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+}
+
+fn copy_with_progress(
+    app: &AppHandle,
+    import_id: &str,
+    source: &Path,
+    target: &Path,
+) -> Result<u64, ImportError> {
+    let mut input = std::fs::File::open(source).map_err(|e| ImportError::from_io(source, e))?;
+    let total_bytes = input.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut output = std::fs::File::create(target).map_err(|e| ImportError::from_io(target, e))?;
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut bytes_copied = 0u64;
+    let mut next_report = PROGRESS_STEP;
+    loop {
+        let n = input.read(&mut buffer).map_err(|e| ImportError::from_io(source, e))?;
+        if n == 0 {
+            break;
+        }
+        output.write_all(&buffer[..n]).map_err(|e| ImportError::from_io(target, e))?;
+        bytes_copied += n as u64;
+        if bytes_copied >= next_report {
+            next_report += PROGRESS_STEP;
+            let _ = app.emit(
+                "import://progress",
+                ImportProgressPayload {
+                    import_id: import_id.to_string(),
+                    file: source.to_path_buf(),
+                    bytes_copied,
+                    total_bytes,
+                },
+            );
+        }
+    }
+    output.flush().map_err(|e| ImportError::from_io(target, e))?;
+    Ok(bytes_copied)
+}
+
+// 同一次导入中来自不同文件夹的同名文件依次改名为 "name (2).ext"、"name (3).ext"，不互相覆盖
+fn unique_target(dir: &Path, source: &Path) -> PathBuf {
+    let file_name = source.file_name().unwrap_or_default();
+    let target = dir.join(file_name);
+    if !target.exists() {
+        return target;
+    }
+    let name = Path::new(file_name);
+    (2..)
+        .map(|n| {
+            let mut candidate = name.file_stem().unwrap_or_default().to_os_string();
+            candidate.push(format!(" ({})", n));
+            if let Some(extension) = name.extension() {
+                candidate.push(".");
+                candidate.push(extension);
+            }
+            dir.join(candidate)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(target)
+}
+
+fn import_files(app: &AppHandle, import_id: &str, sources: &[PathBuf]) -> Result<Vec<ImportedFile>, ImportError> {
+    let data_dir = crate::backend_data_dir(app)
+        .map_err(|message| ImportError::Other { path: PathBuf::new(), message })?;
+    let dir = data_dir.join(IMPORTS_DIR_NAME).join(import_id);
+    std::fs::create_dir_all(&dir).map_err(|e| ImportError::from_io(&dir, e))?;
+
+    let mut files = Vec::new();
+    for source in sources {
+        let target = unique_target(&dir, source);
+        let size = copy_with_progress(app, import_id, source, &target)?;
+        files.push(ImportedFile { path: target, original_path: source.clone(), size });
+    }
+    Ok(files)
+}
+
+/// 处理一次文件拖放，复制在后台进行，不阻塞窗口事件循环
+pub fn handle_drop(app: &AppHandle, paths: Vec<PathBuf>) {
+    let extensions = crate::app_config(app).import_extensions;
+    // 拖进来的文件夹不展开
+    let (accepted, skipped): (Vec<PathBuf>, Vec<PathBuf>) = paths
+        .into_iter()
+        .partition(|p| p.is_file() && is_allowed(p, &extensions));
+    if accepted.is_empty() {
+        if !skipped.is_empty() {
+            app_log!("Ignoring dropped files with unsupported types: {:?}", skipped);
+        }
+        return;
+    }
+    start_import(app, accepted, skipped);
+}
+
+/// 导入经 pick_files 选中的文件，返回 import_id；进度与结果同拖放导入，以 `import://*` 事件通知
+#[tauri::command]
+pub fn import_file(app: AppHandle, path: String) -> Result<String, String> {
+    let path = crate::file_picker::ensure_picked(&app, &path)?;
+    if !is_allowed(&path, &crate::app_config(&app).import_extensions) {
+        return Err(format!("{} is not a supported import file type", path.display()));
+    }
+    Ok(start_import(&app, vec![path], Vec::new()))
+}
+
+// 在后台复制文件，返回本次导入的 import_id
+fn start_import(app: &AppHandle, accepted: Vec<PathBuf>, skipped: Vec<PathBuf>) -> String {
+    let app = app.clone();
+    let import_id = new_import_id();
+    let result = import_id.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Ok(data_dir) = crate::backend_data_dir(&app) {
+            if !crate::storage::confirm_free_space(&app, &data_dir, "import") {
+                app_log!("Import of {} file(s) cancelled because of low disk space", accepted.len());
+                return;
+            }
+        }
+        match import_files(&app, &import_id, &accepted) {
+            Ok(files) => {
+                app_log!("Imported {} file(s) into {}", files.len(), import_id);
+                let _ = app.emit("import://files", ImportFilesPayload { import_id, files, skipped });
+            }
+            Err(e) => {
+                app_error!("Failed to import files: {:?}", e);
+                let _ = app.emit("import://error", e);
+            }
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_named_files_get_numbered_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut targets = Vec::new();
+        for source in ["/a/report.pdf", "/b/report.pdf", "/c/report.pdf", "/a/notes", "/b/notes"] {
+            let target = unique_target(dir.path(), Path::new(source));
+            std::fs::write(&target, source).unwrap();
+            targets.push(target.file_name().unwrap().to_string_lossy().into_owned());
+        }
+        assert_eq!(targets, ["report.pdf", "report (2).pdf", "report (3).pdf", "notes", "notes (2)"]);
+    }
+}
@@ -0,0 +1,444 @@
+// 后台任务：备份、恢复、数据目录迁移、上传、诊断包导出、缓存清理与解压都登记为任务。
+// 每类任务有同时运行的上限（备份、恢复、迁移各一个，上传、解压最多两个），超出的排队，按优先级、同优先级先到先得开始。
+// 独占数据目录的任务（备份、恢复、迁移）彼此互斥，运行期间持有 BackendManager 的数据目录写锁，与后端重启互斥。
+// 状态变化（排队、运行及进度、完成、失败、取消）以 `job://state` 发送，list_jobs / cancel_job 供前端查询与取消。
+// 排队中的任务总能取消；运行中的任务须声明可取消，由任务自己检查 JobHandle::is_cancelled 或通过 on_cancel 中止。
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Notify, OwnedRwLockWriteGuard};
+
+use crate::backend::ServerState;
+
+// list_jobs 中保留的已结束任务数
+const MAX_FINISHED: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Backup,
+    Restore,
+    // 数据目录迁移与切换配置档
+    Migration,
+    Upload,
+    Diagnostics,
+    ClearCache,
+    Extract,
+}
+
+impl JobKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Backup => "backup",
+            JobKind::Restore => "restore",
+            JobKind::Migration => "migration",
+            JobKind::Upload => "upload",
+            JobKind::Diagnostics => "diagnostics",
+            JobKind::ClearCache => "clear_cache",
+            JobKind::Extract => "extract",
+        }
+    }
+
+    fn limit(self) -> usize {
+        match self {
+            JobKind::Upload | JobKind::Extract => 2,
+            _ => 1,
+        }
+    }
+
+    /// 需要独占数据目录：与其他独占任务及后端重启互斥
+    pub fn exclusive(self) -> bool {
+        matches!(self, JobKind::Backup | JobKind::Restore | JobKind::Migration)
+    }
+
+    // 与 other 争用同一组名额：同类任务，或同为独占任务
+    fn competes_with(self, other: JobKind) -> bool {
+        self == other || (self.exclusive() && other.exclusive())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    // 定时任务等，用户发起的任务排在前面
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    fn is_finished(self) -> bool {
+        matches!(self, JobState::Done | JobState::Failed | JobState::Cancelled)
+    }
+}
+
+/// `list_jobs` 的元素，也是 `job://state` 事件负载
+#[derive(Clone, serde::Serialize)]
+pub struct JobInfo {
+    pub id: u64,
+    pub kind: JobKind,
+    /// 可读的说明，例如目标文件名
+    pub label: String,
+    pub priority: JobPriority,
+    pub state: JobState,
+    /// 运行中是否可以取消；排队中总能取消
+    pub cancellable: bool,
+    /// 0..1，不报告进度的任务为 None
+    pub progress: Option<f64>,
+    pub error: Option<String>,
+    pub queued_at: SystemTime,
+    pub started_at: Option<SystemTime>,
+    pub finished_at: Option<SystemTime>,
+}
+
+type CancelHook = Arc<dyn Fn() + Send + Sync>;
+
+/// 要登记的任务
+pub struct JobSpec {
+    kind: JobKind,
+    label: String,
+    priority: JobPriority,
+    cancellable: bool,
+    on_cancel: Option<CancelHook>,
+}
+
+impl JobSpec {
+    pub fn new(kind: JobKind, label: impl Into<String>) -> Self {
+        Self { kind, label: label.into(), priority: JobPriority::Normal, cancellable: false, on_cancel: None }
+    }
+
+    pub fn priority(mut self, priority: JobPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 运行中可以取消，任务自己检查 JobHandle::is_cancelled
+    pub fn cancellable(mut self) -> Self {
+        self.cancellable = true;
+        self
+    }
+
+    /// 取消时调用（不持有任务表的锁），用于需要主动中止的任务；隐含 cancellable
+    pub fn on_cancel(mut self, hook: impl Fn() + Send + Sync + 'static) -> Self {
+        self.cancellable = true;
+        self.on_cancel = Some(Arc::new(hook));
+        self
+    }
+}
+
+struct Entry {
+    info: JobInfo,
+    cancelled: Arc<AtomicBool>,
+    on_cancel: Option<CancelHook>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    entries: BTreeMap<u64, Entry>,
+}
+
+impl Jobs {
+    fn insert(&mut self, spec: JobSpec) -> (u64, Arc<AtomicBool>) {
+        self.next_id += 1;
+        let id = self.next_id;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let info = JobInfo {
+            id,
+            kind: spec.kind,
+            label: spec.label,
+            priority: spec.priority,
+            state: JobState::Queued,
+            cancellable: spec.cancellable,
+            progress: None,
+            error: None,
+            queued_at: SystemTime::now(),
+            started_at: None,
+            finished_at: None,
+        };
+        self.entries.insert(id, Entry { info, cancelled: cancelled.clone(), on_cancel: spec.on_cancel });
+        (id, cancelled)
+    }
+
+    // 名额未满，且同组中没有排在前面（优先级更高或同优先级更早）的排队任务
+    fn can_start(&self, id: u64) -> bool {
+        let Some(job) = self.entries.get(&id).map(|entry| &entry.info) else {
+            return false;
+        };
+        let competing = || {
+            self.entries
+                .values()
+                .map(|entry| &entry.info)
+                .filter(|other| other.id != id && job.kind.competes_with(other.kind))
+        };
+        let running = || competing().filter(|other| other.state == JobState::Running);
+        if job.kind.exclusive() && running().any(|other| other.kind.exclusive()) {
+            return false;
+        }
+        if running().filter(|other| other.kind == job.kind).count() >= job.kind.limit() {
+            return false;
+        }
+        let rank = |info: &JobInfo| (info.priority, Reverse(info.id));
+        !competing().any(|other| other.state == JobState::Queued && rank(other) > rank(job))
+    }
+
+    // 只保留最近 MAX_FINISHED 个已结束的任务
+    fn prune(&mut self) {
+        let finished: Vec<u64> =
+            self.entries.values().filter(|entry| entry.info.state.is_finished()).map(|entry| entry.info.id).collect();
+        for id in finished.iter().take(finished.len().saturating_sub(MAX_FINISHED)) {
+            self.entries.remove(id);
+        }
+    }
+}
+
+/// 任务表，在 setup 中注册
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Mutex<Jobs>,
+    // 任务状态变化（开始、结束、取消）时唤醒排队的任务重新检查
+    changed: Notify,
+}
+
+impl JobManager {
+    // 修改任务并发送 `job://state`；任务不存在或已结束时不修改
+    fn update(&self, app: &AppHandle, id: u64, change: impl FnOnce(&mut JobInfo)) {
+        let (info, state_changed) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(entry) = jobs.entries.get_mut(&id).filter(|entry| !entry.info.state.is_finished()) else {
+                return;
+            };
+            let previous = entry.info.state;
+            change(&mut entry.info);
+            let info = entry.info.clone();
+            if info.state.is_finished() {
+                jobs.prune();
+            }
+            let state_changed = info.state != previous;
+            (info, state_changed)
+        };
+        if state_changed {
+            self.changed.notify_waiters();
+        }
+        let _ = app.emit("job://state", info);
+    }
+
+    fn finish(&self, app: &AppHandle, id: u64, state: JobState, error: Option<String>) {
+        self.update(app, id, |info| {
+            info.state = state;
+            info.error = error;
+            info.finished_at = Some(SystemTime::now());
+        });
+    }
+
+    /// 取消排队中的任务，或请求可取消的运行中任务停止
+    pub fn cancel(&self, app: &AppHandle, id: u64) -> Result<(), String> {
+        let (queued, hook) = {
+            let jobs = self.jobs.lock().unwrap();
+            let entry = jobs.entries.get(&id).ok_or_else(|| format!("Job {} does not exist", id))?;
+            match entry.info.state {
+                JobState::Queued => {}
+                JobState::Running if entry.info.cancellable => {}
+                JobState::Running => return Err(format!("Job {} cannot be cancelled while running", id)),
+                _ => return Err(format!("Job {} has already finished", id)),
+            }
+            entry.cancelled.store(true, Ordering::SeqCst);
+            (entry.info.state == JobState::Queued, entry.on_cancel.clone())
+        };
+        app_log!("Cancelling job {}", id);
+        if queued {
+            self.finish(app, id, JobState::Cancelled, None);
+        }
+        if let Some(hook) = hook {
+            hook();
+        }
+        Ok(())
+    }
+
+    /// 取消给定类型的全部排队中与运行中任务，返回是否有任务被取消
+    pub fn cancel_kinds(&self, app: &AppHandle, kinds: &[JobKind]) -> bool {
+        let ids: Vec<u64> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .entries
+            .values()
+            .filter(|entry| kinds.contains(&entry.info.kind) && !entry.info.state.is_finished())
+            .map(|entry| entry.info.id)
+            .collect();
+        ids.into_iter().filter(|id| self.cancel(app, *id).is_ok()).count() > 0
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        self.jobs.lock().unwrap().entries.values().map(|entry| entry.info.clone()).collect()
+    }
+}
+
+/// 任务中传给工作代码的句柄：报告进度、检查取消
+#[derive(Clone)]
+pub struct JobHandle {
+    app: AppHandle,
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 已完成 done / total；调用方自行控制频率（例如每 8MB 一次）
+    pub fn set_progress(&self, done: u64, total: u64) {
+        let progress = if total == 0 { 1.0 } else { (done as f64 / total as f64).clamp(0.0, 1.0) };
+        self.app.state::<JobManager>().update(&self.app, self.id, |info| info.progress = Some(progress));
+    }
+}
+
+/// 已开始的任务；独占任务期间持有数据目录写锁。未调用 finish 就丢弃（任务被中止）时记为取消
+pub struct Job {
+    handle: JobHandle,
+    _access: Option<OwnedRwLockWriteGuard<()>>,
+    finished: bool,
+}
+
+impl Job {
+    pub fn handle(&self) -> JobHandle {
+        self.handle.clone()
+    }
+
+    /// 按结果记为完成或失败；已请求取消后失败记为取消
+    pub fn finish<T, E: fmt::Display>(mut self, result: &Result<T, E>) {
+        self.finished = true;
+        let (state, error) = match result {
+            Ok(_) => (JobState::Done, None),
+            Err(_) if self.handle.is_cancelled() => (JobState::Cancelled, None),
+            Err(e) => (JobState::Failed, Some(e.to_string())),
+        };
+        self.handle.app.state::<JobManager>().finish(&self.handle.app, self.handle.id, state, error);
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.handle.app.state::<JobManager>().finish(&self.handle.app, self.handle.id, JobState::Cancelled, None);
+        }
+    }
+}
+
+fn mark_running(app: &AppHandle, id: u64) {
+    app.state::<JobManager>().update(app, id, |info| {
+        info.state = JobState::Running;
+        info.started_at = Some(SystemTime::now());
+    });
+}
+
+/// 登记任务并排队等到可以开始；排队期间被取消返回 "cancelled"
+pub async fn start(app: &AppHandle, spec: JobSpec) -> Result<Job, String> {
+    let manager = app.state::<JobManager>();
+    let kind = spec.kind;
+    let (id, cancelled, info) = {
+        let mut jobs = manager.jobs.lock().unwrap();
+        let (id, cancelled) = jobs.insert(spec);
+        (id, cancelled, jobs.entries[&id].info.clone())
+    };
+    let _ = app.emit("job://state", info);
+    // 从这里起丢弃（调用方的任务被中止）也会把任务记为取消
+    let mut job = Job { handle: JobHandle { app: app.clone(), id, cancelled }, _access: None, finished: false };
+    loop {
+        let notified = manager.changed.notified();
+        tokio::pin!(notified);
+        // 先登记唤醒再检查，检查之后发生的变化不会错过
+        notified.as_mut().enable();
+        let ready = {
+            let jobs = manager.jobs.lock().unwrap();
+            match jobs.entries.get(&id).map(|entry| entry.info.state) {
+                Some(JobState::Queued) => jobs.can_start(id),
+                _ => return Err("cancelled".to_string()),
+            }
+        };
+        if ready {
+            break;
+        }
+        notified.await;
+    }
+    mark_running(app, id);
+    if kind.exclusive() {
+        // 等待进行中的后端重启结束
+        job._access = Some(app.state::<ServerState>().backend.exclusive_data_dir().await);
+        if job.handle.is_cancelled() {
+            return Err("cancelled".to_string());
+        }
+    }
+    Ok(job)
+}
+
+/// 可以立即开始时登记并开始任务，否则不登记、直接返回错误；用于应当推迟而不是排队的定时任务
+pub fn try_start(app: &AppHandle, spec: JobSpec) -> Result<Job, String> {
+    let kind = spec.kind;
+    let busy = || {
+        if kind.exclusive() {
+            "Another backup, restore or data migration is in progress".to_string()
+        } else {
+            format!("Too many {} jobs are running", kind.as_str())
+        }
+    };
+    let access = match kind.exclusive() {
+        true => Some(app.state::<ServerState>().backend.try_exclusive_data_dir().ok_or_else(busy)?),
+        false => None,
+    };
+    let manager = app.state::<JobManager>();
+    let (id, cancelled) = {
+        let mut jobs = manager.jobs.lock().unwrap();
+        let (id, cancelled) = jobs.insert(spec);
+        if !jobs.can_start(id) {
+            jobs.entries.remove(&id);
+            return Err(busy());
+        }
+        (id, cancelled)
+    };
+    mark_running(app, id);
+    Ok(Job { handle: JobHandle { app: app.clone(), id, cancelled }, _access: access, finished: false })
+}
+
+/// 排队、执行 work 并按结果结束任务
+pub async fn run<T, F, Fut>(app: &AppHandle, spec: JobSpec, work: F) -> Result<T, String>
+where
+    F: FnOnce(JobHandle) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let job = start(app, spec).await?;
+    let result = work(job.handle()).await;
+    job.finish(&result);
+    result
+}
+
+/// 排队中、运行中与最近结束的任务，按登记顺序
+#[tauri::command]
+pub fn list_jobs(jobs: tauri::State<'_, JobManager>) -> Vec<JobInfo> {
+    jobs.list()
+}
+
+/// 取消排队中的任务，或请求运行中的任务停止；结果以 `job://state` 通知。
+/// 任务不存在、已结束或运行中不可取消时返回错误
+#[tauri::command]
+pub fn cancel_job(app: AppHandle, id: u64) -> Result<(), String> {
+    app.state::<JobManager>().cancel(&app, id)
+}
@@ -0,0 +1,197 @@
+// 旧版数据迁移：早期 DD-OS 版本的数据放在另一个名字的目录（app_data_dir 旁的 com.ddos.desktop / DD-OS），
+// Tauri 之前的原型使用 ~/.dd-os。首次启动且默认数据目录为空时检查这些位置，含 ddos_v2.db 或 .ddos.lock
+// 即视为旧数据，弹窗询问是否迁移；同时存在多处时取修改时间最新的一处。
+// 迁移在启动后端之前进行：先复制到数据目录下的临时目录（发送 `migration://progress`），全部成功后再移入，
+// 中途失败则删除已复制的内容，以空数据目录启动。无论迁移与否都写入 migration_done，不会再次询问。
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind};
+
+use crate::alerts::{self, Alert, AlertCategory, AlertPriority};
+use crate::i18n;
+
+const MARKER_FILE_NAME: &str = "migration_done";
+// app_data_dir 旁边的旧目录名
+const LEGACY_DIR_NAMES: [&str; 2] = ["com.ddos.desktop", "DD-OS"];
+// 用户主目录下的原型数据目录
+const PROTOTYPE_DIR_NAME: &str = ".dd-os";
+// 任一存在即视为有可迁移的数据
+const RECOGNIZED_FILES: [&str; 2] = ["ddos_v2.db", crate::data_lock::LOCK_FILE_NAME];
+// 不复制的顶层条目：旧日志，以及旧进程留下的锁和 PID 文件
+const SKIPPED: [&str; 3] = [crate::logs::LOGS_DIR_NAME, crate::data_lock::LOCK_FILE_NAME, crate::pid_file::PID_FILE_NAME];
+const STAGING_DIR_NAME: &str = ".migrating";
+// 除数据本身外至少保留的剩余空间
+const FREE_SPACE_MARGIN: u64 = 100 * 1024 * 1024;
+
+#[derive(serde::Serialize)]
+struct Marker {
+    source: PathBuf,
+    migrated: bool,
+    at: String,
+}
+
+fn candidates(app: &AppHandle, default_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = LEGACY_DIR_NAMES.iter().map(|name| default_dir.with_file_name(name)).collect();
+    if let Ok(home) = app.path().home_dir() {
+        dirs.push(home.join(PROTOTYPE_DIR_NAME));
+    }
+    dirs
+}
+
+// 可识别文件中最新的修改时间；没有可识别文件时返回 None
+fn last_modified(dir: &Path) -> Option<SystemTime> {
+    RECOGNIZED_FILES
+        .iter()
+        .filter_map(|name| std::fs::metadata(dir.join(name)).ok()?.modified().ok())
+        .max()
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    match std::fs::read_dir(path) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(e) => e.kind() == std::io::ErrorKind::NotFound,
+    }
+}
+
+/// 在 setup 中、创建数据目录之前调用：默认数据目录为空（或不存在）且找到旧数据时返回其位置。
+/// 使用 --data-dir、其他档案或已改过数据目录位置时不检查
+pub fn detect(app: &AppHandle) -> Option<PathBuf> {
+    if app.state::<crate::cli::CliArgs>().data_dir.is_some() || crate::profiles::active_data_dir(app).is_some() {
+        return None;
+    }
+    let default_dir = app.path().app_data_dir().ok()?;
+    if crate::data_dir::read_pointer(&default_dir).is_some() || !is_empty_dir(&default_dir) {
+        return None;
+    }
+    let mut found: Vec<(SystemTime, PathBuf)> = candidates(app, &default_dir)
+        .into_iter()
+        .filter_map(|dir| Some((last_modified(&dir)?, dir)))
+        .collect();
+    found.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    let mut found = found.into_iter().map(|(_, dir)| dir);
+    let newest = found.next()?;
+    app_log!("Found data from a previous version in {:?}", newest);
+    for other in found {
+        app_log!("Also found older legacy data in {:?}, it will not be migrated", other);
+    }
+    Some(newest)
+}
+
+fn write_marker(data_dir: &Path, source: &Path, migrated: bool) {
+    let marker = Marker { source: source.to_path_buf(), migrated, at: chrono::Local::now().to_rfc3339() };
+    let path = data_dir.join(MARKER_FILE_NAME);
+    let result = serde_json::to_string_pretty(&marker)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        app_error!("Failed to write {:?}: {}", path, e);
+    }
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+// 把临时目录中的条目移入数据目录，覆盖启动时已创建的同名文件（如默认的 config.json）；
+// 失败时撤回已移入的条目并恢复默认配置文件
+fn move_into(staging: &Path, data_dir: &Path) -> Result<(), String> {
+    let entries = std::fs::read_dir(staging).map_err(|e| format!("Failed to read {:?}: {}", staging, e))?;
+    let mut moved: Vec<PathBuf> = Vec::new();
+    for entry in entries.flatten() {
+        let target = data_dir.join(entry.file_name());
+        let result = if target.exists() { remove_entry(&target) } else { Ok(()) }
+            .and_then(|()| std::fs::rename(entry.path(), &target));
+        if let Err(e) = result {
+            for path in &moved {
+                let _ = remove_entry(path);
+            }
+            let _ = crate::config::save(&crate::config::config_path(data_dir), &crate::config::AppConfig::default());
+            return Err(format!("Failed to move {:?} into {:?}: {}", entry.path(), data_dir, e));
+        }
+        moved.push(target);
+    }
+    Ok(())
+}
+
+fn copy(app: &AppHandle, legacy_dir: &Path, data_dir: &Path) -> Result<(), String> {
+    let total_bytes = crate::storage::scan(legacy_dir).total_bytes;
+    if let Some(available) = crate::storage::available_space(data_dir) {
+        if available < total_bytes + FREE_SPACE_MARGIN {
+            return Err(format!(
+                "Not enough free space: {} MB needed, {} MB available",
+                (total_bytes + FREE_SPACE_MARGIN) / 1024 / 1024,
+                available / 1024 / 1024
+            ));
+        }
+    }
+    let staging = data_dir.join(STAGING_DIR_NAME);
+    let _ = std::fs::remove_dir_all(&staging);
+    let progress = |bytes_processed| {
+        let _ = app.emit(
+            "migration://progress",
+            crate::backup::ProgressPayload { operation: "legacy_migration", bytes_processed, total_bytes },
+        );
+    };
+    let result = crate::data_dir::copy_contents(legacy_dir, &staging, &SKIPPED, progress)
+        .and_then(|()| move_into(&staging, data_dir));
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn migrate(app: &AppHandle, legacy_dir: &Path) {
+    let data_dir = match crate::backend_data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            app_error!("Skipping legacy data migration: {}", e);
+            return;
+        }
+    };
+    let alert = Alert::new(AlertCategory::Data, i18n::tf("dialog.legacy_migration", &[("path", &legacy_dir.display())]))
+        .priority(AlertPriority::High)
+        .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("button.migrate"), i18n::t("button.start_fresh")));
+    let confirmed = alerts::blocking_ask(app, alert);
+    if !confirmed {
+        app_log!("Legacy data migration declined, starting fresh");
+        write_marker(&data_dir, legacy_dir, false);
+        return;
+    }
+
+    let _ = app.emit("splash://status", i18n::t("splash.migrating"));
+    app_log!("Migrating legacy data {:?} -> {:?}", legacy_dir, data_dir);
+    match copy(app, legacy_dir, &data_dir) {
+        Ok(()) => {
+            app_log!("Legacy data migrated");
+            write_marker(&data_dir, legacy_dir, true);
+            // 旧数据中的 config.json 替换了启动时创建的默认配置
+            if let Err(e) = app.state::<crate::config::ConfigState>().reload() {
+                app_error!("{}, keeping default settings", e);
+            }
+        }
+        Err(e) => {
+            app_error!("Legacy data migration failed, starting fresh: {}", e);
+            write_marker(&data_dir, legacy_dir, false);
+            let args: [(&str, &dyn std::fmt::Display); 2] = [("error", &e), ("path", &legacy_dir.display())];
+            let alert = Alert::new(AlertCategory::Data, i18n::tf("dialog.legacy_migration.failed", &args))
+                .kind(MessageDialogKind::Error)
+                .priority(AlertPriority::High);
+            alerts::blocking_ask(app, alert);
+        }
+    }
+}
+
+/// 启动后端之前调用：询问并迁移 detect 找到的旧数据，完成（或放弃）后返回。
+/// 无界面模式下无法询问，跳过且不写入标记
+pub async fn run(app: &AppHandle, legacy_dir: PathBuf) {
+    if crate::headless::enabled(app) {
+        app_log!("Running headless, not migrating legacy data in {:?}", legacy_dir);
+        return;
+    }
+    let handle = app.clone();
+    let _ = tauri::async_runtime::spawn_blocking(move || migrate(&handle, &legacy_dir)).await;
+}
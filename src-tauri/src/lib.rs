@@ -0,0 +1,626 @@
+// DunCrew Tauri Application
+// 应用本体：插件、命令与窗口事件的装配 + OpenClaw 扩展自动部署；Python 后端 Sidecar 的管理见 backend 模块。
+// 可执行文件（main.rs）只调用 run；拆成库是为了 tests/ 下的集成测试能直接使用 backend 模块
+
+#[macro_use]
+mod logs;
+
+mod cli;
+mod alerts;
+mod app_events;
+mod app_menu;
+mod app_update;
+mod archives;
+mod assets;
+mod auto_backup;
+mod autostart;
+pub mod backend;
+mod backend_client;
+mod backend_update;
+mod backup;
+mod cache;
+mod clipboard;
+mod config;
+mod crash_report;
+mod data_dir;
+mod data_lock;
+mod deep_link;
+mod devtools;
+mod diagnostics;
+mod doctor;
+mod file_picker;
+mod folders;
+mod fs_watch;
+mod headless;
+mod health;
+mod i18n;
+mod imports;
+mod jobs;
+mod legacy_migration;
+mod lifecycle_history;
+mod locale;
+mod log_viewer;
+mod metrics;
+mod network;
+mod notify;
+mod open_external;
+mod open_file;
+mod pdf_export;
+mod pid_file;
+mod power;
+mod process_guard;
+mod profiles;
+mod progress;
+mod proxy;
+mod screenshot;
+mod secondary_windows;
+mod secrets;
+mod session;
+mod shortcut;
+mod safe_mode;
+mod sidecar_integrity;
+mod splash;
+mod storage;
+mod streams;
+mod system_info;
+mod telemetry;
+mod theme;
+mod tray;
+mod uploads;
+mod window_controls;
+mod window_state;
+mod zoom;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use backend::{PortPolicy, ServerState};
+use secondary_windows::CloseAction;
+
+// `app://second-instance` 事件负载：第二次启动时的命令行参数（不含程序路径）与工作目录
+#[derive(Clone, serde::Serialize)]
+struct SecondInstancePayload {
+    args: Vec<String>,
+    cwd: String,
+}
+
+// ============================================
+// OpenClaw Extension 自动部署
+// ============================================
+
+/// 获取 OpenClaw extensions 目标目录: ~/.openclaw/extensions/ddos/
+fn get_openclaw_extension_target() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    Some(home.join(".openclaw").join("extensions").join("duncrew"))
+}
+
+/// 读取 package.json 中的 version 字段
+fn read_package_version(dir: &Path) -> Option<String> {
+    let pkg_path = dir.join("package.json");
+    let content = std::fs::read_to_string(&pkg_path).ok()?;
+    let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
+    parsed.get("version")?.as_str().map(|s| s.to_string())
+}
+
+/// 递归复制目录内容
+fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dst)
+        .map_err(|e| format!("Failed to create dir {:?}: {}", dst, e))?;
+
+    let entries = std::fs::read_dir(src)
+        .map_err(|e| format!("Failed to read dir {:?}: {}", src, e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let src_path = entry.path();
+        let file_name = entry.file_name();
+        let dst_path = dst.join(&file_name);
+
+        if src_path.is_dir() {
+            copy_dir_recursive(&src_path, &dst_path)?;
+        } else {
+            std::fs::copy(&src_path, &dst_path)
+                .map_err(|e| format!("Failed to copy {:?} -> {:?}: {}", src_path, dst_path, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 部署捆绑的 OpenClaw 扩展到 ~/.openclaw/extensions/ddos/
+/// 如果目标不存在或版本不同则复制，否则跳过。
+fn install_openclaw_extension(app: &AppHandle) {
+    // 1. 定位捆绑的扩展资源
+    let resource_dir = match app.path().resource_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            app_error!("Cannot resolve resource dir: {}", e);
+            return;
+        }
+    };
+    let bundled_ext = resource_dir.join("openclaw-extension");
+    if !bundled_ext.exists() {
+        app_log!("No bundled openclaw-extension found, skipping auto-deploy");
+        return;
+    }
+
+    // 2. 确定目标路径
+    let target_dir = match get_openclaw_extension_target() {
+        Some(dir) => dir,
+        None => {
+            app_error!("Cannot determine home directory, skipping extension deploy");
+            return;
+        }
+    };
+
+    // 3. 版本比较 —— 相同则跳过
+    let bundled_version = read_package_version(&bundled_ext);
+    let installed_version = read_package_version(&target_dir);
+
+    if let Some(v) = &bundled_version {
+        if installed_version.as_ref() == Some(v) {
+            app_log!("OpenClaw extension v{} already installed, skipping", v);
+            return;
+        }
+    }
+
+    // 4. 执行复制
+    app_log!(
+        "Deploying OpenClaw extension: {:?} -> {:?}",
+        bundled_ext, target_dir
+    );
+    match copy_dir_recursive(&bundled_ext, &target_dir) {
+        Ok(()) => {
+            app_log!("OpenClaw extension deployed successfully");
+            if let Some(v) = bundled_version {
+                app_log!("Installed version: {}", v);
+            }
+        }
+        Err(e) => {
+            app_error!("Failed to deploy extension: {}", e);
+        }
+    }
+}
+
+// 配置的数据目录（不检查是否存在）：命令行 --data-dir 优先，其次是当前配置档案，最后是默认档案的目录
+fn configured_data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(match app.state::<cli::CliArgs>().data_dir.clone() {
+        Some(dir) => dir,
+        None => match profiles::active_data_dir(app) {
+            Some(dir) => dir,
+            None => default_data_dir(app)?,
+        },
+    })
+}
+
+// 获取并确保后端数据目录存在；本次运行临时改用默认目录时返回默认目录
+fn backend_data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let data_dir = match data_dir::temporary_dir() {
+        Some(dir) => dir,
+        None => configured_data_dir(app)?,
+    };
+    // 自定义位置不存在时多半是所在驱动器未连接，不自动创建
+    if !data_dir.exists() && data_dir::is_custom_location(app, &data_dir) {
+        return Err(format!("{:?} does not exist; the drive it is on may be disconnected", data_dir));
+    }
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create {:?}: {}", data_dir, e))?;
+    Ok(data_dir)
+}
+
+// 默认档案的数据目录：set_data_dir 写入的指针文件优先，否则为 app_data_dir
+fn default_data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let default_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    Ok(data_dir::read_pointer(&default_dir).unwrap_or(default_dir))
+}
+
+// 当前生效的配置：config.json（未注册时为默认值）叠加命令行参数
+fn app_config(app: &AppHandle) -> config::AppConfig {
+    let mut config = app
+        .try_state::<config::ConfigState>()
+        .map(|state| state.get())
+        .unwrap_or_default();
+    app.state::<cli::CliArgs>().apply(&mut config);
+    config
+}
+
+/// 读取内存中最近的后端日志。`since` 为上次拿到的最大 seq，用于增量拉取。
+#[tauri::command]
+fn get_backend_logs(
+    logs: tauri::State<'_, logs::LogBuffer>,
+    limit: usize,
+    since: Option<u64>,
+) -> Vec<logs::LogEntry> {
+    logs.recent(limit, since)
+}
+
+#[tauri::command]
+fn clear_backend_logs(logs: tauri::State<'_, logs::LogBuffer>) {
+    logs.clear();
+}
+
+/// 打开 / 关闭 `backend://log` 实时推送（payload 为 LogEntry 数组），
+/// 日志面板关闭时应关闭以免白白占用 IPC。
+#[tauri::command]
+fn set_backend_log_streaming(logs: tauri::State<'_, logs::LogBuffer>, enabled: bool) {
+    logs.set_streaming(enabled);
+}
+
+/// 应用内“退出”：优雅停止后端（`force` 为 true 时直接结束进程，不等待），保存窗口状态、
+/// 刷新日志后退出应用。命令在开始停止前就返回，前端可以显示“正在退出”遮罩；
+/// 已在退出流程中时不会重复执行，返回 false
+#[tauri::command]
+fn shutdown_app(app: AppHandle, force: bool) -> bool {
+    begin_exit(&app, force, 0)
+}
+
+// 所有退出路径（应用内退出、关闭主窗口、ExitRequested）共用：保存窗口状态、停止后端、
+// 记下会话正常结束并刷新日志后以 `code` 退出；窗口保持（隐藏的）存活直到后端进程确实退出
+fn begin_exit(app: &AppHandle, force: bool, code: i32) -> bool {
+    let state = app.state::<ServerState>();
+    if state.exiting.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    app_log!("Shutting down (force: {})", force);
+    window_state::save_main(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<ServerState>();
+        if force {
+            state.backend.kill(&app).await;
+        } else {
+            state.backend.shutdown(&app, backend::shutdown_timeout(&app)).await;
+        }
+        session::mark_clean();
+        logs::flush_app_log();
+        state.exit_ready.store(true, Ordering::SeqCst);
+        app.exit(code);
+    });
+    true
+}
+
+pub fn run() {
+    let cli_args = cli::CliArgs::from_env();
+    let headless = cli_args.headless;
+    // 发布版本没有控制台窗口，只写 app.log；无界面模式以控制台为主要输出
+    logs::init_tracing(cfg!(debug_assertions) || headless);
+    let mut builder = tauri::Builder::default()
+        .manage(cli_args)
+        // 必须最先注册：第二个实例在这里就把参数转交给已运行的实例并退出，不会再启动后端。
+        // 锁由系统对象承担（Windows 命名互斥体 / Linux DBus 名称 / macOS socket），
+        // 持有者崩溃后会被系统释放或判定失效，不会残留导致无法启动
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            app_log!("Second instance launched, focusing existing window");
+            tray::show_main_window(app);
+            let args: Vec<String> = argv.into_iter().skip(1).collect();
+            open_file::open_paths(app, open_file::paths_from_args(&args, Path::new(&cwd)));
+            let _ = app.emit("app://second-instance", SecondInstancePayload { args, cwd });
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(autostart::plugin())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_updater::Builder::new().build());
+    builder = assets::register(builder);
+    // 全局快捷键插件初始化时需要连接显示服务，无界面模式不注册
+    if !headless {
+        builder = builder.plugin(shortcut::plugin());
+    }
+    let app = builder
+        .invoke_handler(tauri::generate_handler![
+            backend::restart_backend,
+            backend::send_backend_command,
+            backend::set_backend_log_level,
+            backend::set_backend_priority,
+            backend::set_backend_args,
+            lifecycle_history::get_lifecycle_events,
+            backend::get_backend_status,
+            backend::get_backend_state,
+            backend::wait_for_backend,
+            backend::get_backend_port,
+            backend::get_backend_token,
+            backend::get_tls_info,
+            backend::get_startup_metrics,
+            get_backend_logs,
+            clear_backend_logs,
+            set_backend_log_streaming,
+            log_viewer::tail_log_file,
+            log_viewer::follow_log_file,
+            log_viewer::unfollow_log_file,
+            diagnostics::export_diagnostics,
+            devtools::toggle_devtools,
+            devtools::get_debug_info,
+            doctor::run_backend_doctor,
+            config::get_config,
+            config::set_config,
+            config::get_effective_backend_env,
+            autostart::get_autostart,
+            autostart::set_autostart,
+            splash::wait_for_backend_ready,
+            splash::dismiss_splash,
+            app_events::frontend_ready,
+            folders::open_data_dir,
+            folders::open_logs_dir,
+            folders::get_app_paths,
+            cache::clear_cache,
+            storage::get_storage_usage,
+            backup::create_backup,
+            backup::cancel_backup,
+            backup::restore_backup,
+            data_dir::set_data_dir,
+            backend_update::check_backend_update,
+            backend_update::apply_backend_update,
+            metrics::get_backend_metrics,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
+            secondary_windows::open_secondary_window,
+            proxy::backend_request,
+            streams::start_backend_stream,
+            streams::cancel_backend_stream,
+            uploads::upload_file_to_backend,
+            uploads::cancel_upload,
+            jobs::list_jobs,
+            jobs::cancel_job,
+            shortcut::set_global_shortcut,
+            shutdown_app,
+            open_external::open_external,
+            file_picker::pick_files,
+            file_picker::pick_save_path,
+            i18n::set_locale,
+            window_controls::set_decorations,
+            window_controls::window_minimize,
+            window_controls::window_toggle_maximize,
+            window_controls::window_start_dragging,
+            window_controls::window_close,
+            zoom::set_zoom,
+            zoom::get_zoom,
+            zoom::reset_zoom,
+            theme::get_system_theme,
+            secrets::set_secret,
+            secrets::get_secret,
+            secrets::delete_secret,
+            secrets::import_legacy_secrets,
+            telemetry::get_telemetry_status,
+            telemetry::set_telemetry_enabled,
+            app_update::check_for_updates,
+            app_update::install_update,
+            auto_backup::get_backup_history,
+            pdf_export::export_to_pdf,
+            pdf_export::pdf_render_complete,
+            screenshot::capture_window_screenshot,
+            progress::set_progress,
+            system_info::get_system_info,
+            network::get_network_status,
+            clipboard::clipboard_write_text,
+            clipboard::clipboard_write_html,
+            clipboard::clipboard_write_image,
+            clipboard::clipboard_read_text,
+            imports::import_file,
+            archives::extract_archive,
+            fs_watch::set_watched_paths,
+            crash_report::list_crash_reports,
+            crash_report::read_crash_report,
+            alerts::get_pending_alerts,
+            alerts::dismiss_alert,
+            alerts::mute_alerts,
+            session::get_last_session_report
+        ])
+        .setup(move |app| {
+            // 0. 选择配置档案，初始化其 logs/app.log
+            profiles::init(app.handle());
+            // 必须在创建数据目录之前检查，否则无法判断是否为首次启动
+            let legacy_data = legacy_migration::detect(app.handle());
+            let data_dir = backend_data_dir(app.handle());
+            if let Ok(dir) = &data_dir {
+                logs::init_app_log(&dir.join(logs::LOGS_DIR_NAME));
+                session::init(dir);
+            }
+
+            // 读取 config.json；格式错误时保留用户文件，本次使用默认配置
+            let (config_path, loaded_config) = match &data_dir {
+                Ok(dir) => {
+                    let path = config::config_path(dir);
+                    let loaded = config::load(&path).unwrap_or_else(|e| {
+                        app_error!("{}, falling back to defaults", e);
+                        config::AppConfig::default()
+                    });
+                    (path, loaded)
+                }
+                Err(_) => (PathBuf::from(config::CONFIG_FILE_NAME), config::AppConfig::default()),
+            };
+            app.manage(config::ConfigState::new(config_path, loaded_config));
+            let effective_config = app_config(app.handle());
+            logs::apply_filter(&effective_config.app_log_filter);
+            i18n::init(app.handle());
+            telemetry::init(app.handle());
+            system_info::warm_up();
+
+            // 深度链接：冷启动链接暂存到前端与后端就绪后发送
+            app.manage(app_events::AppEventQueue::default());
+            app.manage(alerts::AlertCoordinator::default());
+            session::notify_previous(app.handle());
+            config::notify_recovered(app.handle());
+            deep_link::init(app.handle());
+            safe_mode::init(app.handle(), app.state::<cli::CliArgs>().safe_mode);
+            if let Ok(cwd) = std::env::current_dir() {
+                let paths = open_file::paths_from_args(&app.state::<cli::CliArgs>().positional, &cwd);
+                open_file::open_paths(app.handle(), paths);
+            }
+
+            // 1. 自动部署 OpenClaw 扩展
+            install_openclaw_extension(app.handle());
+
+            // 2. 清理上次崩溃遗留的后端进程，再启动后端服务器
+            if let Ok(dir) = &data_dir {
+                pid_file::kill_stale(&dir.join(pid_file::PID_FILE_NAME));
+            }
+            app.manage(ServerState::default());
+            app.manage(jobs::JobManager::default());
+            app.manage(logs::LogBuffer::default());
+            logs::attach_log_buffer(app.handle());
+            app.manage(metrics::MetricsState::default());
+            app.manage(file_picker::PickedPaths::default());
+            logs::spawn_log_streamer(app.handle().clone());
+            lifecycle_history::init(app.handle());
+            // --no-backend：不启动 Sidecar，连接本机配置端口上自行运行的后端
+            let external_url = effective_config.external_backend_url().or_else(|| {
+                app.state::<cli::CliArgs>()
+                    .no_backend
+                    .then(|| format!("http://127.0.0.1:{}", effective_config.port))
+            });
+            if let Some(url) = external_url {
+                backend::connect_external_backend(app.handle(), url);
+            } else {
+                // 校验 Sidecar 需要读取整个二进制，放到后台，启动画面显示进度
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(legacy_dir) = legacy_data {
+                        legacy_migration::run(&app_handle, legacy_dir).await;
+                    }
+                    // 迁移可能带来旧的 config.json，端口在迁移之后读取
+                    let port = app_config(&app_handle).port;
+                    if sidecar_integrity::required(&app_handle) {
+                        let _ = app_handle.emit("splash://status", i18n::t("splash.verifying"));
+                        let handle = app_handle.clone();
+                        let _ = tauri::async_runtime::spawn_blocking(move || sidecar_integrity::verify(&handle)).await;
+                        let _ = app_handle.emit("splash://status", i18n::t("splash.starting"));
+                    }
+                    backend::start_initial_backend(&app_handle, PortPolicy::Exact(port)).await;
+                });
+            }
+
+            // 3. 主窗口（tauri.conf.json 中 create 为 false，由这里创建）与托盘图标；
+            // 无界面模式不创建窗口、托盘与快捷键，收到终止信号时退出
+            if headless {
+                app_log!("Running headless; press Ctrl+C or send SIGTERM to stop");
+                headless::exit_on_signal(app.handle());
+            } else {
+                if let Some(window_config) = app.config().app.windows.first() {
+                    let mut window_config = window_config.clone();
+                    window_config.decorations = !effective_config.custom_titlebar;
+                    tauri::WebviewWindowBuilder::from_config(app.handle(), &window_config)?.build()?;
+                }
+                if let Err(e) = tray::init(app.handle()) {
+                    app_error!("Failed to create tray icon: {}", e);
+                }
+                if let Err(e) = app_menu::init(app.handle()) {
+                    app_error!("Failed to create app menu: {}", e);
+                }
+                shortcut::init(app.handle());
+                theme::init(app.handle());
+                app_update::check_on_startup(app.handle());
+                // 主窗口初始不可见：先恢复上次的位置和大小，后端就绪后由启动画面切换显示；
+                // 开机自启的实例直接留在托盘
+                if let Some(window) = app.get_webview_window("main") {
+                    window_state::restore(&window);
+                    zoom::restore(&window);
+                    if autostart::start_minimized(app.handle()) {
+                        app_log!("Starting minimized to the tray");
+                    } else {
+                        if let Err(e) = splash::open(app.handle()) {
+                            app_error!("Failed to open splash window: {}", e);
+                            let _ = window.show();
+                        }
+                    }
+                }
+            }
+
+            // 4. 启动健康检查
+            health::spawn(app.handle().clone(), health::HealthCheckConfig::default());
+            metrics::spawn(app.handle().clone());
+            auto_backup::spawn(app.handle().clone());
+            network::spawn(app.handle().clone());
+            locale::spawn(app.handle().clone());
+            power::init(app.handle());
+            Ok(())
+        })
+        .on_page_load(|webview, payload| {
+            // 主窗口刷新后旧页面的监听已失效，事件重新暂存到新页面调用 frontend_ready
+            if webview.label() == "main" && payload.event() == tauri::webview::PageLoadEvent::Started {
+                app_events::frontend_unloaded(webview.app_handle());
+            }
+        })
+        .on_window_event(|window, event| {
+            window_controls::track(window, event);
+            theme::track(window, event);
+            window_state::track(window, event);
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let app = window.app_handle();
+                // 开启 close_to_tray 时关闭主窗口只是隐藏，后端继续运行；托盘不可用时仍按关闭处理。
+                // 以托盘方式启动的这次运行同样如此，否则从托盘打开再关闭窗口会退出应用
+                let hide = (app_config(app).close_to_tray || autostart::start_minimized(app)) && tray::is_available(app);
+                let secondary_open = secondary_windows::secondary_windows(app).len();
+                match secondary_windows::close_action(window.label(), hide, secondary_open) {
+                    // 启动画面、辅助窗口等其他窗口的关闭与后端无关
+                    CloseAction::Close => {}
+                    CloseAction::Hide => {
+                        api.prevent_close();
+                        let _ = window.hide();
+                    }
+                    // 还有辅助窗口时先确认，确认后统一销毁，由 Destroyed 停止后端
+                    CloseAction::Confirm => {
+                        api.prevent_close();
+                        secondary_windows::confirm_close_main(app, window, secondary_open);
+                    }
+                    // 先隐藏窗口等后端停止（可能需要等它写完数据再强制结束），进程确实退出后再退出应用，
+                    // 避免用户以为卡死而从任务管理器结束本应用
+                    CloseAction::Exit => {
+                        api.prevent_close();
+                        let _ = window.hide();
+                        begin_exit(app, false, 0);
+                    }
+                }
+                return;
+            }
+            if window.label() != "main" {
+                return;
+            }
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                imports::handle_drop(window.app_handle(), paths.clone());
+            }
+            // 窗口被销毁（例如 WebView 崩溃，没有 CloseRequested）时停止后端，
+            // 停止请求交给后台任务排队执行，不卡住事件循环
+            if let tauri::WindowEvent::Destroyed = event {
+                let app = window.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(state) = app.try_state::<ServerState>() {
+                        state.backend.shutdown(&app, backend::shutdown_timeout(&app)).await;
+                    }
+                });
+            }
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+
+    // Cmd+Q、应用菜单退出、托盘退出等路径不会触发 CloseRequested，
+    // 在 ExitRequested 中先阻止退出，等后端停止后再真正退出
+    app.run(|app_handle, event| match event {
+        tauri::RunEvent::ExitRequested { code, api, .. } => {
+            let Some(state) = app_handle.try_state::<ServerState>() else {
+                return;
+            };
+            if state.exit_ready.load(Ordering::SeqCst) {
+                return;
+            }
+            api.prevent_exit();
+            begin_exit(app_handle, false, code.unwrap_or(0));
+        }
+        tauri::RunEvent::Exit => {
+            if let Some(state) = app_handle.try_state::<ServerState>() {
+                state.backend.kill_now(app_handle);
+            }
+        }
+        // macOS 通过 Apple Event 而不是命令行参数传递要打开的文件
+        #[cfg(target_os = "macos")]
+        tauri::RunEvent::Opened { urls } => {
+            let paths = urls.into_iter().filter_map(|url| url.to_file_path().ok());
+            open_file::open_paths(app_handle, paths);
+        }
+        _ => {}
+    });
+}
@@ -0,0 +1,139 @@
+// 后端生命周期历史：把状态转移及启动、停止、崩溃、重启、无响应、更新等事件追加写入数据目录的 lifecycle.jsonl，
+// 用于回答“这周后端重启了几次、为什么”。与托盘、菜单一样监听 backend:// 事件，回调只把记录送入通道，
+// 由单独的写入线程串行落盘，不会阻塞进程管理。文件超过 1 MB 时滚动为 lifecycle.1.jsonl（只保留一个归档），
+// get_lifecycle_events 把两者视为一个序列从新到旧分页读取。
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use tauri::{AppHandle, Listener};
+
+const FILE_NAME: &str = "lifecycle.jsonl";
+const ROTATED_FILE_NAME: &str = "lifecycle.1.jsonl";
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+/// 诊断包中附带的最近事件数
+pub const DIAGNOSTICS_EVENTS: usize = 50;
+
+// 记录的事件及写入文件时的名称；backend://exited 按 intentional 区分为 stopped / crashed
+const EVENTS: [(&str, &str); 10] = [
+    ("backend://state", "state"),
+    ("backend://lifecycle", "lifecycle"),
+    ("backend://ready", "ready"),
+    ("backend://slow-start", "slow_start"),
+    ("backend://exited", "exited"),
+    ("backend://restarted", "restarted"),
+    ("backend://hung", "hung"),
+    ("backend://crash-loop", "crash_loop"),
+    // 优雅停止超时，已强制结束
+    ("backend://shutdown-timeout", "shutdown_timeout"),
+    ("backend-update://applied", "update_applied"),
+];
+
+// 事件负载中不落盘的字段
+const OMITTED_FIELDS: [&str; 1] = ["token"];
+
+// 上一条记录的时间戳；同一毫秒内的事件顺延 1 毫秒，保证按 ts 分页时不会漏掉或重复
+static LAST_TS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(serde::Serialize)]
+struct Record {
+    // Unix 毫秒时间戳，也是分页游标
+    ts: u64,
+    event: &'static str,
+    data: serde_json::Value,
+}
+
+fn record(name: &'static str, payload: &str) -> Record {
+    let mut data = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+    if let Some(object) = data.as_object_mut() {
+        for field in OMITTED_FIELDS {
+            object.remove(field);
+        }
+    }
+    let event = match name {
+        "exited" if data["intentional"].as_bool() == Some(true) => "stopped",
+        "exited" => "crashed",
+        name => name,
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    let previous = LAST_TS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(now.max(last + 1)))
+        .unwrap_or(now);
+    Record { ts: now.max(previous + 1), event, data }
+}
+
+fn append(data_dir: &Path, record: &Record) -> Result<(), String> {
+    let path = data_dir.join(FILE_NAME);
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_FILE_BYTES) {
+        std::fs::rename(&path, data_dir.join(ROTATED_FILE_NAME))
+            .map_err(|e| format!("Failed to rotate {:?}: {}", path, e))?;
+    }
+    let mut line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// 在 setup 中、启动后端之前调用：注册事件监听并启动写入线程
+pub fn init(app: &AppHandle) {
+    let (tx, rx) = mpsc::channel::<Record>();
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        for record in rx {
+            // 每次重新解析数据目录：切换配置档案后写到新目录
+            let result = crate::backend_data_dir(&handle).and_then(|dir| append(&dir, &record));
+            if let Err(e) = result {
+                app_error!("Failed to record lifecycle event: {}", e);
+            }
+        }
+    });
+    for (event, name) in EVENTS {
+        let tx: Sender<Record> = tx.clone();
+        app.listen(event, move |e| {
+            let _ = tx.send(record(name, e.payload()));
+        });
+    }
+}
+
+/// 从新到旧读取 ts 早于 before 的最多 limit 条事件；文件不存在时返回空列表
+pub fn read(data_dir: &Path, limit: usize, before: Option<u64>) -> Vec<serde_json::Value> {
+    let mut events: Vec<serde_json::Value> = [ROTATED_FILE_NAME, FILE_NAME]
+        .iter()
+        .filter_map(|name| std::fs::read_to_string(data_dir.join(name)).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|event| before.is_none_or(|before| event["ts"].as_u64().is_some_and(|ts| ts < before)))
+        .collect();
+    let start = events.len().saturating_sub(limit);
+    let mut page = events.split_off(start);
+    page.reverse();
+    page
+}
+
+/// 分页读取生命周期历史，最新的在前。limit 默认 100、最多 1000；
+/// 下一页把 before 设为本页最后一条的 ts
+#[tauri::command]
+pub async fn get_lifecycle_events(
+    app: AppHandle,
+    limit: Option<usize>,
+    before: Option<u64>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let data_dir = crate::backend_data_dir(&app)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || read(&data_dir, limit, before))
+        .await
+        .map_err(|e| e.to_string())
+}
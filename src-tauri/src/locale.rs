@@ -0,0 +1,106 @@
+// 系统区域设置：冻结的 Python 后端拿不到有用的 locale，生成的报告日期总是 UTC、英文月份。
+// 每次启动后端时重新检测系统语言（sys_locale）、首选语言列表与 IANA 时区（iana-time-zone），
+// 以 --locale / --languages / --timezone 传给 Sidecar，Unix 上另设 TZ，旅行途中换了时区重启后端即可生效。
+// 运行期间每隔 CHECK_INTERVAL 重新检测，系统设置变化（含夏令时切换导致的偏移变化）时发送 `app://locale-changed`；
+// config.json 的 locale 为空（界面跟随系统语言）时同时切换界面语言。检测结果随 get_system_info 返回。
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// 最近一次检测到的设置，用于发现变化
+static LAST: Mutex<Option<LocaleInfo>> = Mutex::new(None);
+
+/// 系统区域设置，也是 `app://locale-changed` 的负载；取不到的项为 None
+#[derive(Clone, PartialEq, serde::Serialize)]
+pub struct LocaleInfo {
+    /// BCP 47 语言标记，例如 "zh-CN"
+    pub locale: Option<String>,
+    /// 用户的首选语言，按优先级排列，第一项即 locale
+    pub languages: Vec<String>,
+    /// IANA 时区，例如 "Asia/Shanghai"
+    pub timezone: Option<String>,
+    /// 当前与 UTC 的偏移（分钟），含夏令时
+    pub utc_offset_minutes: i32,
+}
+
+pub fn detect() -> LocaleInfo {
+    let languages: Vec<String> = sys_locale::get_locales().filter(|lang| !lang.trim().is_empty()).collect();
+    LocaleInfo {
+        locale: languages.first().cloned(),
+        languages,
+        timezone: iana_time_zone::get_timezone().ok().filter(|tz| !tz.is_empty()),
+        utc_offset_minutes: chrono::Local::now().offset().local_minus_utc() / 60,
+    }
+}
+
+// 检测并记录；与上次不同时返回 true（第一次检测不算变化）
+fn refresh() -> (LocaleInfo, bool) {
+    let info = detect();
+    let previous = LAST.lock().unwrap().replace(info.clone());
+    let changed = previous.is_some_and(|previous| previous != info);
+    (info, changed)
+}
+
+fn describe(info: &LocaleInfo) -> String {
+    format!(
+        "{}, timezone {}",
+        info.locale.as_deref().unwrap_or("unknown"),
+        info.timezone.as_deref().unwrap_or("unknown")
+    )
+}
+
+fn changed(app: &AppHandle, info: &LocaleInfo) {
+    app_log!("System locale changed: {}", describe(info));
+    if crate::app_config(app).locale.trim().is_empty() {
+        crate::i18n::apply(app);
+    }
+    let _ = app.emit("app://locale-changed", info.clone());
+}
+
+/// 启动后端时调用：重新检测，结果传给 sidecar_args / sidecar_env
+pub fn for_backend(app: &AppHandle) -> LocaleInfo {
+    let (info, is_changed) = refresh();
+    if is_changed {
+        changed(app, &info);
+    }
+    app_log!("System locale: {}", describe(&info));
+    info
+}
+
+pub fn sidecar_args(info: &LocaleInfo) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(locale) = &info.locale {
+        args.extend(["--locale".to_string(), locale.clone()]);
+    }
+    if !info.languages.is_empty() {
+        args.extend(["--languages".to_string(), info.languages.join(",")]);
+    }
+    if let Some(timezone) = &info.timezone {
+        args.extend(["--timezone".to_string(), timezone.clone()]);
+    }
+    args
+}
+
+/// Windows 上的 C 运行库不认识 IANA 时区名，TZ 只在 Unix 上设置
+pub fn sidecar_env(info: &LocaleInfo) -> Vec<(&'static str, String)> {
+    match &info.timezone {
+        Some(timezone) if cfg!(unix) => vec![("TZ", timezone.clone())],
+        _ => Vec::new(),
+    }
+}
+
+/// 在 setup 中调用，定期检查系统区域设置是否变化
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let (info, is_changed) = refresh();
+            if is_changed {
+                changed(&app, &info);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
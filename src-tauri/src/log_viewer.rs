@@ -0,0 +1,344 @@
+// 日志查看器读取磁盘上的日志文件：内存缓冲只有本次运行最近的输出，排查问题时常需要往前翻看已滚动的归档。
+// tail_log_file 把 backend.5.log … backend.1.log、backend.log 视为一个连续的字节流按偏移分块读取，
+// 前端据此实现无限滚动；偏移以当前这组文件为准，文件滚动后整体前移（total_size 随之变化）。
+// follow_log_file 定期检查当前文件的新增内容，按整行发送 `log-file://follow`；文件变小或被替换（inode 变化）
+// 视为已滚动：先读完旧文件（此时已改名为 .1.log）剩余的部分，再发送 rotated 标记，从新文件开头继续。
+
+use std::collections::HashMap;
+use std::fs::{File, Metadata};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::logs::{rotated_path, APP_LOG_NAME, BACKEND_LOG_NAME, KEEP_ROTATED};
+
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_CHUNK_BYTES: u32 = 1024 * 1024;
+// 每次轮询最多读取的新内容，文件增长很快时分多次发送
+const MAX_FOLLOW_READ: u64 = 256 * 1024;
+
+// 正在跟随的文件及对应任务的编号；取消或重新跟随后，旧任务发现编号不符即退出
+static FOLLOWERS: Mutex<Option<HashMap<LogFileName, u64>>> = Mutex::new(None);
+static NEXT_FOLLOWER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFileName {
+    Backend,
+    App,
+}
+
+impl LogFileName {
+    fn file_name(self) -> &'static str {
+        match self {
+            LogFileName::Backend => BACKEND_LOG_NAME,
+            LogFileName::App => APP_LOG_NAME,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct LogChunk {
+    /// 截到完整行的内容，无效的 UTF-8 替换为 U+FFFD
+    pub content: String,
+    pub offset: u64,
+    pub next_offset: u64,
+    /// 全部文件的总大小，next_offset 等于它表示已读到末尾
+    pub total_size: u64,
+}
+
+// `log-file://follow` 事件负载
+#[derive(Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum FollowEvent {
+    Lines { name: LogFileName, lines: Vec<String> },
+    Rotated { name: LogFileName },
+}
+
+fn current_path(app: &AppHandle, name: LogFileName) -> Result<PathBuf, String> {
+    Ok(crate::folders::logs_dir(app)?.join(name.file_name()))
+}
+
+// 从最旧的归档到当前文件，附带各自的大小；不存在的文件跳过
+fn segments(current: &Path) -> Vec<(PathBuf, u64)> {
+    let mut paths: Vec<PathBuf> = (1..=KEEP_ROTATED).rev().map(|n| rotated_path(current, n)).collect();
+    paths.push(current.to_path_buf());
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let size = std::fs::metadata(&path).ok()?.len();
+            Some((path, size))
+        })
+        .collect()
+}
+
+fn read_range(path: &Path, start: u64, len: u64) -> Result<Vec<u8>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    file.seek(SeekFrom::Start(start)).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let mut data = Vec::new();
+    file.take(len)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    Ok(data)
+}
+
+// current 为当前文件（backend.log 等），归档在同一目录下
+fn read_chunk(current: &Path, offset: u64, max_bytes: u32) -> Result<LogChunk, String> {
+    let segments = segments(current);
+    let total_size: u64 = segments.iter().map(|(_, size)| size).sum();
+    let offset = offset.min(total_size);
+    let end_pos = offset + u64::from(max_bytes.clamp(1, MAX_CHUNK_BYTES)).min(total_size - offset);
+
+    let mut data = Vec::new();
+    let (mut pos, mut start) = (offset, 0u64);
+    for (path, size) in &segments {
+        let end = start + size;
+        if pos < end && pos < end_pos {
+            let len = end.min(end_pos) - pos;
+            data.extend(read_range(path, pos - start, len)?);
+            pos += len;
+        }
+        start = end;
+    }
+    // 未读到末尾时截到最后一个换行，下一块从新的一行开始；单行超过 max_bytes 时原样返回
+    if offset + (data.len() as u64) < total_size {
+        if let Some(last_newline) = data.iter().rposition(|&b| b == b'\n') {
+            data.truncate(last_newline + 1);
+        }
+    }
+    Ok(LogChunk {
+        content: String::from_utf8_lossy(&data).into_owned(),
+        offset,
+        next_offset: offset + data.len() as u64,
+        total_size,
+    })
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+// Windows 上取文件 ID 需要额外打开句柄，只依赖大小变小来判断
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+// 取出已完整的行，剩余不足一行的部分留到下次
+fn take_lines(pending: &mut Vec<u8>) -> Vec<String> {
+    let Some(last_newline) = pending.iter().rposition(|&b| b == b'\n') else {
+        return Vec::new();
+    };
+    let complete: Vec<u8> = pending.drain(..=last_newline).collect();
+    String::from_utf8_lossy(&complete).lines().map(str::to_string).collect()
+}
+
+fn is_following(name: LogFileName, id: u64) -> bool {
+    FOLLOWERS.lock().unwrap().as_ref().and_then(|followers| followers.get(&name)) == Some(&id)
+}
+
+async fn follow<R: Runtime>(app: AppHandle<R>, name: LogFileName, path: PathBuf, id: u64) {
+    let metadata = std::fs::metadata(&path).ok();
+    let mut offset = metadata.as_ref().map_or(0, Metadata::len);
+    let mut identity = metadata.as_ref().and_then(file_id);
+    let mut pending = Vec::new();
+    let emit_lines = |pending: &mut Vec<u8>| {
+        let lines = take_lines(pending);
+        if !lines.is_empty() {
+            let _ = app.emit("log-file://follow", FollowEvent::Lines { name, lines });
+        }
+    };
+
+    while is_following(name, id) {
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+        // 滚动的瞬间当前文件可能还不存在
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        if metadata.len() < offset || (identity.is_some() && file_id(&metadata) != identity) {
+            if let Ok(rest) = read_range(&rotated_path(&path, 1), offset, u64::MAX) {
+                pending.extend(rest);
+            }
+            if !pending.is_empty() && pending.last() != Some(&b'\n') {
+                pending.push(b'\n');
+            }
+            emit_lines(&mut pending);
+            let _ = app.emit("log-file://follow", FollowEvent::Rotated { name });
+            offset = 0;
+            identity = file_id(&metadata);
+        }
+        if metadata.len() > offset {
+            match read_range(&path, offset, (metadata.len() - offset).min(MAX_FOLLOW_READ)) {
+                Ok(data) => {
+                    offset += data.len() as u64;
+                    pending.extend(data);
+                }
+                Err(e) => {
+                    app_error!("Failed to follow log file: {}", e);
+                    continue;
+                }
+            }
+            emit_lines(&mut pending);
+        }
+    }
+}
+
+/// 从 `offset` 起读取最多 `max_bytes`（上限 1 MB）字节，返回内容与下一块的偏移。
+/// 偏移 0 是最旧归档的开头；从末尾往前翻时，用 total_size 减去已读长度计算偏移
+#[tauri::command]
+pub async fn tail_log_file(app: AppHandle, name: LogFileName, offset: u64, max_bytes: u32) -> Result<LogChunk, String> {
+    let current = current_path(&app, name)?;
+    tauri::async_runtime::spawn_blocking(move || read_chunk(&current, offset, max_bytes))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 从当前末尾开始跟随文件的新增内容，直到调用 unfollow_log_file；重复调用会替换之前的跟随
+#[tauri::command]
+pub fn follow_log_file(app: AppHandle, name: LogFileName) -> Result<(), String> {
+    let path = current_path(&app, name)?;
+    let id = NEXT_FOLLOWER.fetch_add(1, Ordering::SeqCst);
+    FOLLOWERS.lock().unwrap().get_or_insert_with(HashMap::new).insert(name, id);
+    tauri::async_runtime::spawn(follow(app, name, path, id));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unfollow_log_file(name: LogFileName) {
+    if let Some(followers) = FOLLOWERS.lock().unwrap().as_mut() {
+        followers.remove(&name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tauri::Listener;
+
+    fn write(path: &Path, content: &[u8]) {
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn append(path: &Path, content: &[u8]) {
+        use std::io::Write;
+        std::fs::OpenOptions::new().append(true).open(path).unwrap().write_all(content).unwrap();
+    }
+
+    // 读完全部内容，返回各块
+    fn read_all(current: &Path, max_bytes: u32) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let mut offset = 0;
+        loop {
+            let chunk = read_chunk(current, offset, max_bytes).unwrap();
+            if chunk.next_offset == chunk.offset {
+                return chunks;
+            }
+            offset = chunk.next_offset;
+            chunks.push(chunk.content);
+        }
+    }
+
+    #[test]
+    fn archives_and_current_file_form_one_stream() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join(BACKEND_LOG_NAME);
+        write(&rotated_path(&current, 2), b"one\n");
+        write(&rotated_path(&current, 1), b"two\nthree\n");
+        write(&current, b"four\n");
+
+        let chunk = read_chunk(&current, 0, MAX_CHUNK_BYTES).unwrap();
+        assert_eq!(chunk.content, "one\ntwo\nthree\nfour\n");
+        assert_eq!((chunk.offset, chunk.next_offset, chunk.total_size), (0, 19, 19));
+        // 跨越文件边界读取
+        assert_eq!(read_chunk(&current, 2, 6).unwrap().content, "e\ntwo\n");
+        assert_eq!(read_all(&current, 6), ["one\n", "two\n", "three\n", "four\n"]);
+    }
+
+    #[test]
+    fn chunks_end_at_a_newline_unless_a_line_is_too_long() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join(BACKEND_LOG_NAME);
+        write(&current, b"ab\ncdefghij\nk");
+        assert_eq!(read_chunk(&current, 0, 8).unwrap().content, "ab\n");
+        assert_eq!(read_chunk(&current, 3, 4).unwrap().content, "cdef");
+        // 最后一块读到末尾，不完整的行也返回
+        assert_eq!(read_chunk(&current, 12, 8).unwrap().content, "k");
+        assert_eq!(read_all(&current, 8).concat(), "ab\ncdefghij\nk");
+    }
+
+    #[test]
+    fn offsets_and_sizes_are_clamped() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join(BACKEND_LOG_NAME);
+        write(&current, b"line\n");
+        let chunk = read_chunk(&current, 100, 10).unwrap();
+        assert_eq!((chunk.offset, chunk.next_offset, chunk.content.as_str()), (5, 5, ""));
+        // max_bytes 为 0 时至少读 1 字节
+        assert_eq!(read_chunk(&current, 0, 0).unwrap().next_offset, 1);
+        // 没有任何日志文件
+        let missing = read_chunk(&dir.path().join(APP_LOG_NAME), 0, 10).unwrap();
+        assert_eq!((missing.total_size, missing.content.as_str()), (0, ""));
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join(BACKEND_LOG_NAME);
+        write(&current, b"ok \xff\xfe\n");
+        assert_eq!(read_chunk(&current, 0, 100).unwrap().content, "ok \u{fffd}\u{fffd}\n");
+    }
+
+    #[test]
+    fn take_lines_keeps_the_partial_line() {
+        let mut pending = b"a\r\nb\nc".to_vec();
+        assert_eq!(take_lines(&mut pending), ["a", "b"]);
+        assert_eq!(pending, b"c");
+        assert!(take_lines(&mut pending).is_empty());
+        pending.extend(b"d\n");
+        assert_eq!(take_lines(&mut pending), ["cd"]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn follow_reads_the_rest_of_a_rotated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join(APP_LOG_NAME);
+        write(&current, b"before follow\n");
+
+        let app = tauri::test::mock_app();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        app.listen_any("log-file://follow", move |e| {
+            sink.lock().unwrap().push(serde_json::from_str::<serde_json::Value>(e.payload()).unwrap());
+        });
+        let id = NEXT_FOLLOWER.fetch_add(1, Ordering::SeqCst);
+        FOLLOWERS.lock().unwrap().get_or_insert_with(HashMap::new).insert(LogFileName::App, id);
+        let task = tauri::async_runtime::spawn(follow(app.handle().clone(), LogFileName::App, current.clone(), id));
+
+        let settle = || std::thread::sleep(FOLLOW_INTERVAL * 3);
+        append(&current, b"first\nsecond");
+        settle();
+        // 滚动前又写入了半行
+        append(&current, b" line\nlast");
+        std::fs::rename(&current, rotated_path(&current, 1)).unwrap();
+        write(&current, b"new file\n");
+        settle();
+        unfollow_log_file(LogFileName::App);
+        tauri::async_runtime::block_on(task).unwrap();
+
+        let lines = |event: &serde_json::Value| -> Vec<String> { serde_json::from_value(event["lines"].clone()).unwrap() };
+        let events = events.lock().unwrap();
+        let kinds: Vec<&str> = events.iter().map(|e| e["kind"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["lines", "lines", "rotated", "lines"], "{:?}", events);
+        assert_eq!(lines(&events[0]), ["first"]);
+        assert_eq!(lines(&events[1]), ["second line", "last"]);
+        assert_eq!(events[2]["name"], "app");
+        assert_eq!(lines(&events[3]), ["new file"]);
+    }
+}
@@ -0,0 +1,751 @@
+// 日志文件：后端输出写入 logs/backend.log，Rust 侧日志写入 logs/app.log，
+// 均按大小滚动（backend.log → backend.1.log → ... → backend.5.log）
+//
+// Rust 侧日志经 tracing 输出，同一事件同时送往三处：app.log（JSON 行，带所在 span 链）、
+// 控制台（调试构建与无界面模式）以及内存日志缓冲（stream 为 app，与后端输出一起出现在日志面板）。
+// 后端输出以 target "backend" 经同一订阅者输出到控制台（backend.log 与内存缓冲由调用方直接写入）。
+// 级别过滤按模块配置，语法同 RUST_LOG（如 "info,duncrew_lib::backend=debug"）：
+// 环境变量 DUNCREW_LOG 优先，否则使用 config.json 的 app_log_filter，修改后立即生效。
+// 写出前把会话 token 与凭据值替换为 ***。
+
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::Manager;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::fmt::time::ChronoLocal;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+pub const KEEP_ROTATED: usize = 5;
+
+pub const LOGS_DIR_NAME: &str = "logs";
+pub const BACKEND_LOG_NAME: &str = "backend.log";
+pub const APP_LOG_NAME: &str = "app.log";
+
+pub const LOG_FILTER_ENV: &str = "DUNCREW_LOG";
+/// 后端输出在 tracing 中的 target，过滤中写 "backend=debug" 可在控制台看到后端的调试输出
+pub const BACKEND_TARGET: &str = "backend";
+pub const DEFAULT_LOG_FILTER: &str = "info";
+// 短于此长度的凭据值不替换，避免误伤普通文本
+const MIN_SECRET_LEN: usize = 4;
+
+/// INFO 级应用日志，target 为调用处的模块路径
+macro_rules! app_log {
+    ($($arg:tt)*) => {{
+        ::tracing::info!($($arg)*);
+    }};
+}
+
+/// ERROR 级应用日志
+macro_rules! app_error {
+    ($($arg:tt)*) => {{
+        ::tracing::error!($($arg)*);
+    }};
+}
+
+/// 本地时间戳，精确到毫秒
+pub fn timestamp() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+/// 第 n 个归档文件名：backend.log → backend.n.log
+pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}.log", stem, n))
+}
+
+/// 按大小滚动的追加写日志文件
+pub struct RotatingLog {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    size: u64,
+}
+
+impl RotatingLog {
+    pub fn open(path: PathBuf) -> Self {
+        let mut log = Self { path, writer: None, size: 0 };
+        log.reopen();
+        log
+    }
+
+    fn reopen(&mut self) {
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match OpenOptions::new().create(true).append(true).open(&self.path) {
+            Ok(file) => {
+                self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+                self.writer = Some(BufWriter::new(file));
+            }
+            Err(e) => {
+                tracing::error!("Failed to open log file {:?}: {}", self.path, e);
+                self.writer = None;
+            }
+        }
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        rotated_path(&self.path, n)
+    }
+
+    fn rotate(&mut self) {
+        self.flush();
+        self.writer = None;
+        let _ = std::fs::remove_file(self.rotated_path(KEEP_ROTATED));
+        for n in (1..KEEP_ROTATED).rev() {
+            let _ = std::fs::rename(self.rotated_path(n), self.rotated_path(n + 1));
+        }
+        let _ = std::fs::rename(&self.path, self.rotated_path(1));
+        self.reopen();
+    }
+
+    /// 追加一行（自动加时间戳）
+    pub fn write_line(&mut self, tag: &str, line: &str) {
+        self.write_raw(&format!("{} [{}] {}\n", timestamp(), tag, redact(line)));
+    }
+
+    // 原样追加，entry 自带换行
+    fn write_raw(&mut self, entry: &str) {
+        if self.size >= MAX_LOG_BYTES {
+            self.rotate();
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        if writer.write_all(entry.as_bytes()).is_ok() {
+            self.size += entry.len() as u64;
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl Drop for RotatingLog {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+static APP_LOG: OnceLock<Mutex<RotatingLog>> = OnceLock::new();
+
+thread_local! {
+    // 正在写 app.log（滚动时重新打开失败会再记一条日志），此时不重入，避免对 APP_LOG 二次加锁
+    static WRITING_APP_LOG: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// 初始化 app.log，在 setup 中确定数据目录后调用；切换配置档案后再次调用，改写到新目录
+pub fn init_app_log(logs_dir: &Path) {
+    let log = RotatingLog::open(logs_dir.join(APP_LOG_NAME));
+    match APP_LOG.get() {
+        Some(current) => *current.lock().unwrap() = log,
+        None => {
+            let _ = APP_LOG.set(Mutex::new(log));
+        }
+    }
+}
+
+/// 退出前确保 app.log 已落盘
+pub fn flush_app_log() {
+    if let Some(log) = APP_LOG.get() {
+        log.lock().unwrap().flush();
+    }
+}
+
+// ============================================
+// tracing 订阅者：app.log、控制台与内存日志缓冲
+// ============================================
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+static SECRETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// setup 中注册 LogBuffer 后设置，此前的日志只写文件与控制台
+static BUFFER_APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// 登记需要在日志中隐藏的值（会话 token、凭据）
+pub fn add_secret(value: &str) {
+    if value.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut secrets = SECRETS.lock().unwrap();
+    if !secrets.iter().any(|secret| secret == value) {
+        secrets.push(value.to_string());
+    }
+}
+
+/// 把已登记的值替换为 ***
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let secrets = SECRETS.lock().unwrap();
+    if !secrets.iter().any(|secret| text.contains(secret.as_str())) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(secrets.iter().fold(text.to_string(), |text, secret| text.replace(secret.as_str(), "***")))
+}
+
+// 每个事件由 fmt 层格式化后一次写入
+struct AppLogWriter;
+
+impl Write for AppLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(log) = APP_LOG.get() {
+            if WRITING_APP_LOG.replace(true) {
+                return Ok(buf.len());
+            }
+            let mut log = log.lock().unwrap();
+            log.write_raw(&redact(&String::from_utf8_lossy(buf)));
+            // 应用日志量很小，立即落盘以免崩溃时丢失
+            log.flush();
+            WRITING_APP_LOG.set(false);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// WARN 及以上写 stderr，其余写 stdout
+struct ConsoleWriter {
+    stderr: bool,
+}
+
+impl Write for ConsoleWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        let text = redact(&text);
+        if self.stderr {
+            std::io::stderr().write_all(text.as_bytes())?;
+        } else {
+            std::io::stdout().write_all(text.as_bytes())?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Console;
+
+impl<'a> fmt::MakeWriter<'a> for Console {
+    type Writer = ConsoleWriter;
+
+    fn make_writer(&'a self) -> ConsoleWriter {
+        ConsoleWriter { stderr: false }
+    }
+
+    fn make_writer_for(&'a self, meta: &tracing::Metadata<'_>) -> ConsoleWriter {
+        ConsoleWriter { stderr: *meta.level() <= tracing::Level::WARN }
+    }
+}
+
+// 取出事件的消息与其余字段（key=value 追加在消息之后）
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+}
+
+// 日志面板中的一行应用日志，module 为事件的 target
+fn app_line(event: &tracing::Event<'_>) -> BackendLine {
+    let mut visitor = MessageVisitor::default();
+    event.record(&mut visitor);
+    let meta = event.metadata();
+    BackendLine {
+        stream: "app",
+        level: LogLevel::from_tracing(*meta.level()),
+        module: Some(meta.target().to_string()),
+        message: visitor.message + &visitor.fields,
+        structured: true,
+    }
+}
+
+// 把事件写入内存日志缓冲，get_backend_logs 与 `backend://log` 由此看到与 app.log 相同的内容
+struct BufferLayer;
+
+impl<S: tracing::Subscriber> Layer<S> for BufferLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        // 后端输出已由 record_backend_output 以原来的 stream 写入缓冲
+        if event.metadata().target() == BACKEND_TARGET {
+            return;
+        }
+        let Some(app) = BUFFER_APP.get() else {
+            return;
+        };
+        let Some(buffer) = app.try_state::<LogBuffer>() else {
+            return;
+        };
+        let line = app_line(event);
+        let generation = app
+            .try_state::<crate::backend::ServerState>()
+            .map_or(0, |state| state.generation.load(std::sync::atomic::Ordering::SeqCst));
+        buffer.push(&line, generation);
+    }
+}
+
+// 环境变量优先于配置
+fn filter_directives(configured: &str) -> String {
+    std::env::var(LOG_FILTER_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| configured.to_string())
+}
+
+// app.log 的格式：每个事件一行 JSON，带当前 span 与完整的 span 链；后端输出已写入 backend.log，不再重复
+fn file_layer<S, W>(make_writer: W) -> impl Layer<S>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_timer(ChronoLocal::rfc_3339())
+        .with_ansi(false)
+        .with_writer(make_writer)
+        .with_filter(tracing_subscriber::filter::filter_fn(|meta| meta.target() != BACKEND_TARGET))
+}
+
+/// 在 main 开头调用一次；console 为 true 时同时输出到控制台。
+/// app.log 在 init_app_log 确定数据目录之后才开始写入
+pub fn init_tracing(console: bool) {
+    let filter = EnvFilter::try_new(filter_directives(DEFAULT_LOG_FILTER))
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+    let _ = FILTER.set(handle);
+    let file = file_layer(|| AppLogWriter);
+    let console = console.then(|| {
+        fmt::layer()
+            .with_timer(ChronoLocal::new("%H:%M:%S%.3f".to_string()))
+            .with_writer(Console)
+    });
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(file)
+        .with(console)
+        .with(BufferLayer)
+        .try_init();
+}
+
+/// 注册 LogBuffer 后调用，此后的应用日志也进入内存日志缓冲
+pub fn attach_log_buffer(app: &tauri::AppHandle) {
+    let _ = BUFFER_APP.set(app.clone());
+}
+
+/// 检查 app_log_filter 的语法
+pub fn validate_filter(filter: &str) -> Result<(), String> {
+    EnvFilter::try_new(filter).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// 按配置切换级别过滤；设置了 DUNCREW_LOG 时以环境变量为准
+pub fn apply_filter(configured: &str) {
+    let directives = filter_directives(configured);
+    let filter = match EnvFilter::try_new(&directives) {
+        Ok(filter) => filter,
+        Err(e) => {
+            app_error!("Invalid log filter \"{}\": {}", directives, e);
+            return;
+        }
+    };
+    if let Some(handle) = FILTER.get() {
+        let _ = handle.reload(filter);
+    }
+}
+
+// ============================================
+// 后端输出解析：JSON 行（{"level","msg","module"}）按级别分类，其余按来源兜底
+// ============================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Critical,
+}
+
+impl LogLevel {
+    fn parse(level: &str) -> Option<Self> {
+        match level.to_ascii_lowercase().as_str() {
+            "debug" | "trace" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            "critical" | "fatal" => Some(LogLevel::Critical),
+            _ => None,
+        }
+    }
+
+    fn from_tracing(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::ERROR => LogLevel::Error,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::INFO => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+            LogLevel::Critical => "critical",
+        }
+    }
+}
+
+/// 解析后的一行后端输出
+pub struct BackendLine {
+    // stdout / stderr / app
+    pub stream: &'static str,
+    pub level: LogLevel,
+    pub module: Option<String>,
+    pub message: String,
+    // 是否来自 JSON 结构化日志
+    pub structured: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct JsonLogLine {
+    level: String,
+    msg: String,
+    #[serde(default)]
+    module: Option<String>,
+}
+
+impl BackendLine {
+    /// Rust 侧插入的标记行（如重启分隔线）
+    pub fn marker(message: String) -> Self {
+        Self { stream: "app", level: LogLevel::Info, module: None, message, structured: false }
+    }
+
+    /// 解析一行输出；非 JSON 行保持原有语义：stdout 为 info，stderr 为 error
+    pub fn parse(stream: &'static str, raw: &str) -> Self {
+        if raw.trim_start().starts_with('{') {
+            if let Ok(json) = serde_json::from_str::<JsonLogLine>(raw) {
+                if let Some(level) = LogLevel::parse(&json.level) {
+                    return Self { stream, level, module: json.module, message: json.msg, structured: true };
+                }
+            }
+        }
+        let level = if stream == "stderr" { LogLevel::Error } else { LogLevel::Info };
+        Self { stream, level, module: None, message: raw.to_string(), structured: false }
+    }
+
+    /// 写入日志文件的内容（不带颜色）
+    pub fn display(&self) -> String {
+        match &self.module {
+            Some(module) => format!("{}: {}", module, self.message),
+            None => self.message.clone(),
+        }
+    }
+
+    /// 以 target "backend" 发出 tracing 事件：与应用日志经同一过滤、脱敏与格式输出到控制台
+    pub fn emit(&self) {
+        let text = self.display();
+        match self.level {
+            LogLevel::Debug => tracing::debug!(target: BACKEND_TARGET, stream = self.stream, "{}", text),
+            LogLevel::Info => tracing::info!(target: BACKEND_TARGET, stream = self.stream, "{}", text),
+            LogLevel::Warn => tracing::warn!(target: BACKEND_TARGET, stream = self.stream, "{}", text),
+            LogLevel::Error | LogLevel::Critical => {
+                tracing::error!(target: BACKEND_TARGET, stream = self.stream, "{}", text)
+            }
+        }
+    }
+}
+
+// ============================================
+// 内存日志缓冲：供前端“后端日志”面板读取
+// ============================================
+
+const LOG_BUFFER_CAPACITY: usize = 2000;
+// 实时推送：每 100ms 或攒满 50 行发送一批 `backend://log`
+const STREAM_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+const STREAM_BATCH_LINES: usize = 50;
+
+#[derive(Clone, serde::Serialize)]
+pub struct LogEntry {
+    // 单调递增序号，前端用 since 增量拉取
+    pub seq: u64,
+    // Unix 毫秒时间戳
+    pub ts: u64,
+    // stdout / stderr / app
+    pub stream: &'static str,
+    pub level: LogLevel,
+    pub module: Option<String>,
+    pub line: String,
+    // 后端进程代数（每次启动 +1），前端据此区分不同运行
+    pub generation: u64,
+}
+
+#[derive(Default)]
+struct LogBufferInner {
+    entries: std::collections::VecDeque<LogEntry>,
+    next_seq: u64,
+}
+
+/// 最近的后端输出，跨后端重启保留
+#[derive(Default)]
+pub struct LogBuffer {
+    inner: Mutex<LogBufferInner>,
+    // 日志面板打开时才推送事件
+    streaming: std::sync::atomic::AtomicBool,
+    pending: Mutex<Vec<LogEntry>>,
+    batch_full: tokio::sync::Notify,
+}
+
+impl LogBuffer {
+    pub fn push(&self, line: &BackendLine, generation: u64) -> LogEntry {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut inner = self.inner.lock().unwrap();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        if inner.entries.len() >= LOG_BUFFER_CAPACITY {
+            inner.entries.pop_front();
+        }
+        let entry = LogEntry {
+            seq,
+            ts,
+            stream: line.stream,
+            level: line.level,
+            module: line.module.clone(),
+            line: redact(&line.message).into_owned(),
+            generation,
+        };
+        if self.streaming.load(std::sync::atomic::Ordering::Relaxed) {
+            let mut pending = self.pending.lock().unwrap();
+            pending.push(entry.clone());
+            if pending.len() >= STREAM_BATCH_LINES {
+                self.batch_full.notify_one();
+            }
+        }
+        inner.entries.push_back(entry.clone());
+        entry
+    }
+
+    pub fn set_streaming(&self, enabled: bool) {
+        self.streaming.store(enabled, std::sync::atomic::Ordering::Relaxed);
+        if !enabled {
+            self.pending.lock().unwrap().clear();
+        }
+    }
+
+    /// 是否写入过任何内容（清空后仍视为写入过）
+    pub fn has_history(&self) -> bool {
+        self.inner.lock().unwrap().next_seq > 0
+    }
+
+    /// 返回 seq 大于 since 的最近 limit 条
+    pub fn recent(&self, limit: usize, since: Option<u64>) -> Vec<LogEntry> {
+        let inner = self.inner.lock().unwrap();
+        let matching: Vec<&LogEntry> = inner
+            .entries
+            .iter()
+            .filter(|e| since.is_none_or(|since| e.seq > since))
+            .collect();
+        let skip = matching.len().saturating_sub(limit);
+        matching.into_iter().skip(skip).cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().entries.clear();
+    }
+}
+
+/// 启动日志推送任务：按批次把新日志以 `backend://log` 事件发送给前端
+pub fn spawn_log_streamer(app: tauri::AppHandle) {
+    use tauri::{Emitter, Manager};
+    tauri::async_runtime::spawn(async move {
+        let buffer = app.state::<LogBuffer>();
+        loop {
+            let _ = tokio::time::timeout(STREAM_FLUSH_INTERVAL, buffer.batch_full.notified()).await;
+            let batch = std::mem::take(&mut *buffer.pending.lock().unwrap());
+            if !batch.is_empty() {
+                let _ = app.emit("backend://log", batch);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    // 收集 file_layer 写出的内容
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            let text = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+        }
+    }
+
+    // 在只含 filter 与 file_layer 的订阅者下运行 f，返回写出的 JSON 行
+    fn capture(filter: &str, f: impl FnOnce()) -> Vec<serde_json::Value> {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry()
+            .with(EnvFilter::new(filter))
+            .with(file_layer(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, f);
+        captured.lines()
+    }
+
+    #[test]
+    fn app_log_lines_are_json_with_the_span_chain() {
+        let lines = capture("info", || {
+            let start = tracing::info_span!("start_backend", attempt = 2);
+            let _start = start.enter();
+            let wait = tracing::info_span!("wait_for_ready", pid = 42);
+            let _wait = wait.enter();
+            tracing::info!(port = 8080, "Backend server ready");
+        });
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["target"], module_path!());
+        assert_eq!(line["fields"]["message"], "Backend server ready");
+        assert_eq!(line["fields"]["port"], 8080);
+        assert_eq!(line["span"]["name"], "wait_for_ready");
+        assert_eq!(line["span"]["pid"], 42);
+        let spans: Vec<&str> = line["spans"].as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+        assert_eq!(spans, ["start_backend", "wait_for_ready"]);
+        assert!(chrono::DateTime::parse_from_rfc3339(line["timestamp"].as_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn filter_applies_per_module() {
+        let filter = format!("warn,{}=debug", module_path!());
+        let lines = capture(&filter, || {
+            tracing::debug!("from this module");
+            tracing::info!(target: "duncrew_lib::other", "filtered out");
+            tracing::warn!(target: "duncrew_lib::other", "kept");
+        });
+        let messages: Vec<&str> = lines.iter().map(|l| l["fields"]["message"].as_str().unwrap()).collect();
+        assert_eq!(messages, ["from this module", "kept"]);
+    }
+
+    #[test]
+    fn backend_output_stays_out_of_app_log() {
+        let lines = capture("debug", || {
+            BackendLine::parse("stdout", r#"{"level":"warning","module":"skills","msg":"slow plugin"}"#).emit();
+            BackendLine::parse("stderr", "Traceback (most recent call last):").emit();
+            tracing::info!("app line");
+        });
+        let messages: Vec<&str> = lines.iter().map(|l| l["fields"]["message"].as_str().unwrap()).collect();
+        assert_eq!(messages, ["app line"]);
+    }
+
+    #[test]
+    fn filter_syntax_is_validated() {
+        assert!(validate_filter("info").is_ok());
+        assert!(validate_filter("info,duncrew_lib::backend=debug").is_ok());
+        assert!(validate_filter("info,duncrew_lib::backend=loud").is_err());
+    }
+
+    #[test]
+    fn buffered_lines_carry_level_module_and_fields() {
+        struct Collect(Arc<Mutex<Vec<BackendLine>>>);
+        impl<S: tracing::Subscriber> Layer<S> for Collect {
+            fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                self.0.lock().unwrap().push(app_line(event));
+            }
+        }
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(Collect(lines.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(target: "duncrew_lib::backend", pid = 7, reason = "exited", "Backend crashed");
+            tracing::trace!("verbose");
+        });
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines[0].stream, "app");
+        assert_eq!(lines[0].level, LogLevel::Error);
+        assert_eq!(lines[0].module.as_deref(), Some("duncrew_lib::backend"));
+        assert_eq!(lines[0].message, "Backend crashed pid=7 reason=exited");
+        assert_eq!(lines[1].level, LogLevel::Debug);
+    }
+
+    #[test]
+    fn registered_secrets_are_redacted() {
+        add_secret("tok-6b1f0c2a");
+        add_secret("abc");
+        assert_eq!(redact("Authorization: Bearer tok-6b1f0c2a"), "Authorization: Bearer ***");
+        assert!(matches!(redact("abc stays"), Cow::Borrowed("abc stays")));
+    }
+
+    #[test]
+    fn full_log_rotates_and_keeps_a_bounded_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(APP_LOG_NAME);
+        for n in 0..=KEEP_ROTATED {
+            // 已写满的当前文件，下一次写入前滚动
+            File::create(&path).unwrap().set_len(MAX_LOG_BYTES).unwrap();
+            let mut log = RotatingLog::open(path.clone());
+            log.write_raw(&format!("entry {}\n", n));
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), format!("entry {}\n", KEEP_ROTATED));
+        for n in 1..=KEEP_ROTATED {
+            assert_eq!(std::fs::metadata(rotated_path(&path, n)).unwrap().len(), MAX_LOG_BYTES, "{}", n);
+        }
+        assert!(!rotated_path(&path, KEEP_ROTATED + 1).exists());
+    }
+
+    #[test]
+    fn backend_json_lines_are_classified() {
+        let line = BackendLine::parse("stdout", r#"{"level":"warning","msg":"slow query","module":"db"}"#);
+        assert_eq!((line.level, line.structured), (LogLevel::Warn, true));
+        assert_eq!(line.display(), "db: slow query");
+        let line = BackendLine::parse("stderr", r#"{"level":"verbose","msg":"?"}"#);
+        assert_eq!((line.level, line.structured), (LogLevel::Error, false));
+        assert_eq!(BackendLine::parse("stdout", "plain").level, LogLevel::Info);
+    }
+}
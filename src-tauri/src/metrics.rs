@@ -0,0 +1,203 @@
+// 后端进程资源占用：定期采样 Sidecar 的内存（RSS）与 CPU，保留最近一段历史，
+// 通过 `get_backend_metrics` 和 `backend://metrics` 提供给前端，用于区分是 WebView 还是 Python 占用资源。
+// `get_backend_metrics` 另外附带本应用访问后端的客户端侧指标（请求数、失败数、延迟），见 backend_client。
+// 同时按 config.json 的 memory_limit 检查内存上限，持续超限时通知或重启后端。
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend::ServerState;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+// 5 秒一次，保留最近 5 分钟
+const HISTORY_LEN: usize = 60;
+
+#[derive(Clone, serde::Serialize)]
+pub struct MetricsSample {
+    pub pid: u32,
+    pub rss_bytes: u64,
+    // 占整机 CPU 的百分比（已按核数归一化）；进程刚启动的第一次采样没有基准，为 None
+    pub cpu_percent: Option<f32>,
+    pub sampled_at: String,
+}
+
+/// `backend://metrics` 事件负载
+#[derive(Clone, serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BackendMetrics {
+    Running {
+        current: MetricsSample,
+        history: Vec<MetricsSample>,
+    },
+    NotRunning,
+    // 外部后端不是本应用启动的进程，不采样
+    External,
+}
+
+/// `get_backend_metrics` 返回值：进程资源占用（字段同 `backend://metrics`）加上客户端侧指标
+#[derive(serde::Serialize)]
+pub struct BackendMetricsReport {
+    #[serde(flatten)]
+    process: BackendMetrics,
+    client: crate::backend_client::ClientMetrics,
+}
+
+#[derive(Default)]
+pub struct MetricsState {
+    // 只保存当前进程的样本，重启后清空
+    history: Mutex<VecDeque<MetricsSample>>,
+}
+
+impl MetricsState {
+    /// 指定进程最近一次的采样
+    pub fn latest(&self, pid: u32) -> Option<MetricsSample> {
+        self.history.lock().unwrap().back().filter(|sample| sample.pid == pid).cloned()
+    }
+
+    fn snapshot(&self, app: &AppHandle) -> BackendMetrics {
+        match backend_pid(app) {
+            (_, true) => BackendMetrics::External,
+            (None, false) => BackendMetrics::NotRunning,
+            (Some(pid), false) => {
+                let history = self.history.lock().unwrap();
+                match history.back() {
+                    Some(current) if current.pid == pid => BackendMetrics::Running {
+                        current: current.clone(),
+                        history: history.iter().cloned().collect(),
+                    },
+                    // 新进程尚未采样
+                    _ => BackendMetrics::NotRunning,
+                }
+            }
+        }
+    }
+}
+
+// 当前后端 PID 及是否为外部后端
+fn backend_pid(app: &AppHandle) -> (Option<u32>, bool) {
+    let state = app.state::<ServerState>();
+    let process = state.process.lock().unwrap();
+    (process.pid, process.external_url.is_some())
+}
+
+// `backend://memory-limit` 事件负载；action 为 "notify" / "restart" / "gave_up"
+#[derive(Clone, serde::Serialize)]
+struct MemoryLimitPayload {
+    pid: u32,
+    rss_bytes: u64,
+    limit_bytes: u64,
+    samples: u32,
+    action: &'static str,
+}
+
+// 连续超限次数达到配置的采样数时触发一次，然后重新计数
+fn check_memory_limit(app: &AppHandle, sample: &MetricsSample, over_limit: &mut u32) {
+    let config = crate::app_config(app).memory_limit;
+    let limit_bytes = config.limit_mb * 1024 * 1024;
+    if limit_bytes == 0 || sample.rss_bytes <= limit_bytes {
+        *over_limit = 0;
+        return;
+    }
+    *over_limit += 1;
+    if *over_limit < config.samples {
+        return;
+    }
+    *over_limit = 0;
+
+    let restart = config.action == "restart";
+    app_error!(
+        "Backend memory usage {} MB has exceeded the {} MB limit for {} samples",
+        sample.rss_bytes / 1024 / 1024, config.limit_mb, config.samples
+    );
+    let payload = MemoryLimitPayload {
+        pid: sample.pid,
+        rss_bytes: sample.rss_bytes,
+        limit_bytes,
+        samples: config.samples,
+        action: if restart { "restart" } else { "notify" },
+    };
+    let _ = app.emit("backend://memory-limit", payload.clone());
+    let args: [(&str, &dyn std::fmt::Display); 2] = [("rss", &(sample.rss_bytes / 1024 / 1024)), ("limit", &config.limit_mb)];
+    if !restart {
+        crate::notify::backend_failure(app, &crate::i18n::tf("notify.memory", &args), false);
+        return;
+    }
+    crate::notify::backend_failure(app, &crate::i18n::tf("notify.memory.restarting", &args), false);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::backend::restart_for_memory_limit(&app).await {
+            app_error!("Memory limit restart skipped: {}", e);
+            let _ = app.emit("backend://memory-limit", MemoryLimitPayload { action: "gave_up", ..payload });
+        }
+    });
+}
+
+fn cpu_count() -> f32 {
+    std::thread::available_parallelism().map(|n| n.get() as f32).unwrap_or(1.0)
+}
+
+/// 启动后台采样循环
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut system = System::new();
+        // 上次采样的进程，PID 变化时重新解析，避免把 PID 复用后的无关进程当成后端
+        let mut sampled_pid: Option<u32> = None;
+        let mut over_limit = 0u32;
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            let state = app.state::<MetricsState>();
+            let (pid, external) = backend_pid(&app);
+            let Some(pid) = pid.filter(|_| !external) else {
+                if sampled_pid.take().is_some() {
+                    state.history.lock().unwrap().clear();
+                    let _ = app.emit("backend://metrics", state.snapshot(&app));
+                }
+                continue;
+            };
+
+            let first_sample = sampled_pid != Some(pid);
+            if first_sample {
+                system = System::new();
+                state.history.lock().unwrap().clear();
+                sampled_pid = Some(pid);
+                over_limit = 0;
+            }
+            let sys_pid = Pid::from_u32(pid);
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[sys_pid]),
+                true,
+                ProcessRefreshKind::nothing().with_memory().with_cpu(),
+            );
+            let Some(process) = system.process(sys_pid) else {
+                continue;
+            };
+            let sample = MetricsSample {
+                pid,
+                rss_bytes: process.memory(),
+                cpu_percent: (!first_sample).then(|| process.cpu_usage() / cpu_count()),
+                sampled_at: crate::logs::timestamp(),
+            };
+            {
+                let mut history = state.history.lock().unwrap();
+                if history.len() == HISTORY_LEN {
+                    history.pop_front();
+                }
+                history.push_back(sample.clone());
+            }
+            check_memory_limit(&app, &sample, &mut over_limit);
+            let _ = app.emit("backend://metrics", state.snapshot(&app));
+        }
+    });
+}
+
+/// 后端当前资源占用、最近的历史样本及客户端侧请求指标
+#[tauri::command]
+pub fn get_backend_metrics(app: AppHandle) -> BackendMetricsReport {
+    BackendMetricsReport {
+        process: app.state::<MetricsState>().snapshot(&app),
+        client: crate::backend_client::metrics(),
+    }
+}
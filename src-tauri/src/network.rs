@@ -0,0 +1,127 @@
+// 网络连通性：后端的 AI 功能需要联网，离线时前端据此给出明确提示，而不是只看到请求失败。
+// 每隔几秒比对本机网卡的地址列表，发现变化（连上 / 断开 Wi-Fi、插拔网线、VPN）时立即探测一次，
+// 否则按 config.json 的 network_check.interval_secs 定期探测 network_check.url；收到任何 HTTP 响应即视为在线。
+// 所有网卡都没有地址时直接判定离线，不发请求。状态变化时发送 `net://online` / `net://offline`。
+// 检测需在 config.json 中开启 network_check.enabled（默认关闭），关闭时不发出任何探测请求，状态为未知。
+// 网卡变化靠轮询而不是系统通知（NotifyIpInterfaceChange / netlink / SCNetworkReachability）：
+// 三个平台的接口各不相同，而几秒的延迟对离线提示足够，轮询只读本地信息，开销可以忽略
+
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::Networks;
+use tauri::{AppHandle, Emitter};
+
+// 网卡列表的检查间隔；只读本地信息，不产生网络流量
+const CHANGE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+static STATUS: Mutex<NetworkStatus> = Mutex::new(NetworkStatus::disabled());
+
+/// `get_network_status` 返回值，也是 `net://online` / `net://offline` 的负载
+#[derive(Clone, serde::Serialize)]
+pub struct NetworkStatus {
+    pub enabled: bool,
+    /// 尚未探测或已关闭检测时为 None
+    pub online: Option<bool>,
+    pub checked_at: Option<String>,
+    /// 最近一次在线 / 离线切换的时间
+    pub changed_at: Option<String>,
+    /// 判定离线的原因
+    pub error: Option<String>,
+}
+
+impl NetworkStatus {
+    const fn disabled() -> Self {
+        Self { enabled: false, online: None, checked_at: None, changed_at: None, error: None }
+    }
+}
+
+// 非回环网卡的名称与地址，排序后用于比较
+fn interfaces() -> Vec<(String, IpAddr)> {
+    let networks = Networks::new_with_refreshed_list();
+    let mut addresses: Vec<_> = networks
+        .iter()
+        .flat_map(|(name, data)| data.ip_networks().iter().map(move |net| (name.clone(), net.addr)))
+        .filter(|(_, addr)| !addr.is_loopback() && !addr.is_unspecified())
+        .collect();
+    addresses.sort();
+    addresses
+}
+
+fn client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| reqwest::Client::builder().timeout(PROBE_TIMEOUT).build().unwrap_or_default())
+        .clone()
+}
+
+async fn probe(url: &str) -> Result<(), String> {
+    client().head(url).send().await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn update(app: &AppHandle, result: Result<(), String>) {
+    let online = result.is_ok();
+    let now = crate::logs::timestamp();
+    let status = {
+        let mut status = STATUS.lock().unwrap();
+        let changed = status.online != Some(online);
+        status.enabled = true;
+        status.online = Some(online);
+        status.checked_at = Some(now.clone());
+        status.error = result.err();
+        if !changed {
+            return;
+        }
+        status.changed_at = Some(now);
+        status.clone()
+    };
+    if online {
+        app_log!("Network is online");
+        let _ = app.emit("net://online", status);
+    } else {
+        app_log!("Network is offline: {}", status.error.as_deref().unwrap_or_default());
+        let _ = app.emit("net://offline", status);
+    }
+}
+
+/// 启动连通性监视；检测关闭期间只按间隔重新读取配置
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut known_interfaces = None;
+        let mut next_probe = Instant::now();
+        loop {
+            let config = crate::app_config(&app).network_check;
+            if !config.enabled {
+                if STATUS.lock().unwrap().enabled {
+                    app_log!("Network check disabled");
+                    *STATUS.lock().unwrap() = NetworkStatus::disabled();
+                }
+                known_interfaces = None;
+                tokio::time::sleep(CHANGE_POLL_INTERVAL).await;
+                continue;
+            }
+
+            let current = tauri::async_runtime::spawn_blocking(interfaces).await.unwrap_or_default();
+            let changed = known_interfaces.as_ref().is_some_and(|known| *known != current);
+            let no_address = current.is_empty();
+            known_interfaces = Some(current);
+            if changed || Instant::now() >= next_probe {
+                let result = if no_address {
+                    Err("No network interface has an address".to_string())
+                } else {
+                    probe(config.url.trim()).await
+                };
+                update(&app, result);
+                next_probe = Instant::now() + Duration::from_secs(config.interval_secs);
+            }
+            tokio::time::sleep(CHANGE_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// 当前网络状态；online 为 null 表示尚未探测或已在设置中关闭检测
+#[tauri::command]
+pub fn get_network_status() -> NetworkStatus {
+    STATUS.lock().unwrap().clone()
+}
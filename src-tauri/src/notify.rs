@@ -0,0 +1,30 @@
+// 后端崩溃 / 不健康时的系统通知。
+// 同一段崩溃循环内只通知一次；桌面端的通知插件不提供点击回调，
+// 所以通知正文提示用户从托盘菜单重启，而不是依赖点击通知。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::alerts::{self, AlertCategory};
+
+// 与自动重启的窗口期一致：窗口期内的后续失败视为同一次故障
+const NOTIFY_COOLDOWN: Duration = crate::backend::RESTART_WINDOW;
+
+static LAST_NOTIFIED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 发送后端故障通知；配置关闭或处于冷却期时不发送。
+/// `final_failure` 用于放弃自动重启等需要用户介入的情况，不受冷却期限制
+pub fn backend_failure(app: &AppHandle, message: &str, final_failure: bool) {
+    if !crate::app_config(app).notify_on_backend_failure {
+        return;
+    }
+    {
+        let mut last = LAST_NOTIFIED.lock().unwrap();
+        if !final_failure && last.is_some_and(|t| t.elapsed() < NOTIFY_COOLDOWN) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    alerts::notify(app, AlertCategory::BackendFailure, message);
+}
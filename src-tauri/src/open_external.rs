@@ -0,0 +1,164 @@
+// 在系统浏览器中打开外部链接（文档、OAuth 授权页等）。前端不直接使用 opener 插件的 JS API，
+// 只能经 open_external 命令，且 URL 必须是 http(s)，主机在 config.json 的 external_url_allowlist 中；
+// mailto: 交给邮件客户端，不检查允许列表。含控制字符或超过 MAX_URL_LEN 的 URL 一律拒绝。
+
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+/// 打开失败原因：blocked 为策略拒绝，launch_failed 为系统没能启动浏览器
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OpenExternalError {
+    Blocked { url: String, reason: String },
+    LaunchFailed { message: String },
+}
+
+// 远超任何正常链接，也低于各平台传给浏览器的命令行长度上限
+const MAX_URL_LEN: usize = 4096;
+
+fn blocked(url: &str, reason: impl Into<String>) -> OpenExternalError {
+    OpenExternalError::Blocked { url: url.to_string(), reason: reason.into() }
+}
+
+// 允许列表项："*" 匹配任意主机，"*.example.com" 匹配其子域名（不含 example.com 本身），其余须完全相同
+fn host_allowed(host: &str, allowlist: &[String]) -> bool {
+    allowlist.iter().any(|entry| {
+        let entry = entry.trim().trim_end_matches('.').to_lowercase();
+        if entry == "*" {
+            return true;
+        }
+        match entry.strip_prefix("*.") {
+            Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+            None => host == entry,
+        }
+    })
+}
+
+/// 校验并规范化 URL：只允许 http(s)（不带用户信息、主机在允许列表中）与 mailto
+fn validate(raw: &str, allowlist: &[String]) -> Result<reqwest::Url, OpenExternalError> {
+    let raw = raw.trim();
+    if raw.len() > MAX_URL_LEN {
+        let prefix: String = raw.chars().take(64).collect();
+        return Err(blocked(&prefix, format!("URL is longer than {} bytes", MAX_URL_LEN)));
+    }
+    // 解析时会静默去掉制表符和换行，这里先拒绝，不让日志与实际打开的地址不一致
+    if raw.chars().any(char::is_control) {
+        return Err(blocked(&raw.escape_default().to_string(), "URL contains control characters"));
+    }
+    let url = reqwest::Url::parse(raw).map_err(|e| blocked(raw, format!("invalid URL: {}", e)))?;
+    if url.scheme() == "mailto" {
+        if url.path().is_empty() {
+            return Err(blocked(raw, "mailto URL has no recipient"));
+        }
+        return Ok(url);
+    }
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(blocked(raw, format!("scheme \"{}\" is not allowed", url.scheme())));
+    }
+    // https://github.com@evil.example 之类的写法容易让人误判目标主机
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(blocked(raw, "URLs with embedded credentials are not allowed"));
+    }
+    let host = url
+        .host_str()
+        .map(|host| host.trim_end_matches('.').to_lowercase())
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| blocked(raw, "URL has no host"))?;
+    if !host_allowed(&host, allowlist) {
+        return Err(blocked(raw, format!("host \"{}\" is not in external_url_allowlist", host)));
+    }
+    Ok(url)
+}
+
+/// 在系统默认浏览器中打开 `url`；不满足 external_url_allowlist 策略时返回 blocked
+#[tauri::command]
+pub fn open_external(app: AppHandle, url: String) -> Result<(), OpenExternalError> {
+    let allowlist = crate::app_config(&app).external_url_allowlist;
+    let url = validate(&url, &allowlist).inspect_err(|e| app_error!("Refused to open external URL: {:?}", e))?;
+    // 查询串可能带有 OAuth state 等参数，日志只记录到路径
+    if url.scheme() == "mailto" {
+        app_log!("Opening mailto link");
+    } else {
+        app_log!("Opening external URL {}://{}{}", url.scheme(), url.host_str().unwrap_or_default(), url.path());
+    }
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| OpenExternalError::LaunchFailed { message: e.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowlist() -> Vec<String> {
+        vec!["example.com".to_string(), "*.docs.example.org".to_string()]
+    }
+
+    fn reason(raw: &str) -> String {
+        match validate(raw, &allowlist()) {
+            Err(OpenExternalError::Blocked { reason, .. }) => reason,
+            other => panic!("{:?} was not blocked: {:?}", raw, other),
+        }
+    }
+
+    #[test]
+    fn allows_http_https_and_mailto() {
+        for raw in [
+            "http://example.com/",
+            "https://example.com/docs?page=1#top",
+            "  https://EXAMPLE.com./path  ",
+            "https://api.docs.example.org/v1",
+            "mailto:support@example.net",
+            "mailto:a@example.com?subject=Hello%20World",
+        ] {
+            assert!(validate(raw, &allowlist()).is_ok(), "{:?} was rejected", raw);
+        }
+    }
+
+    #[test]
+    fn rejects_other_schemes() {
+        assert!(reason("file:///etc/passwd").contains("scheme \"file\""));
+        assert!(reason("javascript:alert(1)").contains("scheme \"javascript\""));
+        assert!(reason("JavaScript:alert(document.cookie)").contains("scheme \"javascript\""));
+        assert!(reason("data:text/html,<script>alert(1)</script>").contains("scheme \"data\""));
+        assert!(reason("duncrew://open/settings").contains("scheme \"duncrew\""));
+    }
+
+    #[test]
+    fn rejects_scheme_less_urls() {
+        for raw in ["example.com", "//example.com/path", "/relative/path", ""] {
+            assert!(reason(raw).starts_with("invalid URL"), "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_urls() {
+        let raw = format!("https://example.com/{}", "a".repeat(MAX_URL_LEN));
+        assert!(reason(&raw).contains("longer than"));
+        let fits = format!("https://example.com/{}", "a".repeat(MAX_URL_LEN - 20));
+        assert!(validate(&fits, &allowlist()).is_ok());
+    }
+
+    #[test]
+    fn rejects_control_characters() {
+        for raw in [
+            "https://example.com/\npath",
+            "https://exa\tmple.com/",
+            "https://example.com/\u{0}",
+            "https://example.com/\u{1b}[31m",
+            "mailto:a@example.com\r\nBcc:b@example.com",
+        ] {
+            assert_eq!(reason(raw), "URL contains control characters", "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn rejects_hosts_outside_the_allowlist_and_credentials() {
+        assert!(reason("https://evil.example/").contains("not in external_url_allowlist"));
+        // 通配只匹配子域名
+        assert!(reason("https://docs.example.org/").contains("not in external_url_allowlist"));
+        assert!(reason("https://example.com.evil.example/").contains("not in external_url_allowlist"));
+        assert!(reason("https://example.com@evil.example/").contains("credentials"));
+        assert!(reason("mailto:").contains("no recipient"));
+    }
+}
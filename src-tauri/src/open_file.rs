@@ -0,0 +1,56 @@
+// 双击 .ddos 导出文件打开应用：校验后复制到 <app_data_dir>/imports/staging，
+// 再以 `app://open-file` 事件交给前端导入。后端未就绪时事件会暂存而不是丢弃。
+
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+pub const EXPORT_EXTENSION: &str = "ddos";
+const STAGING_DIR: [&str; 2] = ["imports", "staging"];
+
+#[derive(Clone, serde::Serialize)]
+struct OpenFilePayload {
+    // 暂存副本，导入完成后可删除
+    path: PathBuf,
+    original_path: PathBuf,
+}
+
+fn is_export_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case(EXPORT_EXTENSION))
+        .unwrap_or(false)
+}
+
+fn stage(app: &AppHandle, source: &Path) -> Result<PathBuf, String> {
+    if !source.is_file() {
+        return Err(format!("{:?} does not exist or is not a file", source));
+    }
+    let mut dir = crate::backend_data_dir(app)?;
+    dir.extend(STAGING_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    let file_name = source.file_name().unwrap_or_default().to_string_lossy();
+    let target = dir.join(format!("{}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), file_name));
+    std::fs::copy(source, &target).map_err(|e| format!("Failed to copy {:?}: {}", source, e))?;
+    Ok(target)
+}
+
+/// 处理启动参数、第二实例转交或 macOS 打开文件事件中的路径，非 .ddos 文件会被忽略
+pub fn open_paths(app: &AppHandle, paths: impl IntoIterator<Item = PathBuf>) {
+    for path in paths.into_iter().filter(|p| is_export_file(p)) {
+        match stage(app, &path) {
+            Ok(staged) => {
+                app_log!("Opening export file {:?}", path);
+                let payload = OpenFilePayload { path: staged, original_path: path };
+                crate::app_events::emit_when_ready(app, "app://open-file", payload);
+            }
+            Err(e) => app_error!("Cannot open export file: {}", e),
+        }
+    }
+}
+
+/// 从命令行参数中取出文件路径（跳过选项与深度链接），相对路径按 cwd 解析
+pub fn paths_from_args<'a>(args: impl IntoIterator<Item = &'a String>, cwd: &Path) -> Vec<PathBuf> {
+    args.into_iter()
+        .filter(|arg| !arg.starts_with('-') && !arg.contains("://"))
+        .map(|arg| cwd.join(arg))
+        .collect()
+}
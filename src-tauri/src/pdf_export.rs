@@ -0,0 +1,234 @@
+// 导出 PDF：在隐藏的 WebView 窗口中加载应用路由或一段 HTML，等页面渲染完成后用平台 WebView 的
+// 打印能力直接写出 PDF，不需要打印机驱动。Windows 使用 WebView2 的 PrintToPdf，Linux 使用 WebKitGTK 的
+// “打印到文件”；macOS 暂不支持。
+// 路由页面渲染完成后调用 pdf_render_complete；HTML 内容以页面加载完成为准。
+// 同一时间只有一个导出在进行，其余排队；进度、完成与失败分别以 `pdf://progress` / `pdf://done` / `pdf://error` 通知。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Emitter, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tokio::sync::oneshot;
+
+const WINDOW_PREFIX: &str = "pdf-export-";
+// 页面迟迟不报告渲染完成时放弃
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const PRINT_TIMEOUT: Duration = Duration::from_secs(60);
+// 隐藏窗口按 A4 纸在 96 DPI 下的尺寸布局
+const PAGE_WIDTH: f64 = 794.0;
+const PAGE_HEIGHT: f64 = 1123.0;
+
+static NEXT_EXPORT: AtomicU64 = AtomicU64::new(1);
+// 排队执行，避免多个导出争用同一个打印流程
+static EXPORT_QUEUE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+// 等待渲染完成信号的导出，键为隐藏窗口标签
+static PENDING_READY: Mutex<Option<HashMap<String, oneshot::Sender<()>>>> = Mutex::new(None);
+
+#[derive(Clone, serde::Serialize)]
+struct PdfProgress {
+    export_id: String,
+    // "queued" / "loading" / "printing"
+    stage: &'static str,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PdfDone {
+    export_id: String,
+    path: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PdfError {
+    export_id: String,
+    message: String,
+}
+
+fn signal_ready(label: &str) -> bool {
+    let sender = PENDING_READY.lock().unwrap().as_mut().and_then(|pending| pending.remove(label));
+    match sender {
+        Some(sender) => sender.send(()).is_ok(),
+        None => false,
+    }
+}
+
+// HTML 内容写入临时文件再加载，页面可以引用同目录的相对资源
+fn write_html(app: &AppHandle, export_id: &str, html: &str) -> Result<PathBuf, String> {
+    let dir = crate::folders::cache_dir(app)?.join("pdf");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    let path = dir.join(format!("{}.html", export_id));
+    std::fs::write(&path, html).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(path)
+}
+
+// 完成回调只会被调用一次，但平台接口要求回调本身可以多次持有
+type PrintResult = Arc<Mutex<Option<oneshot::Sender<Result<(), String>>>>>;
+
+fn finish_print(sender: &PrintResult, result: Result<(), String>) {
+    if let Some(sender) = sender.lock().unwrap().take() {
+        let _ = sender.send(result);
+    }
+}
+
+#[cfg(windows)]
+fn start_print(window: &WebviewWindow, path: &Path, sender: PrintResult) -> Result<(), String> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{ICoreWebView2PrintSettings, ICoreWebView2_7};
+    use webview2_com::PrintToPdfCompletedHandler;
+    use windows_core::{Interface, HSTRING};
+
+    let path = HSTRING::from(path.to_string_lossy().as_ref());
+    window
+        .with_webview(move |webview| {
+            let handler_sender = sender.clone();
+            let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, success| {
+                let outcome = match result {
+                    Ok(()) if success => Ok(()),
+                    Ok(()) => Err("WebView2 reported that printing to PDF failed".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                finish_print(&handler_sender, outcome);
+                Ok(())
+            }));
+            // SAFETY: 在 WebView2 所在的 UI 线程上调用，COM 接口由 tauri 持有并保持有效
+            let started = unsafe {
+                webview
+                    .controller()
+                    .CoreWebView2()
+                    .and_then(|core| core.cast::<ICoreWebView2_7>())
+                    .and_then(|core| core.PrintToPdf(&path, None::<&ICoreWebView2PrintSettings>, &handler))
+            };
+            if let Err(e) = started {
+                finish_print(&sender, Err(format!("PrintToPdf is not available: {}", e)));
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn start_print(window: &WebviewWindow, path: &Path, sender: PrintResult) -> Result<(), String> {
+    use webkit2gtk::PrintOperationExt;
+
+    let uri = reqwest::Url::from_file_path(path)
+        .map_err(|_| format!("{} is not an absolute path", path.display()))?
+        .to_string();
+    window
+        .with_webview(move |webview| {
+            let operation = webkit2gtk::PrintOperation::new(&webview.inner());
+            let settings = gtk::PrintSettings::new();
+            settings.set_printer("Print to File");
+            settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+            settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(&uri));
+            operation.set_print_settings(&settings);
+            let finished = sender.clone();
+            operation.connect_finished(move |_| finish_print(&finished, Ok(())));
+            operation.connect_failed(move |_, e| finish_print(&sender, Err(e.to_string())));
+            operation.print();
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn start_print(_window: &WebviewWindow, _path: &Path, _sender: PrintResult) -> Result<(), String> {
+    Err("PDF export is not supported on this platform yet".to_string())
+}
+
+async fn print_to_pdf(window: &WebviewWindow, path: &Path) -> Result<(), String> {
+    let (tx, rx) = oneshot::channel();
+    start_print(window, path, Arc::new(Mutex::new(Some(tx))))?;
+    match tokio::time::timeout(PRINT_TIMEOUT, rx).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("Printing was interrupted".to_string()),
+        Err(_) => Err(format!("Printing did not finish within {}s", PRINT_TIMEOUT.as_secs())),
+    }
+}
+
+async fn run_export(app: &AppHandle, export_id: &str, label: &str, source: &str, output: &Path) -> Result<(), String> {
+    let (ready_tx, ready_rx) = oneshot::channel();
+    PENDING_READY
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(label.to_string(), ready_tx);
+
+    let _ = app.emit("pdf://progress", PdfProgress { export_id: export_id.to_string(), stage: "loading" });
+    let is_route = source.starts_with('/');
+    let url = if is_route {
+        WebviewUrl::App(source.trim_start_matches('/').into())
+    } else {
+        let html = write_html(app, export_id, source)?;
+        let url = reqwest::Url::from_file_path(&html).map_err(|_| format!("Invalid path {:?}", html))?;
+        WebviewUrl::External(url)
+    };
+    let window = WebviewWindowBuilder::new(app, label, url)
+        .visible(false)
+        .inner_size(PAGE_WIDTH, PAGE_HEIGHT)
+        .on_page_load(move |window, payload| {
+            // 路由页面需等待前端数据加载完，由 pdf_render_complete 报告
+            if !is_route && payload.event() == PageLoadEvent::Finished {
+                signal_ready(window.label());
+            }
+        })
+        .build()
+        .map_err(|e| format!("Failed to create export window: {}", e))?;
+
+    let result = async {
+        match tokio::time::timeout(READY_TIMEOUT, ready_rx).await {
+            Ok(Ok(())) => {}
+            _ => return Err(format!("The page did not finish rendering within {}s", READY_TIMEOUT.as_secs())),
+        }
+        let _ = app.emit("pdf://progress", PdfProgress { export_id: export_id.to_string(), stage: "printing" });
+        print_to_pdf(&window, output).await
+    }
+    .await;
+    let _ = window.destroy();
+    result
+}
+
+/// 将应用路由（以 / 开头，如 `/reports/42`）或一段 HTML 导出为 PDF，写入 `output_path`
+/// （须是 pick_save_path 选中的路径）。立即返回 export_id，导出排队在后台进行；
+/// 路由页面渲染完成后须调用 pdf_render_complete，30 秒内未调用视为失败
+#[tauri::command]
+pub fn export_to_pdf(app: AppHandle, route_or_html: String, output_path: String) -> Result<String, String> {
+    if route_or_html.starts_with('/') {
+        crate::secondary_windows::validate_route(&route_or_html)?;
+    }
+    let output = crate::file_picker::ensure_picked(&app, &output_path)?;
+    let number = NEXT_EXPORT.fetch_add(1, Ordering::SeqCst);
+    let export_id = format!("pdf-{}", number);
+    let label = format!("{}{}", WINDOW_PREFIX, number);
+
+    let result = export_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = app.emit("pdf://progress", PdfProgress { export_id: export_id.clone(), stage: "queued" });
+        let _queue = EXPORT_QUEUE.lock().await;
+        app_log!("Exporting PDF {} to {:?}", export_id, output);
+        let outcome = run_export(&app, &export_id, &label, &route_or_html, &output).await;
+        PENDING_READY.lock().unwrap().as_mut().map(|pending| pending.remove(&label));
+        if let Ok(dir) = crate::folders::cache_dir(&app) {
+            let _ = std::fs::remove_file(dir.join("pdf").join(format!("{}.html", export_id)));
+        }
+        match outcome {
+            Ok(()) => {
+                app_log!("Exported PDF {}", export_id);
+                let path = output.to_string_lossy().to_string();
+                let _ = app.emit("pdf://done", PdfDone { export_id, path });
+            }
+            Err(message) => {
+                app_error!("PDF export {} failed: {}", export_id, message);
+                let _ = app.emit("pdf://error", PdfError { export_id, message });
+            }
+        }
+    });
+    Ok(result)
+}
+
+/// 由导出窗口中的页面在内容渲染完成后调用
+#[tauri::command]
+pub fn pdf_render_complete(window: WebviewWindow) -> Result<(), String> {
+    if !window.label().starts_with(WINDOW_PREFIX) || !signal_ready(window.label()) {
+        return Err("This window is not waiting for a PDF export".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// 后端 PID 文件：记录 Sidecar 的 PID 与启动时间，
+// 上次会话崩溃遗留的后端进程在下次启动时据此清理
+
+use std::path::Path;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const SIDECAR_PROCESS_NAME: &str = "duncrew-server";
+pub const PID_FILE_NAME: &str = "backend.pid";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PidRecord {
+    pid: u32,
+    // 进程启动时间（秒级 Unix 时间戳），用于识别 PID 复用
+    start_time: u64,
+}
+
+fn refresh_process(system: &mut System, pid: Pid) {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+}
+
+/// 记录刚启动的后端进程
+pub fn write(path: &Path, pid: u32) {
+    let mut system = System::new();
+    let sys_pid = Pid::from_u32(pid);
+    refresh_process(&mut system, sys_pid);
+    let start_time = system.process(sys_pid).map(|p| p.start_time()).unwrap_or(0);
+
+    let record = PidRecord { pid, start_time };
+    if let Err(e) = serde_json::to_string(&record)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()))
+    {
+        app_error!("Failed to write PID file {:?}: {}", path, e);
+    }
+}
+
+pub fn remove(path: &Path) {
+    let _ = std::fs::remove_file(path);
+}
+
+/// 清理上次会话遗留的后端进程。
+/// 只有 PID、启动时间、进程名全部吻合时才会结束进程，避免误杀复用了该 PID 的无关进程。
+pub fn kill_stale(path: &Path) {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let Ok(record) = serde_json::from_str::<PidRecord>(&content) else {
+        remove(path);
+        return;
+    };
+
+    let mut system = System::new();
+    let sys_pid = Pid::from_u32(record.pid);
+    refresh_process(&mut system, sys_pid);
+
+    if let Some(process) = system.process(sys_pid) {
+        let name = process.name().to_string_lossy();
+        let same_process = name.starts_with(SIDECAR_PROCESS_NAME)
+            && (record.start_time == 0 || process.start_time() == record.start_time);
+        if same_process {
+            app_log!("Killing orphaned backend process (pid {})", record.pid);
+            process.kill();
+            // 等待端口和数据目录被释放
+            for _ in 0..20 {
+                std::thread::sleep(Duration::from_millis(100));
+                refresh_process(&mut system, sys_pid);
+                if system.process(sys_pid).is_none() {
+                    break;
+                }
+            }
+        } else {
+            app_log!(
+                "PID {} from stale PID file belongs to another process ({}), leaving it alone",
+                record.pid, name
+            );
+        }
+    }
+    remove(path);
+}
@@ -0,0 +1,176 @@
+// 系统睡眠 / 唤醒：唤醒后后端的连接和定时器经常已失效。挂起期间暂停健康检查计数，
+// 唤醒后立即探测一次，不通则重启后端，并广播 `backend://resumed` 让前端重新拉取数据。
+// Windows 订阅系统挂起 / 恢复通知；其他平台通过单调时钟与墙上时钟的差值检测睡眠
+// （单调时钟在睡眠期间不前进），只能在唤醒后得知，挂起通知缺失由唤醒后的宽限期弥补。
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+// 唤醒后网络与后端恢复需要一点时间，这段时间内健康检查失败不计数
+const RESUME_GRACE: Duration = Duration::from_secs(15);
+
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
+// 最近一次唤醒的 Unix 时间（秒），0 表示本次运行尚未睡眠过
+static RESUMED_AT: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerEvent {
+    // 时钟差值检测无法提前得知挂起
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Suspend,
+    Resume,
+}
+
+pub type PowerSink = Arc<dyn Fn(PowerEvent) + Send + Sync>;
+
+/// 各平台的电源事件来源
+pub trait PowerMonitor {
+    fn start(self: Box<Self>, sink: PowerSink) -> Result<(), String>;
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ResumedPayload {
+    // 唤醒后探测失败并重启了后端
+    restarted: bool,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// 健康检查是否应暂停：系统挂起中，或刚唤醒不久
+pub fn health_checks_paused() -> bool {
+    if SUSPENDED.load(Ordering::SeqCst) {
+        return true;
+    }
+    let resumed_at = RESUMED_AT.load(Ordering::SeqCst);
+    resumed_at != 0 && unix_now().saturating_sub(resumed_at) < RESUME_GRACE.as_secs()
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{PowerEvent, PowerMonitor, PowerSink};
+    use windows_sys::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND,
+    };
+
+    /// 系统挂起 / 恢复通知（与窗口收到的 WM_POWERBROADCAST 相同，但不需要窗口）
+    pub struct SuspendResumeNotification;
+
+    unsafe extern "system" fn callback(context: *const core::ffi::c_void, kind: u32, _setting: *const core::ffi::c_void) -> u32 {
+        // SAFETY: context 是 start 中泄漏的 PowerSink，在进程生命周期内有效
+        let sink = unsafe { &*(context as *const PowerSink) };
+        match kind {
+            PBT_APMSUSPEND => sink(PowerEvent::Suspend),
+            // 自动唤醒与用户唤醒都会收到，重复的 Resume 无害
+            PBT_APMRESUMEAUTOMATIC | PBT_APMRESUMESUSPEND => sink(PowerEvent::Resume),
+            _ => {}
+        }
+        0
+    }
+
+    impl PowerMonitor for SuspendResumeNotification {
+        fn start(self: Box<Self>, sink: PowerSink) -> Result<(), String> {
+            // 订阅伴随整个进程，参数与回调上下文有意泄漏
+            let context = Box::into_raw(Box::new(sink));
+            let params = Box::into_raw(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+                Callback: Some(callback),
+                Context: context as *mut core::ffi::c_void,
+            }));
+            let mut handle = std::ptr::null_mut();
+            // SAFETY: params 指向有效且不会释放的结构体
+            let result = unsafe { PowerRegisterSuspendResumeNotification(DEVICE_NOTIFY_CALLBACK, params as _, &mut handle) };
+            if result != 0 {
+                return Err(format!("PowerRegisterSuspendResumeNotification failed with error {}", result));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn monitor() -> Box<dyn PowerMonitor> {
+        Box::new(SuspendResumeNotification)
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::{PowerEvent, PowerMonitor, PowerSink};
+    use std::time::{Duration, Instant, SystemTime};
+
+    const TICK: Duration = Duration::from_secs(5);
+    // 墙上时钟比单调时钟多走超过此值即认为系统睡眠过
+    const SLEEP_THRESHOLD: Duration = Duration::from_secs(10);
+
+    /// 比较单调时钟（睡眠时停止）与墙上时钟（睡眠时继续）检测唤醒
+    pub struct ClockGap;
+
+    impl PowerMonitor for ClockGap {
+        fn start(self: Box<Self>, sink: PowerSink) -> Result<(), String> {
+            std::thread::Builder::new()
+                .name("power-monitor".to_string())
+                .spawn(move || {
+                    let mut last_instant = Instant::now();
+                    let mut last_wall = SystemTime::now();
+                    loop {
+                        std::thread::sleep(TICK);
+                        let (instant, wall) = (Instant::now(), SystemTime::now());
+                        let monotonic = instant.duration_since(last_instant);
+                        let elapsed = wall.duration_since(last_wall).unwrap_or(monotonic);
+                        if elapsed > monotonic + SLEEP_THRESHOLD {
+                            sink(PowerEvent::Resume);
+                        }
+                        last_instant = instant;
+                        last_wall = wall;
+                    }
+                })
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    pub fn monitor() -> Box<dyn PowerMonitor> {
+        Box::new(ClockGap)
+    }
+}
+
+// 唤醒后立即探测，不通则重启
+async fn handle_resume(app: AppHandle) {
+    let state = app.state::<crate::backend::ServerState>();
+    let running = state.process.lock().unwrap().pid.is_some();
+    let client = crate::backend_client::BackendClient::current(&app);
+    let healthy = crate::health::probe(&client, Duration::from_secs(3)).await.is_ok();
+    let mut restarted = false;
+    if !healthy && running {
+        app_error!("Backend did not respond after system resume, restarting");
+        match crate::backend::restart_backend_exclusive(&app, &state).await {
+            Ok(_) => restarted = true,
+            Err(e) => app_error!("Failed to restart backend after resume: {}", e),
+        }
+    }
+    let _ = app.emit("backend://resumed", ResumedPayload { restarted });
+}
+
+/// 订阅电源事件
+pub fn init(app: &AppHandle) {
+    let app = app.clone();
+    let sink: PowerSink = Arc::new(move |event| match event {
+        PowerEvent::Suspend => {
+            app_log!("System is suspending");
+            SUSPENDED.store(true, Ordering::SeqCst);
+        }
+        PowerEvent::Resume => {
+            app_log!("System resumed");
+            SUSPENDED.store(false, Ordering::SeqCst);
+            RESUMED_AT.store(unix_now(), Ordering::SeqCst);
+            tauri::async_runtime::spawn(handle_resume(app.clone()));
+        }
+    });
+    if let Err(e) = platform::monitor().start(sink) {
+        app_error!("Failed to subscribe to power events: {}", e);
+    }
+}
@@ -0,0 +1,90 @@
+// 后端进程与本进程的生命周期绑定：即使本进程被强杀（kill -9 / 任务管理器 / panic=abort），
+// 后端也会在一两秒内随之退出，不会成为孤儿进程。
+//
+// - Windows：把后端加入设置了 JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE 的 Job Object，
+//   本进程退出时系统关闭 Job 句柄并结束其中所有进程（包括 PyInstaller 拉起的子进程）。
+// - Unix：shell 插件没有 pre_exec 钩子，无法设置 PR_SET_PDEATHSIG 或进程组，
+//   改为通过 `--parent-pid` 让后端自行轮询父进程是否存活。
+
+/// 需要追加到 Sidecar 启动参数中的选项
+pub fn sidecar_args() -> Vec<String> {
+    if cfg!(unix) {
+        vec!["--parent-pid".to_string(), std::process::id().to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// 将刚启动的后端进程绑定到本进程
+pub fn attach(pid: u32) {
+    if let Err(e) = imp::attach(pid) {
+        app_error!("Failed to bind backend lifetime to app: {}", e);
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+    // Job 句柄在进程整个生命周期内保持打开，由系统在退出时关闭
+    struct Job(HANDLE);
+    unsafe impl Send for Job {}
+    unsafe impl Sync for Job {}
+
+    static JOB: OnceLock<Result<Job, String>> = OnceLock::new();
+
+    fn create_job() -> Result<Job, String> {
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(format!("CreateJobObjectW failed: {}", GetLastError()));
+            }
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let ok = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const core::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if ok == 0 {
+                let err = GetLastError();
+                CloseHandle(job);
+                return Err(format!("SetInformationJobObject failed: {}", err));
+            }
+            Ok(Job(job))
+        }
+    }
+
+    pub fn attach(pid: u32) -> Result<(), String> {
+        let job = JOB.get_or_init(create_job).as_ref().map_err(|e| e.clone())?;
+        unsafe {
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                return Err(format!("OpenProcess({}) failed: {}", pid, GetLastError()));
+            }
+            let ok = AssignProcessToJobObject(job.0, process);
+            let err = GetLastError();
+            CloseHandle(process);
+            if ok == 0 {
+                return Err(format!("AssignProcessToJobObject failed: {}", err));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod imp {
+    // 由后端根据 --parent-pid 自行退出，这里无需额外处理
+    pub fn attach(_pid: u32) -> Result<(), String> {
+        Ok(())
+    }
+}
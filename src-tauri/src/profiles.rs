@@ -0,0 +1,270 @@
+// 配置档案：应用配置目录下的 profiles.json 列出命名档案及各自的数据目录，用于完全隔离个人与工作数据。
+// 启动时按 --profile 或上次切换到的档案选择；"default" 为原有的默认数据目录，不写入文件。
+// config.json、logs/ 与 .ddos.lock 都在数据目录下，因此每个档案各有一份。
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+pub const PROFILES_FILE_NAME: &str = "profiles.json";
+pub const DEFAULT_PROFILE: &str = "default";
+// 新档案的数据目录放在默认数据目录旁，例如 %APPDATA%/com.duncrew.app.profiles/<name>
+const PROFILES_DIR_SUFFIX: &str = ".profiles";
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct ProfileEntry {
+    name: String,
+    data_dir: PathBuf,
+}
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct ProfilesFile {
+    // 上次切换到的档案，None 为默认档案
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(default)]
+    profiles: Vec<ProfileEntry>,
+}
+
+/// `list_profiles` / `create_profile` 返回值
+#[derive(Clone, serde::Serialize)]
+pub struct ProfileInfo {
+    name: String,
+    data_dir: String,
+    active: bool,
+}
+
+// `profile://switched` 事件负载
+#[derive(Clone, serde::Serialize)]
+struct SwitchedPayload {
+    name: String,
+    data_dir: String,
+}
+
+/// 本次运行使用的档案，None 为默认档案
+#[derive(Default)]
+pub struct ProfileState {
+    active: Mutex<Option<ProfileEntry>>,
+}
+
+fn profiles_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(PROFILES_FILE_NAME))
+        .map_err(|e| format!("Failed to get app config dir: {}", e))
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Result<ProfilesFile, String> {
+    let path = profiles_path(app)?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ProfilesFile::default()),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+    serde_json::from_str(&content).map_err(|e| format!("Invalid {:?}: {}", path, e))
+}
+
+fn save(app: &AppHandle, file: &ProfilesFile) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let content = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+// 名称同时用作目录名，只允许字母、数字、空格、- 和 _
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("Profile name must be 1-{} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_')) {
+        return Err("Profile name may only contain letters, digits, spaces, '-' and '_'".to_string());
+    }
+    if name.eq_ignore_ascii_case(DEFAULT_PROFILE) {
+        return Err(format!("\"{}\" is reserved for the default profile", DEFAULT_PROFILE));
+    }
+    Ok(name.to_string())
+}
+
+fn profile_dir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let default_dir = app.path().app_data_dir().map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let base = default_dir.file_name().unwrap_or_default().to_string_lossy();
+    Ok(default_dir.with_file_name(format!("{}{}", base, PROFILES_DIR_SUFFIX)).join(name))
+}
+
+/// 启动时选择档案：--profile 优先，其次是上次切换到的档案；须在首次调用 backend_data_dir 之前执行
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(ProfileState::default());
+    let requested = app.state::<crate::cli::CliArgs>().profile.clone();
+    let file = match load(app) {
+        Ok(file) => file,
+        Err(e) => {
+            app_error!("{}, using the default profile", e);
+            return;
+        }
+    };
+    let Some(name) = requested.or(file.active).filter(|name| name != DEFAULT_PROFILE) else {
+        return;
+    };
+    match file.profiles.into_iter().find(|p| p.name == name) {
+        Some(entry) => *app.state::<ProfileState>().active.lock().unwrap() = Some(entry),
+        None => app_error!("Profile \"{}\" does not exist, using the default profile", name),
+    }
+}
+
+/// 当前档案的数据目录；默认档案返回 None，由调用方使用默认数据目录
+pub fn active_data_dir<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    let state = app.try_state::<ProfileState>()?;
+    let active = state.active.lock().unwrap();
+    active.as_ref().map(|entry| entry.data_dir.clone())
+}
+
+pub fn active_name(app: &AppHandle) -> String {
+    app.try_state::<ProfileState>()
+        .and_then(|state| state.active.lock().unwrap().as_ref().map(|entry| entry.name.clone()))
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+fn info(name: &str, data_dir: &Path, active: &str) -> ProfileInfo {
+    ProfileInfo {
+        name: name.to_string(),
+        data_dir: data_dir.to_string_lossy().to_string(),
+        active: name == active,
+    }
+}
+
+/// 所有档案，默认档案排在最前
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Result<Vec<ProfileInfo>, String> {
+    let active = active_name(&app);
+    let mut profiles = vec![info(DEFAULT_PROFILE, &crate::default_data_dir(&app)?, &active)];
+    profiles.extend(load(&app)?.profiles.iter().map(|p| info(&p.name, &p.data_dir, &active)));
+    Ok(profiles)
+}
+
+/// 新建档案并创建其数据目录，不会切换过去
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<ProfileInfo, String> {
+    let name = validate_name(&name)?;
+    let mut file = load(&app)?;
+    if file.profiles.iter().any(|p| p.name.to_lowercase() == name.to_lowercase()) {
+        return Err(format!("Profile \"{}\" already exists", name));
+    }
+    let data_dir = profile_dir(&app, &name)?;
+    std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create {:?}: {}", data_dir, e))?;
+    file.profiles.push(ProfileEntry { name: name.clone(), data_dir: data_dir.clone() });
+    save(&app, &file)?;
+    app_log!("Created profile \"{}\" at {:?}", name, data_dir);
+    Ok(info(&name, &data_dir, &active_name(&app)))
+}
+
+/// 删除档案。当前档案与默认档案不能删除；`delete_data` 为 true 时同时删除数据目录，
+/// 目录正被其他 DunCrew 实例使用时拒绝
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String, delete_data: bool) -> Result<(), String> {
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    if name == active_name(&app) {
+        return Err(format!("Profile \"{}\" is in use; switch to another profile first", name));
+    }
+    let mut file = load(&app)?;
+    let index = file
+        .profiles
+        .iter()
+        .position(|p| p.name == name)
+        .ok_or_else(|| format!("Profile \"{}\" does not exist", name))?;
+
+    if delete_data {
+        let data_dir = file.profiles[index].data_dir.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            if !data_dir.exists() {
+                return Ok(());
+            }
+            // 能拿到锁说明没有其他实例在用，释放后立即删除
+            match crate::data_lock::acquire(&data_dir) {
+                Ok(lock) => drop(lock),
+                Err(crate::data_lock::LockError::Held { pid }) => {
+                    return Err(match pid {
+                        Some(pid) => format!("The profile's data is in use by another DunCrew instance (pid {})", pid),
+                        None => "The profile's data is in use by another DunCrew instance".to_string(),
+                    });
+                }
+                Err(crate::data_lock::LockError::Io(message)) => return Err(message),
+            }
+            std::fs::remove_dir_all(&data_dir).map_err(|e| format!("Failed to delete {:?}: {}", data_dir, e))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
+
+    file.profiles.remove(index);
+    if file.active.as_deref() == Some(name.as_str()) {
+        file.active = None;
+    }
+    save(&app, &file)?;
+    app_log!("Deleted profile \"{}\" (data deleted: {})", name, delete_data);
+    Ok(())
+}
+
+// 后端已停止：记录新档案，配置与 app.log 改用新数据目录
+fn switch_to(app: &AppHandle, entry: Option<ProfileEntry>) -> Result<PathBuf, String> {
+    let data_dir = match &entry {
+        Some(entry) => entry.data_dir.clone(),
+        None => crate::default_data_dir(app)?,
+    };
+    std::fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create {:?}: {}", data_dir, e))?;
+    let mut file = load(app)?;
+    file.active = entry.as_ref().map(|entry| entry.name.clone());
+    save(app, &file)?;
+
+    *app.state::<ProfileState>().active.lock().unwrap() = entry;
+    crate::pid_file::kill_stale(&data_dir.join(crate::pid_file::PID_FILE_NAME));
+    if let Err(e) = app.state::<crate::config::ConfigState>().relocate(crate::config::config_path(&data_dir)) {
+        app_error!("{}, using default settings", e);
+    }
+    crate::logs::init_app_log(&data_dir.join(crate::logs::LOGS_DIR_NAME));
+    crate::logs::apply_filter(&app.state::<crate::config::ConfigState>().get().app_log_filter);
+    Ok(data_dir)
+}
+
+/// 切换到另一个档案：优雅停止后端，改用该档案的数据目录（及其中的配置）重新启动，
+/// 并广播 `profile://switched`，前端应重新加载数据。命令行指定了 --data-dir 时不可用
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<String, String> {
+    if app.state::<crate::cli::CliArgs>().data_dir.is_some() {
+        return Err("The data directory is set by --data-dir; profiles cannot be switched".to_string());
+    }
+    let current = active_name(&app);
+    if name == current {
+        return crate::backend_data_dir(&app).map(|dir| dir.to_string_lossy().to_string());
+    }
+    let entry = if name == DEFAULT_PROFILE {
+        None
+    } else {
+        let found = load(&app)?.profiles.into_iter().find(|p| p.name == name);
+        Some(found.ok_or_else(|| format!("Profile \"{}\" does not exist", name))?)
+    };
+
+    app_log!("Switching profile from \"{}\" to \"{}\"", current, name);
+    let app_handle = app.clone();
+    let spec = crate::jobs::JobSpec::new(crate::jobs::JobKind::Migration, format!("profile {}", name));
+    let data_dir = crate::jobs::run(&app, spec, |_| async {
+        crate::backend::with_backend_stopped(&app, "profile", move || switch_to(&app_handle, entry))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r)
+    })
+    .await
+    .inspect_err(|e| app_error!("Failed to switch profile: {}", e))?;
+
+    app_log!("Now using profile \"{}\" at {:?}", name, data_dir);
+    let data_dir = data_dir.to_string_lossy().to_string();
+    let _ = app.emit("profile://switched", SwitchedPayload { name, data_dir: data_dir.clone() });
+    Ok(data_dir)
+}
@@ -0,0 +1,108 @@
+// 任务进度：后端在 stdout 输出 `{"progress": 0.42, "job": "index"}` 这样的行报告长时间任务的进度，
+// 这类行不写入日志，由这里汇总后显示在系统上：Windows 任务栏按钮、macOS 程序坞图标的进度条，
+// 其他平台（Linux 多数桌面不支持进度条）写进托盘提示文字。前端也可以用 set_progress 直接设置。
+// progress 为 null、不小于 1 或带 "done": true 表示任务结束；后端退出或重启时清除其全部任务。
+// 同时有多个任务时显示平均进度；变化时发送 `app://progress`。
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Emitter, Manager};
+
+// set_progress 设置的进度在任务表中使用的名称
+const FRONTEND_JOB: &str = "app";
+
+// 进行中的任务及其进度（0..1）
+static JOBS: Mutex<BTreeMap<String, f64>> = Mutex::new(BTreeMap::new());
+
+#[derive(serde::Deserialize)]
+struct ProgressLine {
+    job: String,
+    progress: Option<f64>,
+    #[serde(default)]
+    done: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct JobProgress {
+    job: String,
+    progress: f64,
+}
+
+/// `app://progress` 事件负载；没有进行中的任务时 value 为 null
+#[derive(Clone, serde::Serialize)]
+struct ProgressPayload {
+    value: Option<f64>,
+    jobs: Vec<JobProgress>,
+}
+
+fn apply(app: &AppHandle, jobs: &BTreeMap<String, f64>) {
+    let value = (!jobs.is_empty()).then(|| jobs.values().sum::<f64>() / jobs.len() as f64);
+    if let Some(window) = app.get_webview_window("main") {
+        let state = match value {
+            Some(value) => ProgressBarState {
+                status: Some(ProgressBarStatus::Normal),
+                progress: Some((value * 100.0).round() as u64),
+            },
+            None => ProgressBarState { status: Some(ProgressBarStatus::None), progress: None },
+        };
+        let _ = window.set_progress_bar(state);
+    }
+    if !cfg!(any(windows, target_os = "macos")) {
+        let text = value.map(|value| {
+            let job = jobs.keys().cloned().collect::<Vec<_>>().join(", ");
+            crate::i18n::tf("tray.tooltip.progress", &[("job", &job), ("percent", &format!("{:.0}", value * 100.0))])
+        });
+        crate::tray::set_progress(app, text);
+    }
+    let jobs = jobs.iter().map(|(job, progress)| JobProgress { job: job.clone(), progress: *progress }).collect();
+    let _ = app.emit("app://progress", ProgressPayload { value, jobs });
+}
+
+fn update(app: &AppHandle, job: &str, progress: Option<f64>) {
+    let mut jobs = JOBS.lock().unwrap();
+    let changed = match progress.filter(|value| value.is_finite() && *value < 1.0) {
+        Some(value) => jobs.insert(job.to_string(), value.max(0.0)) != Some(value.max(0.0)),
+        None => jobs.remove(job).is_some(),
+    };
+    if changed {
+        apply(app, &jobs);
+    }
+}
+
+/// 后端的一行 stdout 是进度报告时处理并返回 true，调用方不再把它写入日志
+pub fn handle_backend_line(app: &AppHandle, raw: &str) -> bool {
+    let raw = raw.trim();
+    if !raw.starts_with('{') || !raw.contains("\"progress\"") {
+        return false;
+    }
+    let Ok(line) = serde_json::from_str::<ProgressLine>(raw) else {
+        return false;
+    };
+    // 避免后端把前端设置的进度清掉
+    if line.job.is_empty() || line.job == FRONTEND_JOB {
+        return false;
+    }
+    update(app, &line.job, if line.done { None } else { line.progress });
+    true
+}
+
+/// 后端退出或重启：清除后端报告的任务，前端设置的进度保留
+pub fn clear_backend_jobs(app: &AppHandle) {
+    let mut jobs = JOBS.lock().unwrap();
+    let before = jobs.len();
+    jobs.retain(|job, _| job == FRONTEND_JOB);
+    if jobs.len() != before {
+        apply(app, &jobs);
+    }
+}
+
+/// 设置任务栏 / 程序坞进度（0 到 1），1 或 None 表示完成并清除；与后端报告的任务一起汇总显示
+#[tauri::command]
+pub fn set_progress(app: AppHandle, value: Option<f64>) -> Result<(), String> {
+    if value.is_some_and(|value| !(0.0..=1.0).contains(&value)) {
+        return Err("value must be between 0 and 1".to_string());
+    }
+    update(&app, FRONTEND_JOB, value);
+    Ok(())
+}
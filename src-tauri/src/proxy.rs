@@ -0,0 +1,281 @@
+// 后端请求代理：前端经 `backend_request` 命令由 Rust 转发请求到本机后端，
+// 避开 WebView 的 CORS / 混合内容限制，也不必在前端拼接端口。会话 token 自动附加。
+// 只转发 /api/ 下的路径；大响应体写入 <data_dir>/cache/responses 并返回文件路径，不走 IPC。
+
+use base64::Engine;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+
+use crate::backend::AUTH_TOKEN_HEADER;
+use crate::backend_client::{BackendClient, BackendClientError};
+
+const ALLOWED_PREFIX: &str = "/api/";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_TIMEOUT: Duration = Duration::from_secs(300);
+// 超过此大小的响应体写入临时文件
+const INLINE_BODY_LIMIT: usize = 1024 * 1024;
+const RESPONSES_DIR: &str = "responses";
+// 临时响应文件保留一小时，之后在下次写入时清理
+const RESPONSE_FILE_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestBody {
+    Json { value: serde_json::Value },
+    Base64 { data: String },
+}
+
+#[derive(serde::Deserialize)]
+pub struct BackendRequest {
+    method: String,
+    // 以 /api/ 开头，可带查询串
+    path: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    body: Option<RequestBody>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseBody {
+    Empty,
+    Json { value: serde_json::Value },
+    Text { text: String },
+    Base64 { data: String },
+    // 超过 INLINE_BODY_LIMIT 的响应体
+    File { path: String, size: u64 },
+}
+
+#[derive(serde::Serialize)]
+pub struct BackendResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: ResponseBody,
+}
+
+/// 代理失败原因；backend_down 表示后端未在监听，前端可据此提示“后端未运行”
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProxyError {
+    BackendDown { base_url: String },
+    PathNotAllowed { path: String },
+    Timeout { timeout_ms: u64 },
+    InvalidRequest { message: String },
+    Other { message: String },
+}
+
+impl From<String> for ProxyError {
+    fn from(message: String) -> Self {
+        ProxyError::Other { message }
+    }
+}
+
+impl From<BackendClientError> for ProxyError {
+    fn from(error: BackendClientError) -> Self {
+        match error {
+            BackendClientError::ConnectionRefused { base_url } => ProxyError::BackendDown { base_url },
+            BackendClientError::Timeout { timeout_ms } => ProxyError::Timeout { timeout_ms },
+            BackendClientError::InvalidRequest { message } => ProxyError::InvalidRequest { message },
+            other => ProxyError::Other { message: other.to_string() },
+        }
+    }
+}
+
+pub fn invalid(message: impl Into<String>) -> ProxyError {
+    ProxyError::InvalidRequest { message: message.into() }
+}
+
+/// 拒绝 /api/ 之外的路径及 .. 片段，防止借代理访问 /shutdown 等管理端点
+pub fn validate_path(path: &str) -> Result<(), ProxyError> {
+    let route = path.split(['?', '#']).next().unwrap_or_default();
+    let allowed = route.starts_with(ALLOWED_PREFIX)
+        && !route.contains('\\')
+        && !route.split('/').any(|segment| segment == ".." || segment == "." || segment.contains('%'));
+    if !allowed {
+        return Err(ProxyError::PathNotAllowed { path: path.to_string() });
+    }
+    Ok(())
+}
+
+fn response_headers(response: &reqwest::Response) -> BTreeMap<String, String> {
+    let mut headers = BTreeMap::new();
+    for (name, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        headers
+            .entry(name.as_str().to_string())
+            .and_modify(|existing: &mut String| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    headers
+}
+
+fn inline_body(bytes: Vec<u8>, content_type: &str) -> ResponseBody {
+    if bytes.is_empty() {
+        return ResponseBody::Empty;
+    }
+    if content_type.contains("json") {
+        if let Ok(value) = serde_json::from_slice(&bytes) {
+            return ResponseBody::Json { value };
+        }
+    }
+    let textual = content_type.starts_with("text/") || content_type.contains("json") || content_type.contains("xml");
+    match String::from_utf8(bytes) {
+        Ok(text) if textual => ResponseBody::Text { text },
+        Ok(text) => ResponseBody::Base64 { data: base64::engine::general_purpose::STANDARD.encode(text) },
+        Err(e) => ResponseBody::Base64 { data: base64::engine::general_purpose::STANDARD.encode(e.into_bytes()) },
+    }
+}
+
+// 删除过期的临时响应文件
+async fn clean_responses_dir(dir: &Path) {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let expired = entry
+            .metadata()
+            .await
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > RESPONSE_FILE_TTL);
+        if expired {
+            let _ = tokio::fs::remove_file(entry.path()).await;
+        }
+    }
+}
+
+async fn create_response_file(app: &AppHandle) -> Result<(PathBuf, tokio::fs::File), ProxyError> {
+    let dir = crate::folders::cache_dir(app)?.join(RESPONSES_DIR);
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    clean_responses_dir(&dir).await;
+    let mut suffix = [0u8; 8];
+    getrandom::fill(&mut suffix).map_err(|e| e.to_string())?;
+    let name: String = suffix.iter().map(|b| format!("{:02x}", b)).collect();
+    let path = dir.join(format!("{}.body", name));
+    let file = tokio::fs::File::create(&path).await.map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+    Ok((path, file))
+}
+
+// 转存中途写入失败：删除不完整的文件，请求按失败返回，不把截断的文件交给前端
+async fn spill_failed(path: &Path, error: std::io::Error) -> ProxyError {
+    app_error!("Failed to write {:?}: {}", path, error);
+    let _ = tokio::fs::remove_file(path).await;
+    format!("Failed to write response body to {:?}: {}", path, error).into()
+}
+
+// 读取响应体：不超过上限时留在内存，否则连同已读部分一起转存到文件
+async fn read_body(
+    app: &AppHandle,
+    client: &BackendClient,
+    timeout: Duration,
+    mut response: reqwest::Response,
+    content_type: &str,
+) -> Result<ResponseBody, ProxyError> {
+    let mut buffer = Vec::new();
+    let mut spill: Option<(PathBuf, tokio::fs::File, u64)> = None;
+    // 转存失败过一次后不再尝试，余下部分留在内存
+    let mut inline_only = false;
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                if let Some((path, _, _)) = &spill {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+                return Err(client.error(e, Some(timeout)).into());
+            }
+        };
+        if let Some((path, file, size)) = spill.as_mut() {
+            if let Err(e) = file.write_all(&chunk).await {
+                return Err(spill_failed(path, e).await);
+            }
+            *size += chunk.len() as u64;
+            continue;
+        }
+        buffer.extend_from_slice(&chunk);
+        if !inline_only && buffer.len() > INLINE_BODY_LIMIT {
+            inline_only = true;
+            match create_response_file(app).await {
+                Ok((path, mut file)) => match file.write_all(&buffer).await {
+                    Ok(()) => {
+                        spill = Some((path, file, buffer.len() as u64));
+                        buffer = Vec::new();
+                    }
+                    // 第一次写入就失败时数据还在内存里，退回到内存
+                    Err(e) => {
+                        app_error!("Failed to write {:?}: {}", path, e);
+                        let _ = tokio::fs::remove_file(&path).await;
+                    }
+                },
+                // 无法创建文件时退回到内存，请求仍然成功
+                Err(e) => app_error!("Failed to spill response body to disk: {:?}", e),
+            }
+        }
+    }
+    Ok(match spill {
+        Some((path, mut file, size)) => {
+            if let Err(e) = file.flush().await {
+                return Err(spill_failed(&path, e).await);
+            }
+            ResponseBody::File { path: path.to_string_lossy().to_string(), size }
+        }
+        None => inline_body(buffer, content_type),
+    })
+}
+
+/// 将请求转发到 `http://127.0.0.1:<port><path>` 并自动附加会话 token。
+/// `path` 必须在 /api/ 下；响应体按 Content-Type 返回为 JSON、文本或 base64，
+/// 超过 1MB 时写入临时文件并返回其路径（文件一小时后清理）。没有请求体的 GET / HEAD 在后端暂时不可用时自动重试。
+#[tauri::command]
+pub async fn backend_request(app: AppHandle, request: BackendRequest) -> Result<BackendResponse, ProxyError> {
+    validate_path(&request.path)?;
+    let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+        .map_err(|_| invalid(format!("Invalid HTTP method \"{}\"", request.method)))?;
+    let timeout = request
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT)
+        .min(MAX_TIMEOUT);
+    // GET / HEAD 没有请求体时可以安全重试
+    let idempotent = (method == reqwest::Method::GET || method == reqwest::Method::HEAD) && request.body.is_none();
+
+    let client = BackendClient::current(&app);
+    let mut builder = client.request(method, &request.path);
+    for (name, value) in &request.headers {
+        // token 由这里统一附加，不允许前端覆盖
+        if name.eq_ignore_ascii_case(AUTH_TOKEN_HEADER) || name.eq_ignore_ascii_case("host") {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+    builder = match request.body {
+        Some(RequestBody::Json { value }) => builder.json(&value),
+        Some(RequestBody::Base64 { data }) => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .map_err(|e| invalid(format!("Invalid base64 body: {}", e)))?;
+            builder.body(bytes)
+        }
+        None => builder,
+    };
+
+    let response = if idempotent {
+        client.send_idempotent(builder, timeout).await?
+    } else {
+        client.send(builder, Some(timeout)).await?
+    };
+    let status = response.status().as_u16();
+    let headers = response_headers(&response);
+    let content_type = headers.get("content-type").cloned().unwrap_or_default().to_lowercase();
+    let body = read_body(&app, &client, timeout, response, &content_type).await?;
+    Ok(BackendResponse { status, headers, body })
+}
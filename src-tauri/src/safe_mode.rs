@@ -0,0 +1,90 @@
+// 安全模式：插件损坏、缓存损坏等导致后端反复崩溃时的恢复手段，无需删除数据目录。
+// 安全模式下 Sidecar 附带 --safe-mode 启动（由后端跳过插件与缓存，只提供基本功能），崩溃后不再自动重启。
+// 进入方式：启动时按住 Shift、命令行 --safe-mode，或崩溃循环提示中选择以安全模式重启（无界面模式下自动进入）。
+// 每次进入或退出都发送 `app://safe-mode`；前端调用 restart_backend(safeMode: false) 退出并正常重启后端。
+
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// 安全模式下追加到 Sidecar 启动参数
+pub const SIDECAR_ARG: &str = "--safe-mode";
+
+// 进入安全模式的原因："shift" / "cli" / "crash_loop" / "manual"；None 表示正常模式
+static REASON: Mutex<Option<&'static str>> = Mutex::new(None);
+
+// `app://safe-mode` 事件负载
+#[derive(Clone, serde::Serialize)]
+struct SafeModePayload {
+    active: bool,
+    reason: Option<&'static str>,
+}
+
+#[cfg(windows)]
+fn shift_held() -> bool {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_SHIFT};
+    // 最高位表示按键当前处于按下状态
+    unsafe { GetAsyncKeyState(VK_SHIFT.into()) as u16 & 0x8000 != 0 }
+}
+
+#[cfg(target_os = "macos")]
+fn shift_held() -> bool {
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const FLAG_MASK_SHIFT: u64 = 0x0002_0000;
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceFlagsState(state_id: i32) -> u64;
+    }
+    unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) & FLAG_MASK_SHIFT != 0 }
+}
+
+// setup 时 GTK 已初始化，从默认显示的键盘映射读取修饰键状态
+#[cfg(target_os = "linux")]
+fn shift_held() -> bool {
+    let Some(keymap) = gtk::gdk::Display::default().and_then(|display| gtk::gdk::Keymap::for_display(&display)) else {
+        return false;
+    };
+    keymap.modifier_state() & gtk::gdk::ModifierType::SHIFT_MASK.bits() != 0
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn shift_held() -> bool {
+    false
+}
+
+fn set(app: &AppHandle, reason: Option<&'static str>) {
+    if std::mem::replace(&mut *REASON.lock().unwrap(), reason) == reason {
+        return;
+    }
+    crate::app_events::emit_when_ready(app, "app://safe-mode", SafeModePayload { active: reason.is_some(), reason });
+}
+
+/// 在 setup 中、启动后端之前调用：按命令行参数或 Shift 键决定是否以安全模式启动
+pub fn init(app: &AppHandle, cli_flag: bool) {
+    let reason = if cli_flag {
+        "cli"
+    } else if !crate::headless::enabled(app) && shift_held() {
+        "shift"
+    } else {
+        return;
+    };
+    app_log!("Starting in safe mode ({})", reason);
+    set(app, Some(reason));
+}
+
+pub fn is_active() -> bool {
+    REASON.lock().unwrap().is_some()
+}
+
+pub fn enter(app: &AppHandle, reason: &'static str) {
+    if !is_active() {
+        app_log!("Entering safe mode ({})", reason);
+    }
+    set(app, Some(reason));
+}
+
+pub fn exit(app: &AppHandle) {
+    if is_active() {
+        app_log!("Leaving safe mode");
+    }
+    set(app, None);
+}
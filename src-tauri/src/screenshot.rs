@@ -0,0 +1,156 @@
+// 主窗口截图：用户反馈界面问题时由前端调用，不必让用户自己截图再附上。
+// 截取的是 WebView 渲染的页面内容（不含系统标题栏与其上层的其他窗口），写成 PNG 放在缓存目录 screenshots/ 下。
+// Windows 使用 WebView2 的 CapturePreview，Linux 使用 WebKitGTK 的 snapshot；macOS 暂不支持。
+// 两者都按 WebView 的物理像素输出，窗口在不同缩放比例的显示器之间移动也不会被裁切或拉伸。
+// config.json 的 allow_screenshots 为 false 时拒绝截图，也不会把已暂存的截图放进诊断包。
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tokio::sync::oneshot;
+
+const SCREENSHOTS_DIR_NAME: &str = "screenshots";
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 等待下一次诊断包导出的截图
+static PENDING: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+// 同 pdf_export：完成回调只会被调用一次，但启动失败时也需要用同一个发送端报告
+type CaptureResult = Arc<Mutex<Option<oneshot::Sender<Result<(), String>>>>>;
+
+fn finish_capture(sender: &CaptureResult, result: Result<(), String>) {
+    if let Some(sender) = sender.lock().unwrap().take() {
+        let _ = sender.send(result);
+    }
+}
+
+#[cfg(windows)]
+fn start_capture(window: &WebviewWindow, path: &Path, sender: CaptureResult) -> Result<(), String> {
+    use webview2_com::CapturePreviewCompletedHandler;
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG;
+    use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+    use windows::Win32::System::Com::{IStream, STGM_CREATE, STGM_WRITE};
+    use windows::Win32::UI::Shell::SHCreateStreamOnFileEx;
+    use windows_core::HSTRING;
+
+    let path = HSTRING::from(path.to_string_lossy().as_ref());
+    window
+        .with_webview(move |webview| {
+            // SAFETY: 参数均为有效值，返回的流由 windows 的 IStream 包装管理引用计数
+            let stream = unsafe {
+                SHCreateStreamOnFileEx(&path, (STGM_CREATE | STGM_WRITE).0, FILE_ATTRIBUTE_NORMAL.0, true, None::<&IStream>)
+            };
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => return finish_capture(&sender, Err(format!("Failed to create {}: {}", path, e))),
+            };
+            let handler_sender = sender.clone();
+            let file = stream.clone();
+            let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+                // 释放流即关闭文件，之后再报告完成
+                drop(file);
+                finish_capture(&handler_sender, result.map_err(|e| e.to_string()));
+                Ok(())
+            }));
+            // SAFETY: 在 WebView2 所在的 UI 线程上调用，COM 接口由 tauri 持有并保持有效
+            let started = unsafe {
+                webview
+                    .controller()
+                    .CoreWebView2()
+                    .and_then(|core| core.CapturePreview(COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG, &stream, &handler))
+            };
+            if let Err(e) = started {
+                finish_capture(&sender, Err(format!("CapturePreview is not available: {}", e)));
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn write_png(surface: &cairo::Surface, path: &Path) -> Result<(), String> {
+    let mut file = std::fs::File::create(path).map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+    surface.write_to_png(&mut file).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+#[cfg(target_os = "linux")]
+fn start_capture(window: &WebviewWindow, path: &Path, sender: CaptureResult) -> Result<(), String> {
+    use webkit2gtk::{SnapshotOptions, SnapshotRegion, WebViewExt};
+
+    let path = path.to_path_buf();
+    window
+        .with_webview(move |webview| {
+            // 快照表面按设备缩放比例分配像素，写出的 PNG 即为物理分辨率
+            webview.inner().snapshot(
+                SnapshotRegion::Visible,
+                SnapshotOptions::NONE,
+                None::<&webkit2gtk::gio::Cancellable>,
+                move |result| {
+                    let outcome = result.map_err(|e| e.to_string()).and_then(|surface| write_png(&surface, &path));
+                    finish_capture(&sender, outcome);
+                },
+            );
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn start_capture(_window: &WebviewWindow, _path: &Path, _sender: CaptureResult) -> Result<(), String> {
+    Err("Window screenshots are not supported on this platform yet".to_string())
+}
+
+async fn capture(window: &WebviewWindow, path: &Path) -> Result<(), String> {
+    let (tx, rx) = oneshot::channel();
+    start_capture(window, path, Arc::new(Mutex::new(Some(tx))))?;
+    match tokio::time::timeout(CAPTURE_TIMEOUT, rx).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("Capture was interrupted".to_string()),
+        Err(_) => Err(format!("Capture did not finish within {}s", CAPTURE_TIMEOUT.as_secs())),
+    }
+}
+
+/// 下一次诊断包导出要附带的截图；allow_screenshots 关闭后不再返回
+pub fn pending(app: &AppHandle) -> Option<PathBuf> {
+    if !crate::app_config(app).allow_screenshots {
+        return None;
+    }
+    PENDING.lock().unwrap().clone().filter(|path| path.is_file())
+}
+
+/// 诊断包已写入截图，或用户关闭了 allow_screenshots：删除暂存的截图
+pub fn clear_pending() {
+    if let Some(path) = PENDING.lock().unwrap().take() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 截取主窗口内容，保存为 PNG 并返回路径。include_in_diagnostics 为 true 时同时暂存，
+/// 下一次 export_diagnostics 会以 screenshot.png 放入诊断包（替换之前暂存的截图）
+#[tauri::command]
+pub async fn capture_window_screenshot(app: AppHandle, include_in_diagnostics: bool) -> Result<String, String> {
+    if !crate::app_config(&app).allow_screenshots {
+        return Err("Screenshots are disabled in settings (allow_screenshots)".to_string());
+    }
+    let window = app.get_webview_window("main").ok_or("Main window is not available")?;
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true) {
+        return Err("The main window must be visible to take a screenshot".to_string());
+    }
+    let dir = crate::folders::cache_dir(&app)?.join(SCREENSHOTS_DIR_NAME);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    let path = dir.join(format!("screenshot-{}.png", chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")));
+
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let scale = window.scale_factor().unwrap_or(1.0);
+    if let Err(e) = capture(&window, &path).await {
+        app_error!("Failed to capture the main window: {}", e);
+        let _ = std::fs::remove_file(&path);
+        return Err(e);
+    }
+    app_log!("Captured the main window ({}x{} px, scale {}) to {:?}", size.width, size.height, scale, path);
+
+    if include_in_diagnostics {
+        clear_pending();
+        *PENDING.lock().unwrap() = Some(path.clone());
+    }
+    Ok(path.to_string_lossy().to_string())
+}
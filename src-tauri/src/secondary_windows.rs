@@ -0,0 +1,166 @@
+// 辅助窗口：日志查看器、弹出的文档等指向前端路由的额外窗口，标签统一带 secondary- 前缀。
+// 它们与主窗口共用同一个后端，通过相同的命令读取 ServerState，关闭时不影响后端。
+// 关闭主窗口会停止后端：仍有辅助窗口打开时先询问，确认后连同辅助窗口一起关闭。
+
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder, Window};
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind};
+
+use crate::alerts::{self, Alert, AlertCategory, AlertPriority};
+use crate::i18n::{t, tf};
+
+pub const SECONDARY_PREFIX: &str = "secondary-";
+const MAIN_WINDOW_LABEL: &str = "main";
+const MAX_LABEL_LEN: usize = 64;
+
+// Tauri 窗口标签只允许字母、数字和 -/:_，这里再收紧为字母、数字、- 和 _
+fn validate_label(label: &str) -> Result<(), String> {
+    if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        return Err(format!("Window label must be 1-{} characters", MAX_LABEL_LEN));
+    }
+    if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Window label may only contain ASCII letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+/// 只允许应用内路由，不能借此打开外部页面
+pub fn validate_route(route: &str) -> Result<(), String> {
+    if !route.starts_with('/') || route.starts_with("//") || route.contains('\\') || route.contains("://") {
+        return Err(format!("\"{}\" is not an app route (expected a path such as /logs)", route));
+    }
+    Ok(())
+}
+
+/// 窗口请求关闭时的处理方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseAction {
+    /// 辅助窗口、启动画面等其他窗口：照常关闭，应用与后端不受影响
+    Close,
+    /// 主窗口隐藏到托盘，后端继续运行
+    Hide,
+    /// 主窗口关闭前确认，确认后连同辅助窗口一起关闭
+    Confirm,
+    /// 停止后端后退出应用
+    Exit,
+}
+
+/// hide_to_tray：开启了 close_to_tray（或以托盘方式启动）且托盘可用；secondary_open：打开的辅助窗口数
+pub fn close_action(label: &str, hide_to_tray: bool, secondary_open: usize) -> CloseAction {
+    if label != MAIN_WINDOW_LABEL {
+        CloseAction::Close
+    } else if hide_to_tray {
+        CloseAction::Hide
+    } else if secondary_open > 0 {
+        CloseAction::Confirm
+    } else {
+        CloseAction::Exit
+    }
+}
+
+/// 当前打开的辅助窗口
+pub fn secondary_windows<R: Runtime>(app: &AppHandle<R>) -> Vec<WebviewWindow<R>> {
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| label.starts_with(SECONDARY_PREFIX))
+        .map(|(_, window)| window)
+        .collect()
+}
+
+/// close_action 为 Confirm 时调用：弹窗确认，调用方应阻止本次关闭，确认后由这里销毁所有窗口
+pub fn confirm_close_main(app: &AppHandle, main: &Window, count: usize) {
+    let main = main.clone();
+    let message = if count == 1 { t("dialog.close_all.one") } else { tf("dialog.close_all.other", &[("count", &count)]) };
+    let alert = Alert::new(AlertCategory::General, message)
+        .kind(MessageDialogKind::Warning)
+        .priority(AlertPriority::High)
+        .buttons(MessageDialogButtons::OkCancelCustom(t("button.close_all"), t("button.cancel")));
+    alerts::ask(app, alert, {
+        let app = app.clone();
+        move |close_all| {
+            if !close_all {
+                return;
+            }
+            for window in secondary_windows(&app) {
+                let _ = window.destroy();
+            }
+            // destroy 不会再次触发 CloseRequested，Destroyed 事件负责停止后端
+            let _ = main.destroy();
+        }
+    });
+}
+
+/// 打开指向前端路由 `route`（如 `/logs`）的辅助窗口，返回窗口标签（`secondary-<label>`）。
+/// 同名窗口已存在时改为聚焦该窗口
+#[tauri::command]
+pub fn open_secondary_window(app: AppHandle, label: String, route: String, title: Option<String>) -> Result<String, String> {
+    validate_label(&label)?;
+    validate_route(&route)?;
+    let label = format!("{}{}", SECONDARY_PREFIX, label);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(label);
+    }
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(route.trim_start_matches('/').into()))
+        .title(title.unwrap_or_else(|| "DunCrew".to_string()))
+        .inner_size(960.0, 640.0)
+        .min_inner_size(480.0, 320.0)
+        .build()
+        .map_err(|e| format!("Failed to open window {}: {}", label, e))?;
+    crate::zoom::restore(&window);
+    app_log!("Opened secondary window {} at {}", label, route);
+    Ok(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::test::{mock_builder, mock_context, noop_assets};
+
+    #[test]
+    fn closing_other_windows_never_exits_or_stops_the_backend() {
+        for label in ["secondary-logs", "secondary-docs", "splash"] {
+            for hide_to_tray in [false, true] {
+                for secondary_open in [0, 1, 3] {
+                    assert_eq!(close_action(label, hide_to_tray, secondary_open), CloseAction::Close);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closing_the_main_window() {
+        assert_eq!(close_action("main", true, 2), CloseAction::Hide);
+        assert_eq!(close_action("main", false, 2), CloseAction::Confirm);
+        assert_eq!(close_action("main", false, 0), CloseAction::Exit);
+    }
+
+    #[test]
+    fn secondary_windows_exclude_main_and_splash() {
+        let app = mock_builder().build(mock_context(noop_assets())).unwrap();
+        for label in ["main", "splash", "secondary-logs", "secondary-docs"] {
+            WebviewWindowBuilder::new(&app, label, WebviewUrl::default()).build().unwrap();
+        }
+        let mut labels: Vec<String> = secondary_windows(app.handle()).iter().map(|w| w.label().to_string()).collect();
+        labels.sort();
+        assert_eq!(labels, ["secondary-docs", "secondary-logs"]);
+
+        // 关闭一个辅助窗口后主窗口仍在，再关闭主窗口需要确认剩下的那个
+        app.get_webview_window("secondary-logs").unwrap().destroy().unwrap();
+        assert!(app.get_webview_window("main").is_some());
+        assert_eq!(close_action("main", false, secondary_windows(app.handle()).len()), CloseAction::Confirm);
+    }
+
+    #[test]
+    fn routes_and_labels_are_validated() {
+        assert!(validate_route("/logs").is_ok());
+        for route in ["logs", "//evil.example", "/a\\b", "/redirect?to=https://evil.example"] {
+            assert!(validate_route(route).is_err(), "{:?}", route);
+        }
+        assert!(validate_label("logs_2").is_ok());
+        assert!(validate_label("").is_err());
+        assert!(validate_label("a/b").is_err());
+        assert!(validate_label(&"a".repeat(MAX_LABEL_LEN + 1)).is_err());
+    }
+}
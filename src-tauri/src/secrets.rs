@@ -0,0 +1,224 @@
+// API key 等凭据存放在系统钥匙串（Windows 凭据管理器 / macOS 钥匙串 / libsecret），不写入配置文件或 localStorage。
+// 钥匙串无法列举条目，已保存的名称（不含值）记录在数据目录的 secrets.json 中。
+// 启动后端时每个凭据以环境变量 DDOS_<NAME> 传入（如 llm_api_key → DDOS_LLM_API_KEY），不经 HTTP 传递；
+// 诊断包不包含凭据，日志中出现的凭据值也会替换为 ***。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SERVICE: &str = "com.duncrew.desktop";
+const INDEX_FILE_NAME: &str = "secrets.json";
+const ENV_PREFIX: &str = "DDOS_";
+const MAX_NAME_LEN: usize = 64;
+
+// 读写 secrets.json 时串行，避免并发修改丢失名称
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+// 名称会成为环境变量名的一部分，只允许小写字母、数字和下划线
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!("Secret name must be 1-{} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+        return Err(format!("Secret name \"{}\" may only contain a-z, 0-9 and '_'", name));
+    }
+    Ok(())
+}
+
+fn entry(name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, name).map_err(|e| format!("Keychain is not available: {}", e))
+}
+
+fn index_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(INDEX_FILE_NAME))
+}
+
+fn read_index(app: &AppHandle) -> BTreeSet<String> {
+    index_path(app).map(|path| read_index_file(&path)).unwrap_or_default()
+}
+
+// 文件不存在或已损坏时视为没有已保存的名称
+fn read_index_file(path: &Path) -> BTreeSet<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn update_index(app: &AppHandle, name: &str, present: bool) -> Result<(), String> {
+    update_index_file(&index_path(app)?, name, present)
+}
+
+fn update_index_file(path: &Path, name: &str, present: bool) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let mut names = read_index_file(path);
+    let changed = if present { names.insert(name.to_string()) } else { names.remove(name) };
+    if !changed {
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(&names).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn store(app: &AppHandle, name: &str, value: &str) -> Result<(), String> {
+    validate_name(name)?;
+    if value.is_empty() {
+        return Err("Secret value must not be empty".to_string());
+    }
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to save secret {}: {}", name, e))?;
+    update_index(app, name, true)
+}
+
+fn load(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret {}: {}", name, e)),
+    }
+}
+
+/// 已保存的全部凭据，读取失败的条目跳过
+fn all(app: &AppHandle) -> BTreeMap<String, String> {
+    read_index(app)
+        .into_iter()
+        .filter_map(|name| match load(&name) {
+            Ok(value) => value.map(|value| (name, value)),
+            Err(e) => {
+                app_error!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn env_name(name: &str) -> String {
+    format!("{}{}", ENV_PREFIX, name.to_ascii_uppercase())
+}
+
+/// 启动后端时注入的环境变量
+pub fn backend_env(app: &AppHandle) -> BTreeMap<String, String> {
+    all(app).into_iter().map(|(name, value)| (env_name(&name), value)).collect()
+}
+
+// 太短的值替换后会误伤普通文本，不处理
+fn redactor(values: impl IntoIterator<Item = String>) -> impl Fn(&str) -> String {
+    let values: Vec<String> = values.into_iter().filter(|value| value.len() >= 4).collect();
+    move |text| values.iter().fold(text.to_string(), |text, value| text.replace(value.as_str(), "***"))
+}
+
+/// 把文本中出现的凭据值替换为 ***，用于导出诊断包
+pub fn redact_values(app: &AppHandle) -> impl Fn(&str) -> String {
+    redactor(all(app).into_values())
+}
+
+/// 保存凭据；修改在下次启动后端时生效
+#[tauri::command]
+pub async fn set_secret(app: AppHandle, name: String, value: String) -> Result<(), String> {
+    store(&app, &name, &value)?;
+    app_log!("Saved secret {}", name);
+    Ok(())
+}
+
+/// 读取凭据，不存在时返回 null
+#[tauri::command]
+pub async fn get_secret(name: String) -> Result<Option<String>, String> {
+    validate_name(&name)?;
+    load(&name)
+}
+
+#[tauri::command]
+pub async fn delete_secret(app: AppHandle, name: String) -> Result<(), String> {
+    validate_name(&name)?;
+    match entry(&name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(format!("Failed to delete secret {}: {}", name, e)),
+    }
+    update_index(&app, &name, false)?;
+    app_log!("Deleted secret {}", name);
+    Ok(())
+}
+
+/// 迁移旧版本以明文保存的凭据（前端从 localStorage 读出后调用），钥匙串中已有的条目保持不变。
+/// 返回成功存入钥匙串的名称，前端随后删除对应的明文
+#[tauri::command]
+pub async fn import_legacy_secrets(app: AppHandle, secrets: HashMap<String, String>) -> Result<Vec<String>, String> {
+    let mut imported = Vec::new();
+    for (name, value) in secrets {
+        validate_name(&name)?;
+        if value.is_empty() {
+            continue;
+        }
+        if load(&name).ok().flatten().is_none() {
+            store(&app, &name, &value)?;
+        }
+        imported.push(name);
+    }
+    if !imported.is_empty() {
+        app_log!("Migrated {} plaintext secret(s) to the keychain", imported.len());
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_limited_to_env_safe_characters() {
+        for name in ["llm_api_key", "a", "key2", &"a".repeat(MAX_NAME_LEN)] {
+            assert!(validate_name(name).is_ok(), "{:?}", name);
+        }
+        for name in ["", "LLM_KEY", "api-key", "api key", "../key", "ключ", &"a".repeat(MAX_NAME_LEN + 1)] {
+            assert!(validate_name(name).is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn env_names_are_prefixed_and_uppercased() {
+        assert_eq!(env_name("llm_api_key"), "DDOS_LLM_API_KEY");
+        assert_eq!(env_name("key2"), "DDOS_KEY2");
+    }
+
+    #[test]
+    fn redaction_replaces_every_occurrence() {
+        let redact = redactor(["sk-secret-1".to_string(), "tok9".to_string()]);
+        assert_eq!(redact("auth sk-secret-1 then sk-secret-1, tok9"), "auth *** then ***, ***");
+        assert_eq!(redact("nothing here"), "nothing here");
+    }
+
+    #[test]
+    fn short_values_are_not_redacted() {
+        let redact = redactor(["abc".to_string(), String::new()]);
+        assert_eq!(redact("abc abcdef"), "abc abcdef");
+    }
+
+    #[test]
+    fn index_records_names_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(INDEX_FILE_NAME);
+        assert!(read_index_file(&path).is_empty());
+
+        update_index_file(&path, "llm_api_key", true).unwrap();
+        update_index_file(&path, "search_key", true).unwrap();
+        update_index_file(&path, "llm_api_key", true).unwrap();
+        assert_eq!(read_index_file(&path), BTreeSet::from(["llm_api_key".to_string(), "search_key".to_string()]));
+
+        update_index_file(&path, "llm_api_key", false).unwrap();
+        update_index_file(&path, "missing", false).unwrap();
+        assert_eq!(read_index_file(&path), BTreeSet::from(["search_key".to_string()]));
+    }
+
+    #[test]
+    fn corrupt_index_reads_as_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(INDEX_FILE_NAME);
+        std::fs::write(&path, "{not json").unwrap();
+        assert!(read_index_file(&path).is_empty());
+        update_index_file(&path, "llm_api_key", true).unwrap();
+        assert_eq!(read_index_file(&path), BTreeSet::from(["llm_api_key".to_string()]));
+    }
+}
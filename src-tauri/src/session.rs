@@ -0,0 +1,249 @@
+// 上次会话是如何结束的：启动时在数据目录写入 session.json（会话 ID、开始时间、版本、PID），
+// 优雅退出（shutdown_app、Cmd+Q 等经 ExitRequested 的路径）时记下结束方式，应用内 panic 时记下 panic 信息。
+// 下次启动若上次的标记没有结束方式（被强杀、断电、崩溃），或因 panic、后端反复崩溃、启动失败而退出，
+// 收集上次 app.log 的末尾、那段时间的生命周期事件与崩溃报告写入 last-session.json，
+// 发送 `app://previous-session-abnormal`，prompt_after_abnormal_exit 开启时弹窗提供打开报告或导出诊断包。
+// get_last_session_report 返回该报告，供前端的“从崩溃中恢复”页面使用；上次正常退出时为 None。
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+
+use crate::alerts::{self, Alert, AlertCategory};
+use crate::i18n::{t, tf};
+
+const MARKER_FILE_NAME: &str = "session.json";
+pub const REPORT_FILE_NAME: &str = "last-session.json";
+const LOG_TAIL_LINES: usize = 200;
+const LIFECYCLE_EVENTS: usize = 100;
+
+// 本次会话的标记文件与内容
+static CURRENT: Mutex<Option<(PathBuf, Marker)>> = Mutex::new(None);
+// 退出前由 set_exit_reason 记下的原因，mark_clean 时写入
+static EXIT_REASON: Mutex<Option<SessionEnd>> = Mutex::new(None);
+// 上次会话的报告；PENDING 在前端事件队列注册后发送一次
+static REPORT: Mutex<Option<SessionReport>> = Mutex::new(None);
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+/// 会话的结束方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEnd {
+    /// 用户正常退出
+    Quit,
+    /// 没有经过退出流程：被强杀、断电或应用崩溃
+    Crashed,
+    /// 应用内 panic
+    Panicked,
+    /// 后端反复崩溃后用户选择退出
+    BackendCrashLoop,
+    /// 后端启动失败后用户选择退出
+    BackendStartupFailed,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct Marker {
+    session_id: String,
+    // Unix 毫秒
+    started_at: u64,
+    app_version: String,
+    pid: u32,
+    /// 退出流程中写入，None 表示会话没有正常走到退出
+    ended: Option<SessionEnd>,
+    panic: Option<String>,
+}
+
+/// 上次异常结束的会话，`app://previous-session-abnormal` 的负载
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionReport {
+    pub session_id: String,
+    pub started_at: String,
+    pub app_version: String,
+    pub ended: SessionEnd,
+    pub panic: Option<String>,
+    /// 上次 app.log 的最后 LOG_TAIL_LINES 行（可能含本次启动最初的几行）
+    pub app_log_tail: Vec<String>,
+    /// 上次会话期间的生命周期事件，最新的在前
+    pub lifecycle_events: Vec<serde_json::Value>,
+    /// 上次会话期间写入的崩溃报告文件名，可用 read_crash_report 读取
+    pub crash_reports: Vec<String>,
+    /// 写入的 last-session.json
+    pub path: PathBuf,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+fn write_marker(path: &Path, marker: &Marker) {
+    let result = serde_json::to_string_pretty(marker)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        app_error!("Failed to write session marker {:?}: {}", path, e);
+    }
+}
+
+fn new_session_id() -> String {
+    let mut bytes = [0u8; 16];
+    if getrandom::fill(&mut bytes).is_err() {
+        return format!("{:x}-{}", now_ms(), std::process::id());
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// app.log 末尾；当前文件行数不够时补上最近一个滚动归档
+fn log_tail(logs_dir: &Path) -> Vec<String> {
+    let current = logs_dir.join(crate::logs::APP_LOG_NAME);
+    let mut lines: Vec<String> = std::fs::read_to_string(&current)
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+    if lines.len() < LOG_TAIL_LINES {
+        if let Ok(content) = std::fs::read_to_string(crate::logs::rotated_path(&current, 1)) {
+            let mut rotated: Vec<String> = content.lines().map(str::to_string).collect();
+            rotated.append(&mut lines);
+            lines = rotated;
+        }
+    }
+    let start = lines.len().saturating_sub(LOG_TAIL_LINES);
+    lines.split_off(start)
+}
+
+fn report(data_dir: &Path, previous: Marker, ended: SessionEnd) -> SessionReport {
+    let started = SystemTime::UNIX_EPOCH + Duration::from_millis(previous.started_at);
+    let lifecycle_events = crate::lifecycle_history::read(data_dir, LIFECYCLE_EVENTS, None)
+        .into_iter()
+        .filter(|event| event["ts"].as_u64().is_some_and(|ts| ts >= previous.started_at))
+        .collect();
+    SessionReport {
+        session_id: previous.session_id,
+        started_at: chrono::DateTime::<chrono::Local>::from(started).to_rfc3339(),
+        app_version: previous.app_version,
+        ended,
+        panic: previous.panic,
+        app_log_tail: log_tail(&data_dir.join(crate::logs::LOGS_DIR_NAME)),
+        lifecycle_events,
+        crash_reports: crate::crash_report::written_since(data_dir, started),
+        path: data_dir.join(REPORT_FILE_NAME),
+    }
+}
+
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // 不等待：panic 可能发生在持有该锁期间
+        if let Ok(mut current) = CURRENT.try_lock() {
+            if let Some((path, marker)) = current.as_mut() {
+                marker.ended = Some(SessionEnd::Panicked);
+                marker.panic = Some(info.to_string());
+                write_marker(path, marker);
+            }
+        }
+        previous(info);
+    }));
+}
+
+/// 在 setup 中、app.log 初始化之后尽早调用：检查上次会话的标记，再写入本次会话的标记
+pub fn init(data_dir: &Path) {
+    let path = data_dir.join(MARKER_FILE_NAME);
+    let previous = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Marker>(&content).ok());
+    if let Some(previous) = previous {
+        let ended = previous.ended.unwrap_or(SessionEnd::Crashed);
+        if ended != SessionEnd::Quit {
+            app_error!("Previous session {} did not end normally ({:?})", previous.session_id, ended);
+            let report = report(data_dir, previous, ended);
+            let result = serde_json::to_string_pretty(&report)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&report.path, json).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                app_error!("Failed to write {:?}: {}", report.path, e);
+            }
+            *REPORT.lock().unwrap() = Some(report);
+            PENDING.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let marker = Marker {
+        session_id: new_session_id(),
+        started_at: now_ms(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        pid: std::process::id(),
+        ended: None,
+        panic: None,
+    };
+    app_log!("Session {} started", marker.session_id);
+    write_marker(&path, &marker);
+    *CURRENT.lock().unwrap() = Some((path, marker));
+    install_panic_hook();
+}
+
+/// 因后端故障退出前调用，覆盖 mark_clean 记录的结束方式
+pub fn set_exit_reason(reason: SessionEnd) {
+    *EXIT_REASON.lock().unwrap() = Some(reason);
+}
+
+/// 退出流程中、后端停止之后调用
+pub fn mark_clean() {
+    let ended = EXIT_REASON.lock().unwrap().take().unwrap_or(SessionEnd::Quit);
+    if let Some((path, marker)) = CURRENT.lock().unwrap().as_mut() {
+        marker.ended = Some(ended);
+        write_marker(path, marker);
+    }
+}
+
+/// 上次会话异常结束时发送 `app://previous-session-abnormal` 并按配置弹窗；在 AppEventQueue 注册之后调用
+pub fn notify_previous(app: &AppHandle) {
+    if !PENDING.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let Some(report) = REPORT.lock().unwrap().clone() else {
+        return;
+    };
+    crate::app_events::emit_to_frontend(app, "app://previous-session-abnormal", report.clone());
+    if crate::headless::enabled(app) || !crate::app_config(app).prompt_after_abnormal_exit {
+        return;
+    }
+    let reason = t(match report.ended {
+        SessionEnd::Quit | SessionEnd::Crashed => "session_end.crashed",
+        SessionEnd::Panicked => "session_end.panicked",
+        SessionEnd::BackendCrashLoop => "session_end.backend_crash_loop",
+        SessionEnd::BackendStartupFailed => "session_end.backend_startup_failed",
+    });
+    let (open_report, export, close) = (t("button.open_report"), t("button.export_diagnostics"), t("button.close"));
+    let args: [(&str, &dyn std::fmt::Display); 2] = [("started", &report.started_at), ("reason", &reason)];
+    let alert = Alert::new(AlertCategory::Crash, tf("dialog.previous_session", &args))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(open_report.clone(), export.clone(), close));
+    alerts::ask_with_result(app, alert, {
+        let app = app.clone();
+        move |result| {
+            let MessageDialogResult::Custom(choice) = result else {
+                return;
+            };
+            if choice == open_report {
+                if let Err(e) = crate::folders::open(&report.path) {
+                    app_error!("{}", e);
+                }
+            } else if choice == export {
+                tauri::async_runtime::spawn(async move {
+                    match crate::diagnostics::export_diagnostics(app, None).await {
+                        Ok(_) => {}
+                        Err(e) if e == "cancelled" => {}
+                        Err(e) => app_error!("Failed to export diagnostics: {}", e),
+                    }
+                });
+            }
+        }
+    });
+}
+
+/// 上次会话异常结束时的报告；上次正常退出（或首次运行）时为 None
+#[tauri::command]
+pub fn get_last_session_report() -> Option<SessionReport> {
+    REPORT.lock().unwrap().clone()
+}
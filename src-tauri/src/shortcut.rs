@@ -0,0 +1,110 @@
+// 全局快捷键：在任何地方按下 config.json 中的 global_shortcut（默认 CmdOrCtrl+Shift+D）
+// 显示并聚焦主窗口（包括隐藏在托盘时），主窗口已在前台时隐藏；显示时广播 `app://hotkey`，
+// 前端据此聚焦输入框。global_shortcut 为空字符串表示不注册。
+
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+pub const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Shift+D";
+
+// 当前已注册的快捷键
+static REGISTERED: Mutex<Option<Shortcut>> = Mutex::new(None);
+
+/// 解析快捷键字符串，空字符串返回 None
+pub fn parse(accelerator: &str) -> Result<Option<Shortcut>, String> {
+    let accelerator = accelerator.trim();
+    if accelerator.is_empty() {
+        return Ok(None);
+    }
+    Shortcut::from_str(accelerator)
+        .map(Some)
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", accelerator, e))
+}
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                toggle_main_window(app);
+            }
+        })
+        .build()
+}
+
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let in_front = window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false);
+    if in_front {
+        // 没有托盘时隐藏后无从找回，改为最小化
+        if crate::tray::is_available(app) {
+            let _ = window.hide();
+        } else {
+            let _ = window.minimize();
+        }
+        return;
+    }
+    crate::tray::show_main_window(app);
+    let _ = app.emit("app://hotkey", ());
+}
+
+// 换成新的快捷键；注册失败（通常是被其他程序占用）时恢复原来的快捷键
+fn replace(app: &AppHandle, shortcut: Option<Shortcut>) -> Result<(), String> {
+    let manager = app.global_shortcut();
+    let mut registered = REGISTERED.lock().unwrap();
+    if *registered == shortcut {
+        return Ok(());
+    }
+    if let Some(old) = registered.take() {
+        let _ = manager.unregister(old);
+    }
+    let Some(new) = shortcut else {
+        return Ok(());
+    };
+    if let Err(e) = manager.register(new) {
+        return Err(format!(
+            "Shortcut {} could not be registered, it may already be used by another application ({})",
+            new.into_string(),
+            e
+        ));
+    }
+    *registered = Some(new);
+    Ok(())
+}
+
+/// 按配置注册快捷键（setup 中及 set_config 修改后调用），失败只记录日志
+pub fn init(app: &AppHandle) {
+    // 无界面模式不注册插件
+    if crate::headless::enabled(app) {
+        return;
+    }
+    let result = parse(&crate::app_config(app).global_shortcut).and_then(|shortcut| replace(app, shortcut));
+    if let Err(e) = result {
+        app_error!("{}", e);
+    }
+}
+
+/// 修改全局快捷键并写入 config.json。快捷键无效或注册失败（被占用）时返回错误，原快捷键保持不变；
+/// 空字符串表示取消快捷键
+#[tauri::command]
+pub fn set_global_shortcut(app: AppHandle, accel: String) -> Result<(), String> {
+    if crate::headless::enabled(&app) {
+        return Err("Global shortcuts are not available in headless mode".to_string());
+    }
+    let shortcut = parse(&accel)?;
+    let previous = REGISTERED.lock().unwrap().map(|s| s.into_string());
+    replace(&app, shortcut)?;
+    let result = app.state::<crate::config::ConfigState>().update(|config| config.global_shortcut = accel.trim().to_string());
+    if let Err(e) = result {
+        // 保存失败时恢复原先的注册，保持与配置文件一致
+        let _ = replace(&app, previous.as_deref().and_then(|s| parse(s).ok().flatten()));
+        return Err(e);
+    }
+    app_log!("Global shortcut set to \"{}\"", accel.trim());
+    Ok(())
+}
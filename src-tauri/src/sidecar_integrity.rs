@@ -0,0 +1,74 @@
+// Sidecar 完整性校验：构建时把打包的 duncrew-server 的 SHA-256 写入 sidecar_hash.rs，
+// 启动前对实际要运行的二进制计算哈希并比对。杀毒软件隔离掉部分 PyInstaller 文件后，
+// 后端仍能启动但行为异常，比对失败时拒绝启动并提示重新安装。
+// 通过 backend_update 安装的新版本，其哈希记录在数据目录中，同样视为合法。
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::backend::BackendError;
+
+include!(concat!(env!("OUT_DIR"), "/sidecar_hash.rs"));
+
+// 二进制 100MB 以上，缓存校验结果，替换二进制后调用 invalidate 重新校验
+static RESULT: Mutex<Option<Result<(), BackendError>>> = Mutex::new(None);
+
+// 与 shell 插件解析 sidecar 的方式一致：与主程序位于同一目录
+pub fn sidecar_path() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate app executable: {}", e))?;
+    Ok(exe.with_file_name(format!("{}{}", crate::backend::SIDECAR_NAME, std::env::consts::EXE_SUFFIX)))
+}
+
+pub fn sha256_file(path: &std::path::Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn compute(app: &AppHandle) -> Result<(), BackendError> {
+    let Some(expected) = EXPECTED_SIDECAR_SHA256 else {
+        app_log!("No sidecar hash was embedded at build time, skipping verification");
+        return Ok(());
+    };
+    let path = sidecar_path()?;
+    let actual = sha256_file(&path).map_err(|e| format!("Failed to read sidecar {:?}: {}", path, e))?;
+    let updated = crate::backend_update::installed_sha256(app);
+    if actual != expected && updated.as_deref() != Some(actual.as_str()) {
+        app_error!("Sidecar {:?} failed verification: expected {}, got {}", path, expected, actual);
+        return Err(BackendError::SidecarVerificationFailed {
+            path: path.to_string_lossy().to_string(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    app_log!("Sidecar verified ({})", actual);
+    Ok(())
+}
+
+/// 是否需要校验：从源码运行后端或配置了 skip_sidecar_verification 时不校验
+pub fn required(app: &AppHandle) -> bool {
+    let config = crate::app_config(app);
+    config.backend_source.is_none() && !config.skip_sidecar_verification
+}
+
+/// 校验 Sidecar（结果缓存）。首次调用会读取整个二进制，应在后台线程调用
+pub fn verify(app: &AppHandle) -> Result<(), BackendError> {
+    let mut result = RESULT.lock().unwrap();
+    result.get_or_insert_with(|| compute(app)).clone()
+}
+
+/// 二进制被替换后清除缓存的结果
+pub fn invalidate() {
+    RESULT.lock().unwrap().take();
+}
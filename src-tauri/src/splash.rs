@@ -0,0 +1,47 @@
+// 启动画面：主窗口保持隐藏，直到后端能响应健康检查再切换。
+// 页面见项目根目录 splash.html，就绪判断复用 health::wait_until_ready。
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub const SPLASH_LABEL: &str = "splash";
+// Python 依赖导入在慢机器上需要 5–10 秒，留足余量
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn open(app: &AppHandle) -> tauri::Result<()> {
+    WebviewWindowBuilder::new(app, SPLASH_LABEL, WebviewUrl::App("splash.html".into()))
+        .title("DunCrew")
+        .inner_size(420.0, 280.0)
+        .decorations(false)
+        .resizable(false)
+        .center()
+        .build()?;
+    Ok(())
+}
+
+// 关闭启动画面并显示主窗口
+fn finish(app: &AppHandle) {
+    if let Some(splash) = app.get_webview_window(SPLASH_LABEL) {
+        let _ = splash.close();
+    }
+    crate::tray::show_main_window(app);
+    crate::app_events::mark_backend_ready(app);
+}
+
+/// 等待后端就绪，就绪时切换到主窗口并返回 true；超时返回 false，由启动画面显示错误状态
+#[tauri::command]
+pub async fn wait_for_backend_ready(app: AppHandle) -> bool {
+    let ready = crate::health::wait_until_ready(&app, READY_TIMEOUT).await;
+    if ready {
+        finish(&app);
+    } else {
+        app_error!("Backend did not become ready within {:?}", READY_TIMEOUT);
+    }
+    ready
+}
+
+/// 启动画面的“Continue anyway”：不等后端直接进入主窗口
+#[tauri::command]
+pub fn dismiss_splash(app: AppHandle) {
+    finish(&app);
+}
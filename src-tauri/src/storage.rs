@@ -0,0 +1,201 @@
+// 数据目录占用统计：按顶层子目录（db、logs、imports、cache 等）分类汇总字节数与文件数。
+// 不跟随符号链接，统计失败的文件跳过；扫描有时间上限，结果短时间缓存，设置页轮询不会反复扫盘。
+// 另外负责剩余空间检查：磁盘写满时后端会损坏数据库，启动后端、备份、导入前先检查。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::{MessageDialogButtons, MessageDialogKind};
+
+use crate::alerts::{self, Alert, AlertCategory};
+
+// 超过此时间停止扫描并返回部分结果（truncated = true）
+const SCAN_TIME_LIMIT: Duration = Duration::from_secs(10);
+const CACHE_TTL: Duration = Duration::from_secs(15);
+// 直接位于数据目录下的文件归入此分类
+const ROOT_FILES_BUCKET: &str = "(root)";
+
+#[derive(Clone, Default, serde::Serialize)]
+pub struct StorageBucket {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct StorageUsage {
+    pub path: PathBuf,
+    pub total_bytes: u64,
+    pub total_files: u64,
+    pub buckets: BTreeMap<String, StorageBucket>,
+    // 无法读取的文件 / 目录数
+    pub skipped: u64,
+    // 扫描因超时提前结束，数字偏小
+    pub truncated: bool,
+    pub scanned_at: String,
+    // 数据目录所在卷的剩余空间，每次调用重新读取，不缓存
+    pub available_bytes: Option<u64>,
+    pub minimum_free_bytes: u64,
+}
+
+/// `storage://low-space` 事件负载
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct LowSpace {
+    pub operation: &'static str,
+    pub path: PathBuf,
+    pub available_bytes: u64,
+    pub minimum_bytes: u64,
+}
+
+static CACHE: Mutex<Option<(Instant, StorageUsage)>> = Mutex::new(None);
+
+fn add_file(usage: &mut StorageUsage, bucket: &str, bytes: u64) {
+    let entry = usage.buckets.entry(bucket.to_string()).or_default();
+    entry.bytes += bytes;
+    entry.files += 1;
+    usage.total_bytes += bytes;
+    usage.total_files += 1;
+}
+
+pub(crate) fn scan(data_dir: &Path) -> StorageUsage {
+    let deadline = Instant::now() + SCAN_TIME_LIMIT;
+    let mut usage = StorageUsage {
+        path: data_dir.to_path_buf(),
+        total_bytes: 0,
+        total_files: 0,
+        buckets: BTreeMap::new(),
+        skipped: 0,
+        truncated: false,
+        scanned_at: crate::logs::timestamp(),
+        available_bytes: None,
+        minimum_free_bytes: 0,
+    };
+
+    // 显式栈代替递归；DirEntry::file_type 不跟随符号链接，链接本身不计入也不进入，避免循环
+    let mut stack: Vec<(PathBuf, Option<String>)> = vec![(data_dir.to_path_buf(), None)];
+    while let Some((dir, bucket)) = stack.pop() {
+        if Instant::now() >= deadline {
+            usage.truncated = true;
+            break;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            usage.skipped += 1;
+            continue;
+        };
+        for entry in entries {
+            let Ok(entry) = entry else {
+                usage.skipped += 1;
+                continue;
+            };
+            let Ok(file_type) = entry.file_type() else {
+                usage.skipped += 1;
+                continue;
+            };
+            if file_type.is_dir() {
+                let bucket = bucket
+                    .clone()
+                    .unwrap_or_else(|| entry.file_name().to_string_lossy().to_string());
+                usage.buckets.entry(bucket.clone()).or_default();
+                stack.push((entry.path(), Some(bucket)));
+            } else if file_type.is_file() {
+                match entry.metadata() {
+                    Ok(meta) => add_file(&mut usage, bucket.as_deref().unwrap_or(ROOT_FILES_BUCKET), meta.len()),
+                    Err(_) => usage.skipped += 1,
+                }
+            }
+        }
+    }
+    usage
+}
+
+/// 路径所在卷的剩余空间；路径可以尚不存在，按最近的已存在上级目录判断
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let path = existing.canonicalize().unwrap_or_else(|_| existing.to_path_buf());
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+fn minimum_free_bytes(app: &AppHandle) -> u64 {
+    crate::app_config(app).min_free_space_mb * 1024 * 1024
+}
+
+/// 检查 `path` 所在卷的剩余空间，低于配置的最小值时发送 `storage://low-space` 并返回详情；
+/// 读取不到剩余空间时不拦截
+pub fn check_free_space(app: &AppHandle, path: &Path, operation: &'static str) -> Option<LowSpace> {
+    let minimum_bytes = minimum_free_bytes(app);
+    let available_bytes = available_space(path)?;
+    if minimum_bytes == 0 || available_bytes >= minimum_bytes {
+        return None;
+    }
+    let low = LowSpace { operation, path: path.to_path_buf(), available_bytes, minimum_bytes };
+    app_error!(
+        "Low disk space before {}: {} MB free on the volume of {:?}",
+        operation, available_bytes / 1024 / 1024, path
+    );
+    // 用户选择了暂不提醒：记录日志后放行
+    if alerts::is_muted(app, AlertCategory::LowDisk) {
+        return None;
+    }
+    let _ = app.emit("storage://low-space", low.clone());
+    Some(low)
+}
+
+pub fn low_space_message(low: &LowSpace) -> String {
+    crate::i18n::tf(
+        "dialog.low_space",
+        &[
+            ("available", &(low.available_bytes / 1024 / 1024)),
+            ("path", &low.path.display()),
+            ("minimum", &(low.minimum_bytes / 1024 / 1024)),
+        ],
+    )
+}
+
+/// 空间不足时弹出警告，由用户决定是否继续；空间足够直接返回 true。
+/// 会阻塞等待用户选择，不能在主线程调用
+pub fn confirm_free_space(app: &AppHandle, path: &Path, operation: &'static str) -> bool {
+    let Some(low) = check_free_space(app, path, operation) else {
+        return true;
+    };
+    let alert = Alert::new(AlertCategory::LowDisk, low_space_message(&low))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(crate::i18n::t("button.continue_anyway"), crate::i18n::t("button.cancel")));
+    alerts::blocking_ask(app, alert)
+}
+
+/// 数据目录占用；`refresh` 为 true 时忽略缓存重新扫描
+#[tauri::command]
+pub async fn get_storage_usage(app: AppHandle, refresh: Option<bool>) -> Result<StorageUsage, String> {
+    let data_dir = crate::backend_data_dir(&app)?;
+    let cached = CACHE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(at, usage)| !refresh.unwrap_or(false) && at.elapsed() < CACHE_TTL && usage.path == data_dir)
+        .map(|(_, usage)| usage.clone());
+
+    let mut usage = match cached {
+        Some(usage) => usage,
+        None => {
+            let dir = data_dir.clone();
+            let usage = tauri::async_runtime::spawn_blocking(move || scan(&dir))
+                .await
+                .map_err(|e| e.to_string())?;
+            if usage.truncated {
+                app_error!("Storage scan of {:?} stopped after {:?}", usage.path, SCAN_TIME_LIMIT);
+            }
+            *CACHE.lock().unwrap() = Some((Instant::now(), usage.clone()));
+            usage
+        }
+    };
+    usage.available_bytes = available_space(&data_dir);
+    usage.minimum_free_bytes = minimum_free_bytes(&app);
+    Ok(usage)
+}
@@ -0,0 +1,211 @@
+// 流式响应桥接：WebView 的 EventSource 不能附加 token 头，改由 Rust 打开后端的 SSE /
+// NDJSON 响应，逐条以 `stream://<request_id>` 事件转发，结束时发送 `/done`，失败时发送 `/error`。
+// 每个流由单个任务顺序读取和发送，事件顺序与后端一致；按字节缓冲到换行再解码，
+// 跨网络分片的多字节 UTF-8 字符不会被截断。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::backend_client::{BackendClient, BackendClientError};
+use crate::proxy::{self, ProxyError};
+
+// 同时进行的流数量上限
+const MAX_STREAMS: usize = 8;
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+static STREAMS: Mutex<Option<HashMap<String, tauri::async_runtime::JoinHandle<()>>>> = Mutex::new(None);
+
+/// `stream://<request_id>` 事件负载；SSE 的 data 为字符串，NDJSON 的每一行解析为 JSON
+#[derive(Clone, serde::Serialize)]
+struct StreamChunk {
+    seq: u64,
+    event: Option<String>,
+    id: Option<String>,
+    data: serde_json::Value,
+}
+
+// `stream://<request_id>/done` 事件负载；取消时 chunks 为 0
+#[derive(Clone, serde::Serialize)]
+struct StreamDone {
+    chunks: u64,
+    cancelled: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Sse,
+    Ndjson,
+}
+
+// 一个 SSE 事件的累积状态
+#[derive(Default)]
+struct SseFrame {
+    event: Option<String>,
+    id: Option<String>,
+    data: Vec<String>,
+}
+
+struct ChunkSink {
+    app: AppHandle,
+    request_id: String,
+    seq: u64,
+}
+
+impl ChunkSink {
+    fn emit(&mut self, event: Option<String>, id: Option<String>, data: serde_json::Value) {
+        self.seq += 1;
+        let chunk = StreamChunk { seq: self.seq, event, id, data };
+        let _ = self.app.emit(&format!("stream://{}", self.request_id), chunk);
+    }
+}
+
+// 事件名也用作 Tauri 事件的一部分，只允许字母、数字、- 和 _
+pub fn validate_request_id(request_id: &str) -> Result<(), ProxyError> {
+    let valid = !request_id.is_empty()
+        && request_id.len() <= MAX_REQUEST_ID_LEN
+        && request_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(proxy::invalid("request_id may only contain ASCII letters, digits, '-' and '_'"));
+    }
+    Ok(())
+}
+
+// 处理一行（不含换行符）；SSE 空行表示一个事件结束
+fn handle_line(line: &str, format: Format, frame: &mut SseFrame, out: &mut ChunkSink) {
+    if format == Format::Ndjson {
+        if line.trim().is_empty() {
+            return;
+        }
+        let data = serde_json::from_str(line).unwrap_or_else(|_| serde_json::Value::String(line.to_string()));
+        out.emit(None, None, data);
+        return;
+    }
+    if line.is_empty() {
+        let frame = std::mem::take(frame);
+        if !frame.data.is_empty() {
+            out.emit(frame.event, frame.id, serde_json::Value::String(frame.data.join("\n")));
+        }
+        return;
+    }
+    // 冒号开头为注释（常用作心跳）
+    if line.starts_with(':') {
+        return;
+    }
+    let (field, value) = match line.split_once(':') {
+        Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+        None => (line, ""),
+    };
+    match field {
+        "data" => frame.data.push(value.to_string()),
+        "event" => frame.event = Some(value.to_string()),
+        "id" => frame.id = Some(value.to_string()),
+        _ => {}
+    }
+}
+
+async fn run(
+    app: &AppHandle,
+    request_id: &str,
+    client: BackendClient,
+    request: reqwest::RequestBuilder,
+) -> Result<u64, ProxyError> {
+    let mut response = client.send(request, None).await?;
+    if !response.status().is_success() {
+        return Err(BackendClientError::Http { status: response.status().as_u16() }.into());
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    let format = if content_type.contains("ndjson") || content_type.contains("jsonl") {
+        Format::Ndjson
+    } else {
+        Format::Sse
+    };
+
+    let mut out = ChunkSink { app: app.clone(), request_id: request_id.to_string(), seq: 0 };
+    let mut frame = SseFrame::default();
+    let mut pending: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| client.error(e, None))? {
+        pending.extend_from_slice(&chunk);
+        // 只解码完整的行，未结束的部分（可能含半个 UTF-8 字符）留到下一片
+        while let Some(newline) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            handle_line(line.trim_end_matches(['\r', '\n']), format, &mut frame, &mut out);
+        }
+    }
+    // 流结束时没有换行收尾的最后一行 / 最后一个事件
+    if !pending.is_empty() {
+        let line = String::from_utf8_lossy(&pending).to_string();
+        handle_line(line.trim_end_matches('\r'), format, &mut frame, &mut out);
+    }
+    handle_line("", format, &mut frame, &mut out);
+    Ok(out.seq)
+}
+
+/// 打开后端的流式响应（SSE 或 NDJSON），每条消息以 `stream://<request_id>` 事件发送，
+/// 正常结束发送 `stream://<request_id>/done`，失败发送 `stream://<request_id>/error`（payload 同 backend_request 的错误）。
+/// `path` 限制与 backend_request 相同；`body` 不为空时以 POST 发送 JSON
+#[tauri::command]
+pub fn start_backend_stream(
+    app: AppHandle,
+    path: String,
+    request_id: String,
+    body: Option<serde_json::Value>,
+) -> Result<(), ProxyError> {
+    proxy::validate_path(&path)?;
+    validate_request_id(&request_id)?;
+    let client = BackendClient::current(&app);
+    let request = match body {
+        Some(body) => client.request(reqwest::Method::POST, &path).json(&body),
+        None => client.request(reqwest::Method::GET, &path),
+    }
+    .header(reqwest::header::ACCEPT, "text/event-stream, application/x-ndjson");
+
+    // 持锁完成检查、启动与登记，任务结束时的移除会排在登记之后
+    let mut streams = STREAMS.lock().unwrap();
+    let streams = streams.get_or_insert_with(HashMap::new);
+    if streams.contains_key(&request_id) {
+        return Err(proxy::invalid(format!("Stream {} is already running", request_id)));
+    }
+    if streams.len() >= MAX_STREAMS {
+        return Err(proxy::invalid(format!("Too many concurrent streams (limit {})", MAX_STREAMS)));
+    }
+    let task = {
+        let request_id = request_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = run(&app, &request_id, client, request).await;
+            if let Some(streams) = STREAMS.lock().unwrap().as_mut() {
+                streams.remove(&request_id);
+            }
+            match result {
+                Ok(chunks) => {
+                    let _ = app.emit(&format!("stream://{}/done", request_id), StreamDone { chunks, cancelled: false });
+                }
+                Err(e) => {
+                    app_error!("Backend stream {} failed: {:?}", request_id, e);
+                    let _ = app.emit(&format!("stream://{}/error", request_id), e);
+                }
+            }
+        })
+    };
+    streams.insert(request_id, task);
+    Ok(())
+}
+
+/// 取消进行中的流，中止底层请求并发送 `stream://<request_id>/done`（cancelled 为 true）。
+/// 流不存在或已结束时返回 false
+#[tauri::command]
+pub fn cancel_backend_stream(app: AppHandle, request_id: String) -> bool {
+    let task = STREAMS.lock().unwrap().as_mut().and_then(|streams| streams.remove(&request_id));
+    let Some(task) = task else {
+        return false;
+    };
+    task.abort();
+    let _ = app.emit(&format!("stream://{}/done", request_id), StreamDone { chunks: 0, cancelled: true });
+    true
+}
@@ -0,0 +1,236 @@
+// 系统信息：操作系统、CPU、内存、显卡、WebView 运行时版本、语言与时区等，用户反馈问题时不必再让其自行查找。
+// 同一份 SystemInfo 写入 get_system_info 的返回值、诊断包的 system-info.json 与后端崩溃报告。
+// 任何一项取不到都不影响其余各项：文本字段为 "unknown"，数值字段为 null。
+// 显卡枚举可能很慢（驱动异常时甚至卡住），在单独线程中进行并限时等待，成功后缓存，之后不再重复枚举。
+
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use tauri::AppHandle;
+
+const UNKNOWN: &str = "unknown";
+const GPU_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 枚举到的显卡名称；超时或失败时不缓存，下次再试
+static GPUS: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// `get_system_info` 返回值
+#[derive(Clone, serde::Serialize)]
+pub struct SystemInfo {
+    pub app_version: String,
+    /// windows / macos / linux
+    pub os: String,
+    /// 例如 "Windows 11 Pro" / "macOS 14.5 Sonoma" / "Ubuntu 24.04"
+    pub os_name: String,
+    pub os_version: String,
+    pub kernel_version: String,
+    /// 本应用的构建架构
+    pub arch: String,
+    /// 机器的实际架构；在 Rosetta 或 Windows ARM 的 x64 模拟下与 arch 不同
+    pub machine_arch: String,
+    /// 是否在转译下运行（Rosetta 2 / Windows on ARM 模拟）；无法判断时为 null
+    pub translated: Option<bool>,
+    pub cpu_model: String,
+    pub cpu_logical_cores: Option<usize>,
+    pub cpu_physical_cores: Option<usize>,
+    pub total_memory_bytes: Option<u64>,
+    pub available_memory_bytes: Option<u64>,
+    /// 显卡名称；无法枚举时为 ["unknown"]
+    pub gpus: Vec<String>,
+    /// WebView2 / WebKitGTK / WKWebView 版本
+    pub webview_version: String,
+    /// 系统语言与时区，与传给后端的 --locale / --timezone 相同
+    pub locale: crate::locale::LocaleInfo,
+}
+
+impl SystemInfo {
+    /// 崩溃报告中的 “键: 值” 行
+    pub fn report_lines(&self) -> Vec<String> {
+        let number = |value: Option<u64>| value.map_or_else(|| UNKNOWN.to_string(), |v| v.to_string());
+        let translated = match self.translated {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => UNKNOWN,
+        };
+        vec![
+            format!("OS: {} ({}), kernel {}", self.os_name, self.os_version, self.kernel_version),
+            format!("Architecture: {} on {} (translated: {})", self.arch, self.machine_arch, translated),
+            format!(
+                "CPU: {} ({} logical / {} physical cores)",
+                self.cpu_model,
+                number(self.cpu_logical_cores.map(|n| n as u64)),
+                number(self.cpu_physical_cores.map(|n| n as u64))
+            ),
+            format!(
+                "Memory: {} bytes total, {} bytes available",
+                number(self.total_memory_bytes),
+                number(self.available_memory_bytes)
+            ),
+            format!("GPU: {}", self.gpus.join("; ")),
+            format!("WebView: {}", self.webview_version),
+            format!(
+                "Locale: {}, timezone {} (UTC{:+}min)",
+                self.locale.locale.as_deref().unwrap_or(UNKNOWN),
+                self.locale.timezone.as_deref().unwrap_or(UNKNOWN),
+                self.locale.utc_offset_minutes
+            ),
+        ]
+    }
+}
+
+fn or_unknown(value: Option<String>) -> String {
+    value.filter(|v| !v.trim().is_empty()).unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+#[cfg(windows)]
+fn enumerate_gpus() -> Option<Vec<String>> {
+    use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE};
+
+    // SAFETY: 普通的 DXGI 调用，接口由 windows 的包装类型管理引用计数
+    let factory: IDXGIFactory1 = unsafe { CreateDXGIFactory1() }.ok()?;
+    let mut names = Vec::new();
+    for index in 0.. {
+        let Ok(adapter) = (unsafe { factory.EnumAdapters1(index) }) else {
+            break;
+        };
+        let Ok(desc) = (unsafe { adapter.GetDesc1() }) else {
+            continue;
+        };
+        // 跳过 Microsoft Basic Render Driver 等软件渲染器
+        if desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0 {
+            continue;
+        }
+        let len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+        names.push(String::from_utf16_lossy(&desc.Description[..len]));
+    }
+    Some(names)
+}
+
+#[cfg(target_os = "macos")]
+fn enumerate_gpus() -> Option<Vec<String>> {
+    let output = std::process::Command::new("system_profiler").args(["SPDisplaysDataType", "-json"]).output().ok()?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let displays = json.get("SPDisplaysDataType")?.as_array()?;
+    Some(
+        displays
+            .iter()
+            .filter_map(|display| display.get("sppci_model").and_then(|model| model.as_str()))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+// lspci -mm 每行：槽位 "类别" "厂商" "设备" ...，字段以引号包围
+#[cfg(not(any(windows, target_os = "macos")))]
+fn enumerate_gpus() -> Option<Vec<String>> {
+    let output = std::process::Command::new("lspci").arg("-mm").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(
+        text.lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+                let (class, vendor, device) = (fields.first()?, fields.get(1)?, fields.get(2)?);
+                let display = ["VGA compatible controller", "3D controller", "Display controller"].contains(class);
+                display.then(|| format!("{} {}", vendor, device))
+            })
+            .collect(),
+    )
+}
+
+fn gpus() -> Vec<String> {
+    if let Some(gpus) = GPUS.lock().unwrap().clone() {
+        return gpus;
+    }
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(enumerate_gpus());
+    });
+    match rx.recv_timeout(GPU_TIMEOUT) {
+        Ok(Some(gpus)) if !gpus.is_empty() => {
+            *GPUS.lock().unwrap() = Some(gpus.clone());
+            gpus
+        }
+        Ok(_) => vec![UNKNOWN.to_string()],
+        Err(_) => {
+            app_log!("GPU enumeration did not finish within {}s", GPU_TIMEOUT.as_secs());
+            vec![UNKNOWN.to_string()]
+        }
+    }
+}
+
+#[cfg(windows)]
+fn translated() -> Option<bool> {
+    use windows_sys::Win32::System::SystemInformation::{IMAGE_FILE_MACHINE_ARM64, IMAGE_FILE_MACHINE_UNKNOWN};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+    let mut process_machine = IMAGE_FILE_MACHINE_UNKNOWN;
+    let mut native_machine = IMAGE_FILE_MACHINE_UNKNOWN;
+    // SAFETY: GetCurrentProcess 返回伪句柄，两个输出参数指向有效的局部变量
+    if unsafe { IsWow64Process2(GetCurrentProcess(), &mut process_machine, &mut native_machine) } == 0 {
+        return None;
+    }
+    // 32 位进程走 WOW64；x64 进程在 ARM64 上被模拟时 process_machine 仍为 UNKNOWN
+    let emulated_x64 = native_machine == IMAGE_FILE_MACHINE_ARM64 && !cfg!(target_arch = "aarch64");
+    Some(process_machine != IMAGE_FILE_MACHINE_UNKNOWN || emulated_x64)
+}
+
+#[cfg(target_os = "macos")]
+fn translated() -> Option<bool> {
+    let output = std::process::Command::new("sysctl").args(["-in", "sysctl.proc_translated"]).output().ok()?;
+    // Intel 机器上没有这一项，输出为空
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "1" => Some(true),
+        "0" | "" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn translated() -> Option<bool> {
+    None
+}
+
+/// 收集系统信息；可能因显卡枚举阻塞至多 GPU_TIMEOUT，异步上下文中应放到阻塞线程执行
+pub fn collect(app: &AppHandle) -> SystemInfo {
+    let system = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    let cpu_model = system.cpus().first().map(|cpu| cpu.brand().trim().to_string());
+    let logical_cores = system.cpus().len();
+    let total_memory = system.total_memory();
+    SystemInfo {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        os_name: or_unknown(System::long_os_version()),
+        os_version: or_unknown(System::os_version()),
+        kernel_version: or_unknown(System::kernel_version()),
+        arch: std::env::consts::ARCH.to_string(),
+        machine_arch: or_unknown(Some(System::cpu_arch())),
+        translated: translated(),
+        cpu_model: or_unknown(cpu_model),
+        cpu_logical_cores: (logical_cores > 0).then_some(logical_cores),
+        cpu_physical_cores: System::physical_core_count(),
+        total_memory_bytes: (total_memory > 0).then_some(total_memory),
+        available_memory_bytes: (total_memory > 0).then(|| system.available_memory()),
+        gpus: gpus(),
+        webview_version: or_unknown(tauri::webview_version().ok()),
+        locale: crate::locale::detect(),
+    }
+}
+
+/// 启动时在后台先枚举一次显卡，之后写崩溃报告时不必再等待
+pub fn warm_up() {
+    std::thread::spawn(gpus);
+}
+
+/// 操作系统、CPU、内存、显卡与 WebView 版本，供设置页“关于”与问题反馈使用
+#[tauri::command]
+pub async fn get_system_info(app: AppHandle) -> Result<SystemInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || collect(&app)).await.map_err(|e| e.to_string())
+}
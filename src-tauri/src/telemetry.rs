@@ -0,0 +1,306 @@
+// 匿名使用统计，默认关闭，需在设置中开启（config.json 的 telemetry.enabled）。
+// 只记录粗粒度事件：应用启动、后端启动耗时、后端崩溃退出码、崩溃重启次数，批次附带系统与应用版本；
+// 不含路径、文档内容、用户名或安装 ID，时间只精确到小时。
+// 事件先写入数据目录的 telemetry-queue.json，定期批量 POST 到 telemetry.endpoint，失败按指数退避重试。
+// 关闭时清空队列：尚未发送的事件直接丢弃，不会在关闭后再上传。
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const QUEUE_FILE_NAME: &str = "telemetry-queue.json";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const RETRY_MIN: Duration = Duration::from_secs(60);
+const RETRY_MAX: Duration = Duration::from_secs(4 * 60 * 60);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+const BATCH_SIZE: usize = 100;
+// 长期无法上传时只保留最近的事件
+const MAX_QUEUED: usize = 1000;
+
+// 读写队列文件时串行
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+// 每次清空队列加一；上传期间被清空时，上传完成后不再按条数删除新记录的事件
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static STATUS: Mutex<UploadStatus> = Mutex::new(UploadStatus { last_sent_at: None, last_error: None });
+
+struct UploadStatus {
+    last_sent_at: Option<String>,
+    last_error: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    AppStart,
+    /// 从拉起进程到通过健康检查
+    BackendStart { duration_ms: u64 },
+    BackendCrash { exit_code: Option<i32> },
+    /// 崩溃后自动重启成功，`attempt` 为当前退避窗口内的第几次
+    BackendRestart { attempt: u32 },
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Record {
+    #[serde(flatten)]
+    event: Event,
+    /// UTC，精确到小时，例如 2024-05-01T13:00Z
+    hour: String,
+}
+
+#[derive(serde::Serialize)]
+struct Batch<'a> {
+    app_version: String,
+    os: &'static str,
+    os_version: Option<String>,
+    arch: &'static str,
+    events: &'a [Record],
+}
+
+#[derive(serde::Serialize)]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub endpoint: String,
+    /// 尚未上传的事件数
+    pub queued: usize,
+    pub last_sent_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+fn queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::backend_data_dir(app)?.join(QUEUE_FILE_NAME))
+}
+
+fn read_queue(app: &AppHandle) -> Vec<Record> {
+    queue_path(app).map(|path| read_queue_file(&path)).unwrap_or_default()
+}
+
+// 文件不存在或已损坏时视为空队列
+fn read_queue_file(path: &Path) -> Vec<Record> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_queue(app: &AppHandle, records: &[Record]) -> Result<(), String> {
+    write_queue_file(&queue_path(app)?, records)
+}
+
+fn write_queue_file(path: &Path, records: &[Record]) -> Result<(), String> {
+    let json = serde_json::to_string(records).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn hour(now: chrono::DateTime<chrono::Utc>) -> String {
+    now.format("%Y-%m-%dT%H:00Z").to_string()
+}
+
+// 追加事件，超出 MAX_QUEUED 时丢弃最早的
+fn enqueue(records: &mut Vec<Record>, record: Record) {
+    records.push(record);
+    if records.len() > MAX_QUEUED {
+        records.drain(..records.len() - MAX_QUEUED);
+    }
+}
+
+// 上传失败后的下一次等待：从 RETRY_MIN 开始逐次加倍，不超过 RETRY_MAX
+fn next_retry(retry: Option<Duration>) -> Duration {
+    retry.map_or(RETRY_MIN, |d| (d * 2).min(RETRY_MAX))
+}
+
+/// 记录一个事件；未开启时什么也不做
+pub fn record(app: &AppHandle, event: Event) {
+    if !crate::app_config(app).telemetry.enabled {
+        return;
+    }
+    let _guard = QUEUE_LOCK.lock().unwrap();
+    let mut records = read_queue(app);
+    enqueue(&mut records, Record { event, hour: hour(chrono::Utc::now()) });
+    if let Err(e) = write_queue(app, &records) {
+        app_error!("Failed to queue telemetry event: {}", e);
+    }
+}
+
+/// 丢弃所有尚未上传的事件
+pub fn clear(app: &AppHandle) {
+    let _guard = QUEUE_LOCK.lock().unwrap();
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(path) = queue_path(app) {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                app_error!("Failed to remove {:?}: {}", path, e);
+            }
+        }
+    }
+    *STATUS.lock().unwrap() = UploadStatus { last_sent_at: None, last_error: None };
+}
+
+// 逐批上传直到队列为空；未开启或未配置上报地址时不上传
+async fn flush(app: &AppHandle, client: &reqwest::Client) -> Result<(), String> {
+    loop {
+        let config = crate::app_config(app).telemetry;
+        let endpoint = config.endpoint.trim().to_string();
+        if !config.enabled || endpoint.is_empty() {
+            return Ok(());
+        }
+        let (generation, mut batch) = {
+            let _guard = QUEUE_LOCK.lock().unwrap();
+            (GENERATION.load(Ordering::SeqCst), read_queue(app))
+        };
+        if batch.is_empty() {
+            return Ok(());
+        }
+        batch.truncate(BATCH_SIZE);
+        let body = Batch {
+            app_version: app.package_info().version.to_string(),
+            os: std::env::consts::OS,
+            os_version: sysinfo::System::os_version(),
+            arch: std::env::consts::ARCH,
+            events: &batch,
+        };
+        let response = client
+            .post(&endpoint)
+            .timeout(UPLOAD_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload telemetry: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to upload telemetry: HTTP {}", response.status()));
+        }
+
+        let _guard = QUEUE_LOCK.lock().unwrap();
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            // 上传期间已关闭并清空
+            return Ok(());
+        }
+        let mut records = read_queue(app);
+        records.drain(..batch.len().min(records.len()));
+        write_queue(app, &records)?;
+        STATUS.lock().unwrap().last_sent_at = Some(chrono::Local::now().to_rfc3339());
+    }
+}
+
+/// 记录应用启动，并开始定期上传
+pub fn init(app: &AppHandle) {
+    record(app, Event::AppStart);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut retry: Option<Duration> = None;
+        loop {
+            tokio::time::sleep(retry.unwrap_or(FLUSH_INTERVAL)).await;
+            match flush(&app, &client).await {
+                Ok(()) => {
+                    retry = None;
+                    STATUS.lock().unwrap().last_error = None;
+                }
+                Err(e) => {
+                    let delay = next_retry(retry);
+                    app_log!("{}, retrying in {:?}", e, delay);
+                    STATUS.lock().unwrap().last_error = Some(e);
+                    retry = Some(delay);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_telemetry_status(app: AppHandle) -> TelemetryStatus {
+    let config = crate::app_config(&app).telemetry;
+    let queued = {
+        let _guard = QUEUE_LOCK.lock().unwrap();
+        read_queue(&app).len()
+    };
+    let status = STATUS.lock().unwrap();
+    TelemetryStatus {
+        enabled: config.enabled,
+        endpoint: config.endpoint,
+        queued,
+        last_sent_at: status.last_sent_at.clone(),
+        last_error: status.last_error.clone(),
+    }
+}
+
+/// 开启或关闭匿名统计并写入 config.json；关闭时立即清空本地队列
+#[tauri::command]
+pub fn set_telemetry_enabled(app: AppHandle, enabled: bool) -> Result<TelemetryStatus, String> {
+    app.state::<crate::config::ConfigState>().update(|config| config.telemetry.enabled = enabled)?;
+    if !enabled {
+        clear(&app);
+    }
+    app_log!("Telemetry {}", if enabled { "enabled" } else { "disabled" });
+    Ok(get_telemetry_status(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(event: Event) -> Record {
+        Record { event, hour: "2024-05-01T13:00Z".to_string() }
+    }
+
+    #[test]
+    fn hour_is_truncated_and_utc() {
+        let now = chrono::Utc.with_ymd_and_hms(2024, 5, 1, 13, 59, 58).unwrap();
+        assert_eq!(hour(now), "2024-05-01T13:00Z");
+    }
+
+    #[test]
+    fn records_serialize_only_coarse_fields() {
+        let json = |event| serde_json::to_value(record(event)).unwrap();
+        assert_eq!(json(Event::AppStart), serde_json::json!({ "kind": "app_start", "hour": "2024-05-01T13:00Z" }));
+        assert_eq!(
+            json(Event::BackendStart { duration_ms: 1200 }),
+            serde_json::json!({ "kind": "backend_start", "duration_ms": 1200, "hour": "2024-05-01T13:00Z" })
+        );
+        assert_eq!(
+            json(Event::BackendCrash { exit_code: None }),
+            serde_json::json!({ "kind": "backend_crash", "exit_code": null, "hour": "2024-05-01T13:00Z" })
+        );
+        assert_eq!(json(Event::BackendRestart { attempt: 2 })["attempt"], 2);
+    }
+
+    #[test]
+    fn queue_keeps_only_the_newest_events() {
+        let mut records = Vec::new();
+        for attempt in 0..MAX_QUEUED as u32 + 10 {
+            enqueue(&mut records, record(Event::BackendRestart { attempt }));
+        }
+        assert_eq!(records.len(), MAX_QUEUED);
+        assert!(matches!(records[0].event, Event::BackendRestart { attempt: 10 }));
+    }
+
+    #[test]
+    fn queue_file_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(QUEUE_FILE_NAME);
+        assert!(read_queue_file(&path).is_empty());
+        write_queue_file(&path, &[record(Event::AppStart), record(Event::BackendCrash { exit_code: Some(3) })]).unwrap();
+        let records = read_queue_file(&path);
+        assert_eq!(records.len(), 2);
+        assert!(matches!(records[1].event, Event::BackendCrash { exit_code: Some(3) }));
+
+        std::fs::write(&path, "[{\"kind\":").unwrap();
+        assert!(read_queue_file(&path).is_empty());
+    }
+
+    #[test]
+    fn retry_backs_off_exponentially_up_to_the_cap() {
+        let mut retry = None;
+        let mut delays = Vec::new();
+        for _ in 0..12 {
+            let delay = next_retry(retry);
+            delays.push(delay.as_secs());
+            retry = Some(delay);
+        }
+        assert_eq!(delays[..4], [60, 120, 240, 480]);
+        assert!(delays.iter().all(|&d| d <= RETRY_MAX.as_secs()));
+        assert_eq!(delays.last(), Some(&RETRY_MAX.as_secs()));
+    }
+}
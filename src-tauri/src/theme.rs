@@ -0,0 +1,91 @@
+// 系统深色 / 浅色主题：窗口的 ThemeChanged 事件转发为 `app://theme-changed`，前端据此切换配色。
+// Linux 上该事件依赖 GTK 主题设置，切换 GNOME 等桌面的“深色风格”时经常收不到，
+// 因此另外低频轮询 gsettings 的 color-scheme；没有 gsettings 时只依赖窗口事件。
+
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Theme, Window, WindowEvent};
+
+#[cfg(target_os = "linux")]
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// 上次通知前端的主题，多个窗口会各自收到 ThemeChanged，只在变化时发送
+static LAST: Mutex<Option<&'static str>> = Mutex::new(None);
+
+#[derive(Clone, serde::Serialize)]
+struct ThemeChangedPayload {
+    theme: &'static str,
+}
+
+fn name(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+// GNOME 42+ 的全局偏好："prefer-dark" 为深色，"default" / "prefer-light" 为浅色
+#[cfg(target_os = "linux")]
+fn gsettings_theme() -> Option<&'static str> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let value = String::from_utf8_lossy(&output.stdout);
+    Some(if value.contains("dark") { "dark" } else { "light" })
+}
+
+/// 当前系统主题："dark" 或 "light"；无法判断时为 "light"
+pub fn current(app: &AppHandle) -> &'static str {
+    #[cfg(target_os = "linux")]
+    if let Some(theme) = gsettings_theme() {
+        return theme;
+    }
+    app.webview_windows()
+        .values()
+        .next()
+        .and_then(|window| window.theme().ok())
+        .map_or("light", name)
+}
+
+fn report(app: &AppHandle, theme: &'static str) {
+    let changed = LAST.lock().unwrap().replace(theme) != Some(theme);
+    if changed {
+        app_log!("System theme changed to {}", theme);
+        let _ = app.emit("app://theme-changed", ThemeChangedPayload { theme });
+    }
+}
+
+/// 在 on_window_event 中调用
+pub fn track(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::ThemeChanged(theme) = event {
+        report(window.app_handle(), name(*theme));
+    }
+}
+
+/// 记录初始主题；Linux 上开始轮询 gsettings
+pub fn init(app: &AppHandle) {
+    *LAST.lock().unwrap() = Some(current(app));
+    #[cfg(target_os = "linux")]
+    {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                match tauri::async_runtime::spawn_blocking(gsettings_theme).await {
+                    Ok(Some(theme)) => report(&app, theme),
+                    // 没有 gsettings（非 GNOME 系桌面），不再轮询
+                    _ => return,
+                }
+            }
+        });
+    }
+}
+
+/// Linux 上需要运行 gsettings，命令为异步以免阻塞事件循环
+#[tauri::command]
+pub async fn get_system_theme(app: AppHandle) -> &'static str {
+    current(&app)
+}
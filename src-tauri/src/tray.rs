@@ -0,0 +1,215 @@
+// 系统托盘：图标反映后端状态，菜单提供显示窗口 / 重启后端 / 打开日志目录 / 退出。
+// 状态由已有的生命周期与健康检查事件驱动，不单独轮询。切换界面语言后由 refresh 重建菜单文字。
+
+use tauri::image::Image;
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Listener, Manager, Wry};
+
+use crate::backend::ServerState;
+use crate::i18n::t;
+
+const TRAY_ID: &str = "main";
+
+// 当前状态，切换语言时据此重设提示文字
+static STATUS: Mutex<TrayStatus> = Mutex::new(TrayStatus::Starting);
+// 任务进度（见 progress），附在状态提示之后
+static PROGRESS: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq)]
+enum TrayStatus {
+    Starting,
+    Healthy,
+    Down,
+}
+
+impl TrayStatus {
+    fn tooltip(self) -> String {
+        t(match self {
+            TrayStatus::Starting => "tray.tooltip.starting",
+            TrayStatus::Healthy => "tray.tooltip.running",
+            TrayStatus::Down => "tray.tooltip.stopped",
+        })
+    }
+
+    fn color(self) -> [u8; 3] {
+        match self {
+            TrayStatus::Starting => [0xf5, 0xa6, 0x23],
+            TrayStatus::Healthy => [0x2e, 0xcc, 0x71],
+            TrayStatus::Down => [0xe7, 0x4c, 0x3c],
+        }
+    }
+}
+
+fn tooltip() -> String {
+    let status = STATUS.lock().unwrap().tooltip();
+    match PROGRESS.lock().unwrap().as_deref() {
+        Some(progress) => format!("{} ({})", status, progress),
+        None => status,
+    }
+}
+
+// 在应用图标右下角画一个状态圆点
+fn status_icon(base: &Image<'_>, status: TrayStatus) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = (width.min(height) as f32) * 0.22;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+    let [r, g, b] = status.color();
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance <= radius + 1.0 {
+                let i = ((y * width + x) * 4) as usize;
+                // 外圈 1px 深色描边，便于在浅色任务栏上辨认
+                let pixel = if distance <= radius { [r, g, b, 0xff] } else { [0x20, 0x20, 0x20, 0xff] };
+                rgba[i..i + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+// 会改变托盘状态的后端事件
+const STATUS_EVENTS: [&str; 6] = [
+    "backend://health",
+    "backend://restarted",
+    "backend://exited",
+    "backend://unhealthy",
+    "backend://restart-failed",
+    "backend://start-failed",
+];
+
+fn status_for_event(event: &str, payload: &str) -> Option<TrayStatus> {
+    match event {
+        // 单次探测失败不变红，连续失败会另外收到 backend://unhealthy
+        "backend://health" => serde_json::from_str::<serde_json::Value>(payload)
+            .ok()
+            .and_then(|v| v["healthy"].as_bool())
+            .filter(|healthy| *healthy)
+            .map(|_| TrayStatus::Healthy),
+        "backend://restarted" => Some(TrayStatus::Starting),
+        _ => Some(TrayStatus::Down),
+    }
+}
+
+fn set_status(app: &AppHandle, status: TrayStatus) {
+    *STATUS.lock().unwrap() = status;
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Some(icon) = app.default_window_icon() {
+        let _ = tray.set_icon(Some(status_icon(icon, status)));
+    }
+    let _ = tray.set_tooltip(Some(tooltip()));
+}
+
+/// 托盘图标是否已创建（部分 Linux 桌面环境不支持托盘）
+pub fn is_available(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn open_logs_folder(app: &AppHandle) {
+    // 托盘菜单没有地方显示错误，只记录日志；打开可能要等 xdg-open 返回，放到后台
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = crate::folders::logs_dir(&app).and_then(|dir| crate::folders::reveal(&dir)) {
+            app_error!("{}", e);
+        }
+    });
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "show" => show_main_window(app),
+        "restart" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<ServerState>();
+                if let Err(e) = crate::backend::restart_backend_exclusive(&app, &state).await {
+                    app_error!("Failed to restart backend from tray: {}", e);
+                }
+            });
+        }
+        "open_logs" => open_logs_folder(app),
+        // 走 ExitRequested 流程，先优雅停止后端再退出
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, "show", t("tray.show"), true, None::<&str>)?,
+            &MenuItem::with_id(app, "restart", t("tray.restart"), true, None::<&str>)?,
+            &MenuItem::with_id(app, "open_logs", t("tray.open_logs"), true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?,
+        ],
+    )
+}
+
+/// 按当前语言重建托盘菜单与提示文字
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => app_error!("Failed to rebuild tray menu: {}", e),
+    }
+    let _ = tray.set_tooltip(Some(tooltip()));
+}
+
+/// 在提示文字中显示任务进度，None 表示清除
+pub fn set_progress(app: &AppHandle, text: Option<String>) {
+    *PROGRESS.lock().unwrap() = text;
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tooltip()));
+    }
+}
+
+/// 创建托盘图标并订阅后端事件
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(TrayStatus::Starting.tooltip())
+        // 左键单击显示主窗口，右键打开菜单（Linux 托盘只支持菜单）
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_main_window(tray.app_handle());
+            }
+        })
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(status_icon(icon, TrayStatus::Starting));
+    }
+    builder.build(app)?;
+
+    for event in STATUS_EVENTS {
+        let handle = app.clone();
+        app.listen(event, move |e| {
+            if let Some(status) = status_for_event(event, e.payload()) {
+                set_status(&handle, status);
+            }
+        });
+    }
+    Ok(())
+}
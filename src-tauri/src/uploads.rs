@@ -0,0 +1,248 @@
+// 大文件上传：把 pick_files 选中的文件从磁盘分块 PUT 给后端，文件内容不经过 WebView 内存。
+// 每块带 `Content-Range: bytes <start>-<end>/<total>` 与 `X-Upload-Id`，后端在 `Upload-Offset` 响应头中返回已确认的字节数；
+// 网络错误或 5xx 时先 HEAD 同一端点查询已确认的偏移，从该处重传，单块最多重试 CHUNK_RETRIES 次（后端重启后同样可以续传）。
+// 每个文件是一个 Upload 后台任务（jobs.rs），同时最多上传两个，其余排队，cancel_job 与 cancel_upload 效果相同；进度以 `upload://<request_id>/progress` 发送，
+// 结束发送 `/done`（含最后一块的响应），失败发送 `/error`（payload 同 backend_request 的错误）。
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::backend_client::BackendClient;
+use crate::jobs::{self, JobHandle, JobKind, JobSpec};
+use crate::proxy::{self, ProxyError};
+
+const CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(120);
+const CHUNK_RETRIES: u32 = 3;
+// 排队与进行中的上传总数上限
+const MAX_UPLOADS: usize = 32;
+const UPLOAD_ID_HEADER: &str = "X-Upload-Id";
+const UPLOAD_NAME_HEADER: &str = "X-Upload-Name";
+const UPLOAD_OFFSET_HEADER: &str = "Upload-Offset";
+
+static UPLOADS: Mutex<Option<HashMap<String, tauri::async_runtime::JoinHandle<()>>>> = Mutex::new(None);
+
+/// `upload://<request_id>/progress` 事件负载；排队期间 queued 为 true
+#[derive(Clone, serde::Serialize)]
+struct UploadProgress {
+    bytes_sent: u64,
+    total_bytes: u64,
+    bytes_per_sec: u64,
+    queued: bool,
+}
+
+/// `upload://<request_id>/done` 事件负载；取消时 response 为 null
+#[derive(Clone, serde::Serialize)]
+struct UploadDone {
+    bytes_sent: u64,
+    cancelled: bool,
+    response: serde_json::Value,
+}
+
+struct Upload {
+    app: AppHandle,
+    request_id: String,
+    path: PathBuf,
+    endpoint: String,
+    total_bytes: u64,
+    // 排队结束、开始上传后才有
+    job: Option<JobHandle>,
+}
+
+// 可以重试的失败：连接不上、超时、5xx 及 408 / 429
+fn is_transient(error: &ProxyError) -> bool {
+    match error {
+        ProxyError::BackendDown { .. } | ProxyError::Timeout { .. } => true,
+        ProxyError::Other { message } => {
+            message.starts_with("HTTP 5") || message.starts_with("HTTP 408") || message.starts_with("HTTP 429")
+        }
+        _ => false,
+    }
+}
+
+fn upload_offset(response: &reqwest::Response) -> Option<u64> {
+    response.headers().get(UPLOAD_OFFSET_HEADER)?.to_str().ok()?.trim().parse().ok()
+}
+
+fn read_chunk(path: &Path, offset: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buffer = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+impl Upload {
+    // 每次请求重新读取地址与 token，后端重启后继续上传
+    fn request(&self, client: &BackendClient, method: reqwest::Method) -> reqwest::RequestBuilder {
+        client.request(method, &self.endpoint).header(UPLOAD_ID_HEADER, &self.request_id)
+    }
+
+    fn emit_progress(&self, bytes_sent: u64, bytes_per_sec: u64, queued: bool) {
+        if let Some(job) = &self.job {
+            job.set_progress(bytes_sent, self.total_bytes);
+        }
+        let progress = UploadProgress { bytes_sent, total_bytes: self.total_bytes, bytes_per_sec, queued };
+        let _ = self.app.emit(&format!("upload://{}/progress", self.request_id), progress);
+    }
+
+    // 后端已确认的字节数；尚未收到任何数据（404）时为 0
+    async fn acknowledged_offset(&self) -> Result<u64, ProxyError> {
+        let client = BackendClient::current(&self.app);
+        let response = client.send_idempotent(self.request(&client, reqwest::Method::HEAD), CHUNK_TIMEOUT).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(0);
+        }
+        if !response.status().is_success() {
+            return Err(ProxyError::Other { message: format!("HTTP {}", response.status()) });
+        }
+        Ok(upload_offset(&response).unwrap_or(0).min(self.total_bytes))
+    }
+
+    // 发送从 offset 开始的一块，返回后端确认后的偏移及响应体
+    async fn send_chunk(&self, offset: u64) -> Result<(u64, serde_json::Value), ProxyError> {
+        let len = CHUNK_SIZE.min(self.total_bytes - offset);
+        let path = self.path.clone();
+        let chunk = tauri::async_runtime::spawn_blocking(move || read_chunk(&path, offset, len))
+            .await
+            .map_err(|e| ProxyError::Other { message: e.to_string() })?
+            .map_err(|e| ProxyError::Other { message: format!("Failed to read {}: {}", self.path.display(), e) })?;
+        let end = offset + chunk.len() as u64;
+        // 空文件也发送一次，让后端创建文件
+        let range = if chunk.is_empty() {
+            format!("bytes */{}", self.total_bytes)
+        } else {
+            format!("bytes {}-{}/{}", offset, end - 1, self.total_bytes)
+        };
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let client = BackendClient::current(&self.app);
+        let request = self
+            .request(&client, reqwest::Method::PUT)
+            .header(reqwest::header::CONTENT_RANGE, range)
+            .header(UPLOAD_NAME_HEADER, name)
+            .body(chunk);
+        let response = client.send(request, Some(CHUNK_TIMEOUT)).await?;
+        if !response.status().is_success() {
+            return Err(ProxyError::Other { message: format!("HTTP {}", response.status()) });
+        }
+        let acknowledged = upload_offset(&response).unwrap_or(end).min(self.total_bytes);
+        let bytes = response.bytes().await.map_err(|e| client.error(e, Some(CHUNK_TIMEOUT)))?;
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&bytes).to_string()));
+        Ok((acknowledged, body))
+    }
+
+    async fn run(&mut self) -> Result<(u64, serde_json::Value), ProxyError> {
+        self.emit_progress(0, 0, true);
+        let (app, request_id) = (self.app.clone(), self.request_id.clone());
+        let label = self.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let spec = JobSpec::new(JobKind::Upload, label).on_cancel(move || {
+            cancel_upload(app.clone(), request_id.clone());
+        });
+        let job = jobs::start(&self.app, spec).await.map_err(|message| ProxyError::Other { message })?;
+        self.job = Some(job.handle());
+        let result = self.transfer().await;
+        job.finish(&result.as_ref().map_err(|e| format!("{:?}", e)));
+        result
+    }
+
+    async fn transfer(&self) -> Result<(u64, serde_json::Value), ProxyError> {
+        app_log!("Uploading {} ({} bytes) to {}", self.path.display(), self.total_bytes, self.endpoint);
+
+        let started = Instant::now();
+        // 同一 request_id 之前取消或失败过时从后端已确认的偏移继续
+        let resumed_from = self.acknowledged_offset().await.unwrap_or(0);
+        if resumed_from > 0 {
+            app_log!("Resuming upload {} at {} bytes", self.request_id, resumed_from);
+        }
+        let mut offset = resumed_from;
+        let mut failures = 0u32;
+        loop {
+            match self.send_chunk(offset).await {
+                Ok((acknowledged, _)) if acknowledged <= offset && offset < self.total_bytes => {
+                    return Err(ProxyError::Other { message: format!("Backend did not accept the chunk at {}", offset) });
+                }
+                Ok((acknowledged, body)) => {
+                    failures = 0;
+                    offset = acknowledged;
+                    let elapsed = started.elapsed().as_secs_f64().max(0.001);
+                    let sent = offset.saturating_sub(resumed_from);
+                    self.emit_progress(offset, (sent as f64 / elapsed) as u64, false);
+                    if offset >= self.total_bytes {
+                        return Ok((offset, body));
+                    }
+                }
+                Err(e) if is_transient(&e) && failures < CHUNK_RETRIES => {
+                    failures += 1;
+                    let delay = Duration::from_secs(1 << (failures - 1));
+                    app_log!("Upload {} chunk at {} failed ({:?}), retrying in {:?}", self.request_id, offset, e, delay);
+                    tokio::time::sleep(delay).await;
+                    // 失败的请求可能已部分写入，按后端确认的偏移续传
+                    offset = self.acknowledged_offset().await.unwrap_or(offset);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// 把 pick_files 选中的文件分块上传到后端 `endpoint`（限制同 backend_request 的 path），立即返回；
+/// 进度与结果以 `upload://<request_id>/*` 事件通知。超过同时上传数时排队
+#[tauri::command]
+pub fn upload_file_to_backend(app: AppHandle, path: String, endpoint: String, request_id: String) -> Result<(), ProxyError> {
+    proxy::validate_path(&endpoint)?;
+    crate::streams::validate_request_id(&request_id)?;
+    let path = crate::file_picker::ensure_picked(&app, &path).map_err(proxy::invalid)?;
+    let total_bytes = std::fs::metadata(&path)
+        .map_err(|e| proxy::invalid(format!("{} is not readable: {}", path.display(), e)))?
+        .len();
+
+    // 持锁完成检查、启动与登记，任务结束时的移除会排在登记之后
+    let mut uploads = UPLOADS.lock().unwrap();
+    let uploads = uploads.get_or_insert_with(HashMap::new);
+    if uploads.contains_key(&request_id) {
+        return Err(proxy::invalid(format!("Upload {} is already running", request_id)));
+    }
+    if uploads.len() >= MAX_UPLOADS {
+        return Err(proxy::invalid(format!("Too many pending uploads (limit {})", MAX_UPLOADS)));
+    }
+    let mut upload = Upload { app, request_id: request_id.clone(), path, endpoint, total_bytes, job: None };
+    let task = tauri::async_runtime::spawn(async move {
+        let result = upload.run().await;
+        if let Some(uploads) = UPLOADS.lock().unwrap().as_mut() {
+            uploads.remove(&upload.request_id);
+        }
+        match result {
+            Ok((bytes_sent, response)) => {
+                app_log!("Upload {} finished ({} bytes)", upload.request_id, bytes_sent);
+                let done = UploadDone { bytes_sent, cancelled: false, response };
+                let _ = upload.app.emit(&format!("upload://{}/done", upload.request_id), done);
+            }
+            Err(e) => {
+                app_error!("Upload {} failed: {:?}", upload.request_id, e);
+                let _ = upload.app.emit(&format!("upload://{}/error", upload.request_id), e);
+            }
+        }
+    });
+    uploads.insert(request_id, task);
+    Ok(())
+}
+
+/// 取消排队中或进行中的上传并发送 `upload://<request_id>/done`（cancelled 为 true）；
+/// 后端保留已收到的部分，用同一 request_id 重新上传时从已确认的偏移继续。上传不存在或已结束时返回 false
+#[tauri::command]
+pub fn cancel_upload(app: AppHandle, request_id: String) -> bool {
+    let task = UPLOADS.lock().unwrap().as_mut().and_then(|uploads| uploads.remove(&request_id));
+    let Some(task) = task else {
+        return false;
+    };
+    task.abort();
+    app_log!("Upload {} cancelled", request_id);
+    let done = UploadDone { bytes_sent: 0, cancelled: true, response: serde_json::Value::Null };
+    let _ = app.emit(&format!("upload://{}/done", request_id), done);
+    true
+}
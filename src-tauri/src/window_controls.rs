@@ -0,0 +1,85 @@
+// 自绘标题栏：config.json 中 custom_titlebar 为 true 时主窗口不带系统边框，由前端绘制最小化 / 最大化 / 关闭按钮。
+// 命令作用于调用它的窗口。关闭走 window.close()，与点击系统关闭按钮一样触发 CloseRequested，
+// 隐藏到托盘、确认关闭辅助窗口与停止后端的流程不变。
+// 最大化状态变化时发送 `window://maximized`，前端据此切换还原图标。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager, WebviewWindow, Window, WindowEvent};
+
+// 各窗口上次发送的最大化状态，缩放事件很密集，只在变化时发送
+static MAXIMIZED: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
+
+#[derive(Clone, serde::Serialize)]
+struct MaximizedPayload {
+    label: String,
+    maximized: bool,
+}
+
+fn report_maximized(window: &Window) {
+    let Ok(maximized) = window.is_maximized() else {
+        return;
+    };
+    let changed = MAXIMIZED
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(window.label().to_string(), maximized)
+        != Some(maximized);
+    if changed {
+        let _ = window.emit("window://maximized", MaximizedPayload { label: window.label().to_string(), maximized });
+    }
+}
+
+/// 在 on_window_event 中调用，跟踪所有窗口的最大化状态
+pub fn track(window: &Window, event: &WindowEvent) {
+    match event {
+        WindowEvent::Resized(_) => report_maximized(window),
+        WindowEvent::Destroyed => {
+            if let Some(states) = MAXIMIZED.lock().unwrap().as_mut() {
+                states.remove(window.label());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 显示或隐藏系统边框；对主窗口的修改会写入 config.json 的 custom_titlebar，下次启动沿用
+#[tauri::command]
+pub fn set_decorations(window: WebviewWindow, enabled: bool) -> Result<(), String> {
+    window.set_decorations(enabled).map_err(|e| e.to_string())?;
+    if window.label() == "main" {
+        let app = window.app_handle();
+        app.state::<crate::config::ConfigState>().update(|config| config.custom_titlebar = !enabled)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn window_minimize(window: WebviewWindow) -> Result<(), String> {
+    window.minimize().map_err(|e| e.to_string())
+}
+
+/// 最大化或还原，返回操作后是否处于最大化
+#[tauri::command]
+pub fn window_toggle_maximize(window: WebviewWindow) -> Result<bool, String> {
+    let maximized = window.is_maximized().map_err(|e| e.to_string())?;
+    let result = if maximized { window.unmaximize() } else { window.maximize() };
+    result.map_err(|e| e.to_string())?;
+    Ok(!maximized)
+}
+
+/// 前端拖动区域的 mousedown 处理：按下即交给系统拖动窗口，双击（`click_count` 为 2）时改为最大化 / 还原
+#[tauri::command]
+pub fn window_start_dragging(window: WebviewWindow, click_count: Option<u32>) -> Result<(), String> {
+    if click_count.unwrap_or(1) >= 2 {
+        return window_toggle_maximize(window).map(|_| ());
+    }
+    window.start_dragging().map_err(|e| e.to_string())
+}
+
+/// 自绘关闭按钮：与系统关闭按钮走同一条 CloseRequested 流程
+#[tauri::command]
+pub fn window_close(window: WebviewWindow) -> Result<(), String> {
+    window.close().map_err(|e| e.to_string())
+}
@@ -0,0 +1,253 @@
+// 主窗口位置 / 大小 / 最大化状态：移动、缩放、关闭时写入 <app_data_dir>/window-state.json，
+// 启动时在窗口显示前恢复。保存时所在的显示器已不存在时，移到最近的显示器内。
+// 各窗口（按标签）的缩放比例也保存在同一文件中，见 zoom.rs。
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow, Window, WindowEvent};
+
+pub const WINDOW_STATE_FILE_NAME: &str = "window-state.json";
+const MAIN_WINDOW_LABEL: &str = "main";
+// 拖动 / 缩放时事件非常密集，停止变化一段时间后再写盘
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+// 至少要有这么大一块（标题栏附近）留在某个显示器上，窗口才算可见
+const MIN_VISIBLE_WIDTH: i64 = 100;
+const MIN_VISIBLE_HEIGHT: i64 = 50;
+
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+// 各窗口的缩放比例，启动时从文件读入，保存主窗口状态时一并写入
+static ZOOM: Mutex<BTreeMap<String, f64>> = Mutex::new(BTreeMap::new());
+
+/// 物理像素坐标下的矩形
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    fn center(&self) -> (i64, i64) {
+        (self.x as i64 + self.width as i64 / 2, self.y as i64 + self.height as i64 / 2)
+    }
+
+    fn overlaps_visibly(&self, other: &Rect) -> bool {
+        let width = self.right().min(other.right()) - (self.x as i64).max(other.x as i64);
+        let height = self.bottom().min(other.bottom()) - (self.y as i64).max(other.y as i64);
+        width >= MIN_VISIBLE_WIDTH && height >= MIN_VISIBLE_HEIGHT
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct WindowState {
+    // 非最大化时的位置和大小，最大化期间保持不变，取消最大化后可以还原
+    bounds: Rect,
+    maximized: bool,
+    monitor: Option<String>,
+    // 只记录不是 100% 的窗口
+    #[serde(default)]
+    zoom: BTreeMap<String, f64>,
+}
+
+/// 把窗口矩形放回可见区域：仍与某个显示器有足够重叠时原样返回，
+/// 否则移到中心距离最近的显示器内（必要时缩小到显示器尺寸）
+pub fn clamp_to_monitors(rect: Rect, monitors: &[Rect]) -> Rect {
+    if monitors.iter().any(|m| rect.overlaps_visibly(m)) {
+        return rect;
+    }
+    let (cx, cy) = rect.center();
+    let Some(target) = monitors.iter().min_by_key(|m| {
+        let (mx, my) = m.center();
+        // 文件中的坐标可能是任意值，平方会溢出
+        (mx - cx).saturating_pow(2).saturating_add((my - cy).saturating_pow(2))
+    }) else {
+        return rect;
+    };
+    let width = rect.width.min(target.width);
+    let height = rect.height.min(target.height);
+    let max_x = target.right() - width as i64;
+    let max_y = target.bottom() - height as i64;
+    Rect {
+        x: (rect.x as i64).clamp(target.x as i64, max_x) as i32,
+        y: (rect.y as i64).clamp(target.y as i64, max_y) as i32,
+        width,
+        height,
+    }
+}
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    crate::backend_data_dir(app).ok().map(|dir| dir.join(WINDOW_STATE_FILE_NAME))
+}
+
+fn load(app: &AppHandle) -> Option<WindowState> {
+    let content = std::fs::read_to_string(state_path(app)?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// 读取窗口当前状态；最大化时沿用上次保存的正常尺寸
+fn capture(window: &Window) -> Option<WindowState> {
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window.current_monitor().ok().flatten().and_then(|m| m.name().cloned());
+    let bounds = if maximized {
+        load(window.app_handle())?.bounds
+    } else {
+        let position = window.outer_position().ok()?;
+        let size = window.inner_size().ok()?;
+        Rect { x: position.x, y: position.y, width: size.width, height: size.height }
+    };
+    let zoom = ZOOM.lock().unwrap().clone();
+    Some(WindowState { bounds, maximized, monitor, zoom })
+}
+
+fn save(window: &Window) {
+    // 最小化时位置是系统给的占位值，不保存；隐藏的窗口（以托盘方式启动后从未显示，或已隐藏到托盘）
+    // 的位置在显示期间的移动、缩放与关闭时已经保存过
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true) {
+        return;
+    }
+    let (Some(state), Some(path)) = (capture(window), state_path(window.app_handle())) else {
+        return;
+    };
+    if let Err(e) = serde_json::to_string_pretty(&state)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()))
+    {
+        app_error!("Failed to save window state to {:?}: {}", path, e);
+    }
+}
+
+/// 处理主窗口事件：移动 / 缩放时防抖保存，关闭时立即保存
+pub fn track(window: &Window, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW_LABEL {
+        return;
+    }
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+            let window = window.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(SAVE_DEBOUNCE).await;
+                if SAVE_GENERATION.load(Ordering::SeqCst) == generation {
+                    save(&window);
+                }
+            });
+        }
+        WindowEvent::CloseRequested { .. } => save(window),
+        _ => {}
+    }
+}
+
+/// 立即保存主窗口状态，用于不经过 CloseRequested 的退出路径
+pub fn save_main(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        save(&window.as_ref().window());
+    }
+}
+
+/// 窗口上次保存的缩放比例
+pub fn saved_zoom(label: &str) -> Option<f64> {
+    ZOOM.lock().unwrap().get(label).copied()
+}
+
+/// 记录窗口的缩放比例（None 表示恢复为 100%）并随主窗口状态写入文件
+pub fn save_zoom(app: &AppHandle, label: &str, factor: Option<f64>) {
+    {
+        let mut zoom = ZOOM.lock().unwrap();
+        match factor {
+            Some(factor) => zoom.insert(label.to_string(), factor),
+            None => zoom.remove(label),
+        };
+    }
+    save_main(app);
+}
+
+/// 在窗口显示前恢复上次保存的状态
+pub fn restore(window: &WebviewWindow) {
+    let Some(state) = load(window.app_handle()) else {
+        return;
+    };
+    *ZOOM.lock().unwrap() = state.zoom;
+    let monitors: Vec<Rect> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| Rect {
+            x: m.position().x,
+            y: m.position().y,
+            width: m.size().width,
+            height: m.size().height,
+        })
+        .collect();
+    let bounds = clamp_to_monitors(state.bounds, &monitors);
+    let _ = window.set_size(PhysicalSize::new(bounds.width, bounds.height));
+    let _ = window.set_position(PhysicalPosition::new(bounds.x, bounds.y));
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: Rect = Rect { x: 0, y: 0, width: 1920, height: 1080 };
+    // 主显示器左侧的副显示器
+    const LEFT: Rect = Rect { x: -1280, y: 0, width: 1280, height: 1024 };
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    #[test]
+    fn visible_window_is_unchanged() {
+        for window in [rect(100, 100, 800, 600), rect(-400, 200, 800, 600), rect(1800, 1000, 800, 600)] {
+            assert_eq!(clamp_to_monitors(window, &[PRIMARY, LEFT]), window);
+        }
+    }
+
+    #[test]
+    fn off_screen_window_moves_onto_the_nearest_monitor() {
+        // 只露出主显示器右下角一小块，不算可见
+        assert_eq!(clamp_to_monitors(rect(1900, 1060, 800, 600), &[PRIMARY]), rect(1120, 480, 800, 600));
+        assert_eq!(clamp_to_monitors(rect(5000, -3000, 800, 600), &[PRIMARY]), rect(1120, 0, 800, 600));
+        assert_eq!(clamp_to_monitors(rect(-5000, 300, 800, 600), &[PRIMARY, LEFT]), rect(-1280, 300, 800, 600));
+    }
+
+    #[test]
+    fn too_large_window_shrinks_to_the_monitor() {
+        assert_eq!(clamp_to_monitors(rect(3000, 0, 4000, 3000), &[PRIMARY]), PRIMARY);
+        // 宽度超出、高度合适时只缩小宽度
+        assert_eq!(clamp_to_monitors(rect(-5000, 100, 2000, 600), &[LEFT]), rect(-1280, 100, 1280, 600));
+    }
+
+    #[test]
+    fn window_on_a_removed_monitor_moves_to_a_remaining_one() {
+        // 保存时在左侧副显示器上，如今只剩主显示器
+        let saved = rect(-1000, 200, 800, 600);
+        assert_eq!(clamp_to_monitors(saved, &[PRIMARY]), rect(0, 200, 800, 600));
+    }
+
+    #[test]
+    fn no_monitors_leaves_the_window_alone() {
+        let saved = rect(-1000, 200, 800, 600);
+        assert_eq!(clamp_to_monitors(saved, &[]), saved);
+    }
+
+    #[test]
+    fn extreme_coordinates_do_not_overflow() {
+        let clamped = clamp_to_monitors(rect(i32::MAX, i32::MIN, u32::MAX, u32::MAX), &[PRIMARY]);
+        assert_eq!(clamped, PRIMARY);
+    }
+}
@@ -0,0 +1,85 @@
+// 网页缩放：按窗口标签分别记录，保存在 window-state.json 中，窗口创建后重新应用。
+// 快捷键 CmdOrCtrl+= / CmdOrCtrl+- / CmdOrCtrl+0 由应用菜单的视图菜单注册，
+// 焦点不在网页中（例如在原生对话框里）时同样有效，作用于当前聚焦的窗口。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, WebviewWindow};
+
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 3.0;
+// 快捷键每次调整的幅度
+pub const ZOOM_STEP: f64 = 0.1;
+
+// WebView 不提供读取缩放比例的接口，由这里记录各窗口当前的值
+static CURRENT: Mutex<Option<HashMap<String, f64>>> = Mutex::new(None);
+
+fn current(label: &str) -> f64 {
+    CURRENT
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|zoom| zoom.get(label).copied())
+        .unwrap_or(1.0)
+}
+
+// 限制在 MIN_ZOOM..=MAX_ZOOM 并取两位小数，避免连续调整累积浮点误差
+fn clamp(factor: f64) -> f64 {
+    (factor.clamp(MIN_ZOOM, MAX_ZOOM) * 100.0).round() / 100.0
+}
+
+fn apply(app: &AppHandle, window: &WebviewWindow, factor: f64) -> Result<f64, String> {
+    if !factor.is_finite() {
+        return Err(format!("Invalid zoom factor {}", factor));
+    }
+    let factor = clamp(factor);
+    window.set_zoom(factor).map_err(|e| e.to_string())?;
+    CURRENT
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(window.label().to_string(), factor);
+    let saved = (factor != 1.0).then_some(factor);
+    crate::window_state::save_zoom(app, window.label(), saved);
+    Ok(factor)
+}
+
+/// 窗口创建后调用，恢复上次保存的缩放比例
+pub fn restore(window: &WebviewWindow) {
+    let Some(factor) = crate::window_state::saved_zoom(window.label()) else {
+        return;
+    };
+    let factor = clamp(factor);
+    if let Err(e) = window.set_zoom(factor) {
+        app_error!("Failed to restore zoom for {}: {}", window.label(), e);
+        return;
+    }
+    CURRENT
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(window.label().to_string(), factor);
+}
+
+/// 在当前比例上增减 `delta`，供菜单快捷键使用
+pub fn zoom_by(app: &AppHandle, window: &WebviewWindow, delta: f64) {
+    if let Err(e) = apply(app, window, current(window.label()) + delta) {
+        app_error!("{}", e);
+    }
+}
+
+/// 设置调用方窗口的缩放比例（1.0 为 100%，限制在 0.5–3.0），返回实际生效的值
+#[tauri::command]
+pub fn set_zoom(app: AppHandle, window: WebviewWindow, factor: f64) -> Result<f64, String> {
+    apply(&app, &window, factor)
+}
+
+#[tauri::command]
+pub fn get_zoom(window: WebviewWindow) -> f64 {
+    current(window.label())
+}
+
+#[tauri::command]
+pub fn reset_zoom(app: AppHandle, window: WebviewWindow) -> Result<f64, String> {
+    apply(&app, &window, 1.0)
+}
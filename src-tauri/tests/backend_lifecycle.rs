@@ -0,0 +1,134 @@
+// BackendManager 的生命周期：启动、崩溃后的退避重启、启动中停止、重启次数耗尽。
+// 需要 mock-backend 特性：cargo test --features mock-backend
+
+#![cfg(feature = "mock-backend")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{wait_until, Options, TestHost};
+use duncrew_lib::backend::{BackendError, BackendState, Lifecycle, RestartPolicy};
+use tauri::async_runtime::block_on;
+
+#[test]
+fn start_reaches_running() {
+    block_on(async {
+        let host = TestHost::new(Options::default());
+        let pid = host.start().await.unwrap();
+
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Running);
+        assert_eq!(host.manager().state().state, BackendState::Running);
+        assert_eq!(host.lifecycles(), [Lifecycle::Starting, Lifecycle::Running]);
+        assert!(host.manager().wait_ready(Duration::ZERO).await.is_ok());
+        common::get(host.port(), "/health").await.unwrap();
+
+        // 已在运行时 start 直接返回当前 PID，不再拉起进程
+        assert_eq!(host.start().await.unwrap(), pid);
+        assert_eq!(host.launches().len(), 1);
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+        assert!(host.released());
+    });
+}
+
+#[test]
+fn crash_restarts_after_backoff() {
+    block_on(async {
+        let host = TestHost::new(Options::default());
+        let first = host.start().await.unwrap();
+        host.command(serde_json::json!({ "action": "crash", "code": 3 }));
+
+        assert!(wait_until(Duration::from_secs(5), || host.launches().len() == 2).await);
+        assert!(wait_until(Duration::from_secs(5), || host.manager().lifecycle() == Lifecycle::Running).await);
+        let launches = host.launches();
+        assert_ne!(launches[1].1, first);
+        // 第一次崩溃等待 base_delay 后才重启
+        let crashed = host.crashes()[0];
+        assert!(launches[1].0.duration_since(crashed) >= Duration::from_millis(100));
+        assert_eq!(
+            host.lifecycles(),
+            [
+                Lifecycle::Starting,
+                Lifecycle::Running,
+                Lifecycle::Failed,
+                Lifecycle::Starting,
+                Lifecycle::Running,
+            ]
+        );
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+    });
+}
+
+#[test]
+fn kill_during_starting_fails_the_start_without_restarting() {
+    block_on(async {
+        let host = TestHost::new(Options { startup_delay: Some(Duration::from_secs(30)), ..Options::default() });
+        let start = tauri::async_runtime::spawn({
+            let host = host.clone();
+            async move { host.start().await }
+        });
+        assert!(wait_until(Duration::from_secs(5), || host.launches().len() == 1).await);
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Starting);
+
+        // 关闭窗口等强制停止的路径：不等待进行中的启动
+        host.manager().kill(&host).await;
+        let result = start.await.unwrap();
+        assert!(matches!(result, Err(BackendError::FailedToStart { .. })), "{:?}", result.err());
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+
+        // 被结束的进程不按崩溃处理
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(host.launches().len(), 1);
+        assert!(host.crashes().is_empty());
+    });
+}
+
+#[test]
+fn shutdown_during_starting_waits_for_the_start() {
+    block_on(async {
+        let host = TestHost::new(Options { startup_delay: Some(Duration::from_millis(300)), ..Options::default() });
+        let start = tauri::async_runtime::spawn({
+            let host = host.clone();
+            async move { host.start().await }
+        });
+        assert!(wait_until(Duration::from_secs(5), || host.manager().lifecycle() == Lifecycle::Starting).await);
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+        assert!(start.await.unwrap().is_ok());
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+        assert_eq!(
+            host.lifecycles(),
+            [Lifecycle::Starting, Lifecycle::Running, Lifecycle::Stopping, Lifecycle::Stopped]
+        );
+        assert!(host.crashes().is_empty());
+    });
+}
+
+#[test]
+fn exhausted_restart_budget_enters_crash_loop() {
+    block_on(async {
+        let policy = RestartPolicy {
+            base_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(50),
+            max_attempts: 2,
+            window: Duration::from_secs(60),
+        };
+        let host = TestHost::new(Options { crash_after_ready: true, restart_policy: policy, ..Options::default() });
+        host.start().await.unwrap();
+
+        assert!(wait_until(Duration::from_secs(10), || host.manager().lifecycle() == Lifecycle::CrashLoop).await);
+        assert_eq!(host.manager().state().state, BackendState::CrashLoop);
+        // 首次启动加 max_attempts 次重启，第三次崩溃后放弃
+        assert_eq!(host.launches().len(), 3);
+        assert_eq!(host.crashes().len(), 3);
+
+        let waited = host.manager().wait_ready(Duration::from_secs(1)).await.unwrap_err();
+        assert!(!waited.timed_out);
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(host.launches().len(), 3);
+    });
+}
@@ -0,0 +1,66 @@
+// 并发重启：同时到达的重启请求共用进行中的那次启动，不会拉起第二个进程、替换仍在运行的句柄。
+// 需要 mock-backend 特性：cargo test --features mock-backend
+
+#![cfg(feature = "mock-backend")]
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{Options, TestHost};
+use duncrew_lib::backend::{Host, Lifecycle, PortPolicy, Restart};
+use tauri::async_runtime::block_on;
+
+const CONCURRENT_RESTARTS: usize = 20;
+
+#[test]
+fn concurrent_restarts_join_one_start() {
+    block_on(async {
+        // 就绪前的等待拉长启动过程，所有请求都在进行中的那次重启期间到达
+        let host = TestHost::new(Options { startup_delay: Some(Duration::from_millis(300)), ..Options::default() });
+        let first = host.start().await.unwrap();
+
+        let barrier = Arc::new(tokio::sync::Barrier::new(CONCURRENT_RESTARTS));
+        let tasks: Vec<_> = (0..CONCURRENT_RESTARTS)
+            .map(|_| {
+                let host = host.clone();
+                let barrier = barrier.clone();
+                tauri::async_runtime::spawn(async move {
+                    barrier.wait().await;
+                    host.manager().restart(&host, Duration::from_secs(2), PortPolicy::Any(0), || {}).await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for task in tasks {
+            results.push(task.await.unwrap().unwrap());
+        }
+
+        let started: Vec<_> = results.iter().filter(|r| matches!(r, Restart::Started(_))).collect();
+        assert_eq!(started.len(), 1, "{:?}", results);
+        let pid = started[0].pid();
+        assert_ne!(pid, first);
+        assert!(results.iter().all(|r| r.pid() == pid), "{:?}", results);
+
+        // 只拉起过首次启动与这一次重启，当前句柄就是这次重启的进程
+        let launches: Vec<u32> = host.launches().iter().map(|(_, pid)| *pid).collect();
+        assert_eq!(launches, [first, pid]);
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Running);
+        assert_eq!(host.pid(), Some(pid));
+        assert_eq!(
+            host.lifecycles(),
+            [
+                Lifecycle::Starting,
+                Lifecycle::Running,
+                Lifecycle::Stopping,
+                Lifecycle::Stopped,
+                Lifecycle::Starting,
+                Lifecycle::Running,
+            ]
+        );
+        common::get(host.port(), "/health").await.unwrap();
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+    });
+}
@@ -0,0 +1,347 @@
+// 集成测试共用的 Host：用进程内的模拟后端（backend::mock）驱动 BackendManager，
+// test-sidecar 特性下可改为经 sidecar::spawn 启动 tests/mock_server 的真实进程（Options::sidecar）。
+// 崩溃后按 RestartTracker 退避重启，与应用中 handle_backend_exit / schedule_crash_restart 的顺序相同。
+
+#![allow(dead_code)]
+
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use duncrew_lib::backend::{
+    mock, BackendError, BackendEvent, BackendManager, Host, Launch, Lifecycle, PortPolicy, RestartPolicy,
+    RestartTracker, Spawned, StateTransition,
+};
+
+/// 进程的 PID、退出码与信号
+pub type Exit = (u32, Option<i32>, Option<i32>);
+
+pub struct Options {
+    /// 等待就绪的最长时间
+    pub ready_timeout: Duration,
+    /// 模拟后端开始监听后、响应请求前的等待
+    pub startup_delay: Option<Duration>,
+    /// 每次就绪后立即崩溃，用于耗尽重启次数
+    pub crash_after_ready: bool,
+    pub restart_policy: RestartPolicy,
+    /// 启动 tests/mock_server 进程而不是进程内的模拟后端，需要 test-sidecar 特性
+    pub sidecar: bool,
+    /// 临时数据目录的名称前缀
+    pub data_dir_prefix: &'static str,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            ready_timeout: Duration::from_secs(5),
+            startup_delay: None,
+            crash_after_ready: false,
+            restart_policy: RestartPolicy {
+                base_delay: Duration::from_millis(100),
+                max_delay: Duration::from_millis(400),
+                max_attempts: 3,
+                window: Duration::from_secs(60),
+            },
+            sidecar: false,
+            data_dir_prefix: "duncrew-test-",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TestHost {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    manager: BackendManager,
+    data_dir: tempfile::TempDir,
+    options: Options,
+    pid: Mutex<Option<u32>>,
+    port: Mutex<u16>,
+    // 每次 launch 的时间与 PID
+    launches: Mutex<Vec<(Instant, u32)>>,
+    lifecycles: Mutex<Vec<Lifecycle>>,
+    crashes: Mutex<Vec<Instant>>,
+    restarts: Mutex<RestartTracker>,
+    stdout: Mutex<Vec<String>>,
+    stderr: Mutex<Vec<String>>,
+    exits: Mutex<Vec<Exit>>,
+    released: AtomicBool,
+}
+
+impl TestHost {
+    pub fn new(options: Options) -> Self {
+        let restarts = RestartTracker::new(options.restart_policy);
+        Self {
+            inner: Arc::new(Inner {
+                manager: BackendManager::default(),
+                data_dir: tempfile::Builder::new().prefix(options.data_dir_prefix).tempdir().unwrap(),
+                options,
+                pid: Mutex::new(None),
+                port: Mutex::new(0),
+                launches: Mutex::new(Vec::new()),
+                lifecycles: Mutex::new(Vec::new()),
+                crashes: Mutex::new(Vec::new()),
+                restarts: Mutex::new(restarts),
+                stdout: Mutex::new(Vec::new()),
+                stderr: Mutex::new(Vec::new()),
+                exits: Mutex::new(Vec::new()),
+                released: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn manager(&self) -> &BackendManager {
+        &self.inner.manager
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        self.inner.data_dir.path()
+    }
+
+    pub fn port(&self) -> u16 {
+        *self.inner.port.lock().unwrap()
+    }
+
+    pub fn launches(&self) -> Vec<(Instant, u32)> {
+        self.inner.launches.lock().unwrap().clone()
+    }
+
+    /// 依次进入过的生命周期状态
+    pub fn lifecycles(&self) -> Vec<Lifecycle> {
+        self.inner.lifecycles.lock().unwrap().clone()
+    }
+
+    pub fn crashes(&self) -> Vec<Instant> {
+        self.inner.crashes.lock().unwrap().clone()
+    }
+
+    pub fn stdout(&self) -> Vec<String> {
+        self.inner.stdout.lock().unwrap().clone()
+    }
+
+    pub fn stderr(&self) -> Vec<String> {
+        self.inner.stderr.lock().unwrap().clone()
+    }
+
+    pub fn exits(&self) -> Vec<Exit> {
+        self.inner.exits.lock().unwrap().clone()
+    }
+
+    pub fn released(&self) -> bool {
+        self.inner.released.load(Ordering::SeqCst)
+    }
+
+    /// 向模拟后端下达一次性指令，见 backend/mock.rs
+    pub fn command(&self, command: serde_json::Value) {
+        command_file(self.data_dir(), command);
+    }
+
+    /// 启动并等待就绪
+    pub async fn start(&self) -> Result<u32, BackendError> {
+        let op = self.manager().begin().await;
+        self.manager().start(&op, self, PortPolicy::Any(0)).await
+    }
+
+    // 当前进程退出：与 handle_backend_exit 相同，先记录退出再清除 pid，崩溃时安排重启
+    async fn exited(&self, pid: u32) {
+        let crashed = self.manager().record_exit(self, pid).await;
+        {
+            let mut current = self.inner.pid.lock().unwrap();
+            if *current == Some(pid) {
+                *current = None;
+            }
+        }
+        if crashed {
+            self.inner.crashes.lock().unwrap().push(Instant::now());
+            self.schedule_restart();
+        }
+    }
+
+    fn schedule_restart(&self) {
+        let delay = self.inner.restarts.lock().unwrap().next_delay();
+        let Some(delay) = delay else {
+            self.manager().mark_crash_loop(self);
+            return;
+        };
+        let host = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = host.manager().restart_after_crash(&host, delay, || PortPolicy::Any(0)).await;
+            if let Some(Err(_)) = result {
+                host.schedule_restart();
+            }
+        });
+    }
+
+    // 与应用相同，经 shell 插件的 Command 与 sidecar::spawn 拉起进程，路径参数由 backend::path_args 生成；
+    // startup_delay 改为监听前的等待
+    #[cfg(feature = "test-sidecar")]
+    fn spawn_sidecar(&self, port: u16) -> Result<Spawned, String> {
+        let mut args = duncrew_lib::backend::path_args(self.data_dir()).map_err(|e| e.to_string())?;
+        args.extend(["--port".to_string(), port.to_string()]);
+        if let Some(delay) = self.inner.options.startup_delay {
+            args.extend(["--delay-ms".to_string(), delay.as_millis().to_string()]);
+        }
+        let command = shell_command(env!("CARGO_BIN_EXE_mock_server")).args(args);
+        duncrew_lib::backend::sidecar::spawn(command, false)
+    }
+
+    #[cfg(not(feature = "test-sidecar"))]
+    fn spawn_sidecar(&self, _port: u16) -> Result<Spawned, String> {
+        Err("Options::sidecar requires the test-sidecar feature".to_string())
+    }
+
+    fn watch(&self, pid: u32, mut events: tokio::sync::mpsc::Receiver<BackendEvent>) {
+        let host = self.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let line = |bytes: &[u8]| String::from_utf8_lossy(bytes).trim_end().to_string();
+                match event {
+                    BackendEvent::Stdout(bytes) => host.inner.stdout.lock().unwrap().push(line(&bytes)),
+                    BackendEvent::Stderr(bytes) => host.inner.stderr.lock().unwrap().push(line(&bytes)),
+                    BackendEvent::Terminated { code, signal } => {
+                        host.inner.exits.lock().unwrap().push((pid, code, signal));
+                        host.exited(pid).await;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+}
+
+impl Host for TestHost {
+    type Ready = ();
+
+    fn external_url(&self) -> Option<String> {
+        None
+    }
+
+    fn pid(&self) -> Option<u32> {
+        *self.inner.pid.lock().unwrap()
+    }
+
+    fn lifecycle_changed(&self, lifecycle: Lifecycle, _state: Option<StateTransition>) {
+        self.inner.lifecycles.lock().unwrap().push(lifecycle);
+    }
+
+    fn starting(&self) {}
+
+    async fn launch(&self, _port_policy: PortPolicy) -> Result<Launch, BackendError> {
+        let port = free_port();
+        let Spawned { handle, events } = if self.inner.options.sidecar {
+            self.spawn_sidecar(port)?
+        } else {
+            mock::spawn_with_delay(port, self.data_dir(), self.inner.options.startup_delay)?
+        };
+        let pid = handle.pid();
+        *self.inner.pid.lock().unwrap() = Some(pid);
+        *self.inner.port.lock().unwrap() = port;
+        self.inner.released.store(false, Ordering::SeqCst);
+        self.inner.launches.lock().unwrap().push((Instant::now(), pid));
+        let host = self.clone();
+        Ok(Launch { handle, watch: Box::new(move || host.watch(pid, events)) })
+    }
+
+    async fn wait_ready(&self, pid: u32) -> Result<(), String> {
+        let timeout = self.inner.options.ready_timeout;
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.pid() != Some(pid) {
+                return Err("Backend exited during startup".to_string());
+            }
+            if get(self.port(), "/health").await.is_ok() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(format!("Backend did not become ready within {:?}", timeout));
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    fn started(&self, _pid: u32, _elapsed: Duration, _ready: ()) {
+        if self.inner.options.crash_after_ready {
+            self.command(serde_json::json!({ "action": "crash", "code": 3 }));
+        }
+    }
+
+    fn start_failed(&self, _error: &BackendError) {}
+
+    fn stderr_tail(&self) -> Vec<String> {
+        self.inner.stderr.lock().unwrap().clone()
+    }
+
+    async fn request_shutdown(&self) -> Result<(), String> {
+        get(self.port(), "/shutdown").await
+    }
+
+    async fn wait_for_exit(&self, pid: u32, timeout: Duration, _graceful: bool) -> bool {
+        wait_until(timeout, || self.pid() != Some(pid)).await
+    }
+
+    fn release(&self) {
+        self.inner.released.store(true, Ordering::SeqCst);
+    }
+}
+
+/// 经 shell 插件创建的命令：插件只能从 App 取得，这里用 tauri::test 的模拟运行时构建一个，整个测试进程共用
+#[cfg(feature = "test-sidecar")]
+pub fn shell_command(program: &str) -> tauri_plugin_shell::process::Command {
+    use std::sync::OnceLock;
+    use tauri::test::MockRuntime;
+    use tauri_plugin_shell::ShellExt;
+
+    static APP: OnceLock<tauri::AppHandle<MockRuntime>> = OnceLock::new();
+    let app = APP.get_or_init(|| {
+        let app = tauri::test::mock_builder()
+            .plugin(tauri_plugin_shell::init())
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        let handle = app.handle().clone();
+        // App 在测试进程结束前一直保留
+        std::mem::forget(app);
+        handle
+    });
+    app.shell().command(program)
+}
+
+pub fn command_file(dir: &Path, command: serde_json::Value) {
+    let path: PathBuf = dir.join(mock::CONTROL_FILE_NAME);
+    // 先写临时文件再改名，模拟后端不会读到写了一半的指令
+    let partial = path.with_extension("partial");
+    std::fs::write(&partial, command.to_string()).unwrap();
+    std::fs::rename(&partial, &path).unwrap();
+}
+
+pub fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// 请求本机端口上的后端，非 2xx 或无法连接时返回错误
+pub async fn get(port: u16, path: &str) -> Result<(), String> {
+    let url = format!("http://127.0.0.1:{}{}", port, path);
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(Duration::from_millis(500))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    response.error_for_status().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// 每 20ms 检查一次条件，超时返回 false
+pub async fn wait_until(timeout: Duration, condition: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while !condition() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    true
+}
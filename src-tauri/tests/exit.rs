@@ -0,0 +1,55 @@
+// 应用退出时 Sidecar 随之结束：经真实的 Sidecar 路径拉起不再响应 /shutdown 的 mock_server，
+// 分别走 ExitRequested 的 shutdown 与 RunEvent::Exit 的 kill_now，之后进程不再存在。
+// 需要 test-sidecar 特性：cargo test --features test-sidecar
+
+#![cfg(feature = "test-sidecar")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{wait_until, Options, TestHost};
+use duncrew_lib::backend::Lifecycle;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::async_runtime::block_on;
+
+fn process_exists(pid: u32) -> bool {
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
+    system.process(pid).is_some()
+}
+
+// 启动后让它停止响应，模拟不会自行退出的后端
+async fn start_hung_sidecar() -> (TestHost, u32) {
+    let host = TestHost::new(Options { sidecar: true, ..Options::default() });
+    let pid = host.start().await.unwrap();
+    host.command(serde_json::json!({ "action": "hang" }));
+    assert!(wait_until(Duration::from_secs(5), || host.stderr().iter().any(|l| l.contains("stopped responding"))).await);
+    assert!(process_exists(pid));
+    (host, pid)
+}
+
+#[test]
+fn shutdown_on_exit_request_leaves_no_sidecar() {
+    block_on(async {
+        let (host, pid) = start_hung_sidecar().await;
+        host.manager().shutdown(&host, Duration::from_millis(300)).await;
+
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+        assert!(wait_until(Duration::from_secs(5), || !process_exists(pid)).await, "pid {} is still running", pid);
+    });
+}
+
+#[test]
+fn kill_now_on_exit_leaves_no_sidecar() {
+    block_on(async {
+        let (host, pid) = start_hung_sidecar().await;
+        host.manager().kill_now(&host);
+
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+        assert!(wait_until(Duration::from_secs(5), || !process_exists(pid)).await, "pid {} is still running", pid);
+        assert!(host.released());
+        assert!(host.crashes().is_empty());
+    });
+}
@@ -0,0 +1,117 @@
+// 测试用的模拟 Sidecar（test-sidecar 特性）：以独立进程运行 backend::mock 的请求处理与指令循环，
+// 经 shell 插件、与打包的 duncrew-server 相同的路径启动，供集成测试验证真实进程的启动、输出采集与退出。
+// 参数：--path <数据目录> --port <端口>，--delay-ms <毫秒> 在监听前等待，--host 指定监听地址；
+// 其余参数（应用传入的 --log-level、--cache-dir 等）忽略。启动后先在 stdout 输出一行 JSON，列出收到的全部参数。
+// 指令：数据目录下的 mock-backend.json，格式见 backend/mock.rs；
+// Unix 上也可以用信号：SIGUSR1 以 --signal-exit-code（默认 3）退出，SIGUSR2 不再响应任何请求。
+
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use duncrew_lib::backend::mock::{self, Command};
+use duncrew_lib::backend::BackendEvent;
+
+// 由信号处理函数置位，指令循环下一次轮询时取走
+static CRASH_REQUESTED: AtomicBool = AtomicBool::new(false);
+static HANG_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+struct Args {
+    path: PathBuf,
+    port: u16,
+    host: String,
+    delay: Option<Duration>,
+    signal_exit_code: i32,
+    all: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let all: Vec<String> = std::env::args().skip(1).collect();
+    let (mut path, mut port, mut host, mut delay, mut signal_exit_code) = (None, None, None, None, 3);
+    let mut iter = all.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("{} requires a value", arg));
+        match arg.as_str() {
+            "--path" => path = Some(PathBuf::from(value()?)),
+            "--port" => port = Some(value()?.parse().map_err(|e| format!("Invalid --port: {}", e))?),
+            "--host" => host = Some(value()?.clone()),
+            "--delay-ms" => {
+                let millis = value()?.parse().map_err(|e| format!("Invalid --delay-ms: {}", e))?;
+                delay = Some(Duration::from_millis(millis));
+            }
+            "--signal-exit-code" => {
+                signal_exit_code = value()?.parse().map_err(|e| format!("Invalid --signal-exit-code: {}", e))?;
+            }
+            _ => {}
+        }
+    }
+    Ok(Args {
+        path: path.ok_or("--path is required")?,
+        port: port.ok_or("--port is required")?,
+        host: host.unwrap_or_else(|| "127.0.0.1".to_string()),
+        delay,
+        signal_exit_code,
+        all,
+    })
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    match signal {
+        libc::SIGUSR1 => CRASH_REQUESTED.store(true, Ordering::SeqCst),
+        libc::SIGUSR2 => HANG_REQUESTED.store(true, Ordering::SeqCst),
+        _ => {}
+    }
+}
+
+#[cfg(unix)]
+fn install_signal_handlers() {
+    let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    // SAFETY: 处理函数只写原子变量，是异步信号安全的
+    unsafe {
+        libc::signal(libc::SIGUSR1, handler);
+        libc::signal(libc::SIGUSR2, handler);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_signal_handlers() {}
+
+fn fail(message: &str) -> ! {
+    eprintln!("mock_server: {}", message);
+    std::process::exit(2);
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| fail(&e));
+    println!("{}", serde_json::json!({ "args": args.all }));
+    if let Some(delay) = args.delay {
+        println!("Mock server delaying startup by {:?}", delay);
+        std::thread::sleep(delay);
+    }
+    let listener = TcpListener::bind((args.host.as_str(), args.port))
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+        .unwrap_or_else(|e| fail(&format!("failed to listen on {}:{}: {}", args.host, args.port, e)));
+    install_signal_handlers();
+    println!("Mock server listening on {}:{}", args.host, args.port);
+
+    let control = args.path.join(mock::CONTROL_FILE_NAME);
+    let next_command = || {
+        if CRASH_REQUESTED.swap(false, Ordering::SeqCst) {
+            return Some(Ok(Command::Crash { code: args.signal_exit_code }));
+        }
+        if HANG_REQUESTED.swap(false, Ordering::SeqCst) {
+            return Some(Ok(Command::Hang));
+        }
+        mock::take_command(&control)
+    };
+    let emit = |event| match event {
+        BackendEvent::Stdout(bytes) => println!("{}", String::from_utf8_lossy(&bytes)),
+        BackendEvent::Stderr(bytes) => eprintln!("{}", String::from_utf8_lossy(&bytes)),
+        BackendEvent::Error(message) => eprintln!("mock_server: {}", message),
+        BackendEvent::Terminated { .. } => {}
+    };
+    let code = mock::serve(&listener, &AtomicBool::new(false), next_command, emit);
+    std::process::exit(code.unwrap_or(0));
+}
@@ -0,0 +1,66 @@
+// 数据目录含空格、中文与 emoji 时，--path 与 --cache-dir 原样送达后端：
+// 经真实的 Sidecar 路径拉起 tests/mock_server，比对它收到的参数，并确认它能读到数据目录中的指令文件。
+// 需要 test-sidecar 特性：cargo test --features test-sidecar
+
+#![cfg(feature = "test-sidecar")]
+
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use common::{wait_until, Options, TestHost};
+use tauri::async_runtime::block_on;
+
+// mock_server 启动时输出的第一行：{"args": [...]}
+fn received_args(host: &TestHost) -> Vec<String> {
+    let first = host.stdout().first().cloned().unwrap_or_default();
+    let value: serde_json::Value = serde_json::from_str(&first).unwrap_or_else(|e| panic!("{}: {:?}", e, first));
+    serde_json::from_value(value["args"].clone()).unwrap()
+}
+
+fn arg<'a>(args: &'a [String], name: &str) -> &'a str {
+    let index = args.iter().position(|arg| arg == name).unwrap_or_else(|| panic!("{} missing in {:?}", name, args));
+    &args[index + 1]
+}
+
+fn canonical(path: &Path) -> String {
+    let path = path.canonicalize().unwrap();
+    // Windows 上 canonicalize 带 \\?\ 前缀，应用传给后端的路径去掉了它
+    let text = path.to_str().unwrap();
+    text.strip_prefix(r"\\?\").unwrap_or(text).to_string()
+}
+
+fn check_data_dir(prefix: &'static str) {
+    block_on(async {
+        let host = TestHost::new(Options { sidecar: true, data_dir_prefix: prefix, ..Options::default() });
+        host.start().await.unwrap();
+        assert!(wait_until(Duration::from_secs(5), || !host.stdout().is_empty()).await);
+
+        let args = received_args(&host);
+        assert_eq!(arg(&args, "--path"), canonical(host.data_dir()));
+        assert!(arg(&args, "--path").contains(prefix.trim()));
+        assert_eq!(arg(&args, "--cache-dir"), canonical(&host.data_dir().join("cache")));
+
+        // mock_server 在它收到的 --path 下读取指令文件
+        host.command(serde_json::json!({ "action": "print", "stdout": ["path ok"] }));
+        assert!(wait_until(Duration::from_secs(5), || host.stdout().iter().any(|l| l == "path ok")).await);
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+    });
+}
+
+#[test]
+fn data_dir_with_spaces() {
+    check_data_dir("Program Files  (x86) ");
+}
+
+#[test]
+fn data_dir_with_cjk() {
+    check_data_dir("用户 数据目录");
+}
+
+#[test]
+fn data_dir_with_emoji() {
+    check_data_dir("🚀 duncrew 👩‍💻");
+}
@@ -0,0 +1,141 @@
+// 经真实的 Sidecar 启动路径（shell 插件 + sidecar::spawn）运行 tests/mock_server：
+// 启动与监听前的等待、就绪超时、崩溃重启、停止响应、优雅退出与输出采集。
+// 需要 test-sidecar 特性：cargo test --features test-sidecar
+
+#![cfg(feature = "test-sidecar")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{wait_until, Options, TestHost};
+use duncrew_lib::backend::{BackendError, Host, Lifecycle};
+use tauri::async_runtime::block_on;
+
+fn sidecar() -> Options {
+    Options { sidecar: true, ..Options::default() }
+}
+
+#[test]
+fn starts_and_stops_gracefully() {
+    block_on(async {
+        let host = TestHost::new(Options { startup_delay: Some(Duration::from_millis(300)), ..sidecar() });
+        let started = Instant::now();
+        let pid = host.start().await.unwrap();
+        // mock_server 等待之后才开始监听，就绪探测期间连接被拒绝
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Running);
+        assert_ne!(pid, std::process::id());
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+        assert_eq!(host.exits(), [(pid, Some(0), None)]);
+        assert!(host.crashes().is_empty());
+    });
+}
+
+#[test]
+fn readiness_timeout_fails_the_start() {
+    block_on(async {
+        let host = TestHost::new(Options {
+            startup_delay: Some(Duration::from_secs(30)),
+            ready_timeout: Duration::from_millis(300),
+            ..sidecar()
+        });
+        let result = host.start().await;
+        let Err(BackendError::FailedToStart { reason, .. }) = result else {
+            panic!("expected FailedToStart, got {:?}", result);
+        };
+        assert!(reason.contains("did not become ready"), "{}", reason);
+        assert_eq!(host.manager().lifecycle(), Lifecycle::FailedToStart);
+
+        // 超时的进程被结束，且不按崩溃处理
+        assert!(wait_until(Duration::from_secs(5), || host.exits().len() == 1).await);
+        assert!(host.released());
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(host.launches().len(), 1);
+        assert!(host.crashes().is_empty());
+    });
+}
+
+#[test]
+fn crash_exit_code_is_reported_and_restarted() {
+    block_on(async {
+        let host = TestHost::new(sidecar());
+        let first = host.start().await.unwrap();
+        host.command(serde_json::json!({ "action": "crash", "code": 7 }));
+
+        assert!(wait_until(Duration::from_secs(5), || host.launches().len() == 2).await);
+        assert!(wait_until(Duration::from_secs(5), || host.manager().lifecycle() == Lifecycle::Running).await);
+        assert_eq!(host.exits()[0], (first, Some(7), None));
+        assert!(host.stderr().iter().any(|line| line.contains("crashing with exit code 7")));
+        assert_ne!(host.pid(), Some(first));
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+    });
+}
+
+#[test]
+fn hung_sidecar_is_killed_on_shutdown() {
+    block_on(async {
+        let host = TestHost::new(sidecar());
+        let pid = host.start().await.unwrap();
+        host.command(serde_json::json!({ "action": "hang" }));
+        assert!(wait_until(Duration::from_secs(5), || host.stderr().iter().any(|l| l.contains("stopped responding"))).await);
+        assert!(common::get(host.port(), "/health").await.is_err());
+
+        // /shutdown 没有响应，超时后强制结束
+        host.manager().shutdown(&host, Duration::from_millis(500)).await;
+        assert_eq!(host.manager().lifecycle(), Lifecycle::Stopped);
+        let exits = host.exits();
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].0, pid);
+        assert_ne!(exits[0].1, Some(0));
+        #[cfg(unix)]
+        assert_eq!(exits[0].2, Some(libc::SIGKILL));
+        assert!(host.crashes().is_empty());
+    });
+}
+
+#[test]
+fn printed_output_is_captured() {
+    block_on(async {
+        let host = TestHost::new(sidecar());
+        host.start().await.unwrap();
+        host.command(serde_json::json!({
+            "action": "print",
+            "stdout": ["progress: 50%", "中文输出"],
+            "stderr": ["ERROR: simulated failure"],
+        }));
+
+        assert!(wait_until(Duration::from_secs(5), || host.stdout().iter().any(|l| l == "中文输出")).await);
+        assert!(host.stdout().iter().any(|l| l == "progress: 50%"));
+        assert!(wait_until(Duration::from_secs(5), || host.stderr().iter().any(|l| l == "ERROR: simulated failure")).await);
+        // 启动时先输出收到的参数
+        assert!(host.stdout()[0].contains("--port"), "{:?}", host.stdout());
+
+        host.manager().shutdown(&host, Duration::from_secs(2)).await;
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn signals_crash_and_hang() {
+    block_on(async {
+        let host = TestHost::new(sidecar());
+        let first = host.start().await.unwrap();
+        // SAFETY: 只向本测试拉起的 mock_server 发送信号
+        unsafe { libc::kill(first as libc::pid_t, libc::SIGUSR1) };
+        assert!(wait_until(Duration::from_secs(5), || host.launches().len() == 2).await);
+        assert_eq!(host.exits()[0], (first, Some(3), None));
+
+        assert!(wait_until(Duration::from_secs(5), || host.manager().lifecycle() == Lifecycle::Running).await);
+        let second = host.pid().unwrap();
+        unsafe { libc::kill(second as libc::pid_t, libc::SIGUSR2) };
+        assert!(wait_until(Duration::from_secs(5), || host.stderr().iter().any(|l| l.contains("stopped responding"))).await);
+        assert!(common::get(host.port(), "/health").await.is_err());
+
+        host.manager().kill(&host).await;
+        assert!(wait_until(Duration::from_secs(5), || host.exits().len() == 2).await);
+    });
+}
@@ -12,6 +12,7 @@ import { ApprovalModal } from '@/components/ApprovalModal'
 import { NexusDetailPanel } from '@/components/world/NexusDetailPanel'
 import { InterruptedTasksWarning } from '@/components/InterruptedTasksWarning'
 import { CrashRecoveryBanner } from '@/components/CrashRecoveryBanner'
+import { SafeModeBanner } from '@/components/SafeModeBanner'
 import { useStore } from '@/store'
 import { getHouseById } from '@/houses/registry'
 import { openClawService } from '@/services/OpenClawService'
@@ -23,6 +24,7 @@ import { createInitialScoring } from '@/types'
 import { restoreLLMConfigFromServer } from '@/services/llmService'
 import { persistTaskHistory } from '@/store/slices/sessionsSlice'
 import { getCachedMBTIResult } from '@/services/mbtiAnalyzer'
+import { notifyFrontendReady, subscribeDesktopNavigation } from '@/services/desktopEvents'
 
 /**
  * 一次性迁移: 将 localStorage 中旧 ddos_ 前缀的数据移动到 duncrew_ 前缀
@@ -347,6 +349,13 @@ function App() {
     return () => window.removeEventListener('beforeunload', handleBeforeUnload)
   }, [])
 
+  // 深度链接与打开的文件：订阅后再通知 Rust，冷启动时暂存的事件不会在挂载前投递而丢失
+  useEffect(() => {
+    const unsubscribe = subscribeDesktopNavigation()
+    notifyFrontendReady().catch((e) => console.warn('[App] frontend_ready failed:', e))
+    return unsubscribe
+  }, [])
+
   const initTheme = useStore((s) => s.initTheme)
 
   // 初始化主题
@@ -395,6 +404,9 @@ function App() {
       {/* Crash recovery banner */}
       <CrashRecoveryBanner />
 
+      {/* Safe mode banner */}
+      <SafeModeBanner />
+
       {/* Toast notifications */}
       <ToastContainer />
     </div>
@@ -0,0 +1,80 @@
+import { useState, useEffect } from 'react'
+import { motion, AnimatePresence } from 'framer-motion'
+import { ShieldAlert, RefreshCw } from 'lucide-react'
+import { invoke, isTauri } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useT } from '@/i18n'
+
+interface SafeModePayload {
+  active: boolean
+  reason: string | null
+}
+
+/**
+ * 安全模式横幅
+ * 后端以安全模式运行（停用插件与缓存）时显示，提供退出安全模式并正常重启的入口
+ */
+export function SafeModeBanner() {
+  const t = useT()
+  const [active, setActive] = useState(false)
+  const [restarting, setRestarting] = useState(false)
+
+  useEffect(() => {
+    if (!isTauri()) return
+    invoke<{ safe_mode: boolean }>('get_backend_status')
+      .then((status) => setActive(status.safe_mode))
+      .catch(() => {})
+    const unlisten = listen<SafeModePayload>('app://safe-mode', (event) => {
+      setActive(event.payload.active)
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  const handleExit = async () => {
+    setRestarting(true)
+    try {
+      await invoke('restart_backend', { safeMode: false })
+    } catch (error) {
+      console.warn('[SafeMode] restart_backend failed:', error)
+    } finally {
+      setRestarting(false)
+    }
+  }
+
+  return (
+    <AnimatePresence>
+      {active && (
+        <motion.div
+          initial={{ opacity: 0, y: -20 }}
+          animate={{ opacity: 1, y: 0 }}
+          exit={{ opacity: 0, y: -20 }}
+          className="fixed top-4 left-1/2 -translate-x-1/2 z-[100] w-[90vw] max-w-lg"
+        >
+          <div className="bg-amber-950/90 backdrop-blur-sm border border-amber-500/30 rounded-xl p-4 shadow-2xl">
+            <div className="flex items-start gap-3">
+              <ShieldAlert className="w-5 h-5 text-amber-400 flex-shrink-0 mt-0.5" />
+              <div className="flex-1 min-w-0">
+                <p className="text-sm font-medium text-amber-200">{t('safe_mode.title')}</p>
+                <p className="text-xs text-amber-300/70 mt-1">{t('safe_mode.desc')}</p>
+              </div>
+            </div>
+            <div className="flex items-center gap-2 mt-3 pt-3 border-t border-amber-500/20">
+              <button
+                onClick={handleExit}
+                disabled={restarting}
+                className="flex items-center gap-1.5 px-3 py-1.5 text-xs font-mono
+                         bg-stone-100/80 border border-stone-200 rounded-lg
+                         text-stone-500 hover:text-stone-800 hover:border-stone-300 transition-colors disabled:opacity-50"
+              >
+                <RefreshCw className={`w-3 h-3 ${restarting ? 'animate-spin' : ''}`} />
+                {t('safe_mode.exit')}
+              </button>
+            </div>
+          </div>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  )
+}
@@ -1,8 +1,10 @@
 import { useState, useEffect } from 'react'
 import { motion } from 'framer-motion'
 import { 
-  Monitor, Info, Check, Sparkles, Eye, EyeOff, Type, Wifi, WifiOff, Globe, Languages, Store, LogOut, Loader2
+  Monitor, Info, Check, Sparkles, Eye, EyeOff, Type, Wifi, WifiOff, Globe, Languages, Store, LogOut, Loader2, ShieldCheck
 } from 'lucide-react'
+import { invoke, isTauri } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { GlassCard } from '@/components/GlassCard'
 import { staggerContainer, staggerItem } from '@/utils/animations'
 import { useStore } from '@/store'
@@ -102,6 +104,134 @@ function ClawHubAccountSection() {
   )
 }
 
+// 桌面端匿名使用统计开关，状态保存在 config.json
+function TelemetrySection() {
+  const t = useT()
+  const [enabled, setEnabled] = useState(false)
+
+  useEffect(() => {
+    invoke<{ enabled: boolean }>('get_telemetry_status')
+      .then((status) => setEnabled(status.enabled))
+      .catch(() => {})
+  }, [])
+
+  const toggle = () => {
+    invoke<{ enabled: boolean }>('set_telemetry_enabled', { enabled: !enabled })
+      .then((status) => setEnabled(status.enabled))
+      .catch((e) => console.warn('[Settings] Failed to update telemetry setting:', e))
+  }
+
+  return (
+    <GlassCard className="p-4 flex items-center justify-between gap-4">
+      <div>
+        <h4 className="text-sm font-mono text-stone-700">
+          {t('settings.telemetry')}
+        </h4>
+        <p className="text-xs text-stone-400 mt-0.5">
+          {t('settings.telemetry_desc')}
+        </p>
+      </div>
+      <div
+        onClick={toggle}
+        className="w-10 h-5 shrink-0 bg-stone-100 rounded-full relative cursor-pointer border border-stone-200"
+      >
+        <div
+          className={`absolute top-0.5 w-4 h-4 rounded-full transition-all ${
+            enabled
+              ? 'left-5 bg-cyan-400 shadow-[0_0_6px_rgba(34,211,238,0.5)]'
+              : 'left-0.5 bg-white/30'
+          }`}
+        />
+      </div>
+    </GlassCard>
+  )
+}
+
+interface AppUpdateInfo {
+  version: string
+  current_version: string
+  notes?: string
+}
+
+// 桌面端应用更新：检查后可直接安装，安装完成后应用自动重启
+function AppUpdateSection() {
+  const t = useT()
+  const [status, setStatus] = useState<'idle' | 'checking' | 'latest' | 'available' | 'installing' | 'error'>('idle')
+  const [update, setUpdate] = useState<AppUpdateInfo | null>(null)
+  const [progress, setProgress] = useState<number | null>(null)
+  const [error, setError] = useState('')
+
+  useEffect(() => {
+    const unlisten = [
+      listen<AppUpdateInfo>('update://available', (event) => {
+        setUpdate(event.payload)
+        setStatus('available')
+      }),
+      listen<{ bytes_downloaded: number; total_bytes?: number }>('update://progress', (event) => {
+        const { bytes_downloaded, total_bytes } = event.payload
+        setProgress(total_bytes ? Math.round((bytes_downloaded / total_bytes) * 100) : null)
+      }),
+    ]
+    return () => {
+      unlisten.forEach((p) => p.then((fn) => fn()))
+    }
+  }, [])
+
+  const check = () => {
+    setStatus('checking')
+    invoke<AppUpdateInfo | null>('check_for_updates')
+      .then((info) => {
+        setUpdate(info)
+        setStatus(info ? 'available' : 'latest')
+      })
+      .catch((e) => {
+        setError(String(e))
+        setStatus('error')
+      })
+  }
+
+  const install = () => {
+    setStatus('installing')
+    setProgress(null)
+    invoke('install_update').catch((e) => {
+      setError(String(e))
+      setStatus('error')
+    })
+  }
+
+  return (
+    <div className="mt-3 pt-3 border-t border-stone-200 space-y-2 font-mono text-xs">
+      <div className="flex items-center justify-between">
+        <span className="text-stone-400">
+          {status === 'latest' && t('settings.up_to_date')}
+          {(status === 'available' || status === 'installing') && update &&
+            t('settings.update_available').replace('{version}', update.version)}
+        </span>
+        {status === 'available' || status === 'installing' ? (
+          <button
+            onClick={install}
+            disabled={status === 'installing'}
+            className="px-3 py-1.5 text-cyan-400 bg-cyan-500/20 rounded-lg hover:bg-cyan-500/30 disabled:opacity-50 transition-colors"
+          >
+            {status === 'installing'
+              ? `${t('settings.installing_update')}${progress !== null ? ` ${progress}%` : ''}`
+              : t('settings.install_update')}
+          </button>
+        ) : (
+          <button
+            onClick={check}
+            disabled={status === 'checking'}
+            className="px-3 py-1.5 text-stone-400 border border-stone-200 rounded-lg hover:bg-stone-100/80 disabled:opacity-50 transition-colors"
+          >
+            {status === 'checking' ? t('settings.checking_updates') : t('settings.check_updates')}
+          </button>
+        )}
+      </div>
+      {status === 'error' && <p className="text-red-400 whitespace-pre-wrap">{error}</p>}
+    </div>
+  )
+}
+
 export function SettingsHouse() {
   const t = useT()
 
@@ -616,6 +746,19 @@ export function SettingsHouse() {
         </div>
       </motion.div>
 
+      {/* 隐私（仅桌面端） */}
+      {isTauri() && (
+        <div>
+          <div className="flex items-center gap-2 mb-4">
+            <ShieldCheck className="w-4 h-4 text-stone-400" />
+            <h3 className="font-mono text-sm text-stone-400 tracking-wider">
+              {t('settings.privacy')}
+            </h3>
+          </div>
+          <TelemetrySection />
+        </div>
+      )}
+
       {/* 关于 */}
       <div>
         <div className="flex items-center gap-2 mb-4">
@@ -637,6 +780,7 @@ export function SettingsHouse() {
               </span>
             </div>
           </div>
+          {isTauri() && <AppUpdateSection />}
         </GlassCard>
       </div>
 
@@ -52,8 +52,17 @@ const en: Record<TranslationKey, string> = {
   'settings.about': 'About',
   'settings.version': 'Version',
   'settings.run_mode': 'Run Mode',
+  'settings.check_updates': 'Check for updates',
+  'settings.checking_updates': 'Checking...',
+  'settings.up_to_date': 'Up to date',
+  'settings.update_available': 'Version {version} is available',
+  'settings.install_update': 'Install and restart',
+  'settings.installing_update': 'Installing...',
   'settings.native_local': 'Native (Local)',
   'settings.openclaw_network': 'DunCrew Cloud (Network)',
+  'settings.privacy': 'Privacy',
+  'settings.telemetry': 'Anonymous usage statistics',
+  'settings.telemetry_desc': 'Send anonymous data such as app starts, backend startup time and crash counts to help improve stability. No file paths or document content. Turning this off discards data not yet sent',
   'settings.agent_idle': 'Idle',
   'settings.agent_thinking': 'Thinking',
   'settings.agent_executing': 'Executing',
@@ -261,6 +270,14 @@ const en: Record<TranslationKey, string> = {
   'nexus.sop_hint': 'Custom system prompt',
   'nexus.create_new': 'Create New Nexus',
 
+  // ---- Safe Mode ----
+  'safe_mode.title': 'Safe mode',
+  'safe_mode.desc': 'The backend is running in safe mode with plugins and caches disabled, and will not restart automatically after a crash',
+  'safe_mode.exit': 'Exit safe mode and restart normally',
+
+  // ---- Desktop ----
+  'desktop.open_file': 'File opened, import it from Settings',
+
   // ---- Common ----
   'common.loading': 'Loading...',
   'common.save': 'Save',
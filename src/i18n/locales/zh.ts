@@ -50,8 +50,17 @@ const zh = {
   'settings.about': '关于',
   'settings.version': '版本',
   'settings.run_mode': '运行模式',
+  'settings.check_updates': '检查更新',
+  'settings.checking_updates': '正在检查...',
+  'settings.up_to_date': '已是最新版本',
+  'settings.update_available': '新版本 {version} 可用',
+  'settings.install_update': '安装并重启',
+  'settings.installing_update': '正在安装...',
   'settings.native_local': 'Native (本地)',
   'settings.openclaw_network': 'DunCrew Cloud (网络)',
+  'settings.privacy': '隐私',
+  'settings.telemetry': '匿名使用统计',
+  'settings.telemetry_desc': '发送应用启动、后端启动耗时与崩溃次数等匿名数据，帮助改进稳定性；不包含文件路径或文档内容。关闭时会丢弃尚未发送的数据',
   'settings.agent_idle': '空闲',
   'settings.agent_thinking': '思考中',
   'settings.agent_executing': '执行中',
@@ -259,6 +268,14 @@ const zh = {
   'nexus.sop_hint': '定制化系统提示词',
   'nexus.create_new': '创建新 Nexus',
 
+  // ---- 安全模式 ----
+  'safe_mode.title': '安全模式',
+  'safe_mode.desc': '后端以安全模式运行，插件与缓存已停用，崩溃后不会自动重启',
+  'safe_mode.exit': '退出安全模式并正常重启',
+
+  // ---- 桌面端 ----
+  'desktop.open_file': '已打开文件，可在设置中导入',
+
   // ---- 通用 ----
   'common.loading': '加载中...',
   'common.save': '保存',
@@ -3,12 +3,22 @@ import ReactDOM from 'react-dom/client'
 import App from './App'
 import './index.css'
 import { crashMonitor } from './services/crashMonitor'
+import { backendAuth } from './services/backendAuth'
+import { initDesktopEvents } from './services/desktopEvents'
+import { secureStore } from './services/secureStore'
 
 // 初始化崩溃监控 (必须在 React 渲染前)
 crashMonitor.init()
+// 为本地后端请求附加会话 token (必须在任何请求发出前)
+backendAuth.init()
+// 订阅深度链接等桌面端事件；App 挂载后才通知 Rust 投递暂存的事件
+initDesktopEvents()
 
-ReactDOM.createRoot(document.getElementById('root')!).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>,
-)
+// API key 从系统钥匙串读入后再渲染，首次读取 LLM 配置时即可拿到
+secureStore.init().finally(() => {
+  ReactDOM.createRoot(document.getElementById('root')!).render(
+    <React.StrictMode>
+      <App />
+    </React.StrictMode>,
+  )
+})
@@ -0,0 +1,71 @@
+/**
+ * 后端会话 token
+ * 桌面端每次启动后端都会生成新的 token，这里为发往本地后端的 fetch 请求自动附加
+ * X-DunCrew-Token 头，并在后端重启后更新 token
+ */
+
+import { invoke, isTauri } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+const TOKEN_HEADER = 'X-DunCrew-Token'
+const LOCAL_HOSTS = new Set(['localhost', '127.0.0.1'])
+
+interface BackendRestartedPayload {
+  reason: 'crash' | 'manual'
+  attempt: number
+  pid: number
+  token: string | null
+}
+
+class BackendAuth {
+  private initialized = false
+  private token: string | null = null
+  private port: number | null = null
+  private ready: Promise<void> = Promise.resolve()
+
+  /**
+   * 初始化：拉取 token 并接管 window.fetch（非 Tauri 环境下不做任何事）
+   */
+  init(): void {
+    if (this.initialized || !isTauri()) return
+    this.initialized = true
+
+    this.ready = this.refresh()
+    listen<BackendRestartedPayload>('backend://restarted', (event) => {
+      this.token = event.payload.token
+      // 重启后端口可能变化
+      this.ready = this.refresh()
+    })
+
+    const originalFetch = window.fetch.bind(window)
+    window.fetch = async (input, init) => {
+      const url = new URL(input instanceof Request ? input.url : String(input), window.location.href)
+      if (!LOCAL_HOSTS.has(url.hostname)) {
+        return originalFetch(input, init)
+      }
+      // 等待首次获取 token，避免启动时的请求被后端拒绝
+      await this.ready
+      if (!this.token || Number(url.port) !== this.port) {
+        return originalFetch(input, init)
+      }
+      const headers = new Headers(init?.headers ?? (input instanceof Request ? input.headers : undefined))
+      headers.set(TOKEN_HEADER, this.token)
+      return originalFetch(input, { ...init, headers })
+    }
+  }
+
+  private async refresh(): Promise<void> {
+    try {
+      const [token, port] = await Promise.all([
+        invoke<string | null>('get_backend_token'),
+        invoke<number>('get_backend_port'),
+      ])
+      this.token = token
+      this.port = port
+    } catch (error) {
+      console.warn('[BackendAuth] Failed to load backend token:', error)
+    }
+  }
+}
+
+export const backendAuth = new BackendAuth()
@@ -0,0 +1,95 @@
+/**
+ * 桌面端应用事件
+ * 把 Rust 侧的 app://deep-link、app://open-file 转成 DOM 事件 duncrew:deep-link、duncrew:open-file，供页面按需订阅；
+ * App 挂载并订阅后调用 notifyFrontendReady 通知 Rust，冷启动时暂存的事件随后才会投递
+ */
+
+import { invoke, isTauri } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useStore } from '@/store'
+import { translate } from '@/i18n'
+import type { ViewType } from '@/types'
+
+export interface DeepLink {
+  url: string
+  action: string
+  path: string
+  params: Record<string, string>
+}
+
+export interface OpenFile {
+  /** 暂存到数据目录下的副本 */
+  path: string
+  original_path: string
+}
+
+const VIEWS: readonly ViewType[] = ['world', 'task', 'skill', 'memory', 'soul', 'settings']
+
+let registered: Promise<void> | null = null
+let readyNotified = false
+
+export function initDesktopEvents(): Promise<void> {
+  if (!isTauri()) return Promise.resolve()
+  if (!registered) {
+    registered = (async () => {
+      await listen<DeepLink>('app://deep-link', (event) => {
+        window.dispatchEvent(new CustomEvent<DeepLink>('duncrew:deep-link', { detail: event.payload }))
+      })
+      await listen<OpenFile>('app://open-file', (event) => {
+        window.dispatchEvent(new CustomEvent<OpenFile>('duncrew:open-file', { detail: event.payload }))
+      })
+    })()
+  }
+  return registered
+}
+
+/**
+ * 页面已能处理事件：等 Tauri 监听注册完成后调用 frontend_ready，只调用一次
+ */
+export async function notifyFrontendReady(): Promise<void> {
+  if (!isTauri() || readyNotified) return
+  readyNotified = true
+  await initDesktopEvents()
+  await invoke('frontend_ready')
+}
+
+/**
+ * 链接指向的视图：duncrew://skill、duncrew://open/memory、duncrew://open?view=soul
+ */
+export function deepLinkView(link: DeepLink): ViewType | null {
+  const target = link.action === 'open'
+    ? (link.params.view ?? link.path.split('/').find(Boolean) ?? 'world')
+    : link.action
+  return VIEWS.find((view) => view === target.toLowerCase()) ?? null
+}
+
+/**
+ * 订阅深度链接与打开的文件并切换到对应视图，返回取消订阅函数
+ */
+export function subscribeDesktopNavigation(): () => void {
+  const onDeepLink = (event: Event) => {
+    const link = (event as CustomEvent<DeepLink>).detail
+    const view = deepLinkView(link)
+    if (!view) {
+      console.warn('[DesktopEvents] Unknown deep link target:', link.url)
+      return
+    }
+    useStore.getState().setView(view)
+  }
+  const onOpenFile = (event: Event) => {
+    const file = (event as CustomEvent<OpenFile>).detail
+    const { locale, setView, addToast } = useStore.getState()
+    setView('settings')
+    addToast({
+      type: 'info',
+      title: translate('desktop.open_file', locale),
+      message: file.original_path,
+    })
+  }
+  window.addEventListener('duncrew:deep-link', onDeepLink)
+  window.addEventListener('duncrew:open-file', onOpenFile)
+  return () => {
+    window.removeEventListener('duncrew:deep-link', onDeepLink)
+    window.removeEventListener('duncrew:open-file', onOpenFile)
+  }
+}
@@ -4,7 +4,9 @@
  * 支持流式 (SSE) 和非流式请求
  */
 
+import { isTauri } from '@tauri-apps/api/core'
 import type { LLMConfig, ToolInfo } from '@/types'
+import { secureStore } from './secureStore'
 
 // ============================================
 // Function Calling 类型定义
@@ -72,14 +74,12 @@ export type SimpleChatMessage = {
   reasoning_content?: string
 }
 
-// localStorage keys
+// localStorage keys（API key 经 secureStore 保存，桌面端存放在系统钥匙串）
 const STORAGE_KEYS = {
-  API_KEY: 'duncrew_llm_api_key',
   BASE_URL: 'duncrew_llm_base_url',
   MODEL: 'duncrew_llm_model',
   API_FORMAT: 'duncrew_llm_api_format',
   // Embedding 专用配置
-  EMBED_API_KEY: 'duncrew_embed_api_key',
   EMBED_BASE_URL: 'duncrew_embed_base_url',
   EMBED_MODEL: 'duncrew_embed_model',
 }
@@ -100,19 +100,19 @@ function getLocalServerUrl(): string {
 export function getLLMConfig(): LLMConfig {
   const formatRaw = localStorage.getItem(STORAGE_KEYS.API_FORMAT)
   return {
-    apiKey: localStorage.getItem(STORAGE_KEYS.API_KEY) || '',
+    apiKey: secureStore.get('llm_api_key') || '',
     baseUrl: localStorage.getItem(STORAGE_KEYS.BASE_URL) || '',
     model: localStorage.getItem(STORAGE_KEYS.MODEL) || '',
     apiFormat: (formatRaw as LLMConfig['apiFormat']) || 'auto',
     // Embedding 配置（可选）
-    embedApiKey: localStorage.getItem(STORAGE_KEYS.EMBED_API_KEY) || undefined,
+    embedApiKey: secureStore.get('embed_api_key'),
     embedBaseUrl: localStorage.getItem(STORAGE_KEYS.EMBED_BASE_URL) || undefined,
     embedModel: localStorage.getItem(STORAGE_KEYS.EMBED_MODEL) || undefined,
   }
 }
 
 export function saveLLMConfig(config: Partial<LLMConfig>) {
-  if (config.apiKey !== undefined) localStorage.setItem(STORAGE_KEYS.API_KEY, config.apiKey)
+  if (config.apiKey !== undefined) secureStore.set('llm_api_key', config.apiKey)
   if (config.baseUrl !== undefined) localStorage.setItem(STORAGE_KEYS.BASE_URL, config.baseUrl)
   if (config.model !== undefined) localStorage.setItem(STORAGE_KEYS.MODEL, config.model)
   // API 格式
@@ -121,10 +121,7 @@ export function saveLLMConfig(config: Partial<LLMConfig>) {
     else localStorage.removeItem(STORAGE_KEYS.API_FORMAT)
   }
   // Embedding 配置
-  if (config.embedApiKey !== undefined) {
-    if (config.embedApiKey) localStorage.setItem(STORAGE_KEYS.EMBED_API_KEY, config.embedApiKey)
-    else localStorage.removeItem(STORAGE_KEYS.EMBED_API_KEY)
-  }
+  if (config.embedApiKey !== undefined) secureStore.set('embed_api_key', config.embedApiKey)
   if (config.embedBaseUrl !== undefined) {
     if (config.embedBaseUrl) localStorage.setItem(STORAGE_KEYS.EMBED_BASE_URL, config.embedBaseUrl)
     else localStorage.removeItem(STORAGE_KEYS.EMBED_BASE_URL)
@@ -148,6 +145,8 @@ export function isLLMConfigured(): boolean {
  * 用于解决不同端口访问时localStorage不共享的问题
  */
 async function persistLLMConfigToServer(config: LLMConfig) {
+  // 桌面端的 API key 在钥匙串中，启动后端时经环境变量传入，不再以明文写入后端数据目录
+  if (isTauri()) config = { ...config, apiKey: '', embedApiKey: undefined }
   try {
     const serverUrl = localStorage.getItem('duncrew_server_url') || 'http://localhost:3001'
     await fetch(`${serverUrl}/data/llm_config`, {
@@ -173,9 +172,15 @@ export async function restoreLLMConfigFromServer(): Promise<LLMConfig | null> {
     
     const data = await res.json()
     if (data.exists && data.value) {
-      const config = data.value as LLMConfig
+      const stored = data.value as LLMConfig
+      // 桌面端保存到后端的副本不含 API key，以钥匙串中的值补齐
+      const config: LLMConfig = {
+        ...stored,
+        apiKey: stored.apiKey || secureStore.get('llm_api_key') || '',
+        embedApiKey: stored.embedApiKey || secureStore.get('embed_api_key'),
+      }
       // 恢复到localStorage
-      if (config.apiKey) localStorage.setItem(STORAGE_KEYS.API_KEY, config.apiKey)
+      if (config.apiKey) secureStore.set('llm_api_key', config.apiKey)
       if (config.baseUrl) localStorage.setItem(STORAGE_KEYS.BASE_URL, config.baseUrl)
       if (config.model) localStorage.setItem(STORAGE_KEYS.MODEL, config.model)
       if (config.apiFormat) localStorage.setItem(STORAGE_KEYS.API_FORMAT, config.apiFormat)
@@ -0,0 +1,65 @@
+/**
+ * 敏感配置（API key）存储
+ * 桌面端保存在系统钥匙串（set_secret / get_secret 命令），启动时读入内存供同步读取；
+ * 旧版本留在 localStorage 中的明文会先迁移到钥匙串再删除。浏览器访问时仍使用 localStorage
+ */
+
+import { invoke, isTauri } from '@tauri-apps/api/core'
+
+// 钥匙串中的名称 -> 旧版本使用的 localStorage key
+const LEGACY_KEYS: Record<string, string> = {
+  llm_api_key: 'duncrew_llm_api_key',
+  embed_api_key: 'duncrew_embed_api_key',
+}
+
+class SecureStore {
+  private cache = new Map<string, string>()
+
+  /**
+   * 迁移明文并读入已保存的值；需在读取 LLM 配置之前完成
+   */
+  async init(): Promise<void> {
+    if (!isTauri()) return
+    try {
+      const legacy: Record<string, string> = {}
+      for (const [name, key] of Object.entries(LEGACY_KEYS)) {
+        const value = localStorage.getItem(key)
+        if (value) legacy[name] = value
+      }
+      if (Object.keys(legacy).length > 0) {
+        const imported = await invoke<string[]>('import_legacy_secrets', { secrets: legacy })
+        for (const name of imported) localStorage.removeItem(LEGACY_KEYS[name])
+      }
+      for (const name of Object.keys(LEGACY_KEYS)) {
+        const value = await invoke<string | null>('get_secret', { name })
+        if (value) this.cache.set(name, value)
+      }
+    } catch (e) {
+      // 钥匙串不可用（例如 Linux 没有 Secret Service）时沿用 localStorage
+      console.warn('[SecureStore] Keychain unavailable, keeping secrets in localStorage:', e)
+    }
+  }
+
+  get(name: string): string | undefined {
+    return this.cache.get(name) ?? (localStorage.getItem(LEGACY_KEYS[name]) || undefined)
+  }
+
+  set(name: string, value: string): void {
+    if (!isTauri()) {
+      if (value) localStorage.setItem(LEGACY_KEYS[name], value)
+      else localStorage.removeItem(LEGACY_KEYS[name])
+      return
+    }
+    if (value) this.cache.set(name, value)
+    else this.cache.delete(name)
+    const request = value ? invoke('set_secret', { name, value }) : invoke('delete_secret', { name })
+    request
+      .then(() => localStorage.removeItem(LEGACY_KEYS[name]))
+      .catch((e) => {
+        console.warn('[SecureStore] Failed to update keychain, falling back to localStorage:', e)
+        if (value) localStorage.setItem(LEGACY_KEYS[name], value)
+      })
+  }
+}
+
+export const secureStore = new SecureStore()
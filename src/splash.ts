@@ -0,0 +1,44 @@
+/**
+ * 启动画面
+ * 等待后端就绪（由 Rust 侧使用与健康检查相同的探测逻辑判断），就绪后 Rust 会关闭本窗口并显示主窗口；
+ * 超时则显示重试 / 继续按钮
+ */
+
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+const status = document.getElementById('status')!
+
+// 启动阶段提示，例如校验组件
+listen<string>('splash://status', (event) => {
+  if (!document.body.classList.contains('failed')) {
+    status.textContent = event.payload
+  }
+})
+
+async function waitForBackend(): Promise<void> {
+  document.body.classList.remove('failed')
+  status.textContent = 'Starting DunCrew backend...'
+  const ready = await invoke<boolean>('wait_for_backend_ready')
+  if (!ready) {
+    document.body.classList.add('failed')
+    status.textContent = 'DunCrew backend is not responding'
+  }
+}
+
+document.getElementById('retry')!.addEventListener('click', async () => {
+  document.body.classList.remove('failed')
+  status.textContent = 'Restarting DunCrew backend...'
+  try {
+    await invoke('restart_backend')
+  } catch (error) {
+    console.warn('[Splash] restart_backend failed:', error)
+  }
+  await waitForBackend()
+})
+
+document.getElementById('continue')!.addEventListener('click', () => {
+  invoke('dismiss_splash')
+})
+
+waitForBackend()
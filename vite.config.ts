@@ -18,6 +18,13 @@ export default defineConfig({
   build: {
     outDir: 'dist',
     assetsDir: 'assets',
+    rollupOptions: {
+      // 桌面端启动画面 (splash.html) 作为独立页面打包
+      input: {
+        main: path.resolve(__dirname, 'index.html'),
+        splash: path.resolve(__dirname, 'splash.html'),
+      },
+    },
   },
   server: {
     proxy: {